@@ -134,17 +134,21 @@ mod config {
     impl CacheConfig for Config {
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(15);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = CachedStageInstance; // <-
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
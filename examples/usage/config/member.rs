@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use redlight::{
-    config::{Cacheable, ICachedMember, SerializeMany},
+    config::{Cacheable, ICachedMember, SerializeMany, SerializeWithArena},
     error::UpdateArchiveError,
     rkyv_util::id::IdRkyvMap,
     CachedArchive,
@@ -91,28 +91,9 @@ impl Cacheable for CachedMember {
     }
 
     // Let's be fancy and implement this optional method to slightly improve
-    // performance.
+    // performance. The provided serializer reuses its scratch space and
+    // pre-sizes buffers based on previous members.
     fn serialize_many() -> impl SerializeMany<Self> {
-        SerializeWithRecycle::default()
-    }
-}
-
-#[derive(Default)]
-/// Always serializes into the same byte buffer to avoid reallocations.
-struct SerializeWithRecycle {
-    writer: AlignedVec<8>,
-}
-
-impl SerializeMany<CachedMember> for SerializeWithRecycle {
-    type Bytes = AlignedVec<8>;
-
-    fn serialize_next(&mut self, next: &CachedMember) -> Result<Self::Bytes, rkyv::rancor::Error> {
-        self.writer.clear();
-        rkyv::api::high::to_bytes_in(next, &mut self.writer)?;
-
-        let mut bytes = AlignedVec::<8>::new();
-        bytes.extend_from_slice(self.writer.as_slice());
-
-        Ok(bytes)
+        SerializeWithArena::<8>::new()
     }
 }
@@ -16,17 +16,21 @@ impl CacheConfig for Config {
     // Only if the `metrics` feature is enabled
     const METRICS_INTERVAL_DURATION: std::time::Duration = std::time::Duration::from_secs(30);
 
+    type AutoModerationRule<'a> = Ignore;
     type Channel<'a> = Ignore;
     type CurrentUser<'a> = Ignore;
     type Emoji<'a> = Ignore;
     type Guild<'a> = Ignore;
     type Integration<'a> = Ignore;
+    type Invite<'a> = Ignore;
     type Member<'a> = CachedMember; // <-
     type Message<'a> = Ignore;
     type Presence<'a> = Ignore;
     type Role<'a> = CachedRole<'a>; // <-
+    type ScheduledEvent<'a> = Ignore;
     type StageInstance<'a> = Ignore;
     type Sticker<'a> = Ignore;
+    type ThreadMember<'a> = Ignore;
     type User<'a> = CachedUser; // <-
     type VoiceState<'a> = Ignore;
 }
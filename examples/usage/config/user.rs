@@ -96,6 +96,9 @@ impl Fallible for CachedUser {
     type Error = rkyv::rancor::Error;
 }
 
+// Fails to compile if the array below is too small
+redlight::assert_fits_scratch!(CachedUser, 32);
+
 impl Cacheable for CachedUser {
     type Bytes = [u8; 32];
 
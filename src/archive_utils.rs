@@ -0,0 +1,276 @@
+use rkyv::{
+    primitive::{
+        ArchivedF32, ArchivedF64, ArchivedI16, ArchivedI32, ArchivedI64, ArchivedU16, ArchivedU32,
+        ArchivedU64,
+    },
+    seal::Seal,
+    traits::NoUndef,
+};
+
+/// Implements [`Debug`] and [`PartialEq`] against a twilight type for an
+/// archived cached type.
+///
+/// Each listed field is included in the [`Debug`] output and compared in the
+/// [`PartialEq`] implementation. A field on its own is compared through
+/// `archived.field == twilight.field`. If the types don't match up directly,
+/// provide a closure that receives the archived field and the twilight value
+/// instead.
+///
+/// This is particularly handy for asserting cached values in tests.
+///
+/// # Example
+///
+/// ```
+/// use redlight::{archive_utils::impl_archived_debug_eq, rkyv_util::id::IdRkyv};
+/// use rkyv::{with::InlineAsBox, Archive};
+/// use twilight_model::{
+///     channel::Message,
+///     id::{marker::MessageMarker, Id},
+/// };
+///
+/// #[derive(Archive)]
+/// struct CachedMessage<'a> {
+///     #[rkyv(with = InlineAsBox)]
+///     content: &'a str,
+///     #[rkyv(with = IdRkyv)]
+///     id: Id<MessageMarker>,
+///     kind: u8,
+///     pinned: bool,
+/// }
+///
+/// impl_archived_debug_eq! {
+///     ArchivedCachedMessage<'_> == Message {
+///         content: |content, msg| content.as_ref() == msg.content,
+///         id,
+///         kind: |kind, msg| *kind == u8::from(msg.kind),
+///         pinned,
+///     }
+/// }
+///
+/// fn assert_cached(archived: &ArchivedCachedMessage<'_>, msg: &Message) {
+///     assert_eq!(archived, msg);
+/// }
+/// ```
+#[doc(inline)]
+pub use crate::__impl_archived_debug_eq as impl_archived_debug_eq;
+use crate::rkyv_util::{
+    id::{ArchivedId, ArchivedIdOption},
+    presence::ArchivedStatus,
+    util::{ArchivedPermissions, ArchivedTimestamp, ArchivedTimestampOption},
+};
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_archived_debug_eq {
+    (
+        $archived:ident $( < $( $lt:lifetime ),* > )? == $other:ty {
+            $( $field:ident $( : $cmp:expr )? ),* $(,)?
+        }
+    ) => {
+        impl ::std::fmt::Debug for $archived $( < $( $lt ),* > )? {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(stringify!($archived))
+                    $( .field(stringify!($field), &self.$field) )*
+                    .finish()
+            }
+        }
+
+        impl ::std::cmp::PartialEq<$other> for $archived $( < $( $lt ),* > )? {
+            fn eq(&self, other: &$other) -> bool {
+                true $(
+                    && $crate::__impl_archived_debug_eq!(
+                        @field self, other, $field $( , $cmp )?
+                    )
+                )*
+            }
+        }
+    };
+    (@field $this:ident, $other:ident, $field:ident) => {
+        $this.$field == $other.$field
+    };
+    (@field $this:ident, $other:ident, $field:ident, $cmp:expr) => {
+        $crate::archive_utils::__eq_field(&$this.$field, $other, $cmp)
+    };
+}
+
+/// Calls `f` with the given arguments.
+///
+/// Passing the closure to a function lets the compiler infer its argument
+/// types.
+#[doc(hidden)]
+pub fn __eq_field<A: ?Sized, B: ?Sized>(
+    archived: &A,
+    other: &B,
+    f: impl FnOnce(&A, &B) -> bool,
+) -> bool {
+    f(archived, other)
+}
+
+/// Guard around a sealed archive for in-place mutation.
+///
+/// Created through [`CachedArchive::update_sealed`] or by wrapping a [`Seal`]
+/// of a single field, e.g. after destructuring the sealed archive with
+/// `rkyv::munge::munge!`.
+///
+/// Values can only be replaced through [`SealedMut::set`] if their type
+/// implements [`InPlace`], i.e. if they have a fixed size and contain no
+/// relative pointers. Edits that would change the length of archived data
+/// such as strings or vectors are hence rejected at compile time.
+///
+/// # Example
+///
+/// ```
+/// # use rkyv::Archive;
+/// use redlight::{archive_utils::SealedMut, config::Cacheable, CachedArchive};
+/// use rkyv::munge::munge;
+///
+/// #[derive(Archive)]
+/// struct CachedData {
+///     num: u32,
+///     pinned: bool,
+///     name: String,
+/// }
+///
+/// impl Cacheable for CachedData {
+///     # /*
+///     // ...
+///     # */
+///     # type Bytes = [u8; 0];
+///     # fn expire() -> Option<std::time::Duration> { None }
+///     # fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> { Ok([]) }
+/// }
+///
+/// impl rkyv::rancor::Fallible for CachedData {
+///     type Error = rkyv::rancor::Error;
+/// }
+///
+/// fn handle_archive(archive: &mut CachedArchive<CachedData>, new_num: u32) {
+///     archive
+///         .update_sealed(|sealed| {
+///             munge!(let ArchivedCachedData { num, pinned, .. } = sealed.into_seal());
+///
+///             SealedMut::new(num).set(new_num);
+///             SealedMut::new(pinned).set(true);
+///         })
+///         .unwrap();
+/// }
+/// ```
+///
+/// Fields whose length may change can't be set:
+///
+/// ```compile_fail
+/// # use rkyv::{string::ArchivedString, seal::Seal};
+/// use redlight::archive_utils::SealedMut;
+///
+/// fn rename(name: Seal<'_, ArchivedString>) {
+///     SealedMut::new(name).set("new name");
+/// }
+/// ```
+///
+/// [`CachedArchive::update_sealed`]: crate::CachedArchive::update_sealed
+pub struct SealedMut<'a, T: ?Sized> {
+    seal: Seal<'a, T>,
+}
+
+impl<'a, T: ?Sized> SealedMut<'a, T> {
+    /// Wrap a [`Seal`].
+    pub const fn new(seal: Seal<'a, T>) -> Self {
+        Self { seal }
+    }
+
+    /// Return the wrapped [`Seal`], e.g. to destructure it.
+    pub const fn into_seal(self) -> Seal<'a, T> {
+        self.seal
+    }
+
+    /// Reference to the sealed value.
+    pub fn get(&self) -> &T {
+        &self.seal
+    }
+
+    /// Narrow the guard down to a part of the sealed value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rkyv::Archive;
+    /// use redlight::archive_utils::SealedMut;
+    /// use rkyv::munge::munge;
+    ///
+    /// #[derive(Archive)]
+    /// struct CachedData {
+    ///     num: u32,
+    /// }
+    ///
+    /// fn set_num(sealed: SealedMut<'_, ArchivedCachedData>, num: u32) {
+    ///     sealed
+    ///         .project(|seal| {
+    ///             munge!(let ArchivedCachedData { num } = seal);
+    ///
+    ///             num
+    ///         })
+    ///         .set(num);
+    /// }
+    /// ```
+    pub fn project<U: ?Sized>(
+        self,
+        f: impl FnOnce(Seal<'a, T>) -> Seal<'a, U>,
+    ) -> SealedMut<'a, U> {
+        SealedMut::new(f(self.seal))
+    }
+}
+
+impl<T: InPlace + Unpin> SealedMut<'_, T> {
+    /// Replace the sealed value.
+    pub fn set(&mut self, value: impl Into<T>) {
+        *self.seal = value.into();
+    }
+}
+
+/// Archived types that can be replaced in-place without invalidating the
+/// archive.
+///
+/// Implemented for archived types of a fixed size that contain no relative
+/// pointers, see [`SealedMut::set`]. This trait is sealed and cannot be
+/// implemented outside of this crate.
+pub trait InPlace: NoUndef + private::Sealed {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_in_place {
+    ( $( $ty:ty ),* $(,)? ) => {
+        $(
+            impl private::Sealed for $ty {}
+            impl InPlace for $ty {}
+        )*
+    };
+}
+
+impl_in_place! {
+    bool,
+    u8,
+    i8,
+    ArchivedU16,
+    ArchivedU32,
+    ArchivedU64,
+    ArchivedI16,
+    ArchivedI32,
+    ArchivedI64,
+    ArchivedF32,
+    ArchivedF64,
+    ArchivedPermissions,
+    ArchivedStatus,
+    ArchivedTimestamp,
+    ArchivedTimestampOption,
+}
+
+impl<T> private::Sealed for ArchivedId<T> {}
+impl<T> InPlace for ArchivedId<T> {}
+
+impl<T> private::Sealed for ArchivedIdOption<T> {}
+impl<T> InPlace for ArchivedIdOption<T> {}
+
+impl<T: InPlace, const N: usize> private::Sealed for [T; N] {}
+impl<T: InPlace, const N: usize> InPlace for [T; N] {}
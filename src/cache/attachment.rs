@@ -0,0 +1,112 @@
+use tracing::instrument;
+
+use super::{
+    pipe::{attachment_key, Pipe},
+    RedisCache,
+};
+use crate::{
+    config::{CacheConfig, Cacheable},
+    error::{CacheError, SerializeError, SerializeErrorKind},
+    key::RedisKey,
+    redis::Cmd,
+    CacheResult, CachedArchive,
+};
+
+/// Stores an attachment only if its parent entry exists and applies the
+/// parent's remaining TTL.
+///
+/// KEYS: parent, attachment
+/// ARGV: bytes
+const SET_ATTACHMENT_SCRIPT: &str = r"
+if redis.call('EXISTS', KEYS[1]) == 0 then
+    return 0
+end
+
+redis.call('SET', KEYS[2], ARGV[1])
+
+local pttl = redis.call('PTTL', KEYS[1])
+
+if pttl > 0 then
+    redis.call('PEXPIRE', KEYS[2], pttl)
+end
+
+return 1
+";
+
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "attachments")))]
+impl<C: CacheConfig> RedisCache<C> {
+    /// Attach a custom payload to a cached entry, e.g. moderation flags for
+    /// a member.
+    ///
+    /// The attachment takes on the remaining TTL of its entry and is deleted
+    /// alongside it. Hence, [`Cacheable::expire`] of `T` is not used. Later
+    /// writes of the entry don't extend the attachment's TTL so it must be
+    /// set again to keep it around for as long as a refreshed entry.
+    ///
+    /// Returns `false` and stores nothing if the entry is not cached.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::Cacheable, RedisCache, RedisKey};
+    /// # use twilight_model::id::Id;
+    /// # async fn example<C: redlight::config::CacheConfig, T: Cacheable>(
+    /// #     cache: RedisCache<C>,
+    /// #     flags: T,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let key = RedisKey::Member {
+    ///     guild: Id::new(1),
+    ///     user: Id::new(2),
+    /// };
+    ///
+    /// cache.set_attachment(key, &flags).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(level = "trace", skip(self, value))]
+    pub async fn set_attachment<T: Cacheable>(
+        &self,
+        entity_key: RedisKey,
+        value: &T,
+    ) -> CacheResult<bool> {
+        let bytes = value
+            .serialize_one()
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::Attachment))?;
+
+        let mut conn = self.connection().await?;
+
+        let attachment_key = attachment_key(&self.namespace, &entity_key);
+
+        Cmd::new()
+            .arg("EVAL")
+            .arg(SET_ATTACHMENT_SCRIPT)
+            .arg(2)
+            .arg(self.namespace.key(entity_key))
+            .arg(attachment_key)
+            .arg(bytes.as_ref())
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)
+    }
+
+    /// Get the custom payload attached to a cached entry.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn get_attachment<T: Cacheable>(
+        &self,
+        entity_key: RedisKey,
+    ) -> CacheResult<Option<CachedArchive<T>>> {
+        let attachment_key = attachment_key(&self.namespace, &entity_key);
+
+        Pipe::new(self).get_rendered(attachment_key).await
+    }
+
+    /// Remove the custom payload attached to a cached entry.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn delete_attachment(&self, entity_key: RedisKey) -> CacheResult<()> {
+        let mut conn = self.connection().await?;
+
+        Cmd::del(attachment_key(&self.namespace, &entity_key))
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)
+    }
+}
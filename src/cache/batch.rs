@@ -0,0 +1,139 @@
+use std::{
+    mem,
+    time::{Duration, SystemTime},
+};
+
+use tracing::instrument;
+use twilight_model::gateway::event::Event;
+
+use crate::{cache::pipe::Pipe, config::CacheConfig, CacheResult, RedisCache};
+
+/// Buffers the writes of multiple gateway events and sends them to redis in
+/// a single round trip.
+///
+/// Created through [`RedisCache::batched`]. The buffer is flushed once it
+/// holds the given amount of events or once its oldest event has been
+/// buffered for the given duration.
+///
+/// There is no background task so the duration is only checked whenever an
+/// event is buffered. Call [`BatchedUpdater::flush`] on a timer or before
+/// shutting down so that trailing events are not held back indefinitely.
+///
+/// Reads that an event's writes depend on, e.g. the current entry for
+/// in-place updates, are still executed right away and thus do not observe
+/// writes that are still buffered. Events that update entries in-place
+/// should therefore not follow their creation within the same batch.
+pub struct BatchedUpdater<'c, C> {
+    cache: &'c RedisCache<C>,
+    pipe: Pipe<'c, C>,
+    len: usize,
+    oldest: Option<SystemTime>,
+    max_events: usize,
+    max_delay: Duration,
+}
+
+impl<C: CacheConfig> BatchedUpdater<'_, C> {
+    /// Buffer the writes of an [`Event`] and flush the buffer if a threshold
+    /// is reached.
+    ///
+    /// Returns whether the buffer was flushed.
+    #[instrument(skip_all, fields(event = ?event.kind()))]
+    pub async fn update(&mut self, event: &Event) -> CacheResult<bool> {
+        let mut pipe = Pipe::new(self.cache);
+
+        if let Err(err) = self.cache.fill_pipe(&mut pipe, event, None).await {
+            self.cache.pressure.record_outcome(false);
+
+            return Err(err);
+        }
+
+        self.cache.apply_views(&mut pipe, event);
+
+        if !pipe.is_empty() {
+            self.pipe.append(pipe);
+            self.len += 1;
+            self.oldest.get_or_insert_with(|| self.cache.clock.now());
+        }
+
+        let delay_exceeded = self.oldest.is_some_and(|oldest| {
+            let elapsed = self.cache.clock.now().duration_since(oldest);
+
+            elapsed.unwrap_or_default() >= self.max_delay
+        });
+
+        if self.len < self.max_events && !delay_exceeded {
+            return Ok(false);
+        }
+
+        self.flush().await?;
+
+        Ok(true)
+    }
+
+    /// Send all buffered writes to redis.
+    #[instrument(skip_all, fields(events = self.len))]
+    pub async fn flush(&mut self) -> CacheResult<()> {
+        self.len = 0;
+        self.oldest = None;
+
+        if self.pipe.is_empty() {
+            return Ok(());
+        }
+
+        // Replacing the pipe releases its connection until the next flush
+        let mut pipe = mem::replace(&mut self.pipe, Pipe::new(self.cache));
+
+        let res = pipe.query::<()>().await;
+        self.cache.pressure.record_outcome(res.is_ok());
+
+        res
+    }
+
+    /// The amount of buffered events.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no events are buffered.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Create a [`BatchedUpdater`] that coalesces the writes of many events
+    /// into few round trips.
+    ///
+    /// The buffer is flushed once it holds `max_events` events or once its
+    /// oldest event has been buffered for `max_delay`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # use twilight_model::gateway::event::Event;
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// #     events: Vec<Event>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let mut updater = cache.batched(500, Duration::from_millis(50));
+    ///
+    /// for event in events {
+    ///     updater.update(&event).await?;
+    /// }
+    ///
+    /// updater.flush().await?;
+    /// # Ok(()) }
+    /// ```
+    pub fn batched(&self, max_events: usize, max_delay: Duration) -> BatchedUpdater<'_, C> {
+        BatchedUpdater {
+            cache: self,
+            pipe: Pipe::new(self),
+            len: 0,
+            oldest: None,
+            max_events: max_events.max(1),
+            max_delay,
+        }
+    }
+}
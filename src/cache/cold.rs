@@ -0,0 +1,295 @@
+use std::{
+    error::Error as StdError,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use futures_util::future::BoxFuture;
+use rkyv::util::AlignedVec;
+use tracing::{debug, instrument};
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use super::{get::into_archive, pipe::Pipe};
+use crate::{
+    config::{CacheConfig, Cacheable},
+    error::CacheError,
+    key::RedisKey,
+    maintenance::{parse_entry_id, scan, scan_pattern},
+    redis::{cmd, Cmd, Connection},
+    CacheResult, CachedArchive, RedisCache,
+};
+
+/// Maximum amount of messages that are moved per round trip.
+const BATCH_SIZE: usize = 500;
+
+/// Error type of [`ColdStore`] operations.
+pub type ColdStoreError = Box<dyn StdError + Send + Sync>;
+
+/// Secondary storage that old messages are moved to.
+///
+/// Registered through [`RedisCache::with_cold_store`]. Messages are handed
+/// over as the same bytes that were cached in redis so they can be stored
+/// as-is, e.g. in a database or on disk.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::{collections::HashMap, sync::Mutex};
+///
+/// use futures_util::future::BoxFuture;
+/// use redlight::cache::{ColdStore, ColdStoreError};
+/// use twilight_model::id::{marker::MessageMarker, Id};
+///
+/// #[derive(Default)]
+/// struct MemoryStore(Mutex<HashMap<Id<MessageMarker>, Vec<u8>>>);
+///
+/// impl ColdStore for MemoryStore {
+///     fn store(
+///         &self,
+///         messages: Vec<(Id<MessageMarker>, Vec<u8>)>,
+///     ) -> BoxFuture<'_, Result<(), ColdStoreError>> {
+///         self.0.lock().unwrap().extend(messages);
+///
+///         Box::pin(async { Ok(()) })
+///     }
+///
+///     fn load(
+///         &self,
+///         msg_id: Id<MessageMarker>,
+///     ) -> BoxFuture<'_, Result<Option<Vec<u8>>, ColdStoreError>> {
+///         let bytes = self.0.lock().unwrap().get(&msg_id).cloned();
+///
+///         Box::pin(async move { Ok(bytes) })
+///     }
+/// }
+/// ```
+pub trait ColdStore: Send + Sync + 'static {
+    /// Store the serialized entries of messages that are about to be removed
+    /// from redis.
+    ///
+    /// If this fails, the messages stay in redis and are moved again later.
+    fn store(
+        &self,
+        messages: Vec<(Id<MessageMarker>, Vec<u8>)>,
+    ) -> BoxFuture<'_, Result<(), ColdStoreError>>;
+
+    /// Load the serialized entry of a message that was moved before.
+    fn load(
+        &self,
+        msg_id: Id<MessageMarker>,
+    ) -> BoxFuture<'_, Result<Option<Vec<u8>>, ColdStoreError>>;
+}
+
+impl<C> RedisCache<C> {
+    /// Register a [`ColdStore`] that old messages can be moved to.
+    ///
+    /// Messages are moved through [`RedisCache::run_cold_tier`] or
+    /// [`RedisCache::move_cold_messages`]. Afterwards, [`RedisCache::message`]
+    /// falls back to the cold store if a message is not cached in redis.
+    #[must_use]
+    pub fn with_cold_store(mut self, store: impl ColdStore) -> Self {
+        self.cold_store = Some(Arc::new(store));
+
+        self
+    }
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Move messages older than `threshold` to the [`ColdStore`] every
+    /// `interval`.
+    ///
+    /// This runs until an error occurs so it's meant to be spawned as a
+    /// background task alongside the event loop. Does nothing if no cold
+    /// store is registered.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::{sync::Arc, time::Duration};
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # async fn example<C: CacheConfig>(cache: Arc<RedisCache<C>>) {
+    /// const WEEK: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+    ///
+    /// let cold_cache = Arc::clone(&cache);
+    ///
+    /// tokio::spawn(async move {
+    ///     if let Err(err) = cold_cache
+    ///         .run_cold_tier(WEEK, Duration::from_secs(600))
+    ///         .await
+    ///     {
+    ///         eprintln!("cold tier stopped: {err}");
+    ///     }
+    /// });
+    /// # }
+    /// ```
+    pub async fn run_cold_tier(&self, threshold: Duration, interval: Duration) -> CacheResult<()> {
+        if self.cold_store.is_none() {
+            return Ok(());
+        }
+
+        loop {
+            let moved = self.move_cold_messages(threshold).await?;
+            debug!(moved, "Moved messages to cold store");
+
+            self.clock.sleep(interval).await;
+        }
+    }
+
+    /// Move all messages older than `threshold` to the [`ColdStore`].
+    ///
+    /// Returns the amount of moved messages. Nothing is moved if
+    /// [`CacheConfig::SHADOW`] is enabled.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn move_cold_messages(&self, threshold: Duration) -> CacheResult<usize> {
+        // Deletes would be discarded in shadow mode so nothing can be moved
+        if !C::Message::WANTED || C::SHADOW || self.cold_store.is_none() {
+            return Ok(0);
+        }
+
+        let mut conn = self.connection().await?;
+        let pattern = scan_pattern(&self.namespace, RedisKey::CHANNEL_MESSAGES_PREFIX);
+
+        let mut cursor = 0;
+        let mut moved = 0;
+
+        loop {
+            let (next, keys) = scan(&mut conn, cursor, Some(&pattern)).await?;
+
+            for key in keys {
+                let Some(channel_id) =
+                    parse_entry_id(&self.namespace, RedisKey::CHANNEL_MESSAGES_PREFIX, &key)
+                        .and_then(Id::new_checked)
+                else {
+                    continue;
+                };
+
+                moved += self.move_channel(&mut conn, channel_id, threshold).await?;
+            }
+
+            if next == 0 {
+                return Ok(moved);
+            }
+
+            cursor = next;
+        }
+    }
+
+    /// Move messages of a channel older than `threshold` to the
+    /// [`ColdStore`].
+    ///
+    /// Returns the amount of moved messages.
+    #[instrument(level = "debug", skip(self))]
+    pub async fn move_cold_channel_messages(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        threshold: Duration,
+    ) -> CacheResult<usize> {
+        if !C::Message::WANTED || C::SHADOW || self.cold_store.is_none() {
+            return Ok(0);
+        }
+
+        let mut conn = self.connection().await?;
+
+        self.move_channel(&mut conn, channel_id, threshold).await
+    }
+
+    async fn move_channel(
+        &self,
+        conn: &mut Connection<'_>,
+        channel_id: Id<ChannelMarker>,
+        threshold: Duration,
+    ) -> CacheResult<usize> {
+        let Some(ref store) = self.cold_store else {
+            return Ok(0);
+        };
+
+        let cutoff = self
+            .clock
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_sub(threshold);
+
+        // Scores are negated message timestamps so older messages score higher
+        let min = format!(
+            "({}",
+            -i64::try_from(cutoff.as_micros()).unwrap_or(i64::MAX)
+        );
+        let key = self.namespace.key(RedisKey::ChannelMessages {
+            channel: channel_id,
+        });
+
+        let mut moved = 0;
+
+        loop {
+            let ids: Vec<u64> = cmd("ZRANGEBYSCORE")
+                .arg(&key)
+                .arg(&min)
+                .arg("+inf")
+                .arg("LIMIT")
+                .arg(0)
+                .arg(BATCH_SIZE)
+                .query_async(conn)
+                .await?;
+
+            if ids.is_empty() {
+                return Ok(moved);
+            }
+
+            let msg_ids: Vec<Id<MessageMarker>> =
+                ids.iter().copied().filter_map(Id::new_checked).collect();
+
+            let entry_keys: Vec<_> = msg_ids.iter().map(|&id| RedisKey::Message { id }).collect();
+
+            let entries: Vec<Option<Vec<u8>>> = Cmd::mget(self.namespace.key(entry_keys))
+                .query_async(conn)
+                .await?;
+
+            // Messages that expired in the meantime only need their index removed
+            let messages: Vec<_> = msg_ids
+                .iter()
+                .zip(entries)
+                .filter_map(|(&id, bytes)| Some((id, bytes?)))
+                .collect();
+
+            moved += messages.len();
+
+            if !messages.is_empty() {
+                store.store(messages).await.map_err(CacheError::ColdStore)?;
+            }
+
+            let mut pipe = Pipe::new(self);
+            self.delete_messages(&mut pipe, &msg_ids, channel_id);
+
+            if !pipe.is_empty() {
+                pipe.query::<()>().await?;
+            }
+
+            if ids.len() < BATCH_SIZE {
+                return Ok(moved);
+            }
+        }
+    }
+
+    /// Load a message from the [`ColdStore`] if one is registered.
+    pub(crate) async fn cold_message(
+        &self,
+        msg_id: Id<MessageMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Message<'static>>>> {
+        let Some(ref store) = self.cold_store else {
+            return Ok(None);
+        };
+
+        let Some(bytes) = store.load(msg_id).await.map_err(CacheError::ColdStore)? else {
+            return Ok(None);
+        };
+
+        let mut aligned = AlignedVec::<16>::with_capacity(bytes.len());
+        aligned.extend_from_slice(&bytes);
+
+        into_archive(aligned)
+    }
+}
@@ -6,17 +6,31 @@ use std::{
 };
 
 use rkyv::{rancor::ResultExt, util::AlignedVec, with::With};
-use tracing::{info, instrument, trace};
+use tracing::{info, instrument, trace, warn};
 use twilight_gateway::Session;
 
 use crate::{
+    cache::IO_TARGET,
     error::CacheError,
-    key::RedisKey,
-    redis::Cmd,
+    key::{Namespace, RedisKey},
+    redis::{Cmd, Connection},
     rkyv_util::session::{ArchivedSessions, SessionsRkyv},
     CacheResult, RedisCache,
 };
 
+/// Amount of keys to scan for at once when flushing a key prefix.
+const FLUSH_BATCH_SIZE: usize = 500;
+
+/// Sessions retrieved through [`RedisCache::defrost_within`].
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "cold_resume")))]
+#[derive(Debug)]
+pub struct DefrostedSessions<S = RandomState> {
+    /// All sessions that could be loaded in time.
+    pub sessions: HashMap<u64, Session, S>,
+    /// Requested shard ids for which no session was loaded.
+    pub missing: Vec<u64>,
+}
+
 #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "cold_resume")))]
 impl<C> RedisCache<C> {
     /// Given a map of shard ids and sessions, store those sessions in the cache
@@ -42,18 +56,18 @@ impl<C> RedisCache<C> {
         let bytes = rkyv::api::high::to_bytes_in(sessions, AlignedVec::<8>::new())
             .map_err(CacheError::SerializeSessions)?;
 
-        trace!(bytes = bytes.len());
+        trace!(target: IO_TARGET, bytes = bytes.len());
 
         let mut conn = self.connection().await?;
 
         #[allow(clippy::cast_possible_truncation)]
         let cmd = match expire {
             Some(duration) => Cmd::set_ex(
-                RedisKey::Sessions,
+                self.namespace.key(RedisKey::Sessions),
                 bytes.as_slice(),
                 duration.as_secs() as usize,
             ),
-            None => Cmd::set(RedisKey::Sessions, bytes.as_slice()),
+            None => Cmd::set(self.namespace.key(RedisKey::Sessions), bytes.as_slice()),
         };
 
         let _: () = cmd.query_async(&mut conn).await?;
@@ -65,8 +79,11 @@ impl<C> RedisCache<C> {
     /// given hasher.
     ///
     /// If `flush_if_missing` is set to `true` and there are no stored sessions,
-    /// the redis command `FLUSHDB` will be executed, clearing **all** data from
-    /// the database and ensuring that no invalid cached data remains.
+    /// all keys within [`CacheConfig::KEY_PREFIX`] are removed, ensuring that
+    /// no invalid cached data remains. Without a key prefix, the redis command
+    /// `FLUSHDB` is executed instead, clearing **all** data from the database.
+    ///
+    /// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
     ///
     /// To store sessions, use [`freeze`](RedisCache::freeze).
     #[instrument(level = "trace", name = "defrost", skip_all)]
@@ -79,37 +96,66 @@ impl<C> RedisCache<C> {
     {
         let mut conn = self.connection().await?;
 
-        let bytes: Vec<u8> = Cmd::get(RedisKey::Sessions).query_async(&mut conn).await?;
+        let bytes: Vec<u8> = Cmd::get(self.namespace.key(RedisKey::Sessions))
+            .query_async(&mut conn)
+            .await?;
 
         if bytes.is_empty() {
             if flush_if_missing {
                 info!("Sessions not found; flushing redis database");
 
-                let _: () = Cmd::new().arg("FLUSHDB").query_async(&mut conn).await?;
+                self.flush(&mut conn).await?;
             }
 
             return Ok(None);
         }
 
-        #[cfg(feature = "bytecheck")]
-        let archived: &ArchivedSessions = rkyv::access(&bytes).map_err(CacheError::Validation)?;
+        decode_sessions(&bytes).map(Some)
+    }
+
+    /// Remove all keys within the key prefix, or the whole database if there
+    /// is no prefix.
+    async fn flush(&self, conn: &mut Connection<'_>) -> CacheResult<()> {
+        if self.namespace.as_bytes().is_empty() {
+            let _: () = Cmd::new().arg("FLUSHDB").query_async(conn).await?;
 
-        #[cfg(not(feature = "bytecheck"))]
-        let archived: &ArchivedSessions = unsafe { rkyv::access_unchecked(&bytes) };
+            return Ok(());
+        }
 
-        let sessions = rkyv::api::deserialize_using::<_, _, Infallible>(
-            With::<_, SessionsRkyv>::cast(archived),
-            &mut (),
-        );
+        let pattern = flush_pattern(&self.namespace);
+        let mut cursor = 0;
 
-        Ok(Some(sessions.always_ok()))
+        loop {
+            let (next, keys): (u64, Vec<Vec<u8>>) = Cmd::new()
+                .arg("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(FLUSH_BATCH_SIZE)
+                .query_async(conn)
+                .await?;
+
+            if !keys.is_empty() {
+                let _: () = Cmd::new().arg("UNLINK").arg(keys).query_async(conn).await?;
+            }
+
+            if next == 0 {
+                return Ok(());
+            }
+
+            cursor = next;
+        }
     }
 
     /// Retrieve stored sessions and provide them in a default [`HashMap`].
     ///
     /// If `flush_if_missing` is set to `true` and there are no stored sessions,
-    /// the redis command `FLUSHDB` will be executed, clearing **all** data from
-    /// the database and ensuring that no invalid cached data remains.
+    /// all keys within [`CacheConfig::KEY_PREFIX`] are removed, ensuring that
+    /// no invalid cached data remains. Without a key prefix, the redis command
+    /// `FLUSHDB` is executed instead, clearing **all** data from the database.
+    ///
+    /// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
     ///
     /// To store sessions, use [`freeze`](RedisCache::freeze).
     pub async fn defrost(
@@ -119,4 +165,131 @@ impl<C> RedisCache<C> {
         self.defrost_with_hasher::<RandomState>(flush_if_missing)
             .await
     }
+
+    /// Retrieve stored sessions within the given timeout and provide them in
+    /// a [`DefrostedSessions`] with the given hasher.
+    ///
+    /// If the sessions could not be loaded in time, e.g. due to a slow redis,
+    /// the result contains no sessions and all given shard ids are marked as
+    /// missing so that startup can continue by identifying those shards anew.
+    ///
+    /// If `delete` is set to `true`, the sessions are atomically removed while
+    /// being read so that no other instance resumes them a second time. Note
+    /// that sessions may still be deleted if the timeout elapses while redis
+    /// is already processing the command.
+    ///
+    /// To store sessions, use [`freeze`](RedisCache::freeze).
+    #[instrument(level = "trace", name = "defrost_within", skip_all)]
+    pub async fn defrost_within_with_hasher<S>(
+        &self,
+        shard_ids: impl IntoIterator<Item = u64>,
+        timeout: Duration,
+        delete: bool,
+    ) -> CacheResult<DefrostedSessions<S>>
+    where
+        S: BuildHasher + Default,
+    {
+        let fut = async {
+            let mut conn = self.connection().await?;
+            let cmd = if delete { "GETDEL" } else { "GET" };
+
+            let bytes: Vec<u8> = Cmd::new()
+                .arg(cmd)
+                .arg(self.namespace.key(RedisKey::Sessions))
+                .query_async(&mut conn)
+                .await?;
+
+            Ok::<_, CacheError>(bytes)
+        };
+
+        let sessions = if let Ok(res) = tokio::time::timeout(timeout, fut).await {
+            let bytes = res?;
+
+            if bytes.is_empty() {
+                HashMap::default()
+            } else {
+                decode_sessions(&bytes)?
+            }
+        } else {
+            warn!(?timeout, "Timed out while retrieving sessions");
+
+            HashMap::default()
+        };
+
+        let missing = shard_ids
+            .into_iter()
+            .filter(|shard_id| !sessions.contains_key(shard_id))
+            .collect();
+
+        Ok(DefrostedSessions { sessions, missing })
+    }
+
+    /// Retrieve stored sessions within the given timeout and provide them in
+    /// a [`DefrostedSessions`] with a default hasher.
+    ///
+    /// See [`defrost_within_with_hasher`](RedisCache::defrost_within_with_hasher)
+    /// for more details.
+    pub async fn defrost_within(
+        &self,
+        shard_ids: impl IntoIterator<Item = u64>,
+        timeout: Duration,
+        delete: bool,
+    ) -> CacheResult<DefrostedSessions> {
+        self.defrost_within_with_hasher::<RandomState>(shard_ids, timeout, delete)
+            .await
+    }
+}
+
+/// Pattern matching all keys within the namespace, escaping glob characters
+/// of the key prefix.
+fn flush_pattern(namespace: &Namespace) -> Vec<u8> {
+    let namespace = namespace.as_bytes();
+    let mut pattern = Vec::with_capacity(namespace.len() + 1);
+
+    for &byte in namespace {
+        if matches!(byte, b'*' | b'?' | b'[' | b']' | b'\\') {
+            pattern.push(b'\\');
+        }
+
+        pattern.push(byte);
+    }
+
+    pattern.push(b'*');
+
+    pattern
+}
+
+// Only fallible when validating
+#[cfg_attr(not(feature = "bytecheck"), allow(clippy::unnecessary_wraps))]
+fn decode_sessions<S>(bytes: &[u8]) -> CacheResult<HashMap<u64, Session, S>>
+where
+    S: BuildHasher + Default,
+{
+    #[cfg(feature = "bytecheck")]
+    let archived: &ArchivedSessions = rkyv::access(bytes).map_err(CacheError::Validation)?;
+
+    #[cfg(not(feature = "bytecheck"))]
+    let archived: &ArchivedSessions = unsafe { rkyv::access_unchecked(bytes) };
+
+    let sessions = rkyv::api::deserialize_using::<_, _, Infallible>(
+        With::<_, SessionsRkyv>::cast(archived),
+        &mut (),
+    );
+
+    Ok(sessions.always_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flush_pattern;
+    use crate::key::Namespace;
+
+    #[test]
+    fn flush_pattern_escapes_prefix() {
+        let namespace = Namespace::new("bot").unwrap();
+        assert_eq!(flush_pattern(&namespace), b"bot:*");
+
+        let namespace = Namespace::new("a*[b]?").unwrap();
+        assert_eq!(flush_pattern(&namespace), br"a\*\[b\]\?:*");
+    }
 }
@@ -0,0 +1,112 @@
+use tracing::instrument;
+
+use super::RedisCache;
+use crate::{
+    config::{CacheConfig, Cacheable},
+    error::{CacheError, SerializeError, SerializeErrorKind},
+    key::RedisKey,
+    redis::Cmd,
+    CacheResult, CachedArchive,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Get user-defined data that was stored through
+    /// [`RedisCache::set_custom`].
+    ///
+    /// The namespace separates different kinds of data, e.g. `"settings"` for
+    /// per-guild settings, so that their keys do not collide. Neither may
+    /// contain `:` since that is used as separator within redis keys,
+    /// otherwise [`CacheError::InvalidCustomKey`] is returned.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn custom<T: Cacheable>(
+        &self,
+        namespace: &str,
+        key: &str,
+    ) -> CacheResult<Option<CachedArchive<T>>> {
+        let key = custom_key(namespace, key)?;
+
+        self.get_single_from(self.read_preference, key).await
+    }
+
+    /// Store user-defined data such as per-guild settings or cooldowns
+    /// alongside the cached entities.
+    ///
+    /// The entry expires after [`Cacheable::expire`] of `T`, if any, and is
+    /// not touched by any gateway event.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CacheError::InvalidCustomKey`] if the namespace or key
+    /// contains `:`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::Cacheable, RedisCache};
+    /// # async fn example<C: redlight::config::CacheConfig, T: Cacheable>(
+    /// #     cache: RedisCache<C>,
+    /// #     settings: T,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// cache.set_custom("settings", "123", &settings).await?;
+    ///
+    /// let settings = cache.custom::<T>("settings", "123").await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(level = "trace", skip(self, value))]
+    pub async fn set_custom<T: Cacheable>(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: &T,
+    ) -> CacheResult<()> {
+        let key = custom_key(namespace, key)?;
+
+        let bytes = value
+            .serialize_one()
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::Custom))?;
+
+        let mut conn = self.connection().await?;
+
+        let cmd = match T::expire() {
+            Some(duration) => {
+                // Sub-second durations must neither be rounded down to 0,
+                // which redis rejects, nor lose their fraction
+                let millis = usize::try_from(duration.as_millis()).unwrap_or(usize::MAX);
+
+                Cmd::pset_ex(self.namespace.key(key), bytes.as_ref(), millis.max(1))
+            }
+            None => Cmd::set(self.namespace.key(key), bytes.as_ref()),
+        };
+
+        cmd.query_async(&mut conn).await.map_err(CacheError::Redis)
+    }
+
+    /// Delete user-defined data that was stored through
+    /// [`RedisCache::set_custom`].
+    #[instrument(level = "trace", skip(self))]
+    pub async fn delete_custom(&self, namespace: &str, key: &str) -> CacheResult<()> {
+        let key = custom_key(namespace, key)?;
+        let mut conn = self.connection().await?;
+
+        Cmd::del(self.namespace.key(key))
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)
+    }
+}
+
+/// Fails if the namespace or key contains `:` since the key could not be
+/// parsed back, e.g. on expire events.
+pub(super) fn custom_key(namespace: &str, key: &str) -> CacheResult<RedisKey> {
+    if namespace.contains(':') || key.contains(':') {
+        return Err(CacheError::InvalidCustomKey {
+            namespace: namespace.into(),
+            key: key.into(),
+        });
+    }
+
+    Ok(RedisKey::Custom {
+        namespace: namespace.into(),
+        key: key.into(),
+    })
+}
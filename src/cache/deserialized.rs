@@ -0,0 +1,121 @@
+use rkyv::{
+    api::high::HighDeserializer,
+    rancor::{BoxedError, Fallible, Source},
+    Archived, Deserialize,
+};
+use twilight_model::id::{
+    marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    config::{CacheConfig, Cacheable},
+    error::CacheError,
+    key::RedisKey,
+    CacheResult, RedisCache,
+};
+
+type Deserializer<T> = HighDeserializer<<T as Fallible>::Error>;
+
+/// Getters that deserialize entries into owned values.
+///
+/// These are only available if the archived form of the config type can be
+/// deserialized into the config type itself, e.g. by deriving
+/// [`rkyv::Deserialize`]. Prefer the archived getters such as
+/// [`RedisCache::guild`] when only a few fields are needed.
+impl<C: CacheConfig> RedisCache<C> {
+    /// Get a channel entry as an owned value.
+    pub async fn channel_deserialized(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<Option<C::Channel<'static>>>
+    where
+        Archived<C::Channel<'static>>:
+            Deserialize<C::Channel<'static>, Deserializer<C::Channel<'static>>>,
+    {
+        self.get_deserialized(channel_id).await
+    }
+
+    /// Get a guild entry as an owned value.
+    pub async fn guild_deserialized(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<Option<C::Guild<'static>>>
+    where
+        Archived<C::Guild<'static>>:
+            Deserialize<C::Guild<'static>, Deserializer<C::Guild<'static>>>,
+    {
+        self.get_deserialized(guild_id).await
+    }
+
+    /// Get a member entry as an owned value.
+    pub async fn member_deserialized(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<C::Member<'static>>>
+    where
+        Archived<C::Member<'static>>:
+            Deserialize<C::Member<'static>, Deserializer<C::Member<'static>>>,
+    {
+        let key = RedisKey::Member {
+            guild: guild_id,
+            user: user_id,
+        };
+
+        self.get_deserialized(key).await
+    }
+
+    /// Get a message entry as an owned value.
+    pub async fn message_deserialized(
+        &self,
+        msg_id: Id<MessageMarker>,
+    ) -> CacheResult<Option<C::Message<'static>>>
+    where
+        Archived<C::Message<'static>>:
+            Deserialize<C::Message<'static>, Deserializer<C::Message<'static>>>,
+    {
+        self.get_deserialized(msg_id).await
+    }
+
+    /// Get a role entry as an owned value.
+    pub async fn role_deserialized(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> CacheResult<Option<C::Role<'static>>>
+    where
+        Archived<C::Role<'static>>: Deserialize<C::Role<'static>, Deserializer<C::Role<'static>>>,
+    {
+        self.get_deserialized(role_id).await
+    }
+
+    /// Get a user entry as an owned value.
+    pub async fn user_deserialized(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<C::User<'static>>>
+    where
+        Archived<C::User<'static>>: Deserialize<C::User<'static>, Deserializer<C::User<'static>>>,
+    {
+        self.get_deserialized(user_id).await
+    }
+
+    async fn get_deserialized<K, V>(&self, key: K) -> CacheResult<Option<V>>
+    where
+        RedisKey: From<K>,
+        V: Cacheable,
+        Archived<V>: Deserialize<V, Deserializer<V>>,
+    {
+        let Some(archive) = self
+            .get_single_from::<K, V>(self.read_preference, key)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        archive
+            .deserialize_into()
+            .map(Some)
+            .map_err(|e| CacheError::Deserialization(BoxedError::new(e)))
+    }
+}
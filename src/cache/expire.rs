@@ -1,53 +1,146 @@
-use std::{error::Error as StdError, fmt::Write};
+use std::{error::Error as StdError, fmt::Write, sync::atomic::Ordering, time::Duration};
 
-use futures_util::StreamExt;
+use futures_util::{stream, Stream, StreamExt};
 use tracing::{error, info, trace, warn};
 
-use super::meta::MetaKey;
+use super::{
+    meta::{ExpirePipe, MetaKey},
+    pipe::Pipe,
+};
 use crate::{
     config::{CacheConfig, Cacheable},
     error::ExpireError,
-    redis::{aio::PubSub, Cmd, DedicatedConnection, Pipeline, Pool},
+    key::{Namespace, RedisKey},
+    redis::{Cmd, DedicatedConnection, Msg, Pool},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    pub(super) async fn handle_expire(pool: &Pool) -> CacheResult<()> {
-        let any_expire = C::Channel::expire().is_some()
+    /// Start listening to expire events if any type specifies an expire
+    /// duration.
+    ///
+    /// Returns whether the listener was started.
+    pub(super) async fn handle_expire(pool: &Pool, namespace: &Namespace) -> CacheResult<bool> {
+        let any_expire = C::AutoModerationRule::expire().is_some()
+            || C::Channel::expire().is_some()
             || C::Emoji::expire().is_some()
             || C::Guild::expire().is_some()
             || C::Integration::expire().is_some()
+            || C::Invite::expire().is_some()
             || C::Member::expire().is_some()
             || C::Message::expire().is_some()
             || C::Presence::expire().is_some()
             || C::Role::expire().is_some()
+            || C::ScheduledEvent::expire().is_some()
             || C::StageInstance::expire().is_some()
             || C::Sticker::expire().is_some()
+            || C::ThreadMember::expire().is_some()
             || C::User::expire().is_some()
             || C::VoiceState::expire().is_some();
 
         if !any_expire {
-            return Ok(());
+            return Ok(false);
+        }
+
+        Self::spawn_expire_listener(pool, namespace).await?;
+
+        Ok(true)
+    }
+
+    /// Apply an expire duration to many entries at once.
+    ///
+    /// Useful to start expiring a class of entries at runtime without
+    /// redeploying with a different [`Cacheable::expire`]. All keys are
+    /// expired through a single pipeline and attachments of the entries share
+    /// the new duration.
+    ///
+    /// Once the entries expire, their ids are removed from the cache's
+    /// collections. Note that the guild collections of channels, emojis,
+    /// invites, messages, roles, stage instances, and stickers can only be
+    /// cleaned up if their type specifies [`Cacheable::expire`] because the
+    /// required data is not stored otherwise.
+    ///
+    /// Returns the amount of entries that were found and will expire.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use redlight::{config::CacheConfig, RedisCache, RedisKey};
+    /// # use twilight_model::id::Id;
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let user_ids = cache.user_ids().await?;
+    /// cache
+    ///     .expire_many(user_ids, Duration::from_secs(3600))
+    ///     .await?;
+    ///
+    /// let members = [RedisKey::Member {
+    ///     guild: Id::new(1),
+    ///     user: Id::new(2),
+    /// }];
+    /// cache.expire_many(members, Duration::from_secs(60)).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn expire_many<K>(
+        &self,
+        keys: impl IntoIterator<Item = K>,
+        duration: Duration,
+    ) -> CacheResult<usize>
+    where
+        RedisKey: From<K>,
+    {
+        let mut pipe = Pipe::new(self);
+
+        for key in keys {
+            pipe.pexpire(RedisKey::from(key), duration);
         }
 
-        let mut conn = DedicatedConnection::get(pool)
+        if pipe.is_empty() {
+            return Ok(0);
+        }
+
+        if !self.listens_to_expire.swap(true, Ordering::AcqRel) {
+            if let Err(err) = Self::spawn_expire_listener(&self.pool, &self.namespace).await {
+                self.listens_to_expire.store(false, Ordering::Release);
+
+                return Err(err);
+            }
+        }
+
+        let expiring: Vec<usize> = pipe.query().await?;
+
+        Ok(expiring.into_iter().sum())
+    }
+
+    async fn spawn_expire_listener(pool: &Pool, namespace: &Namespace) -> CacheResult<()> {
+        // In cluster mode, each primary only notifies about its own keys
+        let conns = DedicatedConnection::get_per_primary(pool)
             .await
             .map_err(ExpireError::GetConnection)?;
 
-        prepare_setting(&mut conn).await?;
+        let mut msgs = Vec::with_capacity(conns.len());
 
-        let mut pubsub = conn.into_pubsub();
+        for mut conn in conns {
+            prepare_setting(&mut conn).await?;
 
-        pubsub
-            .psubscribe("*")
-            .await
-            .map_err(ExpireError::Subscribe)?;
+            let mut pubsub = conn.into_pubsub();
+
+            pubsub
+                .psubscribe("*")
+                .await
+                .map_err(ExpireError::Subscribe)?;
+
+            msgs.push(pubsub.into_on_message().boxed());
+        }
 
         let conn = DedicatedConnection::get(pool)
             .await
             .map_err(ExpireError::GetConnection)?;
 
-        tokio::spawn(listen_to_expire(pubsub, conn));
+        let pipe = ExpirePipe::new(namespace.clone());
+        tokio::spawn(listen_to_expire(stream::select_all(msgs), conn, pipe));
 
         Ok(())
     }
@@ -100,10 +193,11 @@ async fn prepare_setting(conn: &mut DedicatedConnection) -> CacheResult<()> {
     Ok(())
 }
 
-async fn listen_to_expire(pubsub: PubSub, mut conn: DedicatedConnection) {
-    let mut msgs = pubsub.into_on_message();
-    let mut pipe = Pipeline::new();
-
+async fn listen_to_expire(
+    mut msgs: impl Stream<Item = Msg> + Unpin,
+    mut conn: DedicatedConnection,
+    mut pipe: ExpirePipe,
+) {
     trace!("Listening to expire events...");
 
     while let Some(msg) = msgs.next().await {
@@ -127,9 +221,14 @@ async fn listen_to_expire(pubsub: PubSub, mut conn: DedicatedConnection) {
 
 async fn handle_expire(
     conn: &mut DedicatedConnection,
-    pipe: &mut Pipeline,
+    pipe: &mut ExpirePipe,
     key: &[u8],
 ) -> Result<(), ExpireError> {
+    // Keys of other key prefixes belong to other caches
+    let Some(key) = key.strip_prefix(pipe.namespace().as_bytes()) else {
+        return Ok(());
+    };
+
     let mut split = key.split(|&byte| byte == b':');
 
     let Some(key) = MetaKey::parse(&mut split) else {
@@ -138,9 +237,7 @@ async fn handle_expire(
 
     key.handle_expire(conn, pipe).await?;
 
-    pipe.query_async::<_, ()>(conn)
-        .await
-        .map_err(ExpireError::Pipe)?;
+    pipe.query::<()>(conn).await.map_err(ExpireError::Pipe)?;
 
     Ok(())
 }
@@ -1,25 +1,61 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::atomic::Ordering};
 
+use bytes::Bytes;
 use rkyv::util::AlignedVec;
-use twilight_model::id::{
-    marker::{
-        ChannelMarker, EmojiMarker, GuildMarker, IntegrationMarker, MessageMarker, RoleMarker,
-        StageMarker, StickerMarker, UserMarker,
+use tracing::{instrument, warn, Instrument};
+use twilight_model::{
+    gateway::presence::Status,
+    id::{
+        marker::{
+            AutoModerationRuleMarker, ChannelMarker, EmojiMarker, GuildMarker, IntegrationMarker,
+            MessageMarker, RoleMarker, ScheduledEventMarker, StageMarker, StickerMarker,
+            UserMarker,
+        },
+        Id,
     },
-    Id,
 };
 
-use super::Connection;
+use super::{
+    impls::{member::ordered_user_id, presence::status_from_byte},
+    otel,
+    pipe::previous_key,
+    replica::ReadPreference,
+    Connection,
+};
 use crate::{
-    config::{CacheConfig, Cacheable},
+    config::{CacheConfig, Cacheable, EntityKind},
     error::CacheError,
     key::RedisKey,
-    redis::{Cmd, FromRedisValue},
-    util::BytesWrap,
+    redis::{Cmd, FromRedisValue, Pipeline, ToRedisArgs},
+    util::{convert_ids, convert_ids_vec, BytesWrap},
     CacheResult, CachedArchive, RedisCache,
 };
 
+/// Gets a message and the user of its author.
+///
+/// KEYS: message, message authors
+/// ARGV: message id, user prefix
+const MESSAGE_WITH_AUTHOR_SCRIPT: &str = r"
+local msg = redis.call('GET', KEYS[1])
+local author = redis.call('HGET', KEYS[2], ARGV[1])
+local user = false
+
+if author then
+    user = redis.call('GET', ARGV[2] .. ':' .. author)
+end
+
+return { msg, user }
+";
+
 impl<C: CacheConfig> RedisCache<C> {
+    /// Get an auto moderation rule entry.
+    pub async fn auto_moderation_rule(
+        &self,
+        rule_id: Id<AutoModerationRuleMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::AutoModerationRule<'static>>>> {
+        self.get_single(rule_id).await
+    }
+
     /// Get a channel entry.
     pub async fn channel(
         &self,
@@ -65,6 +101,16 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_single(key).await
     }
 
+    /// Get an invite entry.
+    pub async fn invite(
+        &self,
+        code: &str,
+    ) -> CacheResult<Option<CachedArchive<C::Invite<'static>>>> {
+        let key = RedisKey::Invite { code: code.into() };
+
+        self.get_single(key).await
+    }
+
     /// Get a member entry.
     pub async fn member(
         &self,
@@ -79,12 +125,101 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_single(key).await
     }
 
+    /// Get a member entry alongside the entry of its user.
+    ///
+    /// Both entries are fetched in a single round trip.
+    pub async fn member_with_user(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<(
+        Option<CachedArchive<C::Member<'static>>>,
+        Option<CachedArchive<C::User<'static>>>,
+    )> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+
+        let member_key = RedisKey::Member {
+            guild: guild_id,
+            user: user_id,
+        };
+
+        let user_key = RedisKey::User { id: user_id };
+
+        let (BytesWrap(member), BytesWrap(user)): (BytesWrap<AlignedVec<16>>, _) = Pipeline::new()
+            .get(self.namespace.key(member_key))
+            .get(self.namespace.key(user_key))
+            .query_async(&mut conn)
+            .await?;
+
+        Ok((into_archive(member)?, into_archive(user)?))
+    }
+
     /// Get a message entry.
+    ///
+    /// If the message is not cached in redis, it is loaded from the
+    /// [`ColdStore`] registered through [`RedisCache::with_cold_store`].
+    ///
+    /// [`ColdStore`]: crate::cache::ColdStore
     pub async fn message(
         &self,
         msg_id: Id<MessageMarker>,
     ) -> CacheResult<Option<CachedArchive<C::Message<'static>>>> {
-        self.get_single(msg_id).await
+        match self.get_single(msg_id).await? {
+            Some(msg) => Ok(Some(msg)),
+            None => self.cold_message(msg_id).await,
+        }
+    }
+
+    /// Get the previous versions of a message, most recent first.
+    ///
+    /// Versions are only kept if [`ICachedMessage::EDIT_HISTORY`] is set.
+    ///
+    /// [`ICachedMessage::EDIT_HISTORY`]: crate::config::ICachedMessage::EDIT_HISTORY
+    pub async fn message_history(
+        &self,
+        msg_id: Id<MessageMarker>,
+    ) -> CacheResult<Vec<CachedArchive<C::Message<'static>>>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+        let key = RedisKey::MessageHistory { id: msg_id };
+
+        let versions: Vec<BytesWrap<AlignedVec<16>>> = Cmd::lrange(self.namespace.key(key), 0, -1)
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        versions
+            .into_iter()
+            .filter_map(|BytesWrap(bytes)| into_archive(bytes).transpose())
+            .collect()
+    }
+
+    /// Get a message entry alongside the user entry of its author.
+    ///
+    /// Both entries are fetched in a single round trip. The author is only
+    /// known if users are cached as well.
+    pub async fn message_with_author(
+        &self,
+        msg_id: Id<MessageMarker>,
+    ) -> CacheResult<(
+        Option<CachedArchive<C::Message<'static>>>,
+        Option<CachedArchive<C::User<'static>>>,
+    )> {
+        let mut conn = self.connection().await?;
+
+        let user_prefix = [self.namespace.as_bytes(), RedisKey::USER_PREFIX].concat();
+
+        let (BytesWrap(msg), BytesWrap(author)): (BytesWrap<AlignedVec<16>>, _) = Cmd::new()
+            .arg("EVAL")
+            .arg(MESSAGE_WITH_AUTHOR_SCRIPT)
+            .arg(2)
+            .arg(self.namespace.key(RedisKey::Message { id: msg_id }))
+            .arg(self.namespace.key(RedisKey::MessageAuthors))
+            .arg(msg_id.get())
+            .arg(user_prefix)
+            .query_async(&mut conn)
+            .await?;
+
+        Ok((into_archive(msg)?, into_archive(author)?))
     }
 
     /// Get a presence entry.
@@ -101,6 +236,28 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_single(key).await
     }
 
+    /// Get the status of a user in a guild.
+    ///
+    /// Only available if [`CacheConfig::PRESENCE_STATUS`] is enabled. Users
+    /// that are offline are not tracked so `None` is returned for them as
+    /// well as for unknown users.
+    pub async fn presence_status(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<Status>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+
+        let key = RedisKey::GuildPresenceStatus { id: guild_id };
+
+        let status: Option<Vec<u8>> = Cmd::hget(self.namespace.key(key), user_id.get())
+            .query_async(&mut conn)
+            .instrument(otel::client_span("HGET"))
+            .await?;
+
+        Ok(status.as_deref().and_then(status_from_byte))
+    }
+
     /// Get a role entry.
     pub async fn role(
         &self,
@@ -109,6 +266,14 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_single(role_id).await
     }
 
+    /// Get a scheduled event entry.
+    pub async fn scheduled_event(
+        &self,
+        scheduled_event_id: Id<ScheduledEventMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::ScheduledEvent<'static>>>> {
+        self.get_single(scheduled_event_id).await
+    }
+
     /// Get a stage instance entry.
     pub async fn stage_instance(
         &self,
@@ -125,6 +290,20 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_single(sticker_id).await
     }
 
+    /// Get a thread member entry.
+    pub async fn thread_member(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::ThreadMember<'static>>>> {
+        let key = RedisKey::ThreadMember {
+            channel: channel_id,
+            user: user_id,
+        };
+
+        self.get_single(key).await
+    }
+
     /// Get a user entry.
     pub async fn user(
         &self,
@@ -147,6 +326,87 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_single(key).await
     }
 
+    /// Get the raw bytes stored under a key.
+    ///
+    /// The bytes are neither validated nor wrapped in a [`CachedArchive`]
+    /// which is useful to forward cached payloads, e.g. to another process
+    /// that knows the archived type.
+    ///
+    /// Note that the returned bytes are not guaranteed to be properly aligned
+    /// for accessing the archived type.
+    pub async fn raw_bytes(&self, key: RedisKey) -> CacheResult<Option<Bytes>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+
+        let bytes: Option<Vec<u8>> = Cmd::get(self.namespace.key(key))
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        Ok(bytes.map(Bytes::from))
+    }
+
+    /// Get the previous version of an entity that was overwritten within the
+    /// last [`CacheConfig::PREVIOUS_VERSION_LIFETIME`].
+    ///
+    /// The type `T` must be the configured type of the key's entity, e.g.
+    /// `C::User<'static>` for [`RedisKey::User`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache, RedisKey};
+    /// # use twilight_model::id::Id;
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let key = RedisKey::User { id: Id::new(123) };
+    ///
+    /// if let Some(previous) = cache.previous::<C::User<'static>>(key).await? {
+    ///     // compare with the current entry
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn previous<T: Cacheable>(
+        &self,
+        key: RedisKey,
+    ) -> CacheResult<Option<CachedArchive<T>>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+
+        let key = previous_key(&self.namespace, &key);
+
+        let BytesWrap::<AlignedVec<16>>(bytes) = Cmd::get(key).query_async(&mut conn).await?;
+
+        into_archive(bytes)
+    }
+
+    /// Get all cached auto moderation rule ids.
+    pub async fn auto_moderation_rule_ids(
+        &self,
+    ) -> CacheResult<HashSet<Id<AutoModerationRuleMarker>>> {
+        self.get_ids(RedisKey::AutoModerationRules).await
+    }
+
+    /// Get the id of the guild that a channel belongs to.
+    ///
+    /// Cheaper than fetching the channel itself since only the mapping is
+    /// read. Only available for guild channels that were cached while
+    /// [`CacheConfig::Channel`] was not ignored.
+    pub async fn channel_guild_id(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<Option<Id<GuildMarker>>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+
+        let key = RedisKey::ChannelGuild { id: channel_id };
+
+        let guild_id: Option<u64> = Cmd::get(self.namespace.key(key))
+            .query_async(&mut conn)
+            .instrument(otel::client_span("GET"))
+            .await?;
+
+        Ok(guild_id.and_then(Id::new_checked))
+    }
+
     /// Get all cached channel ids.
     pub async fn channel_ids(&self) -> CacheResult<HashSet<Id<ChannelMarker>>> {
         self.get_ids(RedisKey::Channels).await
@@ -159,28 +419,16 @@ impl<C: CacheConfig> RedisCache<C> {
         &self,
         channel_id: Id<ChannelMarker>,
     ) -> CacheResult<Vec<Id<MessageMarker>>> {
-        fn convert_ids(ids: Vec<u64>) -> Vec<Id<MessageMarker>> {
-            #[cfg(feature = "bytecheck")]
-            if ids.iter().any(|&id| id == 0) {
-                tracing::warn!("IDs must not be zero");
-
-                return ids.into_iter().filter_map(Id::new_checked).collect();
-            }
-
-            // SAFETY: we ensured that all u64s are non-zero
-            unsafe { std::mem::transmute(ids) }
-        }
-
-        let mut conn = self.connection().await?;
+        let mut conn = self.read_connection(self.read_preference).await?;
 
         let key = RedisKey::ChannelMessages {
             channel: channel_id,
         };
 
-        Cmd::zrange(key, 0, -1)
+        Cmd::zrange(self.namespace.key(key), 0, -1)
             .query_async::<_, Vec<u64>>(&mut conn)
             .await
-            .map(convert_ids)
+            .map(convert_ids_vec)
             .map_err(CacheError::Redis)
     }
 
@@ -207,6 +455,11 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_ids(RedisKey::Roles).await
     }
 
+    /// Get all cached scheduled event ids.
+    pub async fn scheduled_event_ids(&self) -> CacheResult<HashSet<Id<ScheduledEventMarker>>> {
+        self.get_ids(RedisKey::ScheduledEvents).await
+    }
+
     /// Get all currently unavailable guild ids.
     pub async fn unavailable_guild_ids(&self) -> CacheResult<HashSet<Id<GuildMarker>>> {
         self.get_ids(RedisKey::UnavailableGuilds).await
@@ -217,6 +470,113 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_ids(RedisKey::Users).await
     }
 
+    /// Get the entity kinds whose set of cached ids contains the given id.
+    ///
+    /// Auto moderation rules, channels, emojis, guilds, messages, roles,
+    /// scheduled events, stage instances, stickers, and users are checked in a
+    /// single round trip.
+    /// Unavailable guilds are reported as [`EntityKind::Guild`].
+    ///
+    /// Mostly useful for debugging when only a raw id is known.
+    pub async fn resolve(&self, id: u64) -> CacheResult<Vec<EntityKind>> {
+        const SETS: [(RedisKey, EntityKind); 11] = [
+            (
+                RedisKey::AutoModerationRules,
+                EntityKind::AutoModerationRule,
+            ),
+            (RedisKey::Channels, EntityKind::Channel),
+            (RedisKey::Emojis, EntityKind::Emoji),
+            (RedisKey::Guilds, EntityKind::Guild),
+            (RedisKey::UnavailableGuilds, EntityKind::Guild),
+            (RedisKey::Messages, EntityKind::Message),
+            (RedisKey::Roles, EntityKind::Role),
+            (RedisKey::ScheduledEvents, EntityKind::ScheduledEvent),
+            (RedisKey::StageInstances, EntityKind::StageInstance),
+            (RedisKey::Stickers, EntityKind::Sticker),
+            (RedisKey::Users, EntityKind::User),
+        ];
+
+        #[cfg(feature = "opentelemetry")]
+        otel::attach_context();
+
+        let mut conn = self.read_connection(self.read_preference).await?;
+        let mut pipe = Pipeline::new();
+
+        for (key, _) in SETS {
+            pipe.sismember(self.namespace.key(key), id);
+        }
+
+        let contained: Vec<bool> = pipe
+            .query_async(&mut conn)
+            .instrument(otel::client_span("SISMEMBER"))
+            .await?;
+
+        let mut kinds: Vec<_> = SETS
+            .into_iter()
+            .zip(contained)
+            .filter_map(|((_, kind), contained)| contained.then_some(kind))
+            .collect();
+
+        kinds.dedup();
+
+        Ok(kinds)
+    }
+
+    /// Get all cached invite codes for a channel.
+    pub async fn channel_invite_codes(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<HashSet<String>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+        let key = RedisKey::ChannelInvites { id: channel_id };
+
+        Self::get_ids_static(self.namespace.key(key), &mut conn).await
+    }
+
+    /// Get all cached user ids of members of a thread.
+    pub async fn channel_thread_member_ids(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<HashSet<Id<UserMarker>>> {
+        self.get_ids(RedisKey::ChannelThreadMembers {
+            channel: channel_id,
+        })
+        .await
+    }
+
+    /// Get all cached auto moderation rule ids for a guild.
+    pub async fn guild_auto_moderation_rule_ids(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<HashSet<Id<AutoModerationRuleMarker>>> {
+        self.get_ids(RedisKey::GuildAutoModerationRules { id: guild_id })
+            .await
+    }
+
+    /// Get all user ids that are banned in a guild.
+    ///
+    /// Requires [`ICachedGuild::CACHE_BANS`] to be enabled.
+    ///
+    /// [`ICachedGuild::CACHE_BANS`]: crate::config::ICachedGuild::CACHE_BANS
+    pub async fn guild_ban_ids(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<HashSet<Id<UserMarker>>> {
+        self.get_ids(RedisKey::GuildBans { id: guild_id }).await
+    }
+
+    /// Get all user ids of members that boost a guild.
+    ///
+    /// Boosters are only tracked if [`ICachedMember::premium_since`] is set.
+    ///
+    /// [`ICachedMember::premium_since`]: crate::config::ICachedMember::premium_since
+    pub async fn guild_boosters(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<HashSet<Id<UserMarker>>> {
+        self.get_ids(RedisKey::GuildBoosters { id: guild_id }).await
+    }
+
     /// Get all cached channel ids for a guild.
     pub async fn guild_channel_ids(
         &self,
@@ -242,6 +602,17 @@ impl<C: CacheConfig> RedisCache<C> {
             .await
     }
 
+    /// Get all cached invite codes for a guild.
+    pub async fn guild_invite_codes(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<HashSet<String>> {
+        let mut conn = self.read_connection(self.read_preference).await?;
+        let key = RedisKey::GuildInvites { id: guild_id };
+
+        Self::get_ids_static(self.namespace.key(key), &mut conn).await
+    }
+
     /// Get all cached member ids for a guild.
     pub async fn guild_member_ids(
         &self,
@@ -250,6 +621,49 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_ids(RedisKey::GuildMembers { id: guild_id }).await
     }
 
+    /// Get up to `limit` cached member ids of a guild ordered by user id,
+    /// starting after `after_user_id`.
+    ///
+    /// Pass the last id of a page as `after_user_id` to get the next page,
+    /// or `None` to get the first page. Unlike [`guild_member_ids`], this does
+    /// not load all member ids of the guild at once.
+    ///
+    /// [`guild_member_ids`]: RedisCache::guild_member_ids
+    pub async fn guild_member_ids_page(
+        &self,
+        guild_id: Id<GuildMarker>,
+        after_user_id: Option<Id<UserMarker>>,
+        limit: usize,
+    ) -> CacheResult<Vec<Id<UserMarker>>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let min = match after_user_id {
+            Some(user_id) => {
+                let padded = ordered_user_id(user_id);
+                let mut min = Vec::with_capacity(1 + padded.0.len());
+                min.push(b'(');
+                min.extend_from_slice(&padded.0);
+
+                min
+            }
+            None => b"-".to_vec(),
+        };
+
+        let mut conn = self.read_connection(self.read_preference).await?;
+        let key = RedisKey::GuildMembersOrdered { id: guild_id };
+
+        let count = isize::try_from(limit).unwrap_or(isize::MAX);
+
+        let ids: Vec<u64> = Cmd::zrangebylex_limit(self.namespace.key(key), min, "+", 0, count)
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        Ok(convert_ids_vec(ids))
+    }
+
     /// Get all cached user ids of presences for a guild.
     pub async fn guild_presence_ids(
         &self,
@@ -267,6 +681,15 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_ids(RedisKey::GuildRoles { id: guild_id }).await
     }
 
+    /// Get all cached scheduled event ids for a guild.
+    pub async fn guild_scheduled_event_ids(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<HashSet<Id<ScheduledEventMarker>>> {
+        self.get_ids(RedisKey::GuildScheduledEvents { id: guild_id })
+            .await
+    }
+
     /// Get all cached stage instance ids for a guild.
     pub async fn guild_stage_instance_ids(
         &self,
@@ -292,42 +715,142 @@ impl<C: CacheConfig> RedisCache<C> {
         self.get_ids(RedisKey::GuildVoiceStates { id: guild_id })
             .await
     }
-}
 
-impl<C> RedisCache<C> {
     async fn get_single<K, V>(&self, key: K) -> CacheResult<Option<CachedArchive<V>>>
     where
         RedisKey: From<K>,
         V: Cacheable,
     {
-        let mut conn = self.connection().await?;
+        self.get_single_from(self.read_preference, key).await
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    pub(crate) async fn get_single_from<K, V>(
+        &self,
+        preference: ReadPreference,
+        key: K,
+    ) -> CacheResult<Option<CachedArchive<V>>>
+    where
+        RedisKey: From<K>,
+        V: Cacheable,
+    {
+        #[cfg(feature = "opentelemetry")]
+        otel::attach_context();
+
+        let key = RedisKey::from(key);
+
+        #[cfg(feature = "local_cache")]
+        let local = match self.local {
+            Some(ref local) if local.is_wanted(&key) => {
+                let rendered = self.namespace.render(&key);
+
+                if let Some(bytes) = local.get(&rendered, self.clock.now()) {
+                    // The bytes were validated before they were stored
+                    return Ok(Some(CachedArchive::new_unchecked(bytes)));
+                }
+
+                // Must be known before reading so that concurrent writes are
+                // noticed
+                let generation = local.generation();
+
+                Some((local, rendered, generation))
+            }
+            _ => None,
+        };
+
+        let mut conn = self.read_connection(preference).await?;
+
+        let bytes = if let Some(ref refresh) = self.refresh {
+            let (BytesWrap::<AlignedVec<16>>(bytes), pttl): (_, i64) = Pipeline::new()
+                .get(self.namespace.key(&key))
+                .pttl(self.namespace.key(&key))
+                .query_async(&mut conn)
+                .instrument(otel::client_span("GET PTTL"))
+                .await?;
+
+            if refresh.is_due(pttl) {
+                refresh.enqueue(key.clone());
+            }
+
+            bytes
+        } else {
+            let BytesWrap::<AlignedVec<16>>(bytes) = Cmd::get(self.namespace.key(&key))
+                .query_async(&mut conn)
+                .instrument(otel::client_span("GET"))
+                .await?;
+
+            bytes
+        };
+
+        // Counting may need a connection to the primary so the read
+        // connection must not be held onto, e.g. for pools of size 1
+        drop(conn);
+        self.count_read(&key, !bytes.is_empty()).await;
+
+        let archive = into_archive::<V>(bytes)?;
 
-        let BytesWrap::<AlignedVec<16>>(bytes) =
-            Cmd::get(RedisKey::from(key)).query_async(&mut conn).await?;
+        #[cfg(feature = "local_cache")]
+        if let (Some((local, rendered, generation)), Some(archive)) = (local, &archive) {
+            local.insert(rendered, archive.bytes(), generation, self.clock.now());
+        }
+
+        Ok(archive)
+    }
 
-        if bytes.is_empty() {
-            return Ok(None);
+    /// Increment the hit or miss counter of the key's entity kind if the read
+    /// is sampled.
+    ///
+    /// Counters are always written to the primary since the read itself may
+    /// have been served by a replica. Failing to count is only logged so
+    /// that the read itself still succeeds.
+    async fn count_read(&self, key: &RedisKey, hit: bool) {
+        let rate = C::READ_COUNTER_SAMPLING;
+
+        if rate == 0 {
+            return;
         }
 
-        #[cfg(feature = "bytecheck")]
-        {
-            CachedArchive::new(bytes).map(Some)
+        let sample = self.read_samples.fetch_add(1, Ordering::Relaxed);
+
+        if !sample.is_multiple_of(u64::from(rate)) {
+            return;
         }
 
-        #[cfg(not(feature = "bytecheck"))]
-        {
-            Ok(Some(CachedArchive::new_unchecked(bytes)))
+        let Some(kind) = key.entity_kind() else {
+            return;
+        };
+
+        let field = format!("{}:{}", kind.name(), if hit { "hits" } else { "misses" });
+
+        let res = match self.connection().await {
+            Ok(mut conn) => Cmd::hincr(self.namespace.key(RedisKey::ReadCounters), field, rate)
+                .query_async::<_, ()>(&mut conn)
+                .await
+                .map_err(CacheError::Redis),
+            Err(err) => Err(err),
+        };
+
+        if let Err(err) = res {
+            warn!(?err, "Failed to count read");
         }
     }
+}
 
+impl<C> RedisCache<C> {
+    #[instrument(level = "debug", skip_all)]
     async fn get_ids<T>(&self, key: RedisKey) -> CacheResult<HashSet<Id<T>>> {
-        let mut conn = self.connection().await?;
+        #[cfg(feature = "opentelemetry")]
+        otel::attach_context();
+
+        let mut conn = self.read_connection(self.read_preference).await?;
 
-        Self::get_ids_static(key, &mut conn).await.map(convert_ids)
+        Self::get_ids_static(self.namespace.key(key), &mut conn)
+            .await
+            .map(convert_ids)
     }
 
     pub(crate) async fn get_ids_static<T>(
-        key: RedisKey,
+        key: impl ToRedisArgs + Send + Sync,
         conn: &mut Connection<'_>,
     ) -> CacheResult<T>
     where
@@ -335,40 +858,28 @@ impl<C> RedisCache<C> {
     {
         Cmd::smembers(key)
             .query_async(conn)
+            .instrument(otel::client_span("SMEMBERS"))
             .await
             .map_err(CacheError::Redis)
     }
 }
 
-fn convert_ids<T>(ids: HashSet<u64>) -> HashSet<Id<T>> {
-    #[cfg(feature = "bytecheck")]
-    if ids.iter().any(|&id| id == 0) {
-        tracing::warn!("IDs must not be zero");
-
-        return ids.into_iter().filter_map(Id::new_checked).collect();
+// Only fallible when validating
+#[cfg_attr(not(feature = "bytecheck"), allow(clippy::unnecessary_wraps))]
+pub(crate) fn into_archive<V: Cacheable>(
+    bytes: AlignedVec<16>,
+) -> CacheResult<Option<CachedArchive<V>>> {
+    if bytes.is_empty() {
+        return Ok(None);
     }
 
-    // SAFETY: we ensured that all u64s are non-zero
-    unsafe { std::mem::transmute(ids) }
-}
-
-#[cfg(test)]
-#[cfg(feature = "bytecheck")]
-mod tests {
-    use std::collections::HashSet;
-
-    use twilight_model::id::{marker::GenericMarker, Id};
-
-    use super::convert_ids;
-
-    #[test]
-    fn test_convert_ids_zero() {
-        let mut ids = HashSet::new();
-        ids.insert(3);
-        ids.insert(0);
-        ids.insert(5);
-        let converted: HashSet<Id<GenericMarker>> = convert_ids(ids);
+    #[cfg(feature = "bytecheck")]
+    {
+        CachedArchive::new(bytes).map(Some)
+    }
 
-        assert_eq!(converted.len(), 2);
+    #[cfg(not(feature = "bytecheck"))]
+    {
+        Ok(Some(CachedArchive::new_unchecked(bytes)))
     }
 }
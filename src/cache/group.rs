@@ -0,0 +1,96 @@
+use std::future::Future;
+
+use futures_util::future;
+use twilight_model::gateway::event::Event;
+
+use crate::{config::CacheConfig, CacheResult, RedisCache};
+
+/// A cache that can be updated with gateway [`Event`]s.
+///
+/// Implemented by [`RedisCache`] and [`CacheConfigGroup`] so that groups can be
+/// nested.
+pub trait UpdateCache {
+    /// Update the cache with an [`Event`] from the gateway.
+    fn update(&self, event: &Event) -> impl Future<Output = CacheResult<()>>;
+}
+
+impl<C: CacheConfig> UpdateCache for RedisCache<C> {
+    fn update(&self, event: &Event) -> impl Future<Output = CacheResult<()>> {
+        RedisCache::update(self, event)
+    }
+}
+
+/// Feeds every [`Event`] to two caches with independent [`CacheConfig`]s.
+///
+/// Useful to e.g. run a lean config for the hot path alongside a verbose
+/// config with TTLs for auditing. Groups can be nested to combine more than
+/// two caches.
+///
+/// Both caches are updated concurrently from the same borrowed event so the
+/// event is neither cloned nor processed twice upfront. Since keys are shared,
+/// the caches should either use separate redis databases or cache disjoint
+/// sets of entities.
+///
+/// # Example
+///
+/// ```no_run
+/// # use redlight::{config::CacheConfig, RedisCache};
+/// # use twilight_model::gateway::event::Event;
+/// use redlight::CacheConfigGroup;
+///
+/// # async fn example<Lean: CacheConfig, Audit: CacheConfig>(
+/// #     event: Event,
+/// # ) -> Result<(), redlight::error::CacheError> {
+/// let lean = RedisCache::<Lean>::new("redis://127.0.0.1:6379/0").await?;
+/// let audit = RedisCache::<Audit>::new("redis://127.0.0.1:6379/1").await?;
+///
+/// let group = CacheConfigGroup::new(lean, audit);
+/// group.update(&event).await?;
+///
+/// // The caches remain accessible for reads
+/// let _ = group.first().stats();
+/// # Ok(()) }
+/// ```
+pub struct CacheConfigGroup<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: UpdateCache, B: UpdateCache> CacheConfigGroup<A, B> {
+    /// Create a new [`CacheConfigGroup`].
+    pub const fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Get a reference to the first cache.
+    pub const fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Get a reference to the second cache.
+    pub const fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Return the inner caches.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+
+    /// Update both caches with an [`Event`] from the gateway.
+    ///
+    /// Both updates run to completion even if one of them fails, in which
+    /// case the first error is returned.
+    pub async fn update(&self, event: &Event) -> CacheResult<()> {
+        let (first, second) =
+            future::join(self.first.update(event), self.second.update(event)).await;
+
+        first.and(second)
+    }
+}
+
+impl<A: UpdateCache, B: UpdateCache> UpdateCache for CacheConfigGroup<A, B> {
+    fn update(&self, event: &Event) -> impl Future<Output = CacheResult<()>> {
+        CacheConfigGroup::update(self, event)
+    }
+}
@@ -0,0 +1,164 @@
+use std::time::{Duration, UNIX_EPOCH};
+
+use tracing::{instrument, trace};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    cache::pipe::Pipe,
+    config::{CacheConfig, ICachedGuild, ICachedPresence, ICachedVoiceState},
+    error::CacheError,
+    key::RedisKey,
+    redis::Cmd,
+    util::convert_ids_vec,
+    CacheResult, RedisCache,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Get the ids of all guilds that did not receive an event within the
+    /// given duration, least recently active first.
+    ///
+    /// Guild activity is only tracked if [`ICachedGuild::STALENESS_THRESHOLD`]
+    /// is set.
+    pub async fn stale_guild_ids(&self, threshold: Duration) -> CacheResult<Vec<Id<GuildMarker>>> {
+        let cutoff = self.unix_secs().saturating_sub(threshold.as_secs());
+
+        let mut conn = self.connection().await?;
+
+        let ids: Vec<u64> =
+            Cmd::zrangebyscore(self.namespace.key(RedisKey::GuildActivity), "-inf", cutoff)
+                .query_async(&mut conn)
+                .await
+                .map_err(CacheError::Redis)?;
+
+        Ok(convert_ids_vec(ids))
+    }
+
+    /// Get the time since the latest presence of a member was cached.
+    ///
+    /// Returns `None` if the presence is not cached or if
+    /// [`ICachedPresence::TRACK_FRESHNESS`] is not enabled.
+    pub async fn presence_age(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<Duration>> {
+        if !C::Presence::TRACK_FRESHNESS {
+            return Ok(None);
+        }
+
+        self.stamp_age(RedisKey::GuildPresenceStamps { id: guild_id }, user_id)
+            .await
+    }
+
+    /// Get the time since the latest voice state of a member was cached.
+    ///
+    /// Returns `None` if the voice state is not cached or if
+    /// [`ICachedVoiceState::TRACK_FRESHNESS`] is not enabled.
+    pub async fn voice_state_age(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<Duration>> {
+        if !C::VoiceState::TRACK_FRESHNESS {
+            return Ok(None);
+        }
+
+        self.stamp_age(RedisKey::GuildVoiceStateStamps { id: guild_id }, user_id)
+            .await
+    }
+
+    async fn stamp_age(
+        &self,
+        key: RedisKey,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<Duration>> {
+        let mut conn = self.connection().await?;
+
+        let stamp: Option<u64> = Cmd::zscore(self.namespace.key(key), user_id.get())
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        let age = stamp.map(|stamp| Duration::from_secs(self.unix_secs().saturating_sub(stamp)));
+
+        Ok(age)
+    }
+
+    pub(crate) fn store_presence_stamps(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[u64],
+    ) {
+        if !C::Presence::TRACK_FRESHNESS || user_ids.is_empty() {
+            return;
+        }
+
+        let now = self.unix_secs();
+        let items: Vec<_> = user_ids.iter().map(|&user_id| (now, user_id)).collect();
+
+        let key = RedisKey::GuildPresenceStamps { id: guild_id };
+        pipe.zadd_multiple(key, &items);
+    }
+
+    pub(crate) fn store_voice_state_stamps(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        user_ids: &[u64],
+    ) {
+        if !C::VoiceState::TRACK_FRESHNESS || user_ids.is_empty() {
+            return;
+        }
+
+        let now = self.unix_secs();
+        let items: Vec<_> = user_ids.iter().map(|&user_id| (now, user_id)).collect();
+
+        let key = RedisKey::GuildVoiceStateStamps { id: guild_id };
+        pipe.zadd_multiple(key, &items);
+    }
+
+    pub(crate) fn store_guild_activity(&self, pipe: &mut Pipe<'_, C>, guild_id: Id<GuildMarker>) {
+        if C::Guild::STALENESS_THRESHOLD.is_none() {
+            return;
+        }
+
+        let key = RedisKey::GuildActivity;
+        pipe.zadd(key, guild_id.get(), self.unix_secs());
+    }
+
+    pub(crate) fn delete_guild_activity(pipe: &mut Pipe<'_, C>, guild_id: Id<GuildMarker>) {
+        if C::Guild::STALENESS_THRESHOLD.is_some() {
+            pipe.zrem(RedisKey::GuildActivity, guild_id.get());
+        }
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) async fn handle_stale_guilds(&self) -> CacheResult<()> {
+        let (Some(threshold), Some(hook)) =
+            (C::Guild::STALENESS_THRESHOLD, C::Guild::on_stale_guilds())
+        else {
+            return Ok(());
+        };
+
+        let guild_ids = self.stale_guild_ids(threshold).await?;
+        trace!(stale = guild_ids.len());
+
+        if !guild_ids.is_empty() {
+            hook(&guild_ids);
+        }
+
+        Ok(())
+    }
+
+    fn unix_secs(&self) -> u64 {
+        self.clock()
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
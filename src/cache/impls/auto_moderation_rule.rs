@@ -0,0 +1,143 @@
+use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer, Archived};
+use tracing::{instrument, trace};
+use twilight_model::{
+    guild::auto_moderation::AutoModerationRule,
+    id::{
+        marker::{AutoModerationRuleMarker, GuildMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cache::{
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
+        pipe::Pipe,
+        IO_TARGET,
+    },
+    config::{CacheConfig, Cacheable, ICachedAutoModerationRule},
+    error::{MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
+    key::RedisKey,
+    rkyv_util::id::IdRkyv,
+    CacheResult, RedisCache,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    #[instrument(level = "trace", skip_all, fields(guild_id = rule.guild_id.get()))]
+    pub(crate) fn store_auto_moderation_rule(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        rule: &AutoModerationRule,
+    ) -> CacheResult<()> {
+        if !C::AutoModerationRule::WANTED {
+            return Ok(());
+        }
+
+        let rule_id = rule.id;
+        let guild_id = rule.guild_id;
+        let key = RedisKey::AutoModerationRule { id: rule_id };
+        let rule = C::AutoModerationRule::from_auto_moderation_rule(rule);
+
+        let bytes = rule
+            .serialize_one()
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::AutoModerationRule))?;
+
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &rule)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::AutoModerationRule))?;
+
+        pipe.version(&key, &rule);
+
+        pipe.set(key, bytes.as_ref(), C::AutoModerationRule::expire());
+
+        let key = RedisKey::GuildAutoModerationRules { id: guild_id };
+        pipe.sadd(key, rule_id.get());
+
+        let key = RedisKey::AutoModerationRules;
+        pipe.sadd(key, rule_id.get());
+
+        if C::AutoModerationRule::expire().is_some() {
+            let key = AutoModerationRuleMetaKey { rule: rule_id };
+
+            AutoModerationRuleMeta { guild: guild_id }
+                .store(pipe, key)
+                .map_err(|e| MetaError::new(e, MetaErrorKind::AutoModerationRule))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn delete_auto_moderation_rule(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        rule_id: Id<AutoModerationRuleMarker>,
+    ) {
+        if !C::AutoModerationRule::WANTED {
+            return;
+        }
+
+        let key = RedisKey::AutoModerationRule { id: rule_id };
+        pipe.del(key);
+
+        let key = RedisKey::GuildAutoModerationRules { id: guild_id };
+        pipe.srem(key, rule_id.get());
+
+        let key = RedisKey::AutoModerationRules;
+        pipe.srem(key, rule_id.get());
+
+        if C::AutoModerationRule::expire().is_some() {
+            let key = RedisKey::AutoModerationRuleMeta { id: rule_id };
+            pipe.del(key);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct AutoModerationRuleMetaKey {
+    rule: Id<AutoModerationRuleMarker>,
+}
+
+impl IMetaKey for AutoModerationRuleMetaKey {
+    fn parse<'a>(split: &mut impl Iterator<Item = &'a [u8]>) -> Option<Self> {
+        split.next().and_then(atoi).map(|rule| Self { rule })
+    }
+
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
+        let key = RedisKey::AutoModerationRules;
+        pipe.srem(key, self.rule.get()).ignore();
+    }
+}
+
+impl HasArchived for AutoModerationRuleMetaKey {
+    type Meta = AutoModerationRuleMeta;
+
+    fn redis_key(&self) -> RedisKey {
+        RedisKey::AutoModerationRuleMeta { id: self.rule }
+    }
+
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
+        let key = RedisKey::GuildAutoModerationRules {
+            id: archived.guild.into(),
+        };
+        pipe.srem(key, self.rule.get());
+    }
+}
+
+#[derive(rkyv::Archive, rkyv::Serialize)]
+pub(crate) struct AutoModerationRuleMeta {
+    #[rkyv(with = IdRkyv)]
+    guild: Id<GuildMarker>,
+}
+
+impl IMeta<AutoModerationRuleMetaKey> for AutoModerationRuleMeta {
+    type Bytes = [u8; 8];
+
+    fn to_bytes(&self) -> Result<Self::Bytes, BoxedError> {
+        let mut bytes = [0; 8];
+        to_bytes_in(self, Buffer::from(&mut bytes))?;
+
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,116 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::instrument;
+use twilight_model::{
+    guild::Ban,
+    id::{marker::GuildMarker, Id},
+    user::User,
+};
+
+use crate::{
+    cache::pipe::Pipe,
+    config::{CacheConfig, ICachedGuild},
+    error::CacheError,
+    key::RedisKey,
+    redis::Cmd,
+    CacheResult, RedisCache,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Store a guild's full list of bans, e.g. fetched through the REST API.
+    ///
+    /// The previously cached ban list of the guild is replaced and the time of
+    /// this snapshot is stored so it can be retrieved through
+    /// [`RedisCache::guild_bans_snapshot`].
+    ///
+    /// Bans are only cached if [`ICachedGuild::CACHE_BANS`] is enabled but
+    /// the banned users will be stored either way. Cached bans are removed
+    /// alongside their guild.
+    #[instrument(level = "trace", skip_all, fields(guild = guild_id.get()))]
+    pub async fn store_guild_bans(
+        &self,
+        guild_id: Id<GuildMarker>,
+        bans: &[Ban],
+    ) -> CacheResult<()> {
+        let mut pipe = Pipe::new(self);
+
+        if C::Guild::CACHE_BANS {
+            let key = RedisKey::GuildBans { id: guild_id };
+            pipe.del(key);
+
+            let user_ids: Vec<_> = bans.iter().map(|ban| ban.user.id.get()).collect();
+
+            if !user_ids.is_empty() {
+                let key = RedisKey::GuildBans { id: guild_id };
+                pipe.sadd(key, user_ids);
+            }
+
+            let timestamp = self
+                .clock()
+                .now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+
+            let key = RedisKey::GuildBansSnapshot { id: guild_id };
+            pipe.set(key, itoa::Buffer::new().format(timestamp).as_bytes(), None);
+        }
+
+        self.store_users(&mut pipe, bans.iter().map(|ban| &ban.user))?;
+
+        if !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get the time at which the ban list of a guild was last stored through
+    /// [`RedisCache::store_guild_bans`].
+    pub async fn guild_bans_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<Option<SystemTime>> {
+        let mut conn = self.connection().await?;
+
+        let timestamp: Option<u64> = Cmd::get(
+            self.namespace
+                .key(RedisKey::GuildBansSnapshot { id: guild_id }),
+        )
+        .query_async(&mut conn)
+        .await
+        .map_err(CacheError::Redis)?;
+
+        Ok(timestamp.map(|secs| UNIX_EPOCH + Duration::from_secs(secs)))
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
+    pub(crate) fn store_ban(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        user: &User,
+    ) -> CacheResult<()> {
+        if C::Guild::CACHE_BANS {
+            let key = RedisKey::GuildBans { id: guild_id };
+            pipe.sadd(key, user.id.get());
+        }
+
+        self.store_user(pipe, user)
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
+    pub(crate) fn delete_ban(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        user: &User,
+    ) -> CacheResult<()> {
+        if C::Guild::CACHE_BANS {
+            let key = RedisKey::GuildBans { id: guild_id };
+            pipe.srem(key, user.id.get());
+        }
+
+        self.store_user(pipe, user)
+    }
+}
@@ -2,7 +2,7 @@ use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer, Arch
 use tracing::{instrument, trace};
 use twilight_model::{
     channel::Channel,
-    gateway::payload::incoming::ChannelPinsUpdate,
+    gateway::payload::incoming::{ChannelPinsUpdate, WebhooksUpdate},
     id::{
         marker::{ChannelMarker, GuildMarker},
         Id,
@@ -11,22 +11,23 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, HasArchived, IMeta, IMetaKey},
+        impls::thread_member::delete_thread_members,
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedChannel, SerializeMany},
     error::{
         MetaError, MetaErrorKind, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind,
     },
     key::RedisKey,
-    redis::Pipeline,
     rkyv_util::id::IdRkyvMap,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = channel.guild_id.map(Id::get)))]
     pub(crate) fn store_channel(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -42,7 +43,13 @@ impl<C: CacheConfig> RedisCache<C> {
                 .serialize_one()
                 .map_err(|e| SerializeError::new(e, SerializeErrorKind::Channel))?;
 
-            trace!(bytes = bytes.as_ref().len());
+            trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+            #[cfg(feature = "serde-mirror")]
+            pipe.mirror(&key, &channel)
+                .map_err(|e| SerializeError::new(e, SerializeErrorKind::Channel))?;
+
+            pipe.version(&key, &channel);
 
             pipe.set(key, bytes.as_ref(), C::Channel::expire());
 
@@ -59,6 +66,14 @@ impl<C: CacheConfig> RedisCache<C> {
             if let Some(guild_id) = guild_id {
                 let key = RedisKey::GuildChannels { id: guild_id };
                 pipe.sadd(key, channel_id.get());
+
+                let key = RedisKey::ChannelGuild { id: channel_id };
+                let mut buf = itoa::Buffer::new();
+                pipe.set(
+                    key,
+                    buf.format(guild_id.get()).as_bytes(),
+                    C::Channel::expire(),
+                );
             }
 
             let key = RedisKey::Channels;
@@ -82,7 +97,39 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = channel.guild_id.map(Id::get)))]
+    pub(crate) async fn store_channel_update(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        channel: &Channel,
+    ) -> CacheResult<()> {
+        if C::Channel::WANTED {
+            if let Some(hook) = C::Channel::on_permission_update() {
+                let key = RedisKey::Channel { id: channel.id };
+
+                if let Some(cached) = pipe.get::<C::Channel<'static>>(key).await? {
+                    hook(&cached, channel);
+                }
+            }
+        }
+
+        self.store_channel(pipe, channel)
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.get()))]
+    pub(crate) fn store_webhooks_update(&self, pipe: &mut Pipe<'_, C>, update: &WebhooksUpdate) {
+        if let Some(hook) = C::Channel::on_webhooks_update() {
+            hook(update);
+        }
+
+        let key = RedisKey::ChannelWebhooks {
+            channel: update.channel_id,
+        };
+
+        pipe.del(key);
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.map(Id::get)))]
     pub(crate) async fn store_channel_pins_update(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -111,8 +158,13 @@ impl<C: CacheConfig> RedisCache<C> {
             id: update.channel_id,
         };
 
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &channel)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::ChannelPins))?;
+
         let bytes = channel.into_bytes();
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
         pipe.set(key, &bytes, C::Channel::expire());
 
         if C::Channel::expire().is_some() {
@@ -131,7 +183,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_channels(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -152,7 +204,13 @@ impl<C: CacheConfig> RedisCache<C> {
                         .serialize_next(&channel)
                         .map_err(|e| SerializeError::new(e, SerializeErrorKind::Channel))?;
 
-                    trace!(bytes = bytes.as_ref().len());
+                    #[cfg(feature = "serde-mirror")]
+                    pipe.mirror(&key, &channel)
+                        .map_err(|e| SerializeError::new(e, SerializeErrorKind::Channel))?;
+
+                    pipe.version(&key, &channel);
+
+                    trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                     Ok(((key, BytesWrap(bytes)), id.get()))
                 })
@@ -166,7 +224,22 @@ impl<C: CacheConfig> RedisCache<C> {
                 pipe.sadd(key, channel_ids.as_slice());
 
                 let key = RedisKey::Channels;
-                pipe.sadd(key, channel_ids);
+                pipe.sadd(key, channel_ids.as_slice());
+
+                let mut buf = itoa::Buffer::new();
+                let guild_id = buf.format(guild_id.get()).as_bytes();
+
+                let guild_entries: Vec<_> = channel_ids
+                    .iter()
+                    .map(|&id| {
+                        (
+                            RedisKey::ChannelGuild { id: Id::new(id) },
+                            BytesWrap(guild_id),
+                        )
+                    })
+                    .collect();
+
+                pipe.mset(&guild_entries, C::Channel::expire());
 
                 if C::Channel::expire().is_some() {
                     channels
@@ -197,19 +270,27 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    pub(crate) fn delete_channel(
+    pub(crate) async fn delete_channel(
         &self,
         pipe: &mut Pipe<'_, C>,
         guild_id: Option<Id<GuildMarker>>,
         channel_id: Id<ChannelMarker>,
-    ) {
+    ) -> CacheResult<()> {
+        self.delete_channel_invites(pipe, guild_id, channel_id)
+            .await?;
+
+        delete_thread_members(pipe, channel_id);
+
         if !C::Channel::WANTED {
-            return;
+            return Ok(());
         }
 
         let key = RedisKey::Channel { id: channel_id };
         pipe.del(key);
 
+        let key = RedisKey::ChannelGuild { id: channel_id };
+        pipe.del(key);
+
         if let Some(guild_id) = guild_id {
             let key = RedisKey::GuildChannels { id: guild_id };
             pipe.srem(key, channel_id.get());
@@ -221,6 +302,8 @@ impl<C: CacheConfig> RedisCache<C> {
         if C::Channel::expire().is_some() {
             pipe.del(RedisKey::ChannelMeta { id: channel_id });
         }
+
+        Ok(())
     }
 }
 
@@ -236,9 +319,12 @@ impl IMetaKey for ChannelMetaKey {
         })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Channels;
         pipe.srem(key, self.channel.get()).ignore();
+
+        let key = RedisKey::ChannelGuild { id: self.channel };
+        pipe.del(key).ignore();
     }
 }
 
@@ -249,7 +335,7 @@ impl HasArchived for ChannelMetaKey {
         RedisKey::ChannelMeta { id: self.channel }
     }
 
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &Archived<Self::Meta>) {
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
         if let Some(guild) = archived.guild.to_id_option() {
             let key = RedisKey::GuildChannels { id: guild };
             pipe.srem(key, self.channel.get());
@@ -2,7 +2,7 @@ use tracing::{instrument, trace};
 use twilight_model::user::CurrentUser;
 
 use crate::{
-    cache::pipe::Pipe,
+    cache::{pipe::Pipe, IO_TARGET},
     config::{CacheConfig, Cacheable, ICachedCurrentUser},
     error::{SerializeError, SerializeErrorKind},
     key::RedisKey,
@@ -27,7 +27,13 @@ impl<C: CacheConfig> RedisCache<C> {
             .serialize_one()
             .map_err(|e| SerializeError::new(e, SerializeErrorKind::CurrentUser))?;
 
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &current_user)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::CurrentUser))?;
+
+        pipe.version(&key, &current_user);
 
         pipe.set(key, bytes.as_ref(), C::CurrentUser::expire());
 
@@ -10,20 +10,20 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, HasArchived, IMeta, IMetaKey},
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedEmoji, SerializeMany},
     error::{MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
     key::RedisKey,
-    redis::Pipeline,
     rkyv_util::id::IdRkyv,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_emojis(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -47,7 +47,13 @@ impl<C: CacheConfig> RedisCache<C> {
                     .serialize_next(&emoji)
                     .map_err(|e| SerializeError::new(e, SerializeErrorKind::Emoji))?;
 
-                trace!(bytes = bytes.as_ref().len());
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &emoji)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::Emoji))?;
+
+                pipe.version(&key, &emoji);
+
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                 Ok(((key, BytesWrap(bytes)), id.get()))
             })
@@ -91,7 +97,7 @@ impl IMetaKey for EmojiMetaKey {
         split.next().and_then(atoi).map(|emoji| Self { emoji })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Emojis;
         pipe.srem(key, self.emoji.get()).ignore();
     }
@@ -104,7 +110,7 @@ impl HasArchived for EmojiMetaKey {
         RedisKey::EmojiMeta { id: self.emoji }
     }
 
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &Archived<Self::Meta>) {
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
         let key = RedisKey::GuildEmojis {
             id: archived.guild.into(),
         };
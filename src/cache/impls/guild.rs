@@ -1,28 +1,35 @@
 use std::vec::IntoIter;
 
+use rkyv::rancor::Fallible;
 use tracing::{instrument, trace};
 use twilight_model::{
-    gateway::payload::incoming::GuildUpdate,
+    gateway::payload::incoming::{GuildEmojisUpdate, GuildStickersUpdate, GuildUpdate},
     guild::Guild,
     id::{marker::GuildMarker, Id},
 };
 
 use crate::{
     cache::{
-        meta::{atoi, IMetaKey},
+        impls::{role::stores_role_meta, thread_member::delete_thread_members},
+        meta::{atoi, ExpirePipe, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
+    },
+    config::{
+        CacheConfig, Cacheable, ICachedGuild, ICachedMember, ICachedMessage, ICachedPresence,
+        ICachedVoiceState,
     },
-    config::{CacheConfig, Cacheable, ICachedGuild},
     error::{
         CacheError, ExpireError, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind,
     },
     key::RedisKey,
-    redis::{DedicatedConnection, Pipeline},
-    CacheResult, RedisCache,
+    redis::DedicatedConnection,
+    util::retain_valid_ids,
+    CacheResult, CachedArchive, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild.id.get()))]
     pub(crate) fn store_guild(&self, pipe: &mut Pipe<'_, C>, guild: &Guild) -> CacheResult<()> {
         if C::Guild::WANTED {
             let guild_id = guild.id;
@@ -33,7 +40,13 @@ impl<C: CacheConfig> RedisCache<C> {
                 .serialize_one()
                 .map_err(|e| SerializeError::new(e, SerializeErrorKind::Guild))?;
 
-            trace!(bytes = bytes.as_ref().len());
+            trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+            #[cfg(feature = "serde-mirror")]
+            pipe.mirror(&key, &guild)
+                .map_err(|e| SerializeError::new(e, SerializeErrorKind::Guild))?;
+
+            pipe.version(&key, &guild);
 
             pipe.set(key, bytes.as_ref(), C::Guild::expire());
 
@@ -57,7 +70,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.id.get()))]
     pub(crate) async fn store_guild_update(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -82,17 +95,74 @@ impl<C: CacheConfig> RedisCache<C> {
             return Ok(());
         };
 
+        self.update_guild_entry(pipe, guild_id, |guild| update_fn(guild, update))
+            .await
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.get()))]
+    pub(crate) async fn store_guild_emojis_update(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        update: &GuildEmojisUpdate,
+    ) -> CacheResult<()> {
+        self.store_emojis(pipe, update.guild_id, &update.emojis)?;
+
+        let Some(update_fn) = C::Guild::on_emojis_update() else {
+            return Ok(());
+        };
+
+        self.update_guild_entry(pipe, update.guild_id, |guild| update_fn(guild, update))
+            .await
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.get()))]
+    pub(crate) async fn store_guild_stickers_update(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        update: &GuildStickersUpdate,
+    ) -> CacheResult<()> {
+        self.store_stickers(pipe, update.guild_id, &update.stickers)?;
+
+        let Some(update_fn) = C::Guild::on_stickers_update() else {
+            return Ok(());
+        };
+
+        self.update_guild_entry(pipe, update.guild_id, |guild| update_fn(guild, update))
+            .await
+    }
+
+    /// Update the cached guild in-place, if present.
+    async fn update_guild_entry<F>(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        update_fn: F,
+    ) -> CacheResult<()>
+    where
+        F: FnOnce(
+            &mut CachedArchive<C::Guild<'static>>,
+        ) -> Result<(), <C::Guild<'static> as Fallible>::Error>,
+    {
+        if !C::Guild::WANTED {
+            return Ok(());
+        }
+
         let key = RedisKey::Guild { id: guild_id };
 
         let Some(mut guild) = pipe.get::<C::Guild<'static>>(key).await? else {
             return Ok(());
         };
 
-        update_fn(&mut guild, update).map_err(|e| UpdateError::new(e, UpdateErrorKind::Guild))?;
+        update_fn(&mut guild).map_err(|e| UpdateError::new(e, UpdateErrorKind::Guild))?;
 
         let key = RedisKey::Guild { id: guild_id };
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &guild)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::Guild))?;
+
         let bytes = guild.into_bytes();
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
         pipe.set(key, &bytes, C::Guild::expire());
 
         Ok(())
@@ -103,13 +173,40 @@ impl<C: CacheConfig> RedisCache<C> {
         pipe: &mut Pipe<'_, C>,
         guild_id: Id<GuildMarker>,
     ) -> CacheResult<()> {
+        let keys_to_delete = self.remove_guild(pipe, guild_id).await?;
+
+        if !keys_to_delete.is_empty() {
+            pipe.del(keys_to_delete);
+        }
+
+        Self::delete_guild_activity(pipe, guild_id);
+
+        Ok(())
+    }
+
+    /// Remove the guild and its entries from all collections and return the
+    /// keys that are no longer used.
+    ///
+    /// Executes the pipeline so it should be empty beforehand.
+    pub(crate) async fn remove_guild(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<Vec<RedisKey>> {
         debug_assert!(pipe.is_empty());
 
+        let mut keys_to_delete = self.guild_invite_keys(pipe, &[guild_id.get()]).await?;
+
         if C::Member::WANTED || C::User::WANTED {
             let key = RedisKey::GuildMembers { id: guild_id };
             pipe.smembers(key);
         }
 
+        if C::AutoModerationRule::WANTED {
+            let key = RedisKey::GuildAutoModerationRules { id: guild_id };
+            pipe.smembers(key);
+        }
+
         if C::Channel::WANTED {
             let key = RedisKey::GuildChannels { id: guild_id };
             pipe.smembers(key);
@@ -135,6 +232,11 @@ impl<C: CacheConfig> RedisCache<C> {
             pipe.smembers(key);
         }
 
+        if C::ScheduledEvent::WANTED {
+            let key = RedisKey::GuildScheduledEvents { id: guild_id };
+            pipe.smembers(key);
+        }
+
         if C::StageInstance::WANTED {
             let key = RedisKey::GuildStageInstances { id: guild_id };
             pipe.smembers(key);
@@ -150,45 +252,28 @@ impl<C: CacheConfig> RedisCache<C> {
             pipe.smembers(key);
         }
 
-        if pipe.is_empty() {
-            if C::Guild::WANTED {
-                let key = RedisKey::Guild { id: guild_id };
-                pipe.del(key);
-
-                let key = RedisKey::Guilds;
-                pipe.srem(key, guild_id.get());
-            }
-
-            return Ok(());
-        }
-
-        let mut iter = pipe.query::<Vec<Vec<u64>>>().await?.into_iter();
-
-        let mut keys_to_delete = Vec::new();
-
-        delete_member_user::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete).await?;
-        delete_channel::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
-        delete_emoji::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
-        delete_integration::<C>(&mut iter, guild_id, &mut keys_to_delete)?;
-        delete_presence::<C>(&mut iter, guild_id, &mut keys_to_delete)?;
-        delete_role::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
-        delete_stage::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
-        delete_sticker::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
-        delete_voice_state::<C>(&mut iter, guild_id, &mut keys_to_delete)?;
-
-        if C::Guild::WANTED {
-            let key = RedisKey::Guild { id: guild_id };
-            keys_to_delete.push(key);
-
-            let key = RedisKey::Guilds;
-            pipe.srem(key, guild_id.get());
+        if !pipe.is_empty() {
+            let mut data = pipe.query::<Vec<Vec<u64>>>().await?;
+            data.iter_mut().for_each(retain_valid_ids);
+
+            let mut iter = data.into_iter();
+
+            delete_member_user::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete).await?;
+            delete_auto_moderation_rule::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_channel::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_emoji::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_integration::<C>(&mut iter, guild_id, &mut keys_to_delete)?;
+            delete_presence::<C>(&mut iter, guild_id, &mut keys_to_delete)?;
+            delete_role::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_scheduled_event::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_stage::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_sticker::<C>(pipe, &mut iter, guild_id, &mut keys_to_delete)?;
+            delete_voice_state::<C>(&mut iter, guild_id, &mut keys_to_delete)?;
         }
 
-        if !keys_to_delete.is_empty() {
-            pipe.del(keys_to_delete);
-        }
+        delete_guild::<C>(pipe, guild_id, &mut keys_to_delete);
 
-        Ok(())
+        Ok(keys_to_delete)
     }
 
     pub(crate) async fn delete_guilds(
@@ -198,12 +283,16 @@ impl<C: CacheConfig> RedisCache<C> {
     ) -> CacheResult<()> {
         debug_assert!(pipe.is_empty());
 
-        let count = usize::from(C::Channel::WANTED)
+        let mut keys_to_delete = self.guild_invite_keys(pipe, guild_ids).await?;
+
+        let count = usize::from(C::AutoModerationRule::WANTED)
+            + usize::from(C::Channel::WANTED)
             + usize::from(C::Emoji::WANTED)
             + usize::from(C::Integration::WANTED)
             + usize::from(C::Member::WANTED || C::User::WANTED)
             + usize::from(C::Presence::WANTED)
             + usize::from(C::Role::WANTED)
+            + usize::from(C::ScheduledEvent::WANTED)
             + usize::from(C::StageInstance::WANTED)
             + usize::from(C::Sticker::WANTED)
             + usize::from(C::VoiceState::WANTED);
@@ -222,6 +311,12 @@ impl<C: CacheConfig> RedisCache<C> {
             add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildMembers { id });
         }
 
+        if C::AutoModerationRule::WANTED {
+            add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildAutoModerationRules {
+                id,
+            });
+        }
+
         if C::Channel::WANTED {
             add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildChannels { id });
         }
@@ -242,6 +337,10 @@ impl<C: CacheConfig> RedisCache<C> {
             add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildRoles { id });
         }
 
+        if C::ScheduledEvent::WANTED {
+            add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildScheduledEvents { id });
+        }
+
         if C::StageInstance::WANTED {
             add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildStageInstances { id });
         }
@@ -254,8 +353,6 @@ impl<C: CacheConfig> RedisCache<C> {
             add_smembers_keys(pipe, guild_ids, |id| RedisKey::GuildVoiceStates { id });
         }
 
-        let mut keys_to_delete = Vec::new();
-
         if pipe.is_empty() {
             delete_guilds(pipe, guild_ids, &mut keys_to_delete);
 
@@ -266,7 +363,8 @@ impl<C: CacheConfig> RedisCache<C> {
             return Ok(());
         }
 
-        let data = pipe.query::<Vec<Vec<u64>>>().await?;
+        let mut data = pipe.query::<Vec<Vec<u64>>>().await?;
+        data.iter_mut().for_each(retain_valid_ids);
 
         if data.len() != count * guild_ids.len() {
             return Err(CacheError::InvalidResponse);
@@ -275,11 +373,13 @@ impl<C: CacheConfig> RedisCache<C> {
         let mut iter = data.into_iter();
 
         delete_members_users::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete).await?;
+        delete_auto_moderation_rules::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
         delete_channels::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
         delete_emojis::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
         delete_integrations::<C>(&mut iter, guild_ids, &mut keys_to_delete);
         delete_presences::<C>(&mut iter, guild_ids, &mut keys_to_delete);
         delete_roles::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
+        delete_scheduled_events::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
         delete_stages::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
         delete_stickers::<C>(pipe, &mut iter, guild_ids, &mut keys_to_delete);
         delete_voice_states::<C>(&mut iter, guild_ids, &mut keys_to_delete);
@@ -340,6 +440,14 @@ async fn delete_member_user<C: CacheConfig>(
         let key = RedisKey::GuildMembers { id: guild_id };
         keys_to_delete.push(key);
 
+        let key = RedisKey::GuildMembersOrdered { id: guild_id };
+        keys_to_delete.push(key);
+
+        if C::Member::INDEX_NAMES {
+            keys_to_delete.push(RedisKey::GuildMemberNames { id: guild_id });
+            keys_to_delete.push(RedisKey::GuildMemberNamesByUser { id: guild_id });
+        }
+
         let member_keys = user_ids.iter().map(|&user_id| RedisKey::Member {
             guild: guild_id,
             user: Id::new(user_id),
@@ -351,6 +459,45 @@ async fn delete_member_user<C: CacheConfig>(
     Ok(())
 }
 
+fn delete_auto_moderation_rule<C: CacheConfig>(
+    pipe: &mut Pipe<'_, C>,
+    iter: &mut IntoIter<Vec<u64>>,
+    guild_id: Id<GuildMarker>,
+    keys_to_delete: &mut Vec<RedisKey>,
+) -> CacheResult<()> {
+    if !C::AutoModerationRule::WANTED {
+        return Ok(());
+    }
+
+    let key = RedisKey::GuildAutoModerationRules { id: guild_id };
+    keys_to_delete.push(key);
+
+    let rule_ids = iter.next().ok_or(CacheError::InvalidResponse)?;
+
+    let key = RedisKey::AutoModerationRules;
+    pipe.srem(key, rule_ids.as_slice());
+
+    if C::AutoModerationRule::expire().is_some() {
+        let rule_keys = rule_ids
+            .iter()
+            .map(|rule_id| RedisKey::AutoModerationRuleMeta {
+                id: Id::new(*rule_id),
+            });
+
+        keys_to_delete.extend(rule_keys);
+    }
+
+    let rule_keys = rule_ids
+        .into_iter()
+        .map(|rule_id| RedisKey::AutoModerationRule {
+            id: Id::new(rule_id),
+        });
+
+    keys_to_delete.extend(rule_keys);
+
+    Ok(())
+}
+
 fn delete_channel<C: CacheConfig>(
     pipe: &mut Pipe<'_, C>,
     iter: &mut IntoIter<Vec<u64>>,
@@ -369,6 +516,22 @@ fn delete_channel<C: CacheConfig>(
     let key = RedisKey::Channels;
     pipe.srem(key, channel_ids.as_slice());
 
+    if C::Invite::WANTED {
+        let invite_keys = channel_ids
+            .iter()
+            .map(|channel_id| RedisKey::ChannelInvites {
+                id: Id::new(*channel_id),
+            });
+
+        keys_to_delete.extend(invite_keys);
+    }
+
+    if C::ThreadMember::WANTED {
+        for &channel_id in &channel_ids {
+            delete_thread_members(pipe, Id::new(channel_id));
+        }
+    }
+
     if C::Channel::expire().is_some() {
         let channel_keys = channel_ids.iter().map(|channel_id| RedisKey::ChannelMeta {
             id: Id::new(*channel_id),
@@ -377,8 +540,10 @@ fn delete_channel<C: CacheConfig>(
         keys_to_delete.extend(channel_keys);
     }
 
-    let channel_keys = channel_ids.into_iter().map(|channel_id| RedisKey::Channel {
-        id: Id::new(channel_id),
+    let channel_keys = channel_ids.into_iter().flat_map(|channel_id| {
+        let id = Id::new(channel_id);
+
+        [RedisKey::Channel { id }, RedisKey::ChannelGuild { id }]
     });
 
     keys_to_delete.extend(channel_keys);
@@ -460,6 +625,14 @@ fn delete_presence<C: CacheConfig>(
     let key = RedisKey::GuildPresences { id: guild_id };
     keys_to_delete.push(key);
 
+    if C::Presence::TRACK_FRESHNESS {
+        keys_to_delete.push(RedisKey::GuildPresenceStamps { id: guild_id });
+    }
+
+    if C::Presence::SKIP_UNCHANGED {
+        keys_to_delete.push(RedisKey::GuildPresenceHashes { id: guild_id });
+    }
+
     let user_ids = iter.next().ok_or(CacheError::InvalidResponse)?;
 
     let presence_keys = user_ids.into_iter().map(|user_id| RedisKey::Presence {
@@ -490,7 +663,7 @@ fn delete_role<C: CacheConfig>(
     let key = RedisKey::Roles;
     pipe.srem(key, role_ids.as_slice());
 
-    if C::Role::expire().is_some() {
+    if stores_role_meta::<C>() {
         let role_keys = role_ids.iter().map(|role_id| RedisKey::RoleMeta {
             id: Id::new(*role_id),
         });
@@ -507,6 +680,47 @@ fn delete_role<C: CacheConfig>(
     Ok(())
 }
 
+fn delete_scheduled_event<C: CacheConfig>(
+    pipe: &mut Pipe<'_, C>,
+    iter: &mut IntoIter<Vec<u64>>,
+    guild_id: Id<GuildMarker>,
+    keys_to_delete: &mut Vec<RedisKey>,
+) -> CacheResult<()> {
+    if !C::ScheduledEvent::WANTED {
+        return Ok(());
+    }
+
+    let key = RedisKey::GuildScheduledEvents { id: guild_id };
+    keys_to_delete.push(key);
+
+    let scheduled_event_ids = iter.next().ok_or(CacheError::InvalidResponse)?;
+
+    let key = RedisKey::ScheduledEvents;
+    pipe.srem(key, scheduled_event_ids.as_slice());
+
+    if C::ScheduledEvent::expire().is_some() {
+        let scheduled_event_keys =
+            scheduled_event_ids
+                .iter()
+                .map(|scheduled_event_id| RedisKey::ScheduledEventMeta {
+                    id: Id::new(*scheduled_event_id),
+                });
+
+        keys_to_delete.extend(scheduled_event_keys);
+    }
+
+    let scheduled_event_keys =
+        scheduled_event_ids
+            .into_iter()
+            .map(|scheduled_event_id| RedisKey::ScheduledEvent {
+                id: Id::new(scheduled_event_id),
+            });
+
+    keys_to_delete.extend(scheduled_event_keys);
+
+    Ok(())
+}
+
 fn delete_stage<C: CacheConfig>(
     pipe: &mut Pipe<'_, C>,
     iter: &mut IntoIter<Vec<u64>>,
@@ -593,6 +807,10 @@ fn delete_voice_state<C: CacheConfig>(
     let key = RedisKey::GuildVoiceStates { id: guild_id };
     keys_to_delete.push(key);
 
+    if C::VoiceState::TRACK_FRESHNESS {
+        keys_to_delete.push(RedisKey::GuildVoiceStateStamps { id: guild_id });
+    }
+
     let user_ids = iter.next().ok_or(CacheError::InvalidResponse)?;
 
     let voice_state_keys = user_ids.into_iter().map(|user_id| RedisKey::VoiceState {
@@ -605,6 +823,36 @@ fn delete_voice_state<C: CacheConfig>(
     Ok(())
 }
 
+fn delete_guild<C: CacheConfig>(
+    pipe: &mut Pipe<'_, C>,
+    guild_id: Id<GuildMarker>,
+    keys_to_delete: &mut Vec<RedisKey>,
+) {
+    if C::Guild::WANTED {
+        let key = RedisKey::Guild { id: guild_id };
+        keys_to_delete.push(key);
+
+        let key = RedisKey::Guilds;
+        pipe.srem(key, guild_id.get());
+    }
+
+    if C::Guild::CACHE_BANS {
+        keys_to_delete.extend(ban_keys(guild_id));
+    }
+
+    if C::Message::starboard_score().is_some() {
+        keys_to_delete.push(RedisKey::GuildStarboard { id: guild_id });
+    }
+
+    if C::Member::premium_since().is_some() {
+        keys_to_delete.push(RedisKey::GuildBoosters { id: guild_id });
+    }
+
+    if C::PRESENCE_STATUS {
+        keys_to_delete.push(RedisKey::GuildPresenceStatus { id: guild_id });
+    }
+}
+
 // Deleting entries of multiple guilds
 
 async fn delete_members_users<C: CacheConfig>(
@@ -660,6 +908,29 @@ async fn delete_members_users<C: CacheConfig>(
 
         keys_to_delete.extend(guild_keys);
 
+        let ordered_keys =
+            guild_ids
+                .iter()
+                .copied()
+                .map(|guild_id| RedisKey::GuildMembersOrdered {
+                    id: Id::new(guild_id),
+                });
+
+        keys_to_delete.extend(ordered_keys);
+
+        if C::Member::INDEX_NAMES {
+            let name_keys = guild_ids.iter().flat_map(|&guild_id| {
+                let id = Id::new(guild_id);
+
+                [
+                    RedisKey::GuildMemberNames { id },
+                    RedisKey::GuildMemberNamesByUser { id },
+                ]
+            });
+
+            keys_to_delete.extend(name_keys);
+        }
+
         let member_keys =
             user_ids_unflattened
                 .iter()
@@ -679,6 +950,49 @@ async fn delete_members_users<C: CacheConfig>(
     Ok(())
 }
 
+fn delete_auto_moderation_rules<C: CacheConfig>(
+    pipe: &mut Pipe<'_, C>,
+    iter: &mut IntoIter<Vec<u64>>,
+    guild_ids: &[u64],
+    keys_to_delete: &mut Vec<RedisKey>,
+) {
+    if !C::AutoModerationRule::WANTED {
+        return;
+    }
+
+    let rule_ids: Vec<_> = iter.by_ref().take(guild_ids.len()).flatten().collect();
+
+    let key = RedisKey::AutoModerationRules;
+    pipe.srem(key, rule_ids.as_slice());
+
+    if C::AutoModerationRule::expire().is_some() {
+        let rule_keys = rule_ids
+            .iter()
+            .map(|rule_id| RedisKey::AutoModerationRuleMeta {
+                id: Id::new(*rule_id),
+            });
+
+        keys_to_delete.extend(rule_keys);
+    }
+
+    let rule_keys = rule_ids
+        .into_iter()
+        .map(|rule_id| RedisKey::AutoModerationRule {
+            id: Id::new(rule_id),
+        });
+
+    keys_to_delete.extend(rule_keys);
+
+    let guild_keys = guild_ids
+        .iter()
+        .copied()
+        .map(|guild_id| RedisKey::GuildAutoModerationRules {
+            id: Id::new(guild_id),
+        });
+
+    keys_to_delete.extend(guild_keys);
+}
+
 fn delete_channels<C: CacheConfig>(
     pipe: &mut Pipe<'_, C>,
     iter: &mut IntoIter<Vec<u64>>,
@@ -694,6 +1008,22 @@ fn delete_channels<C: CacheConfig>(
     let key = RedisKey::Channels;
     pipe.srem(key, channel_ids.as_slice());
 
+    if C::Invite::WANTED {
+        let invite_keys = channel_ids
+            .iter()
+            .map(|channel_id| RedisKey::ChannelInvites {
+                id: Id::new(*channel_id),
+            });
+
+        keys_to_delete.extend(invite_keys);
+    }
+
+    if C::ThreadMember::WANTED {
+        for &channel_id in &channel_ids {
+            delete_thread_members(pipe, Id::new(channel_id));
+        }
+    }
+
     if C::Channel::expire().is_some() {
         let channel_keys = channel_ids.iter().map(|channel_id| RedisKey::ChannelMeta {
             id: Id::new(*channel_id),
@@ -702,8 +1032,10 @@ fn delete_channels<C: CacheConfig>(
         keys_to_delete.extend(channel_keys);
     }
 
-    let channel_keys = channel_ids.into_iter().map(|channel_id| RedisKey::Channel {
-        id: Id::new(channel_id),
+    let channel_keys = channel_ids.into_iter().flat_map(|channel_id| {
+        let id = Id::new(channel_id);
+
+        [RedisKey::Channel { id }, RedisKey::ChannelGuild { id }]
     });
 
     keys_to_delete.extend(channel_keys);
@@ -819,6 +1151,26 @@ fn delete_presences<C: CacheConfig>(
         });
 
     keys_to_delete.extend(guild_keys);
+
+    if C::Presence::TRACK_FRESHNESS {
+        let stamp_keys = guild_ids
+            .iter()
+            .map(|guild_id| RedisKey::GuildPresenceStamps {
+                id: Id::new(*guild_id),
+            });
+
+        keys_to_delete.extend(stamp_keys);
+    }
+
+    if C::Presence::SKIP_UNCHANGED {
+        let hash_keys = guild_ids
+            .iter()
+            .map(|guild_id| RedisKey::GuildPresenceHashes {
+                id: Id::new(*guild_id),
+            });
+
+        keys_to_delete.extend(hash_keys);
+    }
 }
 
 fn delete_roles<C: CacheConfig>(
@@ -836,7 +1188,7 @@ fn delete_roles<C: CacheConfig>(
     let key = RedisKey::Roles;
     pipe.srem(key, role_ids.as_slice());
 
-    if C::Role::expire().is_some() {
+    if stores_role_meta::<C>() {
         let role_keys = role_ids.iter().map(|role_id| RedisKey::RoleMeta {
             id: Id::new(*role_id),
         });
@@ -860,6 +1212,51 @@ fn delete_roles<C: CacheConfig>(
     keys_to_delete.extend(guild_keys);
 }
 
+fn delete_scheduled_events<C: CacheConfig>(
+    pipe: &mut Pipe<'_, C>,
+    iter: &mut IntoIter<Vec<u64>>,
+    guild_ids: &[u64],
+    keys_to_delete: &mut Vec<RedisKey>,
+) {
+    if !C::ScheduledEvent::WANTED {
+        return;
+    }
+
+    let scheduled_event_ids: Vec<_> = iter.by_ref().take(guild_ids.len()).flatten().collect();
+
+    let key = RedisKey::ScheduledEvents;
+    pipe.srem(key, scheduled_event_ids.as_slice());
+
+    if C::ScheduledEvent::expire().is_some() {
+        let scheduled_event_keys =
+            scheduled_event_ids
+                .iter()
+                .map(|scheduled_event_id| RedisKey::ScheduledEventMeta {
+                    id: Id::new(*scheduled_event_id),
+                });
+
+        keys_to_delete.extend(scheduled_event_keys);
+    }
+
+    let scheduled_event_keys =
+        scheduled_event_ids
+            .into_iter()
+            .map(|scheduled_event_id| RedisKey::ScheduledEvent {
+                id: Id::new(scheduled_event_id),
+            });
+
+    keys_to_delete.extend(scheduled_event_keys);
+
+    let guild_keys = guild_ids
+        .iter()
+        .copied()
+        .map(|guild_id| RedisKey::GuildScheduledEvents {
+            id: Id::new(guild_id),
+        });
+
+    keys_to_delete.extend(guild_keys);
+}
+
 fn delete_stages<C: CacheConfig>(
     pipe: &mut Pipe<'_, C>,
     iter: &mut IntoIter<Vec<u64>>,
@@ -972,6 +1369,16 @@ fn delete_voice_states<C: CacheConfig>(
         });
 
     keys_to_delete.extend(guild_keys);
+
+    if C::VoiceState::TRACK_FRESHNESS {
+        let stamp_keys = guild_ids
+            .iter()
+            .map(|guild_id| RedisKey::GuildVoiceStateStamps {
+                id: Id::new(*guild_id),
+            });
+
+        keys_to_delete.extend(stamp_keys);
+    }
 }
 
 fn delete_guilds<C: CacheConfig>(
@@ -979,6 +1386,44 @@ fn delete_guilds<C: CacheConfig>(
     guild_ids: &[u64],
     keys_to_delete: &mut Vec<RedisKey>,
 ) {
+    if C::Guild::CACHE_BANS {
+        let ban_keys = guild_ids
+            .iter()
+            .flat_map(|guild_id| ban_keys(Id::new(*guild_id)));
+
+        keys_to_delete.extend(ban_keys);
+    }
+
+    if C::Message::starboard_score().is_some() {
+        let starboard_keys = guild_ids.iter().map(|guild_id| RedisKey::GuildStarboard {
+            id: Id::new(*guild_id),
+        });
+
+        keys_to_delete.extend(starboard_keys);
+    }
+
+    if C::Member::premium_since().is_some() {
+        let booster_keys = guild_ids.iter().map(|guild_id| RedisKey::GuildBoosters {
+            id: Id::new(*guild_id),
+        });
+
+        keys_to_delete.extend(booster_keys);
+    }
+
+    if C::PRESENCE_STATUS {
+        let status_keys = guild_ids
+            .iter()
+            .map(|guild_id| RedisKey::GuildPresenceStatus {
+                id: Id::new(*guild_id),
+            });
+
+        keys_to_delete.extend(status_keys);
+    }
+
+    if C::Guild::STALENESS_THRESHOLD.is_some() {
+        pipe.zrem(RedisKey::GuildActivity, guild_ids);
+    }
+
     if !C::Guild::WANTED {
         return;
     }
@@ -993,6 +1438,13 @@ fn delete_guilds<C: CacheConfig>(
     pipe.srem(key, guild_ids);
 }
 
+const fn ban_keys(guild_id: Id<GuildMarker>) -> [RedisKey; 2] {
+    [
+        RedisKey::GuildBans { id: guild_id },
+        RedisKey::GuildBansSnapshot { id: guild_id },
+    ]
+}
+
 #[derive(Debug)]
 pub(crate) struct GuildMetaKey {
     guild: Id<GuildMarker>,
@@ -1003,7 +1455,7 @@ impl IMetaKey for GuildMetaKey {
         split.next().and_then(atoi).map(|guild| Self { guild })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Guilds;
         pipe.srem(key, self.guild.get());
     }
@@ -1012,10 +1464,13 @@ impl IMetaKey for GuildMetaKey {
 impl GuildMetaKey {
     pub(crate) async fn async_handle_expire(
         self,
-        pipe: &mut Pipeline,
+        pipe: &mut ExpirePipe,
         conn: &mut DedicatedConnection,
     ) -> Result<(), ExpireError> {
-        debug_assert_eq!(pipe.cmd_iter().count(), 0);
+        debug_assert_eq!(pipe.len(), 0);
+
+        let key = RedisKey::GuildAutoModerationRules { id: self.guild };
+        pipe.smembers(key.clone()).del(key).ignore();
 
         let key = RedisKey::GuildChannels { id: self.guild };
         pipe.smembers(key.clone()).del(key).ignore();
@@ -1029,12 +1484,42 @@ impl GuildMetaKey {
         let key = RedisKey::GuildMembers { id: self.guild };
         pipe.smembers(key.clone()).del(key).ignore();
 
+        let key = RedisKey::GuildMembersOrdered { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildMemberNames { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildMemberNamesByUser { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildStarboard { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildBoosters { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildPresenceStamps { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildPresenceHashes { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildPresenceStatus { id: self.guild };
+        pipe.del(key).ignore();
+
+        let key = RedisKey::GuildVoiceStateStamps { id: self.guild };
+        pipe.del(key).ignore();
+
         let key = RedisKey::GuildPresences { id: self.guild };
         pipe.smembers(key.clone()).del(key).ignore();
 
         let key = RedisKey::GuildRoles { id: self.guild };
         pipe.smembers(key.clone()).del(key).ignore();
 
+        let key = RedisKey::GuildScheduledEvents { id: self.guild };
+        pipe.smembers(key.clone()).del(key).ignore();
+
         let key = RedisKey::GuildStageInstances { id: self.guild };
         pipe.smembers(key.clone()).del(key).ignore();
 
@@ -1044,16 +1529,22 @@ impl GuildMetaKey {
         let key = RedisKey::GuildVoiceStates { id: self.guild };
         pipe.smembers(key.clone()).del(key).ignore();
 
-        let mut iter = pipe
-            .query_async::<_, Vec<Vec<u64>>>(conn)
+        let mut data = pipe
+            .query::<Vec<Vec<u64>>>(conn)
             .await
-            .map_err(ExpireError::Pipe)?
-            .into_iter();
+            .map_err(ExpireError::Pipe)?;
+
+        data.iter_mut().for_each(retain_valid_ids);
+
+        let mut iter = data.into_iter();
 
         pipe.clear();
 
         let mut keys_to_delete = Vec::new();
 
+        let auto_mod_rule_ids = iter.next().unwrap_or_default();
+        self.handle_auto_moderation_rules(pipe, &mut keys_to_delete, &auto_mod_rule_ids);
+
         let channel_ids = iter.next().unwrap_or_default();
         self.handle_channels(pipe, &mut keys_to_delete, &channel_ids);
 
@@ -1073,6 +1564,9 @@ impl GuildMetaKey {
         let role_ids = iter.next().unwrap_or_default();
         self.handle_roles(pipe, &mut keys_to_delete, &role_ids);
 
+        let scheduled_event_ids = iter.next().unwrap_or_default();
+        self.handle_scheduled_events(pipe, &mut keys_to_delete, &scheduled_event_ids);
+
         let stage_ids = iter.next().unwrap_or_default();
         self.handle_stages(pipe, &mut keys_to_delete, &stage_ids);
 
@@ -1087,7 +1581,25 @@ impl GuildMetaKey {
         Ok(())
     }
 
-    fn handle_channels(&self, pipe: &mut Pipeline, buf: &mut Vec<RedisKey>, channel_ids: &[u64]) {
+    fn handle_auto_moderation_rules(
+        &self,
+        pipe: &mut ExpirePipe,
+        buf: &mut Vec<RedisKey>,
+        rule_ids: &[u64],
+    ) {
+        pipe.srem(RedisKey::AutoModerationRules, rule_ids).ignore();
+
+        let iter = rule_ids.iter().flat_map(|rule| {
+            let meta = RedisKey::AutoModerationRuleMeta { id: Id::new(*rule) };
+            let rule = RedisKey::AutoModerationRule { id: Id::new(*rule) };
+
+            [rule, meta]
+        });
+
+        buf.extend(iter);
+    }
+
+    fn handle_channels(&self, pipe: &mut ExpirePipe, buf: &mut Vec<RedisKey>, channel_ids: &[u64]) {
         pipe.srem(RedisKey::Channels, channel_ids).ignore();
 
         let iter = channel_ids.iter().flat_map(|channel| {
@@ -1095,17 +1607,21 @@ impl GuildMetaKey {
                 id: Id::new(*channel),
             };
 
+            let guild = RedisKey::ChannelGuild {
+                id: Id::new(*channel),
+            };
+
             let channel = RedisKey::Channel {
                 id: Id::new(*channel),
             };
 
-            [channel, meta]
+            [channel, guild, meta]
         });
 
         buf.extend(iter);
     }
 
-    fn handle_emojis(&self, pipe: &mut Pipeline, buf: &mut Vec<RedisKey>, emoji_ids: &[u64]) {
+    fn handle_emojis(&self, pipe: &mut ExpirePipe, buf: &mut Vec<RedisKey>, emoji_ids: &[u64]) {
         pipe.srem(RedisKey::Emojis, emoji_ids).ignore();
 
         let iter = emoji_ids.iter().flat_map(|emoji| {
@@ -1136,7 +1652,7 @@ impl GuildMetaKey {
 
     async fn handle_members(
         &self,
-        pipe: &mut Pipeline,
+        pipe: &mut ExpirePipe,
         conn: &mut DedicatedConnection,
         buf: &mut Vec<RedisKey>,
         member_ids: Vec<u64>,
@@ -1150,7 +1666,7 @@ impl GuildMetaKey {
             pipe.srem(key.clone(), self.guild.get()).ignore().scard(key);
         }
 
-        let scards: Vec<usize> = pipe.query_async(conn).await.map_err(ExpireError::Pipe)?;
+        let scards: Vec<usize> = pipe.query(conn).await.map_err(ExpireError::Pipe)?;
         pipe.clear();
 
         let estranged_user_ids: Vec<u64> = member_ids
@@ -1188,7 +1704,7 @@ impl GuildMetaKey {
         buf.extend(iter);
     }
 
-    fn handle_roles(&self, pipe: &mut Pipeline, buf: &mut Vec<RedisKey>, role_ids: &[u64]) {
+    fn handle_roles(&self, pipe: &mut ExpirePipe, buf: &mut Vec<RedisKey>, role_ids: &[u64]) {
         pipe.srem(RedisKey::Roles, role_ids).ignore();
 
         let iter = role_ids.iter().flat_map(|role| {
@@ -1201,7 +1717,31 @@ impl GuildMetaKey {
         buf.extend(iter);
     }
 
-    fn handle_stages(&self, pipe: &mut Pipeline, buf: &mut Vec<RedisKey>, stage_ids: &[u64]) {
+    fn handle_scheduled_events(
+        &self,
+        pipe: &mut ExpirePipe,
+        buf: &mut Vec<RedisKey>,
+        scheduled_event_ids: &[u64],
+    ) {
+        pipe.srem(RedisKey::ScheduledEvents, scheduled_event_ids)
+            .ignore();
+
+        let iter = scheduled_event_ids.iter().flat_map(|scheduled_event| {
+            let meta = RedisKey::ScheduledEventMeta {
+                id: Id::new(*scheduled_event),
+            };
+
+            let scheduled_event = RedisKey::ScheduledEvent {
+                id: Id::new(*scheduled_event),
+            };
+
+            [scheduled_event, meta]
+        });
+
+        buf.extend(iter);
+    }
+
+    fn handle_stages(&self, pipe: &mut ExpirePipe, buf: &mut Vec<RedisKey>, stage_ids: &[u64]) {
         pipe.srem(RedisKey::StageInstances, stage_ids).ignore();
 
         let iter = stage_ids.iter().flat_map(|stage| {
@@ -1219,7 +1759,7 @@ impl GuildMetaKey {
         buf.extend(iter);
     }
 
-    fn handle_stickers(&self, pipe: &mut Pipeline, buf: &mut Vec<RedisKey>, sticker_ids: &[u64]) {
+    fn handle_stickers(&self, pipe: &mut ExpirePipe, buf: &mut Vec<RedisKey>, sticker_ids: &[u64]) {
         pipe.srem(RedisKey::Stickers, sticker_ids).ignore();
 
         let iter = sticker_ids.iter().flat_map(|sticker| {
@@ -9,18 +9,19 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, IMetaKey},
+        meta::{atoi, ExpirePipe, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedIntegration},
     error::{SerializeError, SerializeErrorKind},
     key::RedisKey,
-    redis::Pipeline,
+    util::retain_valid_ids,
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_integration(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -39,7 +40,13 @@ impl<C: CacheConfig> RedisCache<C> {
                 .serialize_one()
                 .map_err(|e| SerializeError::new(e, SerializeErrorKind::Integration))?;
 
-            trace!(bytes = bytes.as_ref().len());
+            trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+            #[cfg(feature = "serde-mirror")]
+            pipe.mirror(&key, &integration)
+                .map_err(|e| SerializeError::new(e, SerializeErrorKind::Integration))?;
+
+            pipe.version(&key, &integration);
 
             pipe.set(key, bytes.as_ref(), C::Integration::expire());
 
@@ -54,6 +61,82 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
+    /// Replace all cached integrations of a guild with the given list, e.g.
+    /// after fetching them from the REST API.
+    ///
+    /// Integrations that are no longer contained in the list are removed.
+    /// Useful to re-sync integrations after [`GuildIntegrationsUpdate`]
+    /// events removed them, see [`ICachedIntegration::on_integrations_update`].
+    ///
+    /// [`GuildIntegrationsUpdate`]: twilight_model::gateway::payload::incoming::GuildIntegrationsUpdate
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
+    pub async fn store_guild_integrations(
+        &self,
+        guild_id: Id<GuildMarker>,
+        integrations: &[GuildIntegration],
+    ) -> CacheResult<()> {
+        let mut pipe = Pipe::new(self);
+
+        if C::Integration::WANTED {
+            let key = RedisKey::GuildIntegrations { id: guild_id };
+            pipe.smembers(key);
+
+            let (mut cached_ids,): (Vec<u64>,) = pipe.query().await?;
+            retain_valid_ids(&mut cached_ids);
+
+            let stale_ids = cached_ids.into_iter().filter(|&id| {
+                !integrations
+                    .iter()
+                    .any(|integration| integration.id.get() == id)
+            });
+
+            for integration_id in stale_ids {
+                self.delete_integration(&mut pipe, guild_id, Id::new(integration_id));
+            }
+        }
+
+        for integration in integrations {
+            self.store_integration(&mut pipe, guild_id, integration)?;
+        }
+
+        if !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remove all cached integrations of a guild.
+    pub(crate) async fn delete_guild_integrations(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<()> {
+        if !C::Integration::WANTED {
+            return Ok(());
+        }
+
+        let key = RedisKey::GuildIntegrations { id: guild_id };
+        let mut integration_ids: Vec<u64> = pipe.set_members([key.clone()]).await?;
+        retain_valid_ids(&mut integration_ids);
+
+        let keys: Vec<_> = integration_ids
+            .into_iter()
+            .map(|integration_id| RedisKey::Integration {
+                guild: guild_id,
+                id: Id::new(integration_id),
+            })
+            .collect();
+
+        if !keys.is_empty() {
+            pipe.del(keys);
+        }
+
+        pipe.del(key);
+
+        Ok(())
+    }
+
     pub(crate) fn delete_integration(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -90,7 +173,7 @@ impl IMetaKey for IntegrationMetaKey {
             .map(|(guild, integration)| Self { guild, integration })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::GuildIntegrations { id: self.guild };
         pipe.srem(key, self.integration.get());
     }
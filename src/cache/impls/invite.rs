@@ -0,0 +1,235 @@
+use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer, Archived};
+use tracing::{instrument, trace};
+use twilight_model::{
+    gateway::payload::incoming::InviteCreate,
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cache::{
+        meta::{ExpirePipe, HasArchived, IMeta, IMetaKey},
+        pipe::Pipe,
+        IO_TARGET,
+    },
+    config::{CacheConfig, Cacheable, ICachedInvite},
+    error::{MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
+    key::RedisKey,
+    rkyv_util::id::IdRkyv,
+    CacheResult, RedisCache,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    #[instrument(level = "trace", skip_all, fields(guild_id = invite.guild_id.get()))]
+    pub(crate) async fn store_invite(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        invite: &InviteCreate,
+    ) -> CacheResult<()> {
+        if C::Invite::WANTED {
+            let guild_id = invite.guild_id;
+            let channel_id = invite.channel_id;
+            let key = RedisKey::Invite {
+                code: invite.code.as_str().into(),
+            };
+            let cached = C::Invite::from_invite(invite);
+
+            let bytes = cached
+                .serialize_one()
+                .map_err(|e| SerializeError::new(e, SerializeErrorKind::Invite))?;
+
+            trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+            #[cfg(feature = "serde-mirror")]
+            pipe.mirror(&key, &cached)
+                .map_err(|e| SerializeError::new(e, SerializeErrorKind::Invite))?;
+
+            pipe.version(&key, &cached);
+
+            pipe.set(key, bytes.as_ref(), C::Invite::expire());
+
+            let key = RedisKey::GuildInvites { id: guild_id };
+            pipe.sadd(key, invite.code.as_str());
+
+            let key = RedisKey::ChannelInvites { id: channel_id };
+            pipe.sadd(key, invite.code.as_str());
+
+            if C::Invite::expire().is_some() {
+                let key = InviteMetaKey {
+                    code: invite.code.as_str().into(),
+                };
+
+                InviteMeta {
+                    channel: channel_id,
+                    guild: guild_id,
+                }
+                .store(pipe, key)
+                .map_err(|e| MetaError::new(e, MetaErrorKind::Invite))?;
+            }
+        }
+
+        if let Some(ref user) = invite.inviter {
+            self.store_user(pipe, user)?;
+        }
+
+        if let Some(ref user) = invite.target_user {
+            self.store_partial_user(pipe, user).await?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn delete_invite(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        channel_id: Id<ChannelMarker>,
+        code: &str,
+    ) {
+        if !C::Invite::WANTED {
+            return;
+        }
+
+        let key = RedisKey::Invite { code: code.into() };
+        pipe.del(key);
+
+        let key = RedisKey::GuildInvites { id: guild_id };
+        pipe.srem(key, code);
+
+        let key = RedisKey::ChannelInvites { id: channel_id };
+        pipe.srem(key, code);
+
+        if C::Invite::expire().is_some() {
+            let key = RedisKey::InviteMeta { code: code.into() };
+            pipe.del(key);
+        }
+    }
+
+    /// Delete all invites of a channel.
+    pub(crate) async fn delete_channel_invites(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Option<Id<GuildMarker>>,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<()> {
+        if !C::Invite::WANTED {
+            return Ok(());
+        }
+
+        let key = RedisKey::ChannelInvites { id: channel_id };
+        let codes: Vec<String> = pipe.set_members([key.clone()]).await?;
+
+        if !codes.is_empty() {
+            if let Some(guild_id) = guild_id {
+                let key = RedisKey::GuildInvites { id: guild_id };
+                pipe.srem(key, codes.as_slice());
+            }
+
+            let keys = invite_keys::<C>(&codes);
+            pipe.del(keys);
+        }
+
+        pipe.del(key);
+
+        Ok(())
+    }
+
+    /// Collect the keys of all invites of the given guilds, including the
+    /// guilds' invite sets.
+    pub(crate) async fn guild_invite_keys(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_ids: &[u64],
+    ) -> CacheResult<Vec<RedisKey>> {
+        if !C::Invite::WANTED || guild_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let guild_keys = guild_ids.iter().map(|&guild_id| RedisKey::GuildInvites {
+            id: Id::new(guild_id),
+        });
+
+        let codes: Vec<String> = pipe.set_members(guild_keys.clone()).await?;
+
+        let mut keys = invite_keys::<C>(&codes);
+        keys.extend(guild_keys);
+
+        Ok(keys)
+    }
+}
+
+/// Keys of the given invite codes, including meta keys if necessary.
+pub(crate) fn invite_keys<C: CacheConfig>(codes: &[String]) -> Vec<RedisKey> {
+    let invite_keys = codes.iter().map(|code| RedisKey::Invite {
+        code: code.as_str().into(),
+    });
+
+    if C::Invite::expire().is_some() {
+        let meta_keys = codes.iter().map(|code| RedisKey::InviteMeta {
+            code: code.as_str().into(),
+        });
+
+        invite_keys.chain(meta_keys).collect()
+    } else {
+        invite_keys.collect()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct InviteMetaKey {
+    code: Box<str>,
+}
+
+impl IMetaKey for InviteMetaKey {
+    fn parse<'a>(split: &mut impl Iterator<Item = &'a [u8]>) -> Option<Self> {
+        split
+            .next()
+            .and_then(|code| std::str::from_utf8(code).ok())
+            .map(|code| Self { code: code.into() })
+    }
+
+    fn handle_expire(&self, _: &mut ExpirePipe) {}
+}
+
+impl HasArchived for InviteMetaKey {
+    type Meta = InviteMeta;
+
+    fn redis_key(&self) -> RedisKey {
+        RedisKey::InviteMeta {
+            code: self.code.clone(),
+        }
+    }
+
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
+        let key = RedisKey::GuildInvites {
+            id: archived.guild.into(),
+        };
+        pipe.srem(key, &*self.code).ignore();
+
+        let key = RedisKey::ChannelInvites {
+            id: archived.channel.into(),
+        };
+        pipe.srem(key, &*self.code).ignore();
+    }
+}
+
+#[derive(rkyv::Archive, rkyv::Serialize)]
+pub(crate) struct InviteMeta {
+    #[rkyv(with = IdRkyv)]
+    channel: Id<ChannelMarker>,
+    #[rkyv(with = IdRkyv)]
+    guild: Id<GuildMarker>,
+}
+
+impl IMeta<InviteMetaKey> for InviteMeta {
+    type Bytes = [u8; 16];
+
+    fn to_bytes(&self) -> Result<Self::Bytes, BoxedError> {
+        let mut bytes = [0; 16];
+        to_bytes_in(self, Buffer::from(&mut bytes))?;
+
+        Ok(bytes)
+    }
+}
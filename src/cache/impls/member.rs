@@ -6,24 +6,53 @@ use twilight_model::{
         marker::{GuildMarker, UserMarker},
         Id,
     },
+    user::User,
 };
 
 use crate::{
     cache::{
         impls::user::UserMetaKey,
-        meta::{atoi, IMetaKey},
+        meta::{atoi, ExpirePipe, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedMember, SerializeMany},
     error::{ExpireError, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind},
     key::RedisKey,
-    redis::{DedicatedConnection, Pipeline},
+    redis::DedicatedConnection,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
+/// Replaces the indexed names of a member. Without names, the member is only
+/// unindexed.
+///
+/// KEYS: names, names by user
+/// ARGV: user id, names...
+const INDEX_NAMES_SCRIPT: &str = r"
+local previous = redis.call('HGET', KEYS[2], ARGV[1])
+
+if previous then
+    for name in string.gmatch(previous, '[^\n]+') do
+        redis.call('ZREM', KEYS[1], name .. '\0' .. ARGV[1])
+    end
+end
+
+if #ARGV == 1 then
+    redis.call('HDEL', KEYS[2], ARGV[1])
+
+    return
+end
+
+for i = 2, #ARGV do
+    redis.call('ZADD', KEYS[1], 0, ARGV[i] .. '\0' .. ARGV[1])
+end
+
+redis.call('HSET', KEYS[2], ARGV[1], table.concat(ARGV, '\n', 2))
+";
+
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_member(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -32,6 +61,13 @@ impl<C: CacheConfig> RedisCache<C> {
     ) -> CacheResult<()> {
         if C::Member::WANTED {
             let user_id = member.user.id;
+
+            if C::Member::INDEX_NAMES {
+                Self::index_names(pipe, guild_id, &member.user, member.nick.as_deref());
+            }
+
+            let is_booster = member.premium_since.is_some();
+
             let key = RedisKey::Member {
                 guild: guild_id,
                 user: user_id,
@@ -42,12 +78,26 @@ impl<C: CacheConfig> RedisCache<C> {
                 .serialize_one()
                 .map_err(|e| SerializeError::new(e, SerializeErrorKind::Member))?;
 
-            trace!(bytes = bytes.as_ref().len());
+            trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+            #[cfg(feature = "serde-mirror")]
+            pipe.mirror(&key, &member)
+                .map_err(|e| SerializeError::new(e, SerializeErrorKind::Member))?;
+
+            pipe.version(&key, &member);
 
             pipe.set(key, bytes.as_ref(), C::Member::expire());
 
             let key = RedisKey::GuildMembers { id: guild_id };
             pipe.sadd(key, user_id.get());
+
+            let key = RedisKey::GuildMembersOrdered { id: guild_id };
+            pipe.zadd(key, ordered_user_id(user_id), 0);
+
+            if C::Member::premium_since().is_some() && is_booster {
+                let key = RedisKey::GuildBoosters { id: guild_id };
+                pipe.sadd(key, user_id.get());
+            }
         }
 
         if C::User::WANTED {
@@ -60,7 +110,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.get()))]
     pub(crate) async fn store_member_update(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -85,7 +135,24 @@ impl<C: CacheConfig> RedisCache<C> {
 
         pipe.sadd(key, user_id.get());
 
+        let key = RedisKey::GuildMembersOrdered {
+            id: update.guild_id,
+        };
+
+        pipe.zadd(key, ordered_user_id(user_id), 0);
+
+        if C::Member::INDEX_NAMES {
+            Self::index_names(pipe, update.guild_id, &update.user, update.nick.as_deref());
+        }
+
+        let premium_fn = C::Member::premium_since();
+        let is_booster = update.premium_since.is_some();
+
         let Some(update_fn) = C::Member::on_member_update() else {
+            if premium_fn.is_some() {
+                Self::store_booster(pipe, update.guild_id, user_id, is_booster);
+            }
+
             return Ok(());
         };
 
@@ -95,9 +162,20 @@ impl<C: CacheConfig> RedisCache<C> {
         };
 
         let Some(mut member) = pipe.get::<C::Member<'static>>(key).await? else {
+            if premium_fn.is_some() {
+                Self::store_booster(pipe, update.guild_id, user_id, is_booster);
+            }
+
             return Ok(());
         };
 
+        if let Some(premium_fn) = premium_fn {
+            if premium_fn(&member).is_some() != is_booster {
+                trace!(is_booster, "Boost status changed");
+                Self::store_booster(pipe, update.guild_id, user_id, is_booster);
+            }
+        }
+
         update_fn(&mut member, update).map_err(|e| UpdateError::new(e, UpdateErrorKind::Member))?;
 
         let key = RedisKey::Member {
@@ -105,14 +183,19 @@ impl<C: CacheConfig> RedisCache<C> {
             user: user_id,
         };
 
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &member)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::Member))?;
+
         let bytes = member.into_bytes();
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
         pipe.set(key, &bytes, C::Member::expire());
 
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_members(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -136,7 +219,13 @@ impl<C: CacheConfig> RedisCache<C> {
                         .serialize_next(&member)
                         .map_err(|e| SerializeError::new(e, SerializeErrorKind::Member))?;
 
-                    trace!(bytes = bytes.as_ref().len());
+                    #[cfg(feature = "serde-mirror")]
+                    pipe.mirror(&key, &member)
+                        .map_err(|e| SerializeError::new(e, SerializeErrorKind::Member))?;
+
+                    pipe.version(&key, &member);
+
+                    trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                     Ok(((key, BytesWrap(bytes)), user_id.get()))
                 })
@@ -149,6 +238,33 @@ impl<C: CacheConfig> RedisCache<C> {
                 let key = RedisKey::GuildMembers { id: guild_id };
                 pipe.sadd(key, user_ids.as_slice());
 
+                let ordered_user_ids: Vec<_> = user_ids
+                    .iter()
+                    .map(|&user_id| (0, ordered_user_id(Id::new(user_id))))
+                    .collect();
+
+                let key = RedisKey::GuildMembersOrdered { id: guild_id };
+                pipe.zadd_multiple(key, &ordered_user_ids);
+
+                if C::Member::INDEX_NAMES {
+                    for member in members {
+                        Self::index_names(pipe, guild_id, &member.user, member.nick.as_deref());
+                    }
+                }
+
+                if C::Member::premium_since().is_some() {
+                    let booster_ids: Vec<_> = members
+                        .iter()
+                        .filter(|member| member.premium_since.is_some())
+                        .map(|member| member.user.id.get())
+                        .collect();
+
+                    if !booster_ids.is_empty() {
+                        let key = RedisKey::GuildBoosters { id: guild_id };
+                        pipe.sadd(key, booster_ids.as_slice());
+                    }
+                }
+
                 if C::User::WANTED {
                     for member in members {
                         let key = RedisKey::UserGuilds { id: member.user.id };
@@ -169,7 +285,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) async fn store_partial_member(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -184,6 +300,19 @@ impl<C: CacheConfig> RedisCache<C> {
             return Ok(());
         };
 
+        if C::Member::PARTIALS_UPDATE_ONLY && C::Member::WANTED {
+            let key = RedisKey::Member {
+                guild: guild_id,
+                user: user.id,
+            };
+
+            if !pipe.exists(key).await? {
+                trace!("Skipping partial member of uncached member");
+
+                return Ok(());
+            }
+        }
+
         if C::User::WANTED {
             let key = RedisKey::UserGuilds { id: user.id };
             pipe.sadd(key, guild_id.get());
@@ -196,6 +325,13 @@ impl<C: CacheConfig> RedisCache<C> {
         let key = RedisKey::GuildMembers { id: guild_id };
         pipe.sadd(key, user.id.get());
 
+        let key = RedisKey::GuildMembersOrdered { id: guild_id };
+        pipe.zadd(key, ordered_user_id(user.id), 0);
+
+        if C::Member::INDEX_NAMES {
+            Self::index_names(pipe, guild_id, user, partial_member.nick.as_deref());
+        }
+
         let Some(update_fn) = C::Member::update_via_partial() else {
             return Ok(());
         };
@@ -217,8 +353,13 @@ impl<C: CacheConfig> RedisCache<C> {
             user: user.id,
         };
 
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &member)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::PartialMember))?;
+
         let bytes = member.into_bytes();
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
         pipe.set(key, &bytes, C::Member::expire());
 
         Ok(())
@@ -232,6 +373,11 @@ impl<C: CacheConfig> RedisCache<C> {
     ) -> CacheResult<()> {
         self.delete_user(pipe, user_id, guild_id).await?;
 
+        if C::PRESENCE_STATUS {
+            let key = RedisKey::GuildPresenceStatus { id: guild_id };
+            pipe.hdel(key, user_id.get());
+        }
+
         if !C::Member::WANTED {
             return Ok(());
         }
@@ -245,8 +391,79 @@ impl<C: CacheConfig> RedisCache<C> {
         let key = RedisKey::GuildMembers { id: guild_id };
         pipe.srem(key, user_id.get());
 
+        let key = RedisKey::GuildMembersOrdered { id: guild_id };
+        pipe.zrem(key, ordered_user_id(user_id));
+
+        if C::Member::premium_since().is_some() {
+            Self::store_booster(pipe, guild_id, user_id, false);
+        }
+
+        if C::Member::INDEX_NAMES {
+            let keys = [
+                RedisKey::GuildMemberNames { id: guild_id },
+                RedisKey::GuildMemberNamesByUser { id: guild_id },
+            ];
+
+            pipe.eval(INDEX_NAMES_SCRIPT, &keys, user_id.get());
+        }
+
         Ok(())
     }
+
+    fn index_names(
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        user: &User,
+        nick: Option<&str>,
+    ) {
+        let mut names: Vec<_> = [Some(user.name.as_str()), user.global_name.as_deref(), nick]
+            .into_iter()
+            .flatten()
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_lowercase().replace('\n', " "))
+            .collect();
+
+        names.sort_unstable();
+        names.dedup();
+
+        let keys = [
+            RedisKey::GuildMemberNames { id: guild_id },
+            RedisKey::GuildMemberNamesByUser { id: guild_id },
+        ];
+
+        pipe.eval(INDEX_NAMES_SCRIPT, &keys, (user.id.get(), names));
+    }
+
+    fn store_booster(
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+        is_booster: bool,
+    ) {
+        let key = RedisKey::GuildBoosters { id: guild_id };
+
+        if is_booster {
+            pipe.sadd(key, user_id.get());
+        } else {
+            pipe.srem(key, user_id.get());
+        }
+    }
+}
+
+/// Zero-pads a user id so that the lexicographic order of
+/// [`RedisKey::GuildMembersOrdered`] members matches their numeric order.
+///
+/// Sorted set scores are doubles which can't represent snowflakes losslessly
+/// so all members share the same score and are ordered lexicographically
+/// instead.
+pub(crate) fn ordered_user_id(user_id: Id<UserMarker>) -> BytesWrap<[u8; 20]> {
+    let mut buf = itoa::Buffer::new();
+    let digits = buf.format(user_id.get()).as_bytes();
+
+    let mut padded = [b'0'; 20];
+    padded[20 - digits.len()..].copy_from_slice(digits);
+
+    BytesWrap(padded)
 }
 
 #[derive(Debug)]
@@ -264,25 +481,40 @@ impl IMetaKey for MemberMetaKey {
             .map(|(guild, user)| Self { guild, user })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::GuildMembers { id: self.guild };
         pipe.srem(key, self.user.get()).ignore();
+
+        let key = RedisKey::GuildMembersOrdered { id: self.guild };
+        pipe.zrem(key, ordered_user_id(self.user)).ignore();
+
+        let key = RedisKey::GuildBoosters { id: self.guild };
+        pipe.srem(key, self.user.get()).ignore();
+
+        // Only tracked if names are indexed but unindexing is harmless
+        // either way
+        let keys = [
+            RedisKey::GuildMemberNames { id: self.guild },
+            RedisKey::GuildMemberNamesByUser { id: self.guild },
+        ];
+
+        pipe.eval(INDEX_NAMES_SCRIPT, &keys, self.user.get());
     }
 }
 
 impl MemberMetaKey {
     pub(crate) async fn async_handle_expire(
         &self,
-        pipe: &mut Pipeline,
+        pipe: &mut ExpirePipe,
         conn: &mut DedicatedConnection,
     ) -> Result<(), ExpireError> {
-        debug_assert_eq!(pipe.cmd_iter().count(), 0);
+        debug_assert_eq!(pipe.len(), 0);
 
         let key = RedisKey::UserGuilds { id: self.user };
 
         let common_guild_count: usize = pipe
             .scard(key)
-            .query_async(conn)
+            .query(conn)
             .await
             .map_err(ExpireError::Pipe)?;
 
@@ -1,33 +1,86 @@
-use std::ptr;
+use std::{mem, ptr};
 
-use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer};
+use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer, Archived};
 use tracing::{instrument, trace};
 use twilight_model::{
     channel::Message,
     gateway::payload::incoming::MessageUpdate,
     id::{
-        marker::{ChannelMarker, MessageMarker},
+        marker::{ChannelMarker, MessageMarker, UserMarker},
         Id,
     },
 };
 
 use crate::{
     cache::{
-        meta::{atoi, HasArchived, IMeta, IMetaKey},
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedMessage, ReactionEvent},
     error::{
-        MetaError, MetaErrorKind, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind,
+        ExpireError, MetaError, MetaErrorKind, SerializeError, SerializeErrorKind, UpdateError,
+        UpdateErrorKind,
     },
     key::RedisKey,
-    redis::Pipeline,
     rkyv_util::id::IdRkyv,
+    util::fnv1a,
     CacheResult, RedisCache,
 };
 
+/// Tracks the size of a message and evicts the channel's oldest messages
+/// until the total size fits into the budget.
+///
+/// KEYS: channel messages, channel message bytes
+/// ARGV: message id, score, size, budget
+const BUDGET_SCRIPT: &str = r"
+local prev = tonumber(redis.call('HGET', KEYS[2], ARGV[1]) or 0)
+redis.call('HSET', KEYS[2], ARGV[1], ARGV[3])
+redis.call('ZADD', KEYS[1], ARGV[2], ARGV[1])
+
+local total = redis.call('HINCRBY', KEYS[2], 'total', tonumber(ARGV[3]) - prev)
+local budget = tonumber(ARGV[4])
+local evicted = {}
+
+while total > budget do
+    local oldest = redis.call('ZRANGE', KEYS[1], -1, -1)[1]
+
+    if not oldest then
+        break
+    end
+
+    redis.call('ZREM', KEYS[1], oldest)
+
+    local size = redis.call('HGET', KEYS[2], oldest)
+
+    if size then
+        redis.call('HDEL', KEYS[2], oldest)
+        total = redis.call('HINCRBY', KEYS[2], 'total', -tonumber(size))
+    end
+
+    evicted[#evicted + 1] = oldest
+end
+
+return evicted
+";
+
+/// Removes the tracked size of messages from the channel's total.
+///
+/// KEYS: channel message bytes
+/// ARGV: message ids
+const UNTRACK_SIZE_SCRIPT: &str = r"
+for i = 1, #ARGV do
+    local size = redis.call('HGET', KEYS[1], ARGV[i])
+
+    if size then
+        redis.call('HDEL', KEYS[1], ARGV[i])
+        redis.call('HINCRBY', KEYS[1], 'total', -tonumber(size))
+    end
+end
+";
+
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = msg.guild_id.map(Id::get)))]
     pub(crate) async fn store_message(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -36,6 +89,7 @@ impl<C: CacheConfig> RedisCache<C> {
         if C::Message::WANTED {
             let msg_id = msg.id;
             let channel_id = msg.channel_id;
+            let author_id = msg.author.id;
             let key = RedisKey::Message { id: msg_id };
             let score = -msg.timestamp.as_micros();
             let msg = C::Message::from_message(msg);
@@ -44,25 +98,40 @@ impl<C: CacheConfig> RedisCache<C> {
                 .serialize_one()
                 .map_err(|e| SerializeError::new(e, SerializeErrorKind::Message))?;
 
-            trace!(bytes = bytes.as_ref().len());
+            let meta = MessageMeta::new(channel_id, bytes.as_ref());
 
-            pipe.set(key, bytes.as_ref(), C::Message::expire());
+            if C::Message::SKIP_UNCHANGED && Self::is_message_unchanged(pipe, msg_id, &meta).await?
+            {
+                trace!(target: IO_TARGET, "Message unchanged; skipping write");
 
-            let key = RedisKey::Messages;
-            pipe.sadd(key, msg_id.get());
+                // The entry is kept as is but should live as long and be
+                // indexed as if it was written anew
+                if let Some(duration) = C::Message::expire() {
+                    pipe.expire(key, duration);
+                }
 
-            let key = RedisKey::ChannelMessages {
-                channel: channel_id,
-            };
-            pipe.zadd(key, msg_id.get(), score);
+                Self::index_message(pipe, msg_id, channel_id, author_id, score);
+            } else if Self::apply_byte_budget(pipe, msg_id, channel_id, score, bytes.as_ref())
+                .await?
+            {
+                trace!(target: IO_TARGET, "Message exceeds byte budget; skipping write");
+            } else {
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
-            if C::Message::expire().is_some() {
-                let meta = MessageMeta {
-                    channel: channel_id,
-                };
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &msg)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::Message))?;
 
-                meta.store(pipe, MessageMetaKey { msg: msg_id })
-                    .map_err(|e| MetaError::new(e, MetaErrorKind::Message))?;
+                pipe.version(&key, &msg);
+
+                pipe.set(key, bytes.as_ref(), C::Message::expire());
+
+                Self::index_message(pipe, msg_id, channel_id, author_id, score);
+
+                if Self::wants_message_meta() {
+                    meta.store(pipe, MessageMetaKey { msg: msg_id })
+                        .map_err(|e| MetaError::new(e, MetaErrorKind::Message))?;
+                }
             }
         }
 
@@ -87,7 +156,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.map(Id::get)))]
     pub(crate) async fn store_message_update(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -111,21 +180,30 @@ impl<C: CacheConfig> RedisCache<C> {
             return Ok(());
         };
 
+        if C::Message::EDIT_HISTORY > 0 {
+            let key = RedisKey::MessageHistory { id: update.id };
+            let len = C::Message::EDIT_HISTORY;
+            pipe.lpush_capped(key, message.bytes(), len, C::Message::expire());
+        }
+
         update_fn(&mut message, update)
             .map_err(|e| UpdateError::new(e, UpdateErrorKind::Message))?;
 
         let key = RedisKey::Message { id: update.id };
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &message)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::Message))?;
+
         let bytes = message.into_bytes();
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
         pipe.set(key, &bytes, C::Message::expire());
 
         let key = RedisKey::Messages;
         pipe.sadd(key, update.id.get());
 
-        if C::Message::expire().is_some() {
-            let meta = MessageMeta {
-                channel: update.channel_id,
-            };
+        if Self::wants_message_meta() {
+            let meta = MessageMeta::new(update.channel_id, &bytes);
 
             meta.store(pipe, MessageMetaKey { msg: update.id })
                 .map_err(|e| MetaError::new(e, MetaErrorKind::Message))?;
@@ -159,18 +237,30 @@ impl<C: CacheConfig> RedisCache<C> {
         update_fn(&mut message, event)
             .map_err(|e| UpdateError::new(e, UpdateErrorKind::Reaction))?;
 
+        if let Some((score_fn, guild_id)) = C::Message::starboard_score().zip(event.guild_id()) {
+            let key = RedisKey::GuildStarboard { id: guild_id };
+
+            match score_fn(&message) {
+                Some(score) => pipe.zadd(key, msg_id.get(), score),
+                None => pipe.zrem(key, msg_id.get()),
+            }
+        }
+
         let key = RedisKey::Message { id: msg_id };
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &message)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::Reaction))?;
+
         let bytes = message.into_bytes();
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
         pipe.set(key, &bytes, C::Message::expire());
 
         let key = RedisKey::Messages;
         pipe.sadd(key, msg_id.get());
 
-        if C::Message::expire().is_some() {
-            let meta = MessageMeta {
-                channel: channel_id,
-            };
+        if Self::wants_message_meta() {
+            let meta = MessageMeta::new(channel_id, &bytes);
 
             meta.store(pipe, MessageMetaKey { msg: msg_id })
                 .map_err(|e| MetaError::new(e, MetaErrorKind::Message))?;
@@ -200,9 +290,25 @@ impl<C: CacheConfig> RedisCache<C> {
         };
         pipe.zrem(key, msg_id.get());
 
-        if C::Message::expire().is_some() {
+        if C::User::WANTED {
+            let key = RedisKey::MessageAuthors;
+            pipe.hdel(key, msg_id.get());
+        }
+
+        if C::Message::CHANNEL_BYTE_BUDGET.is_some() {
+            let key = RedisKey::ChannelMessageBytes {
+                channel: channel_id,
+            };
+            pipe.eval(UNTRACK_SIZE_SCRIPT, &[key], msg_id.get());
+        }
+
+        if Self::wants_message_meta() {
             pipe.del(RedisKey::MessageMeta { id: msg_id });
         }
+
+        if C::Message::EDIT_HISTORY > 0 {
+            pipe.del(RedisKey::MessageHistory { id: msg_id });
+        }
     }
 
     pub(crate) fn delete_messages(
@@ -215,7 +321,7 @@ impl<C: CacheConfig> RedisCache<C> {
             return;
         }
 
-        let keys: Vec<_> = if C::Message::expire().is_some() {
+        let keys: Vec<_> = if Self::wants_message_meta() {
             msg_ids
                 .iter()
                 .copied()
@@ -230,6 +336,7 @@ impl<C: CacheConfig> RedisCache<C> {
         };
 
         pipe.del(keys);
+        Self::delete_message_histories(pipe, msg_ids);
 
         #[allow(clippy::items_after_statements)]
         const fn ids_to_u64(msg_ids: &[Id<MessageMarker>]) -> &[u64] {
@@ -249,6 +356,165 @@ impl<C: CacheConfig> RedisCache<C> {
             channel: channel_id,
         };
         pipe.zrem(key, raw_msg_ids);
+
+        if C::User::WANTED {
+            let key = RedisKey::MessageAuthors;
+            pipe.hdel(key, raw_msg_ids);
+        }
+
+        if C::Message::CHANNEL_BYTE_BUDGET.is_some() {
+            let key = RedisKey::ChannelMessageBytes {
+                channel: channel_id,
+            };
+            pipe.eval(UNTRACK_SIZE_SCRIPT, &[key], raw_msg_ids);
+        }
+    }
+
+    /// Track the size of a message and evict the channel's oldest messages
+    /// if its [`CHANNEL_BYTE_BUDGET`] is exceeded.
+    ///
+    /// Returns `true` if the message itself was evicted and thus should not
+    /// be written.
+    ///
+    /// [`CHANNEL_BYTE_BUDGET`]: ICachedMessage::CHANNEL_BYTE_BUDGET
+    async fn apply_byte_budget(
+        pipe: &mut Pipe<'_, C>,
+        msg_id: Id<MessageMarker>,
+        channel_id: Id<ChannelMarker>,
+        score: i64,
+        bytes: &[u8],
+    ) -> CacheResult<bool> {
+        // The script writes right away which must not happen in shadow mode
+        let Some(budget) = C::Message::CHANNEL_BYTE_BUDGET.filter(|_| !C::SHADOW) else {
+            return Ok(false);
+        };
+
+        let keys = [
+            RedisKey::ChannelMessages {
+                channel: channel_id,
+            },
+            RedisKey::ChannelMessageBytes {
+                channel: channel_id,
+            },
+        ];
+
+        let args = (msg_id.get(), score, bytes.len(), budget);
+        let evicted: Vec<u64> = pipe.eval_query(BUDGET_SCRIPT, &keys, args).await?;
+
+        if evicted.is_empty() {
+            return Ok(false);
+        }
+
+        trace!(
+            evicted = evicted.len(),
+            "Evicted messages due to byte budget"
+        );
+
+        let evicted: Vec<_> = evicted.into_iter().filter_map(Id::new_checked).collect();
+        let is_evicted = evicted.contains(&msg_id);
+
+        // Sizes were already untracked by the script
+        let keys: Vec<_> = if Self::wants_message_meta() {
+            evicted
+                .iter()
+                .copied()
+                .flat_map(|id| [RedisKey::Message { id }, RedisKey::MessageMeta { id }])
+                .collect()
+        } else {
+            evicted
+                .iter()
+                .copied()
+                .map(|id| RedisKey::Message { id })
+                .collect()
+        };
+
+        pipe.del(keys);
+        Self::delete_message_histories(pipe, &evicted);
+
+        let raw_ids: Vec<_> = evicted.iter().copied().map(Id::get).collect();
+
+        if C::User::WANTED {
+            pipe.hdel(RedisKey::MessageAuthors, raw_ids.as_slice());
+        }
+
+        pipe.srem(RedisKey::Messages, raw_ids);
+
+        Ok(is_evicted)
+    }
+
+    fn delete_message_histories(pipe: &mut Pipe<'_, C>, msg_ids: &[Id<MessageMarker>]) {
+        if C::Message::EDIT_HISTORY == 0 || msg_ids.is_empty() {
+            return;
+        }
+
+        let keys: Vec<_> = msg_ids
+            .iter()
+            .map(|&id| RedisKey::MessageHistory { id })
+            .collect();
+
+        pipe.del(keys);
+    }
+
+    /// Add a message to the sets that index messages.
+    fn index_message(
+        pipe: &mut Pipe<'_, C>,
+        msg_id: Id<MessageMarker>,
+        channel_id: Id<ChannelMarker>,
+        author_id: Id<UserMarker>,
+        score: i64,
+    ) {
+        let key = RedisKey::Messages;
+        pipe.sadd(key, msg_id.get());
+
+        let key = RedisKey::ChannelMessages {
+            channel: channel_id,
+        };
+        pipe.zadd(key, msg_id.get(), score);
+
+        if C::User::WANTED {
+            let key = RedisKey::MessageAuthors;
+            pipe.hset(key, msg_id.get(), author_id.get());
+        }
+    }
+
+    /// Whether messages require a [`MessageMeta`] entry.
+    fn wants_message_meta() -> bool {
+        C::Message::expire().is_some() || C::Message::SKIP_UNCHANGED
+    }
+
+    /// Check whether a message is still cached and its stored meta contains
+    /// the same hash.
+    async fn is_message_unchanged(
+        pipe: &mut Pipe<'_, C>,
+        msg_id: Id<MessageMarker>,
+        meta: &MessageMeta,
+    ) -> CacheResult<bool> {
+        let key = RedisKey::MessageMeta { id: msg_id };
+
+        let Some(bytes) = pipe.get_bytes(key).await? else {
+            return Ok(false);
+        };
+
+        // Metas without hash are outdated and get overwritten
+        if bytes.len() != mem::size_of::<Archived<MessageMeta>>() {
+            return Ok(false);
+        }
+
+        #[cfg(feature = "bytecheck")]
+        let archived = rkyv::access::<Archived<MessageMeta>, BoxedError>(&bytes)
+            .map_err(crate::error::CacheError::Validation)?;
+
+        #[cfg(not(feature = "bytecheck"))]
+        let archived = unsafe { rkyv::access_unchecked::<Archived<MessageMeta>>(&bytes) };
+
+        if archived.hash != meta.hash {
+            return Ok(false);
+        }
+
+        // The meta may outlive its message, e.g. if the message was evicted
+        let key = RedisKey::Message { id: msg_id };
+
+        pipe.exists(key).await
     }
 }
 
@@ -262,9 +528,12 @@ impl IMetaKey for MessageMetaKey {
         split.next().and_then(atoi).map(|msg| Self { msg })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Messages;
         pipe.srem(key, self.msg.get()).ignore();
+
+        let key = RedisKey::MessageAuthors;
+        pipe.hdel(key, self.msg.get()).ignore();
     }
 }
 
@@ -275,11 +544,40 @@ impl HasArchived for MessageMetaKey {
         RedisKey::MessageMeta { id: self.msg }
     }
 
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &rkyv::Archived<Self::Meta>) {
-        let key = RedisKey::ChannelMessages {
-            channel: archived.channel.into(),
-        };
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &rkyv::Archived<Self::Meta>) {
+        self.untrack(pipe, archived.channel.into());
+    }
+
+    fn handle_bytes(&self, pipe: &mut ExpirePipe, bytes: &[u8]) -> Result<(), ExpireError> {
+        if bytes.len() == mem::size_of::<Archived<LegacyMessageMeta>>() {
+            #[cfg(feature = "bytecheck")]
+            let archived = rkyv::access::<Archived<LegacyMessageMeta>, BoxedError>(bytes)
+                .map_err(ExpireError::Validation)?;
+
+            #[cfg(not(feature = "bytecheck"))]
+            let archived = unsafe { rkyv::access_unchecked::<Archived<LegacyMessageMeta>>(bytes) };
+
+            self.untrack(pipe, archived.channel.into());
+
+            return Ok(());
+        }
+
+        let archived = MessageMeta::as_archive(bytes)?;
+        self.handle_archived(pipe, archived);
+
+        Ok(())
+    }
+}
+
+impl MessageMetaKey {
+    fn untrack(&self, pipe: &mut ExpirePipe, channel: Id<ChannelMarker>) {
+        let key = RedisKey::ChannelMessages { channel };
         pipe.zrem(key, self.msg.get()).ignore();
+
+        // Only tracked if a byte budget is configured but untracking is
+        // harmless either way
+        let key = RedisKey::ChannelMessageBytes { channel };
+        pipe.eval(UNTRACK_SIZE_SCRIPT, &[key], self.msg.get());
     }
 }
 
@@ -287,13 +585,34 @@ impl HasArchived for MessageMetaKey {
 pub(crate) struct MessageMeta {
     #[rkyv(with = IdRkyv)]
     channel: Id<ChannelMarker>,
+    /// Hash of the serialized message.
+    hash: u64,
+}
+
+/// Layout of [`MessageMeta`] before it contained the message hash.
+///
+/// Metas that were stored in this layout may still be around until their
+/// message expires.
+#[derive(rkyv::Archive)]
+struct LegacyMessageMeta {
+    #[rkyv(with = IdRkyv)]
+    channel: Id<ChannelMarker>,
+}
+
+impl MessageMeta {
+    const fn new(channel: Id<ChannelMarker>, bytes: &[u8]) -> Self {
+        Self {
+            channel,
+            hash: fnv1a(bytes),
+        }
+    }
 }
 
 impl IMeta<MessageMetaKey> for MessageMeta {
-    type Bytes = [u8; 8];
+    type Bytes = [u8; 16];
 
     fn to_bytes(&self) -> Result<Self::Bytes, BoxedError> {
-        let mut bytes = [0; 8];
+        let mut bytes = [0; 16];
         to_bytes_in(self, Buffer::from(&mut bytes))?;
 
         Ok(bytes)
@@ -1,14 +1,21 @@
+pub(super) mod activity;
+pub(super) mod auto_moderation_rule;
+pub(super) mod ban;
 pub(super) mod channel;
 pub(super) mod current_user;
 pub(super) mod emoji;
 pub(super) mod guild;
 pub(super) mod integration;
+pub(super) mod invite;
 pub(super) mod member;
 pub(super) mod message;
 pub(super) mod presence;
 pub(super) mod role;
+pub(super) mod scheduled_event;
 pub(super) mod stage_instance;
 pub(super) mod sticker;
+pub(super) mod thread_member;
+pub(super) mod tombstone;
 pub(super) mod user;
 pub(super) mod voice_state;
 
@@ -27,7 +34,11 @@ use crate::{
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(
+        level = "trace",
+        skip_all,
+        fields(guild_id = interaction.guild_id.map(Id::get))
+    )]
     pub(crate) async fn store_interaction(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -64,7 +75,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) async fn store_unavailable_guild(
         &self,
         pipe: &mut Pipe<'_, C>,
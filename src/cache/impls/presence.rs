@@ -1,6 +1,6 @@
 use tracing::{instrument, trace};
 use twilight_model::{
-    gateway::presence::{Presence, UserOrId},
+    gateway::presence::{Presence, Status, UserOrId},
     id::{
         marker::{GuildMarker, UserMarker},
         Id,
@@ -9,43 +9,94 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, IMetaKey},
+        meta::{atoi, ExpirePipe, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedPresence, SerializeMany},
     error::{SerializeError, SerializeErrorKind},
     key::RedisKey,
-    redis::Pipeline,
-    util::{BytesWrap, ZippedVecs},
+    util::{fnv1a, BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = presence.guild_id.get()))]
     pub(crate) fn store_presence(
         &self,
         pipe: &mut Pipe<'_, C>,
         presence: &Presence,
     ) -> CacheResult<()> {
         if C::Presence::WANTED {
-            let guild_id = presence.guild_id;
-            let user_id = presence.user.id();
-            let key = RedisKey::Presence {
-                guild: guild_id,
-                user: user_id,
-            };
-            let presence = C::Presence::from_presence(presence);
-
-            let bytes = presence
+            let cached = C::Presence::from_presence(presence);
+
+            let bytes = cached
                 .serialize_one()
                 .map_err(|e| SerializeError::new(e, SerializeErrorKind::Presence))?;
 
-            trace!(bytes = bytes.as_ref().len());
+            Self::write_presence(pipe, presence, &cached, bytes.as_ref())?;
+            self.store_presence_stamps(pipe, presence.guild_id, &[presence.user.id().get()]);
+        }
 
-            pipe.set(key, bytes.as_ref(), C::Presence::expire());
+        if C::PRESENCE_STATUS {
+            Self::store_presence_status(pipe, presence);
+        }
 
-            let key = RedisKey::GuildPresences { id: guild_id };
-            pipe.sadd(key, user_id.get());
+        if let UserOrId::User(ref user) = presence.user {
+            self.store_user(pipe, user)?;
+        }
+
+        Ok(())
+    }
+
+    /// Same as [`RedisCache::store_presence`] but skips writing the presence
+    /// if it is unchanged and [`ICachedPresence::SKIP_UNCHANGED`] is enabled.
+    #[instrument(level = "trace", skip_all, fields(guild_id = presence.guild_id.get()))]
+    pub(crate) async fn store_presence_update(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        presence: &Presence,
+    ) -> CacheResult<()> {
+        if !(C::Presence::WANTED && C::Presence::SKIP_UNCHANGED) {
+            return self.store_presence(pipe, presence);
+        }
+
+        let key = RedisKey::Presence {
+            guild: presence.guild_id,
+            user: presence.user.id(),
+        };
+        let cached = C::Presence::from_presence(presence);
+
+        let bytes = cached
+            .serialize_one()
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::Presence))?;
+
+        let hashes_key = RedisKey::GuildPresenceHashes {
+            id: presence.guild_id,
+        };
+
+        let stored: Option<u64> = pipe
+            .hget_if_exists(key.clone(), hashes_key, presence.user.id().get())
+            .await?;
+
+        if stored == Some(fnv1a(bytes.as_ref())) {
+            trace!(target: IO_TARGET, "Presence unchanged; skipping write");
+
+            #[cfg(feature = "metrics")]
+            crate::cache::metrics::record_skipped_presence_write();
+
+            if let Some(duration) = C::Presence::expire() {
+                pipe.expire(key, duration);
+            }
+        } else {
+            Self::write_presence(pipe, presence, &cached, bytes.as_ref())?;
+        }
+
+        // The presence is fresh even if it did not change
+        self.store_presence_stamps(pipe, presence.guild_id, &[presence.user.id().get()]);
+
+        if C::PRESENCE_STATUS {
+            Self::store_presence_status(pipe, presence);
         }
 
         if let UserOrId::User(ref user) = presence.user {
@@ -55,7 +106,43 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    // Only fallible when mirroring
+    #[cfg_attr(not(feature = "serde-mirror"), allow(clippy::unnecessary_wraps))]
+    fn write_presence(
+        pipe: &mut Pipe<'_, C>,
+        presence: &Presence,
+        cached: &C::Presence<'_>,
+        bytes: &[u8],
+    ) -> CacheResult<()> {
+        let guild_id = presence.guild_id;
+        let user_id = presence.user.id();
+        let key = RedisKey::Presence {
+            guild: guild_id,
+            user: user_id,
+        };
+
+        trace!(target: IO_TARGET, bytes = bytes.len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, cached)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::Presence))?;
+
+        pipe.version(&key, cached);
+
+        pipe.set(key, bytes, C::Presence::expire());
+
+        let key = RedisKey::GuildPresences { id: guild_id };
+        pipe.sadd(key, user_id.get());
+
+        if C::Presence::SKIP_UNCHANGED {
+            let key = RedisKey::GuildPresenceHashes { id: guild_id };
+            pipe.hset(key, user_id.get(), fnv1a(bytes));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_presences(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -64,6 +151,7 @@ impl<C: CacheConfig> RedisCache<C> {
     ) -> CacheResult<()> {
         if C::Presence::WANTED {
             let mut serializer = C::Presence::serialize_many();
+            let mut hashes = Vec::new();
 
             let (presence_entries, user_ids) = presences
                 .iter()
@@ -80,7 +168,17 @@ impl<C: CacheConfig> RedisCache<C> {
                         .serialize_next(&presence)
                         .map_err(|e| SerializeError::new(e, SerializeErrorKind::Presence))?;
 
-                    trace!(bytes = bytes.as_ref().len());
+                    #[cfg(feature = "serde-mirror")]
+                    pipe.mirror(&key, &presence)
+                        .map_err(|e| SerializeError::new(e, SerializeErrorKind::Presence))?;
+
+                    pipe.version(&key, &presence);
+
+                    trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+                    if C::Presence::SKIP_UNCHANGED {
+                        hashes.push((user_id.get(), fnv1a(bytes.as_ref())));
+                    }
 
                     Ok(((key, BytesWrap(bytes)), user_id.get()))
                 })
@@ -92,9 +190,20 @@ impl<C: CacheConfig> RedisCache<C> {
 
                 let key = RedisKey::GuildPresences { id: guild_id };
                 pipe.sadd(key, user_ids.as_slice());
+
+                if !hashes.is_empty() {
+                    let key = RedisKey::GuildPresenceHashes { id: guild_id };
+                    pipe.hset_multiple(key, &hashes);
+                }
+
+                self.store_presence_stamps(pipe, guild_id, &user_ids);
             }
         }
 
+        if C::PRESENCE_STATUS {
+            Self::store_presence_statuses(pipe, guild_id, presences);
+        }
+
         let users = presences.iter().filter_map(|presence| match presence.user {
             UserOrId::User(ref user) => Some(user),
             UserOrId::UserId { .. } => None,
@@ -104,6 +213,70 @@ impl<C: CacheConfig> RedisCache<C> {
 
         Ok(())
     }
+
+    fn store_presence_status(pipe: &mut Pipe<'_, C>, presence: &Presence) {
+        let key = RedisKey::GuildPresenceStatus {
+            id: presence.guild_id,
+        };
+        let user_id = presence.user.id().get();
+
+        match status_byte(presence.status) {
+            Some(byte) => pipe.hset(key, user_id, byte),
+            None => pipe.hdel(key, user_id),
+        }
+    }
+
+    fn store_presence_statuses(
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        presences: &[Presence],
+    ) {
+        let mut statuses = Vec::with_capacity(presences.len());
+        let mut offline = Vec::new();
+
+        for presence in presences {
+            let user_id = presence.user.id().get();
+
+            match status_byte(presence.status) {
+                Some(byte) => statuses.push((user_id, byte)),
+                None => offline.push(user_id),
+            }
+        }
+
+        let key = RedisKey::GuildPresenceStatus { id: guild_id };
+
+        if !statuses.is_empty() {
+            pipe.hset_multiple(key.clone(), &statuses);
+        }
+
+        if !offline.is_empty() {
+            pipe.hdel(key, offline);
+        }
+    }
+}
+
+/// The byte that represents a [`Status`] in
+/// [`RedisKey::GuildPresenceStatus`].
+///
+/// Offline users are not stored so `None` is returned for them. Invisible
+/// users appear offline to everyone else so they are treated the same.
+const fn status_byte(status: Status) -> Option<&'static [u8]> {
+    match status {
+        Status::DoNotDisturb => Some(b"d"),
+        Status::Idle => Some(b"i"),
+        Status::Online => Some(b"o"),
+        Status::Invisible | Status::Offline => None,
+    }
+}
+
+/// Parse a [`Status`] from its byte in [`RedisKey::GuildPresenceStatus`].
+pub(crate) fn status_from_byte(bytes: &[u8]) -> Option<Status> {
+    match bytes {
+        b"d" => Some(Status::DoNotDisturb),
+        b"i" => Some(Status::Idle),
+        b"o" => Some(Status::Online),
+        _ => None,
+    }
 }
 
 #[derive(Debug)]
@@ -122,8 +295,14 @@ impl IMetaKey for PresenceMetaKey {
             .map(|(guild, user)| Self { guild, user })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::GuildPresences { id: self.guild };
         pipe.srem(key, self.user.get());
+
+        let key = RedisKey::GuildPresenceStamps { id: self.guild };
+        pipe.zrem(key, self.user.get());
+
+        let key = RedisKey::GuildPresenceHashes { id: self.guild };
+        pipe.hdel(key, self.user.get());
     }
 }
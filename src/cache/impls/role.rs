@@ -10,20 +10,21 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, HasArchived, IMeta, IMetaKey},
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedRole, SerializeMany},
-    error::{MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
+    error::{CacheError, MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
     key::RedisKey,
-    redis::Pipeline,
+    redis::Cmd,
     rkyv_util::id::IdRkyv,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_role(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -42,7 +43,13 @@ impl<C: CacheConfig> RedisCache<C> {
             .serialize_one()
             .map_err(|e| SerializeError::new(e, SerializeErrorKind::Role))?;
 
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &role)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::Role))?;
+
+        pipe.version(&key, &role);
 
         pipe.set(key, bytes.as_ref(), C::Role::expire());
 
@@ -52,7 +59,7 @@ impl<C: CacheConfig> RedisCache<C> {
         let key = RedisKey::Roles;
         pipe.sadd(key, id.get());
 
-        if C::Role::expire().is_some() {
+        if stores_role_meta::<C>() {
             RoleMeta { guild: guild_id }
                 .store(pipe, RoleMetaKey { role: id })
                 .map_err(|e| MetaError::new(e, MetaErrorKind::Role))?;
@@ -61,7 +68,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_roles<'a, I>(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -75,7 +82,7 @@ impl<C: CacheConfig> RedisCache<C> {
             return Ok(());
         }
 
-        let with_expire = C::Role::expire().is_some();
+        let with_meta = stores_role_meta::<C>();
 
         let mut serializer = C::Role::serialize_many();
 
@@ -86,7 +93,7 @@ impl<C: CacheConfig> RedisCache<C> {
                 let key = RedisKey::Role { id };
                 let cached = C::Role::from_role(role);
 
-                if with_expire {
+                if with_meta {
                     RoleMeta { guild: guild_id }
                         .store(pipe, RoleMetaKey { role: id })
                         .map_err(|e| MetaError::new(e, MetaErrorKind::Role))?;
@@ -96,7 +103,13 @@ impl<C: CacheConfig> RedisCache<C> {
                     .serialize_next(&cached)
                     .map_err(|e| SerializeError::new(e, SerializeErrorKind::Role))?;
 
-                trace!(bytes = bytes.as_ref().len());
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &cached)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::Role))?;
+
+                pipe.version(&key, &cached);
+
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                 Ok(((key, BytesWrap(bytes)), id.get()))
             })
@@ -137,10 +150,40 @@ impl<C: CacheConfig> RedisCache<C> {
         let key = RedisKey::Roles;
         pipe.srem(key, role_id.get());
 
-        if C::Role::expire().is_some() {
+        if stores_role_meta::<C>() {
             pipe.del(RedisKey::RoleMeta { id: role_id });
         }
     }
+
+    /// Get the id of the guild that a role belongs to.
+    ///
+    /// Requires either [`ICachedRole::STORE_GUILD_ID`] to be enabled or roles
+    /// to expire.
+    pub async fn role_guild_id(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> CacheResult<Option<Id<GuildMarker>>> {
+        let mut conn = self.connection().await?;
+
+        let bytes: Option<Vec<u8>> =
+            Cmd::get(self.namespace.key(RedisKey::RoleMeta { id: role_id }))
+                .query_async(&mut conn)
+                .await
+                .map_err(CacheError::Redis)?;
+
+        let Some(bytes) = bytes.filter(|bytes| !bytes.is_empty()) else {
+            return Ok(None);
+        };
+
+        let archived = RoleMeta::as_archive(&bytes)?;
+
+        Ok(Some(archived.guild.into()))
+    }
+}
+
+/// Whether [`RoleMeta`] entries are stored alongside roles.
+pub(crate) fn stores_role_meta<C: CacheConfig>() -> bool {
+    C::Role::STORE_GUILD_ID || C::Role::expire().is_some()
 }
 
 #[derive(Debug)]
@@ -153,7 +196,7 @@ impl IMetaKey for RoleMetaKey {
         split.next().and_then(atoi).map(|role| Self { role })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Roles;
         pipe.srem(key, self.role.get()).ignore();
     }
@@ -166,7 +209,7 @@ impl HasArchived for RoleMetaKey {
         RedisKey::RoleMeta { id: self.role }
     }
 
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &Archived<Self::Meta>) {
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
         let key = RedisKey::GuildRoles {
             id: archived.guild.into(),
         };
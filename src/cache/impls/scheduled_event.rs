@@ -0,0 +1,211 @@
+use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer, Archived};
+use tracing::{instrument, trace};
+use twilight_model::{
+    guild::scheduled_event::{GuildScheduledEvent, Status},
+    id::{
+        marker::{GuildMarker, ScheduledEventMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cache::{
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
+        pipe::Pipe,
+        IO_TARGET,
+    },
+    config::{CacheConfig, Cacheable, ICachedScheduledEvent},
+    error::{
+        MetaError, MetaErrorKind, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind,
+    },
+    key::RedisKey,
+    rkyv_util::id::IdRkyv,
+    CacheResult, RedisCache,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    #[instrument(level = "trace", skip_all, fields(guild_id = scheduled_event.guild_id.get()))]
+    pub(crate) fn store_scheduled_event(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        scheduled_event: &GuildScheduledEvent,
+    ) -> CacheResult<()> {
+        if !C::ScheduledEvent::WANTED {
+            return Ok(());
+        }
+
+        let scheduled_event_id = scheduled_event.id;
+        let guild_id = scheduled_event.guild_id;
+
+        // Events that are over won't be updated anymore
+        if matches!(
+            scheduled_event.status,
+            Status::Completed | Status::Cancelled
+        ) {
+            self.delete_scheduled_event(pipe, guild_id, scheduled_event_id);
+
+            return Ok(());
+        }
+
+        let key = RedisKey::ScheduledEvent {
+            id: scheduled_event_id,
+        };
+        let scheduled_event = C::ScheduledEvent::from_scheduled_event(scheduled_event);
+
+        let bytes = scheduled_event
+            .serialize_one()
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::ScheduledEvent))?;
+
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &scheduled_event)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::ScheduledEvent))?;
+
+        pipe.version(&key, &scheduled_event);
+
+        pipe.set(key, bytes.as_ref(), C::ScheduledEvent::expire());
+
+        let key = RedisKey::GuildScheduledEvents { id: guild_id };
+        pipe.sadd(key, scheduled_event_id.get());
+
+        let key = RedisKey::ScheduledEvents;
+        pipe.sadd(key, scheduled_event_id.get());
+
+        if C::ScheduledEvent::expire().is_some() {
+            let key = ScheduledEventMetaKey {
+                scheduled_event: scheduled_event_id,
+            };
+
+            ScheduledEventMeta { guild: guild_id }
+                .store(pipe, key)
+                .map_err(|e| MetaError::new(e, MetaErrorKind::ScheduledEvent))?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip_all, fields(scheduled_event_id = scheduled_event_id.get()))]
+    pub(crate) async fn store_scheduled_event_user_count(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        scheduled_event_id: Id<ScheduledEventMarker>,
+        delta: i64,
+    ) -> CacheResult<()> {
+        if !C::ScheduledEvent::WANTED {
+            return Ok(());
+        }
+
+        let Some(update_fn) = C::ScheduledEvent::on_user_count_update() else {
+            return Ok(());
+        };
+
+        let key = RedisKey::ScheduledEvent {
+            id: scheduled_event_id,
+        };
+
+        let Some(mut scheduled_event) = pipe.get::<C::ScheduledEvent<'static>>(key).await? else {
+            return Ok(());
+        };
+
+        update_fn(&mut scheduled_event, delta)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::ScheduledEvent))?;
+
+        let key = RedisKey::ScheduledEvent {
+            id: scheduled_event_id,
+        };
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &scheduled_event)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::ScheduledEvent))?;
+
+        let bytes = scheduled_event.into_bytes();
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        pipe.set(key, &bytes, C::ScheduledEvent::expire());
+
+        Ok(())
+    }
+
+    pub(crate) fn delete_scheduled_event(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        scheduled_event_id: Id<ScheduledEventMarker>,
+    ) {
+        if !C::ScheduledEvent::WANTED {
+            return;
+        }
+
+        let key = RedisKey::ScheduledEvent {
+            id: scheduled_event_id,
+        };
+        pipe.del(key);
+
+        let key = RedisKey::GuildScheduledEvents { id: guild_id };
+        pipe.srem(key, scheduled_event_id.get());
+
+        let key = RedisKey::ScheduledEvents;
+        pipe.srem(key, scheduled_event_id.get());
+
+        if C::ScheduledEvent::expire().is_some() {
+            let key = RedisKey::ScheduledEventMeta {
+                id: scheduled_event_id,
+            };
+            pipe.del(key);
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ScheduledEventMetaKey {
+    scheduled_event: Id<ScheduledEventMarker>,
+}
+
+impl IMetaKey for ScheduledEventMetaKey {
+    fn parse<'a>(split: &mut impl Iterator<Item = &'a [u8]>) -> Option<Self> {
+        split
+            .next()
+            .and_then(atoi)
+            .map(|scheduled_event| Self { scheduled_event })
+    }
+
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
+        let key = RedisKey::ScheduledEvents;
+        pipe.srem(key, self.scheduled_event.get()).ignore();
+    }
+}
+
+impl HasArchived for ScheduledEventMetaKey {
+    type Meta = ScheduledEventMeta;
+
+    fn redis_key(&self) -> RedisKey {
+        RedisKey::ScheduledEventMeta {
+            id: self.scheduled_event,
+        }
+    }
+
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
+        let key = RedisKey::GuildScheduledEvents {
+            id: archived.guild.into(),
+        };
+        pipe.srem(key, self.scheduled_event.get());
+    }
+}
+
+#[derive(rkyv::Archive, rkyv::Serialize)]
+pub(crate) struct ScheduledEventMeta {
+    #[rkyv(with = IdRkyv)]
+    guild: Id<GuildMarker>,
+}
+
+impl IMeta<ScheduledEventMetaKey> for ScheduledEventMeta {
+    type Bytes = [u8; 8];
+
+    fn to_bytes(&self) -> Result<Self::Bytes, BoxedError> {
+        let mut bytes = [0; 8];
+        to_bytes_in(self, Buffer::from(&mut bytes))?;
+
+        Ok(bytes)
+    }
+}
@@ -2,6 +2,7 @@ use rkyv::{api::high::to_bytes_in, rancor::BoxedError, ser::writer::Buffer, Arch
 use tracing::{instrument, trace};
 use twilight_model::{
     channel::StageInstance,
+    gateway::payload::incoming::StageInstanceUpdate,
     id::{
         marker::{GuildMarker, StageMarker},
         Id,
@@ -10,20 +11,22 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, HasArchived, IMeta, IMetaKey},
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedStageInstance, SerializeMany},
-    error::{MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
+    error::{
+        MetaError, MetaErrorKind, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind,
+    },
     key::RedisKey,
-    redis::Pipeline,
     rkyv_util::id::IdRkyv,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = stage_instance.guild_id.get()))]
     pub(crate) fn store_stage_instance(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -44,7 +47,13 @@ impl<C: CacheConfig> RedisCache<C> {
             .serialize_one()
             .map_err(|e| SerializeError::new(e, SerializeErrorKind::StageInstance))?;
 
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &stage_instance)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::StageInstance))?;
+
+        pipe.version(&key, &stage_instance);
 
         pipe.set(key, bytes.as_ref(), C::StageInstance::expire());
 
@@ -67,7 +76,54 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = update.guild_id.get()))]
+    pub(crate) async fn store_stage_instance_update(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        update: &StageInstanceUpdate,
+    ) -> CacheResult<()> {
+        if !C::StageInstance::WANTED {
+            return Ok(());
+        }
+
+        let Some(update_fn) = C::StageInstance::on_stage_update() else {
+            return self.store_stage_instance(pipe, update);
+        };
+
+        let key = RedisKey::StageInstance { id: update.id };
+
+        let Some(mut stage_instance) = pipe.get::<C::StageInstance<'static>>(key).await? else {
+            return self.store_stage_instance(pipe, update);
+        };
+
+        update_fn(&mut stage_instance, update)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::StageInstance))?;
+
+        let key = RedisKey::StageInstance { id: update.id };
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &stage_instance)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::StageInstance))?;
+
+        let bytes = stage_instance.into_bytes();
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        pipe.set(key, &bytes, C::StageInstance::expire());
+
+        if C::StageInstance::expire().is_some() {
+            let key = StageInstanceMetaKey { stage: update.id };
+
+            StageInstanceMeta {
+                guild: update.guild_id,
+            }
+            .store(pipe, key)
+            .map_err(|e| MetaError::new(e, MetaErrorKind::StageInstance))?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_stage_instances(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -91,7 +147,13 @@ impl<C: CacheConfig> RedisCache<C> {
                     .serialize_next(&stage_instance)
                     .map_err(|e| SerializeError::new(e, SerializeErrorKind::StageInstance))?;
 
-                trace!(bytes = bytes.as_ref().len());
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &stage_instance)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::StageInstance))?;
+
+                pipe.version(&key, &stage_instance);
+
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                 Ok(((key, BytesWrap(bytes)), id.get()))
             })
@@ -166,7 +228,7 @@ impl IMetaKey for StageInstanceMetaKey {
         split.next().and_then(atoi).map(|stage| Self { stage })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::StageInstances;
         pipe.srem(key, self.stage.get()).ignore();
     }
@@ -179,7 +241,7 @@ impl HasArchived for StageInstanceMetaKey {
         RedisKey::StageInstanceMeta { id: self.stage }
     }
 
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &Archived<Self::Meta>) {
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
         let key = RedisKey::GuildStageInstances {
             id: archived.guild.into(),
         };
@@ -10,20 +10,20 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, HasArchived, IMeta, IMetaKey},
+        meta::{atoi, ExpirePipe, HasArchived, IMeta, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedSticker, SerializeMany},
     error::{MetaError, MetaErrorKind, SerializeError, SerializeErrorKind},
     key::RedisKey,
-    redis::Pipeline,
     rkyv_util::id::IdRkyv,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_stickers(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -47,7 +47,13 @@ impl<C: CacheConfig> RedisCache<C> {
                     .serialize_next(&sticker)
                     .map_err(|e| SerializeError::new(e, SerializeErrorKind::Sticker))?;
 
-                trace!(bytes = bytes.as_ref().len());
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &sticker)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::Sticker))?;
+
+                pipe.version(&key, &sticker);
+
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                 Ok(((key, BytesWrap(bytes)), id.get()))
             })
@@ -93,7 +99,7 @@ impl IMetaKey for StickerMetaKey {
         split.next().and_then(atoi).map(|sticker| Self { sticker })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Stickers;
         pipe.srem(key, self.sticker.get()).ignore();
     }
@@ -106,7 +112,7 @@ impl HasArchived for StickerMetaKey {
         RedisKey::StickerMeta { id: self.sticker }
     }
 
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &Archived<Self::Meta>) {
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>) {
         let key = RedisKey::GuildStickers {
             id: archived.guild.into(),
         };
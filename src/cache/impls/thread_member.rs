@@ -0,0 +1,199 @@
+use tracing::{instrument, trace};
+use twilight_model::{
+    channel::thread::ThreadMember,
+    gateway::payload::incoming::ThreadMembersUpdate,
+    id::{
+        marker::{ChannelMarker, UserMarker},
+        Id,
+    },
+};
+
+use crate::{
+    cache::{
+        meta::{atoi, ExpirePipe, IMetaKey},
+        pipe::Pipe,
+        IO_TARGET,
+    },
+    config::{CacheConfig, Cacheable, ICachedThreadMember},
+    error::{SerializeError, SerializeErrorKind},
+    key::RedisKey,
+    CacheResult, RedisCache,
+};
+
+/// Deletes all thread members of a thread alongside the thread's member set.
+///
+/// KEYS: channel thread members
+/// ARGV: thread member prefix, channel id
+const DELETE_THREAD_MEMBERS_SCRIPT: &str = r"
+local user_ids = redis.call('SMEMBERS', KEYS[1])
+
+for _, user_id in ipairs(user_ids) do
+    redis.call('DEL', ARGV[1] .. ':' .. ARGV[2] .. ':' .. user_id)
+end
+
+redis.call('DEL', KEYS[1])
+";
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Store a thread member.
+    ///
+    /// Thread members without thread id or user id, i.e. those contained in
+    /// thread channels themselves, are skipped.
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn store_thread_member(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        thread_member: &ThreadMember,
+    ) -> CacheResult<()> {
+        if !C::ThreadMember::WANTED {
+            return Ok(());
+        }
+
+        let (Some(channel_id), Some(user_id)) = (thread_member.id, thread_member.user_id) else {
+            return Ok(());
+        };
+
+        let key = RedisKey::ThreadMember {
+            channel: channel_id,
+            user: user_id,
+        };
+        let thread_member = C::ThreadMember::from_thread_member(thread_member);
+
+        let bytes = thread_member
+            .serialize_one()
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::ThreadMember))?;
+
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &thread_member)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::ThreadMember))?;
+
+        pipe.version(&key, &thread_member);
+
+        pipe.set(key, bytes.as_ref(), C::ThreadMember::expire());
+
+        let key = RedisKey::ChannelThreadMembers {
+            channel: channel_id,
+        };
+        pipe.sadd(key, user_id.get());
+
+        Ok(())
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    pub(crate) fn store_thread_members<'a, I>(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        thread_members: I,
+    ) -> CacheResult<()>
+    where
+        I: IntoIterator<Item = &'a ThreadMember>,
+    {
+        if !C::ThreadMember::WANTED {
+            return Ok(());
+        }
+
+        for thread_member in thread_members {
+            self.store_thread_member(pipe, thread_member)?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(
+        level = "trace",
+        skip_all,
+        fields(guild_id = update.guild_id.get(), channel_id = update.id.get())
+    )]
+    pub(crate) fn store_thread_members_update(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        update: &ThreadMembersUpdate,
+    ) -> CacheResult<()> {
+        for thread_member in &update.added_members {
+            self.store_thread_member(pipe, thread_member)?;
+
+            if let Some(ref presence) = thread_member.presence {
+                self.store_presence(pipe, presence)?;
+            }
+
+            if let Some(ref member) = thread_member.member {
+                self.store_member(pipe, update.guild_id, member)?;
+            }
+        }
+
+        for &user_id in &update.removed_member_ids {
+            self.delete_thread_member(pipe, update.id, user_id);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn delete_thread_member(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) {
+        if !C::ThreadMember::WANTED {
+            return;
+        }
+
+        let key = RedisKey::ThreadMember {
+            channel: channel_id,
+            user: user_id,
+        };
+        pipe.del(key);
+
+        let key = RedisKey::ChannelThreadMembers {
+            channel: channel_id,
+        };
+        pipe.srem(key, user_id.get());
+    }
+}
+
+/// Delete all thread members of a thread.
+pub(crate) fn delete_thread_members<C: CacheConfig>(
+    pipe: &mut Pipe<'_, C>,
+    channel_id: Id<ChannelMarker>,
+) {
+    if !C::ThreadMember::WANTED {
+        return;
+    }
+
+    let key = RedisKey::ChannelThreadMembers {
+        channel: channel_id,
+    };
+
+    let prefix = [pipe.namespace().as_bytes(), RedisKey::THREAD_MEMBER_PREFIX].concat();
+
+    pipe.eval(
+        DELETE_THREAD_MEMBERS_SCRIPT,
+        &[key],
+        (prefix, channel_id.get()),
+    );
+}
+
+#[derive(Debug)]
+pub(crate) struct ThreadMemberMetaKey {
+    channel: Id<ChannelMarker>,
+    user: Id<UserMarker>,
+}
+
+impl IMetaKey for ThreadMemberMetaKey {
+    fn parse<'a>(split: &mut impl Iterator<Item = &'a [u8]>) -> Option<Self> {
+        split
+            .next()
+            .and_then(atoi)
+            .zip(split.next().and_then(atoi))
+            .map(|(channel, user)| Self { channel, user })
+    }
+
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
+        let key = RedisKey::ChannelThreadMembers {
+            channel: self.channel,
+        };
+        pipe.srem(key, self.user.get());
+    }
+}
@@ -0,0 +1,278 @@
+use std::time::Duration;
+
+use tracing::instrument;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+#[cfg(feature = "serde-mirror")]
+use crate::cache::pipe::is_mirrored;
+use crate::{
+    cache::pipe::Pipe,
+    config::{CacheConfig, Cacheable, ICachedGuild},
+    error::CacheError,
+    key::{Namespace, RedisKey},
+    redis::Cmd,
+    util::retain_valid_ids,
+    CacheResult, RedisCache,
+};
+
+/// Renames all existing keys into the tombstone namespace and remembers their
+/// remaining TTL in the manifest.
+///
+/// KEYS: manifest, keys to move
+/// ARGV: tombstone prefix, TTL in seconds
+const TOMBSTONE_SCRIPT: &str = r"
+for i = 2, #KEYS do
+    local key = KEYS[i]
+
+    if redis.call('EXISTS', key) == 1 then
+        local target = ARGV[1] .. key
+
+        redis.call('HSET', KEYS[1], key, redis.call('PTTL', key))
+        redis.call('RENAME', key, target)
+        redis.call('EXPIRE', target, ARGV[2])
+    end
+end
+
+if redis.call('EXISTS', KEYS[1]) == 1 then
+    redis.call('EXPIRE', KEYS[1], ARGV[2])
+end
+";
+
+/// Renames all keys of the manifest back and restores their TTL.
+///
+/// Keys that exist again in the meantime are fresher than their tombstone so
+/// the tombstone is dropped instead.
+///
+/// KEYS: manifest
+/// ARGV: tombstone prefix
+const RESTORE_SCRIPT: &str = r"
+local entries = redis.call('HGETALL', KEYS[1])
+local restored = 0
+
+for i = 1, #entries, 2 do
+    local key = entries[i]
+    local pttl = tonumber(entries[i + 1])
+    local source = ARGV[1] .. key
+
+    if redis.call('EXISTS', source) == 1 then
+        if redis.call('RENAMENX', source, key) == 1 then
+            if pttl > 0 then
+                redis.call('PEXPIRE', key, pttl)
+            else
+                redis.call('PERSIST', key)
+            end
+
+            restored = restored + 1
+        else
+            redis.call('DEL', source)
+        end
+    end
+end
+
+redis.call('DEL', KEYS[1])
+
+return restored
+";
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Instead of deleting the guild's entries, move them into tombstone keys
+    /// that expire after the given duration.
+    pub(crate) async fn tombstone_guild(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+        duration: Duration,
+    ) -> CacheResult<()> {
+        let keys_to_move = self.remove_guild(pipe, guild_id).await?;
+        Self::delete_guild_activity(pipe, guild_id);
+
+        if keys_to_move.is_empty() {
+            return Ok(());
+        }
+
+        #[cfg(feature = "serde-mirror")]
+        for key in keys_to_move.iter() {
+            if key.entity_kind().is_some_and(is_mirrored::<C>) {
+                pipe.del_mirror(key);
+            }
+        }
+
+        let mut keys = Vec::with_capacity(keys_to_move.len() + 1);
+        keys.push(RedisKey::GuildTombstone { id: guild_id });
+        keys.extend(keys_to_move);
+
+        pipe.eval(
+            TOMBSTONE_SCRIPT,
+            &keys,
+            (
+                tombstone_prefix(&self.namespace, guild_id),
+                duration.as_secs().max(1),
+            ),
+        );
+
+        Ok(())
+    }
+
+    /// Forget the tombstone of a guild that is repopulated so that it won't
+    /// be restored over the fresh entries.
+    ///
+    /// The tombstoned entries themselves expire on their own.
+    pub(crate) fn drop_guild_tombstone(pipe: &mut Pipe<'_, C>, guild_id: Id<GuildMarker>) {
+        if C::Guild::TOMBSTONE_DURATION.is_some() {
+            pipe.del(RedisKey::GuildTombstone { id: guild_id });
+        }
+    }
+
+    /// Restore the entries of a guild that were moved into tombstone keys
+    /// upon its deletion.
+    ///
+    /// Returns `false` if there was no tombstone for the guild, e.g. because
+    /// it expired already or a [`GuildCreate`] event repopulated the guild.
+    /// Entries that were cached again in the meantime are kept as is.
+    ///
+    /// Requires [`ICachedGuild::TOMBSTONE_DURATION`] to be set.
+    ///
+    /// [`GuildCreate`]: twilight_model::gateway::payload::incoming::GuildCreate
+    /// [`ICachedGuild::TOMBSTONE_DURATION`]: crate::config::ICachedGuild::TOMBSTONE_DURATION
+    #[instrument(level = "trace", skip(self))]
+    pub async fn restore_guild_tombstone(&self, guild_id: Id<GuildMarker>) -> CacheResult<bool> {
+        let mut conn = self.connection().await?;
+
+        let restored: usize = Cmd::new()
+            .arg("EVAL")
+            .arg(RESTORE_SCRIPT)
+            .arg(1)
+            .arg(
+                self.namespace
+                    .key(RedisKey::GuildTombstone { id: guild_id }),
+            )
+            .arg(tombstone_prefix(&self.namespace, guild_id))
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        if restored == 0 {
+            return Ok(false);
+        }
+
+        drop(conn);
+
+        let mut pipe = Pipe::new(self);
+        self.readd_guild(&mut pipe, guild_id).await?;
+
+        if !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Add a restored guild and its entries back into all collections.
+    async fn readd_guild(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<()> {
+        if C::Guild::WANTED {
+            let key = RedisKey::Guilds;
+            pipe.sadd(key, guild_id.get());
+        }
+
+        let collections = [
+            (
+                C::Member::WANTED || C::User::WANTED,
+                RedisKey::GuildMembers { id: guild_id },
+            ),
+            (
+                C::AutoModerationRule::WANTED,
+                RedisKey::GuildAutoModerationRules { id: guild_id },
+            ),
+            (C::Channel::WANTED, RedisKey::GuildChannels { id: guild_id }),
+            (C::Emoji::WANTED, RedisKey::GuildEmojis { id: guild_id }),
+            (C::Role::WANTED, RedisKey::GuildRoles { id: guild_id }),
+            (
+                C::ScheduledEvent::WANTED,
+                RedisKey::GuildScheduledEvents { id: guild_id },
+            ),
+            (
+                C::StageInstance::WANTED,
+                RedisKey::GuildStageInstances { id: guild_id },
+            ),
+            (C::Sticker::WANTED, RedisKey::GuildStickers { id: guild_id }),
+        ];
+
+        let mut query = Pipe::new(self);
+
+        for (wanted, key) in collections.iter() {
+            if *wanted {
+                query.smembers(key.clone());
+            }
+        }
+
+        if query.is_empty() {
+            return Ok(());
+        }
+
+        let mut data = query.query::<Vec<Vec<u64>>>().await?;
+        data.iter_mut().for_each(retain_valid_ids);
+
+        let mut iter = data.into_iter();
+
+        if C::Member::WANTED || C::User::WANTED {
+            let user_ids = iter.next().unwrap_or_default();
+
+            if C::User::WANTED && !user_ids.is_empty() {
+                for &user_id in user_ids.iter() {
+                    let key = RedisKey::UserGuilds {
+                        id: Id::new(user_id),
+                    };
+                    pipe.sadd(key, guild_id.get());
+                }
+
+                let key = RedisKey::Users;
+                pipe.sadd(key, user_ids.as_slice());
+            }
+        }
+
+        let globals = [
+            (C::AutoModerationRule::WANTED, RedisKey::AutoModerationRules),
+            (C::Channel::WANTED, RedisKey::Channels),
+            (C::Emoji::WANTED, RedisKey::Emojis),
+            (C::Role::WANTED, RedisKey::Roles),
+            (C::ScheduledEvent::WANTED, RedisKey::ScheduledEvents),
+            (C::StageInstance::WANTED, RedisKey::StageInstances),
+            (C::Sticker::WANTED, RedisKey::Stickers),
+        ];
+
+        for (wanted, key) in globals {
+            if !wanted {
+                continue;
+            }
+
+            let ids = iter.next().unwrap_or_default();
+
+            if !ids.is_empty() {
+                pipe.sadd(key, ids.as_slice());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn tombstone_prefix(namespace: &Namespace, guild_id: Id<GuildMarker>) -> Vec<u8> {
+    let mut buf = itoa::Buffer::new();
+    let id = buf.format(guild_id.get()).as_bytes();
+
+    let namespace = namespace.as_bytes();
+    let prefix = RedisKey::TOMBSTONE_PREFIX;
+
+    let mut tombstone_prefix = Vec::with_capacity(namespace.len() + prefix.len() + id.len() + 2);
+    tombstone_prefix.extend_from_slice(namespace);
+    tombstone_prefix.extend_from_slice(prefix);
+    tombstone_prefix.push(b':');
+    tombstone_prefix.extend_from_slice(id);
+    tombstone_prefix.push(b':');
+
+    tombstone_prefix
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use tracing::{instrument, trace};
 use twilight_model::{
     gateway::payload::incoming::invite_create::PartialUser,
@@ -10,13 +12,13 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, IMetaKey},
+        meta::{atoi, ExpirePipe, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedUser, SerializeMany},
     error::{SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind},
     key::RedisKey,
-    redis::Pipeline,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
@@ -36,9 +38,16 @@ impl<C: CacheConfig> RedisCache<C> {
             .serialize_one()
             .map_err(|e| SerializeError::new(e, SerializeErrorKind::User))?;
 
-        trace!(bytes = bytes.as_ref().len());
+        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
+
+        #[cfg(feature = "serde-mirror")]
+        pipe.mirror(&key, &user)
+            .map_err(|e| SerializeError::new(e, SerializeErrorKind::User))?;
+
+        pipe.version(&key, &user);
 
-        pipe.set(key, bytes.as_ref(), C::User::expire());
+        // Users are commonly stored multiple times within a single event
+        pipe.set_coalesced(key, bytes.as_ref(), C::User::expire());
 
         let key = RedisKey::Users;
         pipe.sadd(key, id.get());
@@ -57,6 +66,21 @@ impl<C: CacheConfig> RedisCache<C> {
 
         let mut serializer = C::User::serialize_many();
 
+        // Only keep the last occurrence of each user
+        let mut seen = HashSet::new();
+        let mut users: Vec<_> = users.into_iter().collect();
+        #[cfg(feature = "metrics")]
+        let count = users.len();
+
+        users.reverse();
+        users.retain(|user| seen.insert(user.id));
+        users.reverse();
+
+        #[cfg(feature = "metrics")]
+        if seen.len() < count {
+            crate::cache::metrics::record_coalesced_writes(count - seen.len());
+        }
+
         let (users, user_ids) = users
             .into_iter()
             .map(|user| {
@@ -68,7 +92,13 @@ impl<C: CacheConfig> RedisCache<C> {
                     .serialize_next(&user)
                     .map_err(|e| SerializeError::new(e, SerializeErrorKind::User))?;
 
-                trace!(bytes = bytes.as_ref().len());
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &user)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::User))?;
+
+                pipe.version(&key, &user);
+
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                 Ok(((key, BytesWrap(bytes)), id.get()))
             })
@@ -115,7 +145,12 @@ impl<C: CacheConfig> RedisCache<C> {
             .map_err(|e| UpdateError::new(e, UpdateErrorKind::PartialUser))?;
 
         let key = RedisKey::User { id };
+        #[cfg(feature = "serde-mirror")]
+        pipe.remirror(&key, &user)
+            .map_err(|e| UpdateError::new(e, UpdateErrorKind::PartialUser))?;
+
         let bytes = user.into_bytes();
+
         pipe.set(key, &bytes, C::Guild::expire());
 
         Ok(())
@@ -163,7 +198,7 @@ impl IMetaKey for UserMetaKey {
         split.next().and_then(atoi).map(|user| Self { user })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::Users;
         pipe.srem(key, self.user.get()).ignore();
 
@@ -9,20 +9,20 @@ use twilight_model::{
 
 use crate::{
     cache::{
-        meta::{atoi, IMetaKey},
+        meta::{atoi, ExpirePipe, IMetaKey},
         pipe::Pipe,
+        IO_TARGET,
     },
     config::{CacheConfig, Cacheable, ICachedVoiceState, SerializeMany},
-    error::{CacheError, SerializeError, SerializeErrorKind},
+    error::{CacheError, SerializeError, SerializeErrorKind, UpdateError, UpdateErrorKind},
     key::RedisKey,
-    redis::Pipeline,
     util::{BytesWrap, ZippedVecs},
     CacheResult, RedisCache,
 };
 
 impl<C: CacheConfig> RedisCache<C> {
-    #[instrument(level = "trace", skip_all)]
-    pub(crate) fn store_voice_state(
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
+    pub(crate) async fn store_voice_state(
         &self,
         pipe: &mut Pipe<'_, C>,
         channel_id: Id<ChannelMarker>,
@@ -35,18 +35,50 @@ impl<C: CacheConfig> RedisCache<C> {
                 guild: guild_id,
                 user: user_id,
             };
-            let voice_state = C::VoiceState::from_voice_state(channel_id, guild_id, voice_state);
 
-            let bytes = voice_state
-                .serialize_one()
-                .map_err(|e| SerializeError::new(e, SerializeErrorKind::VoiceState))?;
+            let cached = match C::VoiceState::on_voice_state_update() {
+                Some(update_fn) => pipe
+                    .get::<C::VoiceState<'static>>(key.clone())
+                    .await?
+                    .map(|cached| (update_fn, cached)),
+                None => None,
+            };
+
+            if let Some((update_fn, mut cached)) = cached {
+                update_fn(&mut cached, voice_state)
+                    .map_err(|e| UpdateError::new(e, UpdateErrorKind::VoiceState))?;
+
+                #[cfg(feature = "serde-mirror")]
+                pipe.remirror(&key, &cached)
+                    .map_err(|e| UpdateError::new(e, UpdateErrorKind::VoiceState))?;
+
+                let bytes = cached.into_bytes();
+                trace!(target: IO_TARGET, bytes = bytes.len());
+
+                pipe.set(key, &bytes, C::VoiceState::expire());
+            } else {
+                let voice_state =
+                    C::VoiceState::from_voice_state(channel_id, guild_id, voice_state);
+
+                let bytes = voice_state
+                    .serialize_one()
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::VoiceState))?;
+
+                trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
-            trace!(bytes = bytes.as_ref().len());
+                #[cfg(feature = "serde-mirror")]
+                pipe.mirror(&key, &voice_state)
+                    .map_err(|e| SerializeError::new(e, SerializeErrorKind::VoiceState))?;
 
-            pipe.set(key, bytes.as_ref(), C::VoiceState::expire());
+                pipe.version(&key, &voice_state);
+
+                pipe.set(key, bytes.as_ref(), C::VoiceState::expire());
+            }
 
             let key = RedisKey::GuildVoiceStates { id: guild_id };
             pipe.sadd(key, user_id.get());
+
+            self.store_voice_state_stamps(pipe, guild_id, &[user_id.get()]);
         }
 
         if let Some(ref member) = voice_state.member {
@@ -56,7 +88,7 @@ impl<C: CacheConfig> RedisCache<C> {
         Ok(())
     }
 
-    #[instrument(level = "trace", skip_all)]
+    #[instrument(level = "trace", skip_all, fields(guild_id = guild_id.get()))]
     pub(crate) fn store_voice_states(
         &self,
         pipe: &mut Pipe<'_, C>,
@@ -82,10 +114,19 @@ impl<C: CacheConfig> RedisCache<C> {
                 let voice_state =
                     C::VoiceState::from_voice_state(channel_id, guild_id, voice_state);
 
+                #[cfg(feature = "serde-mirror")]
+                if let Err(e) = pipe.mirror(&key, &voice_state) {
+                    let err = SerializeError::new(e, SerializeErrorKind::VoiceState);
+
+                    return Some(Err(CacheError::Serialization(err)));
+                }
+
+                pipe.version(&key, &voice_state);
+
                 let res = serializer
                     .serialize_next(&voice_state)
                     .map(|bytes| {
-                        trace!(bytes = bytes.as_ref().len());
+                        trace!(target: IO_TARGET, bytes = bytes.as_ref().len());
 
                         ((key, BytesWrap(bytes)), user_id.get())
                     })
@@ -110,6 +151,8 @@ impl<C: CacheConfig> RedisCache<C> {
         let key = RedisKey::GuildVoiceStates { id: guild_id };
         pipe.sadd(key, user_ids.as_slice());
 
+        self.store_voice_state_stamps(pipe, guild_id, &user_ids);
+
         Ok(())
     }
 
@@ -131,6 +174,11 @@ impl<C: CacheConfig> RedisCache<C> {
 
         let key = RedisKey::GuildVoiceStates { id: guild_id };
         pipe.srem(key, user_id.get());
+
+        if C::VoiceState::TRACK_FRESHNESS {
+            let key = RedisKey::GuildVoiceStateStamps { id: guild_id };
+            pipe.zrem(key, user_id.get());
+        }
     }
 }
 
@@ -149,8 +197,11 @@ impl IMetaKey for VoiceStateMetaKey {
             .map(|(guild, user)| VoiceStateMetaKey { guild, user })
     }
 
-    fn handle_expire(&self, pipe: &mut Pipeline) {
+    fn handle_expire(&self, pipe: &mut ExpirePipe) {
         let key = RedisKey::GuildVoiceStates { id: self.guild };
         pipe.srem(key, self.user.get());
+
+        let key = RedisKey::GuildVoiceStateStamps { id: self.guild };
+        pipe.zrem(key, self.user.get());
     }
 }
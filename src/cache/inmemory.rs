@@ -0,0 +1,80 @@
+use tracing::{info, instrument};
+use twilight_cache_inmemory::InMemoryCache;
+
+use crate::{cache::pipe::Pipe, config::CacheConfig, CacheResult, RedisCache};
+
+/// Amount of entries after which the pipeline is executed to keep it small.
+const FLUSH_THRESHOLD: usize = 1000;
+
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "inmemory")))]
+impl<C: CacheConfig> RedisCache<C> {
+    /// Store the content of an [`InMemoryCache`] through the configured
+    /// `ICached*` conversions.
+    ///
+    /// This allows running both caches side by side while migrating from
+    /// `twilight-cache-inmemory`.
+    ///
+    /// Note that only entries which are stored as `twilight-model` types in
+    /// the [`InMemoryCache`] can be imported, i.e. the current user,
+    /// channels, integrations, roles, stage instances, and users. All other
+    /// entries are stored in their own reduced form and will be skipped.
+    #[instrument(level = "trace", skip_all)]
+    pub async fn import_inmemory(&self, inmemory: &InMemoryCache) -> CacheResult<()> {
+        let mut pipe = Pipe::new(self);
+        let mut count = 0;
+
+        if let Some(current_user) = inmemory.current_user() {
+            self.store_current_user(&mut pipe, &current_user)?;
+        }
+
+        let iter = inmemory.iter();
+
+        for channel in iter.channels() {
+            self.store_channel(&mut pipe, channel.value())?;
+            count += 1;
+            Self::flush_import(&mut pipe, count).await?;
+        }
+
+        for integration in iter.integrations() {
+            let (guild_id, _) = *integration.key();
+            self.store_integration(&mut pipe, guild_id, integration.value().resource())?;
+            count += 1;
+            Self::flush_import(&mut pipe, count).await?;
+        }
+
+        for role in iter.roles() {
+            let role = role.value();
+            self.store_roles(&mut pipe, role.guild_id(), [role.resource()])?;
+            count += 1;
+            Self::flush_import(&mut pipe, count).await?;
+        }
+
+        for stage_instance in iter.stage_instances() {
+            self.store_stage_instance(&mut pipe, stage_instance.value().resource())?;
+            count += 1;
+            Self::flush_import(&mut pipe, count).await?;
+        }
+
+        for user in iter.users() {
+            self.store_user(&mut pipe, user.value())?;
+            count += 1;
+            Self::flush_import(&mut pipe, count).await?;
+        }
+
+        if !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
+
+        info!(count, "Imported entries from in-memory cache");
+
+        Ok(())
+    }
+
+    async fn flush_import(pipe: &mut Pipe<'_, C>, count: usize) -> CacheResult<()> {
+        if count.is_multiple_of(FLUSH_THRESHOLD) && !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures_util::{Stream, StreamExt};
+use tracing::warn;
+
+use crate::{
+    cache::pubsub::{Subscription, Topics},
+    key::{Namespace, RedisKey},
+    CacheResult, RedisCache,
+};
+
+/// Channel that invalidations are published to.
+const INVALIDATION_CHANNEL: &str = "INVALIDATIONS";
+
+/// [`INVALIDATION_CHANNEL`] within the configured key prefix so that caches
+/// of different prefixes don't receive each other's invalidations.
+pub(crate) fn invalidation_channel(namespace: &Namespace) -> String {
+    format!("{}{INVALIDATION_CHANNEL}", namespace.as_str())
+}
+
+/// How an entry changed, see [`RedisCache::subscribe_invalidations`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChangeKind {
+    /// The entry was written.
+    Stored,
+    /// The entry was deleted.
+    Deleted,
+}
+
+impl ChangeKind {
+    const fn tag(self) -> &'static [u8] {
+        match self {
+            Self::Stored => b"SET",
+            Self::Deleted => b"DEL",
+        }
+    }
+
+    fn from_tag(tag: &[u8]) -> Option<Self> {
+        match tag {
+            b"SET" => Some(Self::Stored),
+            b"DEL" => Some(Self::Deleted),
+            _ => None,
+        }
+    }
+}
+
+/// Payload of a published invalidation, i.e. the change kind's tag followed
+/// by a `:` and the rendered key.
+pub(crate) fn invalidation_payload(kind: ChangeKind, key: &[u8]) -> Vec<u8> {
+    let tag = kind.tag();
+
+    let mut payload = Vec::with_capacity(tag.len() + 1 + key.len());
+    payload.extend_from_slice(tag);
+    payload.push(b':');
+    payload.extend_from_slice(key);
+
+    payload
+}
+
+fn parse_payload(namespace: &Namespace, payload: &[u8]) -> Option<(RedisKey, ChangeKind)> {
+    let idx = payload.iter().position(|&byte| byte == b':')?;
+    let (tag, key) = payload.split_at(idx);
+
+    let kind = ChangeKind::from_tag(tag)?;
+    let key = namespace.parse(&key[1..])?;
+
+    Some((key, kind))
+}
+
+/// Stream of changed keys of a subscription created through
+/// [`RedisCache::subscribe_invalidations`].
+///
+/// The items are of type `(RedisKey, ChangeKind)`.
+pub struct Invalidations {
+    subscription: Subscription,
+    namespace: Namespace,
+}
+
+impl Invalidations {
+    /// Receive the next invalidation.
+    ///
+    /// Returns `None` if the background task stopped which only happens if
+    /// the runtime shuts down.
+    pub async fn recv(&mut self) -> Option<(RedisKey, ChangeKind)> {
+        self.next().await
+    }
+}
+
+impl Stream for Invalidations {
+    type Item = (RedisKey, ChangeKind);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let Some(msg) = ready!(self.subscription.poll_next_unpin(cx)) else {
+                return Poll::Ready(None);
+            };
+
+            if let Some(item) = parse_payload(&self.namespace, msg.payload()) {
+                return Poll::Ready(Some(item));
+            }
+
+            warn!(payload = ?msg.payload(), "Received invalid invalidation payload");
+        }
+    }
+}
+
+impl<C> RedisCache<C> {
+    /// Subscribe to the keys that are written or deleted by any process
+    /// sharing this redis instance.
+    ///
+    /// Processes holding in-memory copies of cached entries can use this to
+    /// drop outdated copies. Keys are only published by caches whose
+    /// [`CacheConfig::PUBLISH_INVALIDATIONS`] is enabled.
+    ///
+    /// Same as for [`RedisCache::subscribe`], invalidations that are
+    /// published while reconnecting are missed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// use redlight::{ChangeKind, RedisKey};
+    ///
+    /// # async fn example<C: CacheConfig>(cache: RedisCache<C>) -> Result<(), redlight::error::CacheError> {
+    /// let mut invalidations = cache.subscribe_invalidations().await?;
+    ///
+    /// while let Some((key, kind)) = invalidations.recv().await {
+    ///     if let (RedisKey::User { id }, ChangeKind::Deleted) = (key, kind) {
+    ///         println!("user {id} was deleted");
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    ///
+    /// [`CacheConfig::PUBLISH_INVALIDATIONS`]: crate::config::CacheConfig::PUBLISH_INVALIDATIONS
+    pub async fn subscribe_invalidations(&self) -> CacheResult<Invalidations> {
+        let topics = Topics::new().channel(invalidation_channel(&self.namespace));
+        let subscription = self.subscribe(topics).await?;
+
+        Ok(Invalidations {
+            subscription,
+            namespace: self.namespace.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::id::Id;
+
+    use super::{invalidation_payload, parse_payload, ChangeKind};
+    use crate::key::{Namespace, RedisKey};
+
+    #[test]
+    fn payload_roundtrips() {
+        let namespace = Namespace::new("bot").unwrap();
+
+        let key = RedisKey::Member {
+            guild: Id::new(1),
+            user: Id::new(2),
+        };
+
+        for kind in [ChangeKind::Stored, ChangeKind::Deleted] {
+            let payload = invalidation_payload(kind, &namespace.render(&key));
+
+            assert_eq!(
+                parse_payload(&namespace, &payload),
+                Some((key.clone(), kind))
+            );
+        }
+
+        assert_eq!(parse_payload(&namespace, b"SET:USER:1"), None);
+        assert_eq!(parse_payload(&namespace, b"GET:bot:USER:1"), None);
+        assert_eq!(parse_payload(&namespace, b"SET"), None);
+    }
+}
@@ -0,0 +1,342 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, SystemTime},
+};
+
+use rkyv::util::AlignedVec;
+
+use crate::{config::LocalCache, key::RedisKey, util::fnv1a};
+
+/// Maximum amount of independently locked shards.
+const MAX_SHARDS: usize = 16;
+
+/// Bounded in-process LRU of archived entries, see
+/// [`CacheConfig::LOCAL_CACHE`].
+///
+/// Entries are keyed by their rendered [`RedisKey`] and spread across shards
+/// so that concurrent reads of different keys rarely wait on each other.
+/// Every invalidation bumps a generation so that reads which were in flight
+/// while a pipeline was sent do not store the value they fetched before the
+/// write.
+///
+/// [`CacheConfig::LOCAL_CACHE`]: crate::config::CacheConfig::LOCAL_CACHE
+pub(crate) struct LocalLayer {
+    config: LocalCache,
+    generation: AtomicU64,
+    shards: Box<[Mutex<Shard>]>,
+}
+
+impl LocalLayer {
+    pub(crate) fn new(config: LocalCache) -> Self {
+        let len = config.capacity.clamp(1, MAX_SHARDS);
+
+        // Distribute the capacity so that the shards add up to it exactly
+        let shards = (0..len)
+            .map(|i| {
+                let capacity = config.capacity / len + usize::from(i < config.capacity % len);
+
+                Mutex::new(Shard::new(capacity))
+            })
+            .collect();
+
+        Self {
+            config,
+            generation: AtomicU64::new(0),
+            shards,
+        }
+    }
+
+    /// Whether entries of the key are held in memory.
+    pub(crate) fn is_wanted(&self, key: &RedisKey) -> bool {
+        key.entity_kind()
+            .is_some_and(|kind| self.config.kinds.contains(&kind))
+    }
+
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Get a copy of the entry's bytes and mark it as most recently used.
+    pub(crate) fn get(&self, key: &[u8], now: SystemTime) -> Option<AlignedVec<16>> {
+        self.shard(key).get(key, now, self.config.lifetime)
+    }
+
+    /// Store the bytes of an entry unless the key was invalidated since the
+    /// given generation.
+    pub(crate) fn insert(&self, key: Vec<u8>, bytes: &[u8], generation: u64, now: SystemTime) {
+        if self.config.capacity == 0 {
+            return;
+        }
+
+        let mut shard = self.shard(&key);
+
+        // Checked while holding the shard's lock; an invalidation that bumps
+        // the generation afterwards waits for the lock before removing keys
+        if self.generation() != generation {
+            return;
+        }
+
+        shard.insert(key.into_boxed_slice(), bytes, now);
+    }
+
+    /// Drop the entries of all given rendered keys.
+    pub(crate) fn invalidate(&self, keys: &[Vec<u8>]) {
+        if keys.is_empty() {
+            return;
+        }
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        for key in keys {
+            self.shard(key).remove(key);
+        }
+    }
+
+    /// Drop all entries.
+    pub(crate) fn clear(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+
+        for shard in self.shards.iter() {
+            lock(shard).clear();
+        }
+    }
+
+    fn shard(&self, key: &[u8]) -> MutexGuard<'_, Shard> {
+        // The remainder is smaller than the amount of shards
+        #[allow(clippy::cast_possible_truncation)]
+        let idx = (fnv1a(key) % self.shards.len() as u64) as usize;
+
+        lock(&self.shards[idx])
+    }
+}
+
+fn lock(shard: &Mutex<Shard>) -> MutexGuard<'_, Shard> {
+    shard.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// LRU of the keys of a single shard.
+///
+/// Nodes form a doubly linked list through their indices, ordered from the
+/// most recently used at the head to the least recently used at the tail.
+struct Shard {
+    capacity: usize,
+    index: HashMap<Box<[u8]>, usize>,
+    nodes: Vec<Node>,
+    free: Vec<usize>,
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+struct Node {
+    key: Box<[u8]>,
+    bytes: AlignedVec<16>,
+    inserted: SystemTime,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl Shard {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            index: HashMap::new(),
+            nodes: Vec::new(),
+            free: Vec::new(),
+            head: None,
+            tail: None,
+        }
+    }
+
+    fn get(&mut self, key: &[u8], now: SystemTime, lifetime: Duration) -> Option<AlignedVec<16>> {
+        let idx = *self.index.get(key)?;
+
+        let elapsed = now
+            .duration_since(self.nodes[idx].inserted)
+            .unwrap_or_default();
+
+        if elapsed >= lifetime {
+            self.remove(key);
+
+            return None;
+        }
+
+        self.unlink(idx);
+        self.push_front(idx);
+
+        Some(self.nodes[idx].bytes.clone())
+    }
+
+    fn insert(&mut self, key: Box<[u8]>, bytes: &[u8], now: SystemTime) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut aligned = AlignedVec::with_capacity(bytes.len());
+        aligned.extend_from_slice(bytes);
+
+        if let Some(&idx) = self.index.get(&key) {
+            let node = &mut self.nodes[idx];
+            node.bytes = aligned;
+            node.inserted = now;
+
+            self.unlink(idx);
+            self.push_front(idx);
+
+            return;
+        }
+
+        if self.index.len() >= self.capacity {
+            if let Some(tail) = self.tail {
+                let evicted = self.nodes[tail].key.clone();
+                self.remove(&evicted);
+            }
+        }
+
+        let node = Node {
+            key: key.clone(),
+            bytes: aligned,
+            inserted: now,
+            prev: None,
+            next: None,
+        };
+
+        let idx = if let Some(idx) = self.free.pop() {
+            self.nodes[idx] = node;
+
+            idx
+        } else {
+            self.nodes.push(node);
+
+            self.nodes.len() - 1
+        };
+
+        self.push_front(idx);
+        self.index.insert(key, idx);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        let Some(idx) = self.index.remove(key) else {
+            return;
+        };
+
+        self.unlink(idx);
+
+        // Release the memory of the node until it's reused
+        let node = &mut self.nodes[idx];
+        node.key = Box::default();
+        node.bytes = AlignedVec::new();
+
+        self.free.push(idx);
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.nodes.clear();
+        self.free.clear();
+        self.head = None;
+        self.tail = None;
+    }
+
+    fn unlink(&mut self, idx: usize) {
+        let Node { prev, next, .. } = self.nodes[idx];
+
+        match prev {
+            Some(prev) => self.nodes[prev].next = next,
+            None => self.head = next,
+        }
+
+        match next {
+            Some(next) => self.nodes[next].prev = prev,
+            None => self.tail = prev,
+        }
+
+        let node = &mut self.nodes[idx];
+        node.prev = None;
+        node.next = None;
+    }
+
+    fn push_front(&mut self, idx: usize) {
+        self.nodes[idx].next = self.head;
+
+        match self.head {
+            Some(head) => self.nodes[head].prev = Some(idx),
+            None => self.tail = Some(idx),
+        }
+
+        self.head = Some(idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::Shard;
+
+    const LIFETIME: Duration = Duration::from_mins(1);
+
+    fn key(key: &str) -> Box<[u8]> {
+        key.as_bytes().into()
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let now = SystemTime::UNIX_EPOCH;
+        let mut shard = Shard::new(2);
+
+        shard.insert(key("a"), b"1", now);
+        shard.insert(key("b"), b"2", now);
+
+        // Marks `a` as most recently used
+        assert!(shard.get(b"a", now, LIFETIME).is_some());
+
+        shard.insert(key("c"), b"3", now);
+
+        assert!(shard.get(b"b", now, LIFETIME).is_none());
+        assert_eq!(
+            shard.get(b"a", now, LIFETIME).as_deref(),
+            Some(b"1".as_slice())
+        );
+        assert_eq!(
+            shard.get(b"c", now, LIFETIME).as_deref(),
+            Some(b"3".as_slice())
+        );
+    }
+
+    #[test]
+    fn reuses_removed_nodes() {
+        let now = SystemTime::UNIX_EPOCH;
+        let mut shard = Shard::new(2);
+
+        shard.insert(key("a"), b"1", now);
+        shard.insert(key("b"), b"2", now);
+        shard.remove(b"a");
+        shard.insert(key("c"), b"3", now);
+        shard.insert(key("b"), b"4", now);
+
+        assert_eq!(shard.nodes.len(), 2);
+        assert_eq!(
+            shard.get(b"b", now, LIFETIME).as_deref(),
+            Some(b"4".as_slice())
+        );
+        assert_eq!(
+            shard.get(b"c", now, LIFETIME).as_deref(),
+            Some(b"3".as_slice())
+        );
+    }
+
+    #[test]
+    fn expires_after_lifetime() {
+        let now = SystemTime::UNIX_EPOCH;
+        let mut shard = Shard::new(1);
+
+        shard.insert(key("a"), b"1", now);
+
+        assert!(shard.get(b"a", now + LIFETIME, LIFETIME).is_none());
+        assert!(shard.index.is_empty());
+    }
+}
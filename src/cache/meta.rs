@@ -6,31 +6,132 @@ use twilight_model::id::Id;
 
 use super::{
     impls::{
-        channel::ChannelMetaKey, emoji::EmojiMetaKey, guild::GuildMetaKey,
-        integration::IntegrationMetaKey, member::MemberMetaKey, message::MessageMetaKey,
-        presence::PresenceMetaKey, role::RoleMetaKey, stage_instance::StageInstanceMetaKey,
-        sticker::StickerMetaKey, user::UserMetaKey, voice_state::VoiceStateMetaKey,
+        auto_moderation_rule::AutoModerationRuleMetaKey, channel::ChannelMetaKey,
+        emoji::EmojiMetaKey, guild::GuildMetaKey, integration::IntegrationMetaKey,
+        invite::InviteMetaKey, member::MemberMetaKey, message::MessageMetaKey,
+        presence::PresenceMetaKey, role::RoleMetaKey, scheduled_event::ScheduledEventMetaKey,
+        stage_instance::StageInstanceMetaKey, sticker::StickerMetaKey,
+        thread_member::ThreadMemberMetaKey, user::UserMetaKey, voice_state::VoiceStateMetaKey,
     },
     pipe::Pipe,
 };
 use crate::{
-    config::CheckedArchive,
+    cache::IO_TARGET,
+    config::{CacheConfig, CheckedArchive},
     error::ExpireError,
-    key::RedisKey,
-    redis::{DedicatedConnection, Pipeline},
+    key::{Keys, Namespace, RedisKey},
+    redis::{DedicatedConnection, FromRedisValue, Pipeline, RedisResult, ToRedisArgs},
 };
 
+/// Pipeline of the expire listener that renders keys within the namespace of
+/// its cache.
+pub(crate) struct ExpirePipe {
+    pipe: Pipeline,
+    namespace: Namespace,
+}
+
+impl ExpirePipe {
+    pub(crate) fn new(namespace: Namespace) -> Self {
+        Self {
+            pipe: Pipeline::new(),
+            namespace,
+        }
+    }
+
+    pub(crate) const fn namespace(&self) -> &Namespace {
+        &self.namespace
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pipe.cmd_iter().count()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pipe.clear();
+    }
+
+    pub(crate) async fn query<T: FromRedisValue>(
+        &mut self,
+        conn: &mut DedicatedConnection,
+    ) -> RedisResult<T> {
+        self.pipe.query_async(conn).await
+    }
+
+    /// Evaluate a lua script, ignoring its result.
+    pub(crate) fn eval(&mut self, script: &str, keys: &[RedisKey], args: impl ToRedisArgs) {
+        self.pipe
+            .cmd("EVAL")
+            .arg(script)
+            .arg(keys.len())
+            .arg(self.namespace.key(keys))
+            .arg(args)
+            .ignore();
+    }
+
+    pub(crate) fn ignore(&mut self) -> &mut Self {
+        self.pipe.ignore();
+
+        self
+    }
+
+    pub(crate) fn del(&mut self, keys: impl Keys) -> &mut Self {
+        self.pipe.del(self.namespace.key(keys));
+
+        self
+    }
+
+    pub(crate) fn get_del(&mut self, key: RedisKey) -> &mut Self {
+        self.pipe.get_del(self.namespace.key(key));
+
+        self
+    }
+
+    pub(crate) fn hdel(&mut self, key: RedisKey, fields: impl ToRedisArgs) -> &mut Self {
+        self.pipe.hdel(self.namespace.key(key), fields);
+
+        self
+    }
+
+    pub(crate) fn scard(&mut self, key: RedisKey) -> &mut Self {
+        self.pipe.scard(self.namespace.key(key));
+
+        self
+    }
+
+    pub(crate) fn smembers(&mut self, key: RedisKey) -> &mut Self {
+        self.pipe.smembers(self.namespace.key(key));
+
+        self
+    }
+
+    pub(crate) fn srem(&mut self, key: RedisKey, members: impl ToRedisArgs) -> &mut Self {
+        self.pipe.srem(self.namespace.key(key), members);
+
+        self
+    }
+
+    pub(crate) fn zrem(&mut self, key: RedisKey, members: impl ToRedisArgs) -> &mut Self {
+        self.pipe.zrem(self.namespace.key(key), members);
+
+        self
+    }
+}
+
 pub(crate) enum MetaKey {
+    AutoModerationRule(AutoModerationRuleMetaKey),
     Channel(ChannelMetaKey),
     Emoji(EmojiMetaKey),
     Guild(GuildMetaKey),
     Integration(IntegrationMetaKey),
+    Invite(InviteMetaKey),
     Member(MemberMetaKey),
     Message(MessageMetaKey),
     Presence(PresenceMetaKey),
     Role(RoleMetaKey),
+    ScheduledEvent(ScheduledEventMetaKey),
     StageInstance(StageInstanceMetaKey),
     Sticker(StickerMetaKey),
+    ThreadMember(ThreadMemberMetaKey),
     User(UserMetaKey),
     VoiceState(VoiceStateMetaKey),
 }
@@ -38,18 +139,26 @@ pub(crate) enum MetaKey {
 impl MetaKey {
     pub(crate) fn parse<'a>(split: &mut impl Iterator<Item = &'a [u8]>) -> Option<Self> {
         match split.next() {
+            Some(RedisKey::AUTO_MODERATION_RULE_PREFIX) => {
+                IMetaKey::parse(split).map(Self::AutoModerationRule)
+            }
             Some(RedisKey::CHANNEL_PREFIX) => IMetaKey::parse(split).map(Self::Channel),
             Some(RedisKey::EMOJI_PREFIX) => IMetaKey::parse(split).map(Self::Emoji),
             Some(RedisKey::GUILD_PREFIX) => IMetaKey::parse(split).map(Self::Guild),
             Some(RedisKey::INTEGRATION_PREFIX) => IMetaKey::parse(split).map(Self::Integration),
+            Some(RedisKey::INVITE_PREFIX) => IMetaKey::parse(split).map(Self::Invite),
             Some(RedisKey::MEMBER_PREFIX) => IMetaKey::parse(split).map(Self::Member),
             Some(RedisKey::MESSAGE_PREFIX) => IMetaKey::parse(split).map(Self::Message),
             Some(RedisKey::PRESENCE_PREFIX) => IMetaKey::parse(split).map(Self::Presence),
             Some(RedisKey::ROLE_PREFIX) => IMetaKey::parse(split).map(Self::Role),
+            Some(RedisKey::SCHEDULED_EVENT_PREFIX) => {
+                IMetaKey::parse(split).map(Self::ScheduledEvent)
+            }
             Some(RedisKey::STAGE_INSTANCE_PREFIX) => {
                 IMetaKey::parse(split).map(Self::StageInstance)
             }
             Some(RedisKey::STICKER_PREFIX) => IMetaKey::parse(split).map(Self::Sticker),
+            Some(RedisKey::THREAD_MEMBER_PREFIX) => IMetaKey::parse(split).map(Self::ThreadMember),
             Some(RedisKey::USER_PREFIX) => IMetaKey::parse(split).map(Self::User),
             Some(RedisKey::VOICE_STATE_PREFIX) => IMetaKey::parse(split).map(Self::VoiceState),
             Some(_) | None => None,
@@ -60,104 +169,71 @@ impl MetaKey {
     pub(crate) async fn handle_expire(
         self,
         conn: &mut DedicatedConnection,
-        pipe: &mut Pipeline,
+        pipe: &mut ExpirePipe,
     ) -> Result<(), ExpireError> {
         match self {
-            MetaKey::Channel(meta) => {
-                let key = meta.redis_key();
-
-                let Some(bytes) = Self::fetch_bytes(conn, pipe, key).await? else {
-                    return Ok(());
-                };
-
-                let archived = <ChannelMetaKey as HasArchived>::Meta::as_archive(&bytes)?;
-                meta.handle_archived(pipe, archived);
-                meta.handle_expire(pipe);
-            }
-            MetaKey::Emoji(meta) => {
-                let key = meta.redis_key();
-
-                let Some(bytes) = Self::fetch_bytes(conn, pipe, key).await? else {
-                    return Ok(());
-                };
-
-                let archived = <EmojiMetaKey as HasArchived>::Meta::as_archive(&bytes)?;
-                meta.handle_archived(pipe, archived);
-                meta.handle_expire(pipe);
+            MetaKey::AutoModerationRule(meta) => {
+                Self::handle_archived_expire(&meta, conn, pipe).await?;
             }
+            MetaKey::Channel(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
+            MetaKey::Emoji(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
             MetaKey::Guild(meta) => {
                 meta.handle_expire(pipe);
                 meta.async_handle_expire(pipe, conn).await?;
             }
             MetaKey::Integration(meta) => meta.handle_expire(pipe),
+            MetaKey::Invite(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
             MetaKey::Member(meta) => {
                 meta.handle_expire(pipe);
                 meta.async_handle_expire(pipe, conn).await?;
             }
-            MetaKey::Message(meta) => {
-                let key = meta.redis_key();
-
-                let Some(bytes) = Self::fetch_bytes(conn, pipe, key).await? else {
-                    return Ok(());
-                };
-
-                let archived = <MessageMetaKey as HasArchived>::Meta::as_archive(&bytes)?;
-                meta.handle_archived(pipe, archived);
-                meta.handle_expire(pipe);
-            }
+            MetaKey::Message(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
             MetaKey::Presence(meta) => meta.handle_expire(pipe),
-            MetaKey::Role(meta) => {
-                let key = meta.redis_key();
-
-                let Some(bytes) = Self::fetch_bytes(conn, pipe, key).await? else {
-                    return Ok(());
-                };
-
-                let archived = <RoleMetaKey as HasArchived>::Meta::as_archive(&bytes)?;
-                meta.handle_archived(pipe, archived);
-                meta.handle_expire(pipe);
+            MetaKey::Role(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
+            MetaKey::ScheduledEvent(meta) => {
+                Self::handle_archived_expire(&meta, conn, pipe).await?;
             }
-            MetaKey::StageInstance(meta) => {
-                let key = meta.redis_key();
+            MetaKey::StageInstance(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
+            MetaKey::Sticker(meta) => Self::handle_archived_expire(&meta, conn, pipe).await?,
+            MetaKey::ThreadMember(meta) => meta.handle_expire(pipe),
+            MetaKey::User(meta) => meta.handle_expire(pipe),
+            MetaKey::VoiceState(meta) => meta.handle_expire(pipe),
+        }
 
-                let Some(bytes) = Self::fetch_bytes(conn, pipe, key).await? else {
-                    return Ok(());
-                };
+        trace!(target: IO_TARGET, piped = pipe.len());
 
-                let archived = <StageInstanceMetaKey as HasArchived>::Meta::as_archive(&bytes)?;
-                meta.handle_archived(pipe, archived);
-                meta.handle_expire(pipe);
-            }
-            MetaKey::Sticker(meta) => {
-                let key = meta.redis_key();
-
-                let Some(bytes) = Self::fetch_bytes(conn, pipe, key).await? else {
-                    return Ok(());
-                };
+        Ok(())
+    }
 
-                let archived = <StickerMetaKey as HasArchived>::Meta::as_archive(&bytes)?;
-                meta.handle_archived(pipe, archived);
-                meta.handle_expire(pipe);
-            }
-            MetaKey::User(meta) => meta.handle_expire(pipe),
-            MetaKey::VoiceState(meta) => meta.handle_expire(pipe),
+    /// Clean up after an expired entry whose meta holds additional data.
+    async fn handle_archived_expire<M>(
+        meta: &M,
+        conn: &mut DedicatedConnection,
+        pipe: &mut ExpirePipe,
+    ) -> Result<(), ExpireError>
+    where
+        M: IMetaKey + HasArchived,
+    {
+        if let Some(bytes) = Self::fetch_bytes(conn, pipe, meta.redis_key()).await? {
+            meta.handle_bytes(pipe, &bytes)?;
         }
 
-        trace!(piped = pipe.cmd_iter().count());
+        meta.handle_expire(pipe);
 
         Ok(())
     }
 
     async fn fetch_bytes(
         conn: &mut DedicatedConnection,
-        pipe: &mut Pipeline,
+        pipe: &mut ExpirePipe,
         key: RedisKey,
     ) -> Result<Option<Vec<u8>>, ExpireError> {
-        debug_assert_eq!(pipe.cmd_iter().count(), 0);
+        debug_assert_eq!(pipe.len(), 0);
+
+        pipe.get_del(key);
 
         let res = pipe
-            .get_del(key)
-            .query_async::<_, Option<Vec<u8>>>(conn)
+            .query::<Option<Vec<u8>>>(conn)
             .await
             .map(|opt| opt.filter(|bytes| !bytes.is_empty()))
             .map_err(ExpireError::GetMeta);
@@ -171,16 +247,20 @@ impl MetaKey {
 impl Debug for MetaKey {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         match self {
+            Self::AutoModerationRule(meta) => Debug::fmt(meta, f),
             Self::Channel(meta) => Debug::fmt(meta, f),
             Self::Emoji(meta) => Debug::fmt(meta, f),
             Self::Guild(meta) => Debug::fmt(meta, f),
             Self::Integration(meta) => Debug::fmt(meta, f),
+            Self::Invite(meta) => Debug::fmt(meta, f),
             Self::Member(meta) => Debug::fmt(meta, f),
             Self::Message(meta) => Debug::fmt(meta, f),
             Self::Presence(meta) => Debug::fmt(meta, f),
             Self::Role(meta) => Debug::fmt(meta, f),
+            Self::ScheduledEvent(meta) => Debug::fmt(meta, f),
             Self::StageInstance(meta) => Debug::fmt(meta, f),
             Self::Sticker(meta) => Debug::fmt(meta, f),
+            Self::ThreadMember(meta) => Debug::fmt(meta, f),
             Self::User(meta) => Debug::fmt(meta, f),
             Self::VoiceState(meta) => Debug::fmt(meta, f),
         }
@@ -196,7 +276,7 @@ pub(crate) trait IMetaKey: Sized {
     fn parse<'a>(split: &mut impl Iterator<Item = &'a [u8]>) -> Option<Self>;
 
     /// What to do after the payload has been parsed.
-    fn handle_expire(&self, pipe: &mut Pipeline);
+    fn handle_expire(&self, pipe: &mut ExpirePipe);
 }
 
 /// Specifies that a [`IMetaKey`] has additional archived data.
@@ -207,7 +287,17 @@ pub(crate) trait HasArchived: Sized {
     fn redis_key(&self) -> RedisKey;
 
     /// What to do after the additional data has been retrieved.
-    fn handle_archived(&self, pipe: &mut Pipeline, archived: &Archived<Self::Meta>);
+    fn handle_archived(&self, pipe: &mut ExpirePipe, archived: &Archived<Self::Meta>);
+
+    /// Interpret the retrieved bytes and handle the additional data.
+    ///
+    /// Overriding is only necessary if the bytes might have an outdated layout.
+    fn handle_bytes(&self, pipe: &mut ExpirePipe, bytes: &[u8]) -> Result<(), ExpireError> {
+        let archived = Self::Meta::as_archive(bytes)?;
+        self.handle_archived(pipe, archived);
+
+        Ok(())
+    }
 }
 
 /// Additional data for a [`IMetaKey`] that gets archived in the cache.
@@ -217,7 +307,7 @@ pub(crate) trait IMeta<Key: HasArchived>: CheckedArchive<BoxedError> + Sized {
     fn to_bytes(&self) -> Result<Self::Bytes, BoxedError>;
 
     /// Serialize and store this data in the cache.
-    fn store<C>(&self, pipe: &mut Pipe<'_, C>, key: Key) -> Result<(), BoxedError> {
+    fn store<C: CacheConfig>(&self, pipe: &mut Pipe<'_, C>, key: Key) -> Result<(), BoxedError> {
         let bytes = self.to_bytes()?;
         let key = key.redis_key();
         pipe.set(key, bytes.as_ref(), None);
@@ -1,16 +1,161 @@
+use std::{sync::Arc, time::Duration};
+
+use metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Unit,
+};
+use tracing::{debug, warn};
+use twilight_model::id::{marker::GenericMarker, Id};
+
 use super::RedisCache;
 use crate::{
-    config::{CacheConfig, Cacheable},
-    redis::{Connection, Pool},
+    clock::Clock,
+    config::{CacheConfig, Cacheable, DriftAlarm, EntityKind, ICachedPresence},
+    key::{Namespace, RedisKey},
+    redis::{Cmd, Connection, Pipeline, Pool, RedisError},
+    util::instance_id,
 };
 
+const UPDATE_DURATION: &str = "update_duration";
+const ARCHIVE_SIZE: &str = "archive_size";
+const SHADOW_WRITE_BYTES: &str = "shadow_write_bytes";
+const SKIPPED_PRESENCE_WRITES: &str = "skipped_presence_writes";
+const COALESCED_WRITES: &str = "coalesced_writes";
+const DUPLICATE_WRITERS: &str = "duplicate_writers";
+const INDEX_DRIFT: &str = "index_drift";
+const INDEX_DRIFT_ALARMS: &str = "index_drift_alarms";
+const INVALID_IDS: &str = "invalid_ids";
+
+/// Claims or refreshes the leadership of the metrics loop.
+///
+/// KEYS: leader
+/// ARGV: instance id, lease in milliseconds
+const LEADER_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[1])
+
+if current == ARGV[1] then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+
+    return 1
+end
+
+if not current then
+    redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+
+    return 1
+end
+
+return 0
+";
+
+/// Amount of intervals without heartbeat after which the leadership is lost.
+const LEADER_LEASE_INTERVALS: u32 = 3;
+
+type EntryKey = fn(Id<GenericMarker>) -> RedisKey;
+
+/// Index sets whose members are sampled for drift alongside the kind and key
+/// of their entries.
+const DRIFT_INDEXES: [(RedisKey, EntityKind, EntryKey); 10] = [
+    (
+        RedisKey::AutoModerationRules,
+        EntityKind::AutoModerationRule,
+        |id| RedisKey::AutoModerationRule { id: id.cast() },
+    ),
+    (RedisKey::Channels, EntityKind::Channel, |id| {
+        RedisKey::Channel { id: id.cast() }
+    }),
+    (RedisKey::Emojis, EntityKind::Emoji, |id| RedisKey::Emoji {
+        id: id.cast(),
+    }),
+    (RedisKey::Guilds, EntityKind::Guild, |id| RedisKey::Guild {
+        id: id.cast(),
+    }),
+    (RedisKey::Messages, EntityKind::Message, |id| {
+        RedisKey::Message { id: id.cast() }
+    }),
+    (RedisKey::Roles, EntityKind::Role, |id| RedisKey::Role {
+        id: id.cast(),
+    }),
+    (
+        RedisKey::ScheduledEvents,
+        EntityKind::ScheduledEvent,
+        |id| RedisKey::ScheduledEvent { id: id.cast() },
+    ),
+    (RedisKey::StageInstances, EntityKind::StageInstance, |id| {
+        RedisKey::StageInstance { id: id.cast() }
+    }),
+    (RedisKey::Stickers, EntityKind::Sticker, |id| {
+        RedisKey::Sticker { id: id.cast() }
+    }),
+    (RedisKey::Users, EntityKind::User, |id| RedisKey::User {
+        id: id.cast(),
+    }),
+];
+
 impl<C: CacheConfig> RedisCache<C> {
-    pub(crate) fn init_metrics(pool: &Pool) {
-        let wants_any = C::Channel::WANTED
+    pub(crate) fn init_metrics(pool: &Pool, clock: &Arc<dyn Clock>, namespace: &Namespace) {
+        describe_histogram!(
+            UPDATE_DURATION,
+            Unit::Seconds,
+            "Time it took to process a gateway event"
+        );
+
+        describe_histogram!(
+            ARCHIVE_SIZE,
+            Unit::Bytes,
+            "Size of serialized entries per entity kind at write time"
+        );
+
+        describe_counter!(
+            COALESCED_WRITES,
+            "Amount of entry writes that were dropped because the same event wrote the entry again"
+        );
+
+        describe_counter!(
+            INVALID_IDS,
+            "Amount of zero ids that were skipped while reading index sets"
+        );
+
+        if C::SHADOW {
+            describe_counter!(
+                SHADOW_WRITE_BYTES,
+                Unit::Bytes,
+                "Amount of bytes that would have been written outside of shadow mode"
+            );
+        }
+
+        if C::Presence::SKIP_UNCHANGED {
+            describe_counter!(
+                SKIPPED_PRESENCE_WRITES,
+                "Amount of presence updates that were skipped because they were unchanged"
+            );
+        }
+
+        if C::WRITER_LEASE.is_some() {
+            describe_counter!(
+                DUPLICATE_WRITERS,
+                "Amount of heartbeats that found another process writing events of the same shard"
+            );
+        }
+
+        if C::DRIFT_ALARM.is_some() {
+            describe_gauge!(
+                INDEX_DRIFT,
+                "Fraction of sampled index set members without entry"
+            );
+
+            describe_counter!(
+                INDEX_DRIFT_ALARMS,
+                "Amount of intervals in which an index set drifted beyond the threshold"
+            );
+        }
+
+        let wants_any = C::AutoModerationRule::WANTED
+            || C::Channel::WANTED
             || C::Emoji::WANTED
             || C::Guild::WANTED
             || C::Message::WANTED
             || C::Role::WANTED
+            || C::ScheduledEvent::WANTED
             || C::StageInstance::WANTED
             || C::Sticker::WANTED
             || C::User::WANTED;
@@ -19,89 +164,207 @@ impl<C: CacheConfig> RedisCache<C> {
             return;
         }
 
-        tokio::spawn(metrics_loop::<C>(pool.clone()));
+        tokio::spawn(metrics_loop::<C>(
+            pool.clone(),
+            Arc::clone(clock),
+            namespace.clone(),
+        ));
     }
 }
 
-async fn metrics_loop<C: CacheConfig>(pool: Pool) {
-    use metrics::{describe_gauge, gauge};
-    use tracing::{error, trace};
+/// Record the duration of an update.
+///
+/// Unlike collection sizes, latencies are reported by every instance.
+pub(crate) fn record_update_duration(elapsed: Duration) {
+    histogram!(UPDATE_DURATION).record(elapsed.as_secs_f64());
+}
+
+/// Record the size of a serialized entry, labeled by its entity kind.
+pub(crate) fn record_archive_size(kind: EntityKind, bytes: usize) {
+    #[allow(clippy::cast_precision_loss)]
+    histogram!(ARCHIVE_SIZE, "kind" => kind.name()).record(bytes as f64);
+}
 
-    use crate::{config::Cacheable, key::RedisKey, redis::Pipeline};
+pub(crate) fn record_shadow_writes(bytes: usize) {
+    counter!(SHADOW_WRITE_BYTES).increment(bytes as u64);
+}
 
+pub(crate) fn record_skipped_presence_write() {
+    counter!(SKIPPED_PRESENCE_WRITES).increment(1);
+}
+
+pub(crate) fn record_coalesced_writes(count: usize) {
+    counter!(COALESCED_WRITES).increment(count as u64);
+}
+
+pub(crate) fn record_invalid_ids(count: usize) {
+    counter!(INVALID_IDS).increment(count as u64);
+}
+
+pub(crate) fn record_duplicate_writer(shard: u32) {
+    counter!(DUPLICATE_WRITERS, "shard" => shard.to_string()).increment(1);
+}
+
+/// Leader election so that only one of several instances sharing the same
+/// redis publishes collection sizes.
+struct Leadership {
+    instance_id: String,
+    lease_ms: u64,
+    is_leader: bool,
+}
+
+impl Leadership {
+    fn new(clock: &dyn Clock, interval: Duration) -> Self {
+        let lease = interval * LEADER_LEASE_INTERVALS;
+
+        Self {
+            instance_id: instance_id(clock),
+            lease_ms: u64::try_from(lease.as_millis()).unwrap_or(u64::MAX).max(1),
+            is_leader: false,
+        }
+    }
+
+    /// Claim or refresh the leadership and return whether this instance is
+    /// the leader.
+    async fn claim(
+        &mut self,
+        conn: &mut Connection<'_>,
+        namespace: &Namespace,
+    ) -> Result<bool, RedisError> {
+        let is_leader: bool = Cmd::new()
+            .arg("EVAL")
+            .arg(LEADER_SCRIPT)
+            .arg(1)
+            .arg(namespace.key(RedisKey::MetricsLeader))
+            .arg(&self.instance_id)
+            .arg(self.lease_ms)
+            .query_async(conn)
+            .await?;
+
+        if is_leader != self.is_leader {
+            if is_leader {
+                debug!(instance_id = self.instance_id, "Became metrics leader");
+            } else {
+                debug!(instance_id = self.instance_id, "Lost metrics leadership");
+            }
+
+            self.is_leader = is_leader;
+        }
+
+        Ok(is_leader)
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+async fn metrics_loop<C: CacheConfig>(pool: Pool, clock: Arc<dyn Clock>, namespace: Namespace) {
+    use tracing::{error, trace};
+
+    const AUTO_MODERATION_RULE_COUNT: &str = "auto_moderation_rule_count";
     const CHANNEL_COUNT: &str = "channel_count";
     const EMOJI_COUNT: &str = "emoji_count";
     const GUILD_COUNT: &str = "guild_count";
     const MESSAGE_COUNT: &str = "message_count";
     const ROLE_COUNT: &str = "role_count";
+    const SCHEDULED_EVENT_COUNT: &str = "scheduled_event_count";
     const STAGE_INSTANCE_COUNT: &str = "stage_instance_count";
     const STICKER_COUNT: &str = "sticker_count";
     const UNAVAILABLE_GUILD_COUNT: &str = "unavailable_guild_count";
     const USER_COUNT: &str = "user_count";
 
+    describe_gauge!(
+        AUTO_MODERATION_RULE_COUNT,
+        "Amount of cached auto moderation rules"
+    );
     describe_gauge!(CHANNEL_COUNT, "Amount of cached channels");
     describe_gauge!(EMOJI_COUNT, "Amount of cached emojis");
     describe_gauge!(GUILD_COUNT, "Amount of cached guilds");
     describe_gauge!(MESSAGE_COUNT, "Amount of cached messages");
     describe_gauge!(ROLE_COUNT, "Amount of cached roles");
+    describe_gauge!(SCHEDULED_EVENT_COUNT, "Amount of cached scheduled events");
     describe_gauge!(STAGE_INSTANCE_COUNT, "Amount of cached stage instances");
     describe_gauge!(STICKER_COUNT, "Amount of cached stickers");
     describe_gauge!(UNAVAILABLE_GUILD_COUNT, "Amount of unavailable guilds");
     describe_gauge!(USER_COUNT, "Amount of cached users");
 
     let duration = C::METRICS_INTERVAL_DURATION;
+    let mut leadership = Leadership::new(clock.as_ref(), duration);
     let mut pipe = Pipeline::new();
-    let mut interval = tokio::time::interval(duration);
-
-    trace!(interval = ?duration, "Running metrics loop");
+    let mut next_tick = clock.now() + duration;
 
-    interval.tick().await;
+    trace!(
+        interval = ?duration,
+        instance_id = leadership.instance_id,
+        "Running metrics loop"
+    );
 
     loop {
-        interval.tick().await;
+        // Sleeping until the next tick rather than for the full duration so
+        // that the time spent on requests does not accumulate as drift.
+        let until_tick = next_tick.duration_since(clock.now()).unwrap_or_default();
+        clock.sleep(until_tick).await;
+        next_tick += duration;
+
+        let mut conn = match Connection::get(&pool).await {
+            Ok(conn) => conn,
+            Err(err) => {
+                error!(%err, "Failed to acquire connection for metrics");
+
+                continue;
+            }
+        };
+
+        match leadership.claim(&mut conn, &namespace).await {
+            Ok(true) => {}
+            // Another instance publishes the collection sizes
+            Ok(false) => continue,
+            Err(err) => {
+                error!(%err, "Failed to claim metrics leadership");
+
+                continue;
+            }
+        }
+
+        if C::AutoModerationRule::WANTED {
+            pipe.scard(namespace.key(RedisKey::AutoModerationRules));
+        }
 
         if C::Channel::WANTED {
-            pipe.scard(RedisKey::Channels);
+            pipe.scard(namespace.key(RedisKey::Channels));
         }
 
         if C::Emoji::WANTED {
-            pipe.scard(RedisKey::Emojis);
+            pipe.scard(namespace.key(RedisKey::Emojis));
         }
 
         if C::Guild::WANTED {
-            pipe.scard(RedisKey::Guilds);
-            pipe.scard(RedisKey::UnavailableGuilds);
+            pipe.scard(namespace.key(RedisKey::Guilds));
+            pipe.scard(namespace.key(RedisKey::UnavailableGuilds));
         }
 
         if C::Message::WANTED {
-            pipe.scard(RedisKey::Messages);
+            pipe.scard(namespace.key(RedisKey::Messages));
         }
 
         if C::Role::WANTED {
-            pipe.scard(RedisKey::Roles);
+            pipe.scard(namespace.key(RedisKey::Roles));
+        }
+
+        if C::ScheduledEvent::WANTED {
+            pipe.scard(namespace.key(RedisKey::ScheduledEvents));
         }
 
         if C::StageInstance::WANTED {
-            pipe.scard(RedisKey::StageInstances);
+            pipe.scard(namespace.key(RedisKey::StageInstances));
         }
 
         if C::Sticker::WANTED {
-            pipe.scard(RedisKey::Stickers);
+            pipe.scard(namespace.key(RedisKey::Stickers));
         }
 
         if C::User::WANTED {
-            pipe.scard(RedisKey::Users);
+            pipe.scard(namespace.key(RedisKey::Users));
         }
 
-        let mut conn = match Connection::get(&pool).await {
-            Ok(conn) => conn,
-            Err(err) => {
-                error!(%err, "Failed to acquire connection for metrics");
-
-                continue;
-            }
-        };
-
         let mut scards = match pipe.query_async::<_, Vec<usize>>(&mut conn).await {
             Ok(scards) => scards.into_iter(),
             Err(err) => {
@@ -116,6 +379,10 @@ async fn metrics_loop<C: CacheConfig>(pool: Pool) {
         #[allow(clippy::cast_precision_loss)]
         let mut next_scard = || scards.next().unwrap_or(0) as f64;
 
+        if C::AutoModerationRule::WANTED {
+            gauge!(AUTO_MODERATION_RULE_COUNT).set(next_scard());
+        }
+
         if C::Channel::WANTED {
             gauge!(CHANNEL_COUNT).set(next_scard());
         }
@@ -137,6 +404,10 @@ async fn metrics_loop<C: CacheConfig>(pool: Pool) {
             gauge!(ROLE_COUNT).set(next_scard());
         }
 
+        if C::ScheduledEvent::WANTED {
+            gauge!(SCHEDULED_EVENT_COUNT).set(next_scard());
+        }
+
         if C::StageInstance::WANTED {
             gauge!(STAGE_INSTANCE_COUNT).set(next_scard());
         }
@@ -148,5 +419,93 @@ async fn metrics_loop<C: CacheConfig>(pool: Pool) {
         if C::User::WANTED {
             gauge!(USER_COUNT).set(next_scard());
         }
+
+        if let Some(alarm) = C::DRIFT_ALARM {
+            if let Err(err) = check_drift::<C>(&mut conn, &mut pipe, &namespace, alarm).await {
+                error!(%err, "Failed to check index sets for drift");
+            }
+
+            pipe.clear();
+        }
+    }
+}
+
+/// Sample members of index sets and check whether their entries exist.
+async fn check_drift<C: CacheConfig>(
+    conn: &mut Connection<'_>,
+    pipe: &mut Pipeline,
+    namespace: &Namespace,
+    alarm: DriftAlarm,
+) -> Result<(), RedisError> {
+    let indexes: Vec<_> = DRIFT_INDEXES
+        .into_iter()
+        .filter(|(_, kind, _)| is_indexed::<C>(*kind))
+        .collect();
+
+    for (index, ..) in indexes.iter() {
+        pipe.srandmember_multiple(namespace.key(index), alarm.sample_size);
+    }
+
+    let sampled_ids: Vec<Vec<u64>> = pipe.query_async(conn).await?;
+    pipe.clear();
+
+    let mut sampled = Vec::with_capacity(indexes.len());
+
+    for ((_, kind, entry_key), ids) in indexes.iter().zip(sampled_ids) {
+        let ids: Vec<_> = ids.into_iter().filter_map(Id::new_checked).collect();
+
+        for id in ids.iter() {
+            pipe.exists(namespace.key(entry_key(*id)));
+        }
+
+        sampled.push((*kind, ids.len()));
+    }
+
+    if pipe.cmd_iter().next().is_none() {
+        return Ok(());
+    }
+
+    let exists: Vec<bool> = pipe.query_async(conn).await?;
+    let mut exists = exists.into_iter();
+
+    for (kind, sampled) in sampled {
+        if sampled == 0 {
+            continue;
+        }
+
+        let missing = exists.by_ref().take(sampled).filter(|found| !found).count();
+
+        #[allow(clippy::cast_precision_loss)]
+        let drift = missing as f64 / sampled as f64;
+
+        gauge!(INDEX_DRIFT, "kind" => kind.name()).set(drift);
+
+        if drift > alarm.threshold {
+            warn!(
+                kind = kind.name(),
+                drift, sampled, "Index set drifted from its entries"
+            );
+
+            counter!(INDEX_DRIFT_ALARMS, "kind" => kind.name()).increment(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the index set of the entity kind is maintained.
+const fn is_indexed<C: CacheConfig>(kind: EntityKind) -> bool {
+    match kind {
+        EntityKind::AutoModerationRule => C::AutoModerationRule::WANTED,
+        EntityKind::Channel => C::Channel::WANTED,
+        EntityKind::Emoji => C::Emoji::WANTED,
+        EntityKind::Guild => C::Guild::WANTED,
+        EntityKind::Message => C::Message::WANTED,
+        EntityKind::Role => C::Role::WANTED,
+        EntityKind::ScheduledEvent => C::ScheduledEvent::WANTED,
+        EntityKind::StageInstance => C::StageInstance::WANTED,
+        EntityKind::Sticker => C::Sticker::WANTED,
+        EntityKind::User => C::User::WANTED,
+        _ => false,
     }
 }
@@ -1,41 +1,146 @@
+mod batch;
+mod cold;
+mod custom;
+mod deserialized;
 mod expire;
 mod get;
+mod group;
 mod impls;
+mod invalidation;
 mod meta;
+mod otel;
 mod pipe;
+mod pressure;
+mod pubsub;
+mod rate_limit;
+mod refresh;
+mod replica;
+mod run;
+mod schema;
+mod sequence;
+mod starboard;
+mod transaction;
+mod view;
+mod writer;
 
+#[cfg(feature = "attachments")]
+mod attachment;
 #[cfg(feature = "cold_resume")]
 mod cold_resume;
 
+#[cfg(any(feature = "bb8", feature = "deadpool"))]
+mod snapshot;
+
+#[cfg(feature = "http")]
+mod populate;
+
+#[cfg(feature = "inmemory")]
+mod inmemory;
+
+#[cfg(feature = "local_cache")]
+mod local;
+
 #[cfg(feature = "metrics")]
-mod metrics;
+pub(crate) mod metrics;
 
-use std::marker::PhantomData;
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicBool, AtomicU64},
+        Arc,
+    },
+};
 
-use tracing::instrument;
+use tracing::{instrument, trace};
 use twilight_model::gateway::event::Event;
 
+#[cfg(feature = "cold_resume")]
+pub use self::cold_resume::DefrostedSessions;
+#[cfg(any(feature = "bb8", feature = "deadpool"))]
+pub use self::snapshot::GuildSnapshot;
+pub use self::{
+    batch::BatchedUpdater,
+    cold::{ColdStore, ColdStoreError},
+    group::{CacheConfigGroup, UpdateCache},
+    invalidation::{ChangeKind, Invalidations},
+    pressure::{Pressure, PressureGauge},
+    pubsub::{PubSubMessage, Subscription, Topics},
+    refresh::Refresher,
+    replica::{ReadPreference, ReadRoute},
+    schema::SchemaMismatch,
+    sequence::EventSequence,
+    transaction::Transaction,
+    view::{CacheBatch, DerivedView},
+};
 use crate::{
-    cache::pipe::Pipe,
-    config::{CacheConfig, ReactionEvent},
+    cache::{
+        pipe::Pipe, pressure::PressureTracker, rate_limit::RateLimiter, refresh::RefreshQueue,
+        writer::WriterLeases,
+    },
+    clock::{Clock, SystemClock},
+    config::{
+        CacheConfig, ConfigOverrides, ICachedGuild, ICachedIntegration, RateLimitedOperation,
+        ReactionEvent,
+    },
     error::CacheError,
     iter::RedisCacheIter,
+    key::Namespace,
+    maintenance::Maintenance,
     redis::{Connection, Pool},
-    stats::RedisCacheStats,
+    stats::{RedisCacheStats, UpdateCounters},
+    util::invalid_id_count,
     CacheResult,
 };
 
+/// Tracing target of verbose logs about data written to or read from redis.
+pub(crate) const IO_TARGET: &str = "redlight::io";
+
 /// Redis-based cache for data of twilight's gateway [`Event`]s.
 pub struct RedisCache<C> {
     pool: Pool,
+    replica: Option<Pool>,
+    read_preference: ReadPreference,
+    clock: Arc<dyn Clock>,
+    rate_limiter: RateLimiter,
+    refresh: Option<RefreshQueue>,
+    cold_store: Option<Arc<dyn ColdStore>>,
+    views: Vec<Box<dyn DerivedView>>,
+    overrides: ConfigOverrides,
+    namespace: Namespace,
+    pressure: PressureTracker,
+    writer_leases: WriterLeases,
+    pub(crate) update_counters: UpdateCounters,
+    read_samples: AtomicU64,
+    listens_to_expire: AtomicBool,
+    #[cfg(feature = "local_cache")]
+    local: Option<local::LocalLayer>,
     config: PhantomData<C>,
 }
 
 impl<C> RedisCache<C> {
     pub(crate) async fn connection(&self) -> CacheResult<Connection<'_>> {
-        Connection::get(&self.pool)
+        let start = self.clock.now();
+
+        let res = Connection::get(&self.pool)
+            .await
+            .map_err(CacheError::GetConnection);
+
+        let elapsed = self.clock.now().duration_since(start).unwrap_or_default();
+        self.pressure.record_pool_wait(elapsed);
+
+        res
+    }
+
+    /// The namespace that all keys of the cache are rendered in.
+    pub(crate) const fn namespace(&self) -> &Namespace {
+        &self.namespace
+    }
+
+    /// Wait for or fail on the configured rate limit of the operation.
+    pub(crate) async fn rate_limit(&self, operation: RateLimitedOperation) -> CacheResult<()> {
+        self.rate_limiter
+            .acquire(operation, self.clock.as_ref())
             .await
-            .map_err(CacheError::GetConnection)
     }
 
     /// Create a [`RedisCacheIter`] instance to iterate over various cached
@@ -50,6 +155,49 @@ impl<C> RedisCache<C> {
     pub const fn stats(&self) -> RedisCacheStats<'_, C> {
         RedisCacheStats::new(self)
     }
+
+    /// Create a [`Maintenance`] instance to inspect and repair cached
+    /// collections.
+    pub const fn maintenance(&self) -> Maintenance<'_, C> {
+        Maintenance::new(self)
+    }
+
+    /// Get a snapshot of how much load the write path currently puts on
+    /// redis.
+    pub fn pressure(&self) -> PressureGauge {
+        self.pressure.gauge()
+    }
+
+    /// Get a reference to the [`Clock`] used by the cache.
+    pub fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    /// Amount of invalid ids that were skipped while reading index sets.
+    ///
+    /// Index sets should never contain zero ids. If a set was corrupted,
+    /// e.g. by a third party writing to redis, such ids are skipped and
+    /// logged instead of failing the operation. A non-zero count hints at
+    /// sets that should be repaired through [`RedisCache::maintenance`].
+    ///
+    /// The count is shared by all caches of the process.
+    pub fn invalid_id_count(&self) -> u64 {
+        invalid_id_count()
+    }
+
+    #[cfg(feature = "local_cache")]
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "local_cache")))]
+    /// Drop all entries of the in-process layer, see
+    /// [`CacheConfig::LOCAL_CACHE`].
+    ///
+    /// Useful when entries were changed without going through this cache,
+    /// e.g. by another process, and should not be served until the layer's
+    /// lifetime elapsed.
+    pub fn clear_local_cache(&self) {
+        if let Some(ref local) = self.local {
+            local.clear();
+        }
+    }
 }
 
 impl<C: CacheConfig> RedisCache<C> {
@@ -85,212 +233,542 @@ impl<C: CacheConfig> RedisCache<C> {
         Self::new_with_pool(pool).await
     }
 
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        feature = "multiplexed"
+    ))]
+    /// Create a new [`RedisCache`].
+    ///
+    /// The cache will use a single multiplexed connection through the given
+    /// url.
+    pub async fn new(url: &str) -> CacheResult<Self> {
+        use crate::redis::Client;
+
+        let client = Client::open(url).map_err(CacheError::CreatePool)?;
+        let pool = Pool::new(client);
+
+        Self::new_with_pool(pool).await
+    }
+
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    /// Create a new [`RedisCache`].
+    ///
+    /// The cache will discover the redis cluster through the node at the
+    /// given url.
+    pub async fn new(url: &str) -> CacheResult<Self> {
+        let pool = Pool::new(vec![url]).map_err(CacheError::CreatePool)?;
+
+        Self::new_with_pool(pool).await
+    }
+
     /// Create a new [`RedisCache`] by using the given connection pool.
     ///
     /// This provides a way to customize the pool configuration manually.
     pub async fn new_with_pool(pool: Pool) -> CacheResult<Self> {
-        Self::handle_expire(&pool).await?;
+        Self::new_with_clock(pool, SystemClock).await
+    }
+
+    /// Create a new [`RedisCache`] by using the given connection pool and
+    /// [`Clock`].
+    ///
+    /// Providing a [`MockClock`] allows for deterministic tests of
+    /// time-dependent behavior.
+    ///
+    /// [`MockClock`]: crate::clock::MockClock
+    pub async fn new_with_clock(pool: Pool, clock: impl Clock) -> CacheResult<Self> {
+        #[cfg(all(
+            not(feature = "bb8"),
+            not(feature = "deadpool"),
+            not(feature = "multiplexed"),
+            feature = "cluster"
+        ))]
+        Self::check_cluster_support()?;
+
+        let clock: Arc<dyn Clock> = Arc::new(clock);
+
+        // Must be known before expire events are handled
+        let namespace = Namespace::new(C::KEY_PREFIX)?;
+
+        let listens_to_expire = Self::handle_expire(&pool, &namespace).await?;
 
         #[cfg(feature = "metrics")]
-        Self::init_metrics(&pool);
+        Self::init_metrics(&pool, &clock, &namespace);
+
+        let rate_limiter = RateLimiter::new(C::RATE_LIMITS, clock.now());
+        let writer_leases = WriterLeases::new(clock.as_ref());
 
         Ok(Self {
             pool,
+            replica: None,
+            read_preference: ReadPreference::Primary,
+            clock,
+            rate_limiter,
+            refresh: None,
+            cold_store: None,
+            views: Vec::new(),
+            overrides: ConfigOverrides::default(),
+            namespace,
+            pressure: PressureTracker::default(),
+            writer_leases,
+            update_counters: UpdateCounters::default(),
+            read_samples: AtomicU64::new(0),
+            listens_to_expire: AtomicBool::new(listens_to_expire),
+            #[cfg(feature = "local_cache")]
+            local: C::LOCAL_CACHE.map(local::LocalLayer::new),
             config: PhantomData,
         })
     }
 
+    /// Reject options whose scripts access keys of different slots at once.
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    const fn check_cluster_support() -> CacheResult<()> {
+        use crate::config::{ICachedMember, ICachedMessage};
+
+        let option = if C::PREVIOUS_VERSION_LIFETIME.is_some() {
+            "CacheConfig::PREVIOUS_VERSION_LIFETIME"
+        } else if C::Guild::TOMBSTONE_DURATION.is_some() {
+            "ICachedGuild::TOMBSTONE_DURATION"
+        } else if C::Member::INDEX_NAMES {
+            "ICachedMember::INDEX_NAMES"
+        } else if C::Message::CHANNEL_BYTE_BUDGET.is_some() {
+            "ICachedMessage::CHANNEL_BYTE_BUDGET"
+        } else {
+            return Ok(());
+        };
+
+        Err(CacheError::ClusterUnsupported { option })
+    }
+
     /// Get a reference to the underlying redis connection pool.
     pub const fn pool(&self) -> &Pool {
         &self.pool
     }
 
+    /// Register a connection pool of a redis replica that reads can be
+    /// routed to.
+    ///
+    /// Reads keep going to the primary unless a different
+    /// [`ReadPreference`] is set through [`RedisCache::with_read_preference`]
+    /// or passed to [`RedisCache::read_from`]. Writes and scripts always go
+    /// to the primary.
+    #[must_use]
+    pub fn with_replica(mut self, pool: Pool) -> Self {
+        self.replica = Some(pool);
+
+        self
+    }
+
+    /// Set the [`ReadPreference`] of getters.
+    ///
+    /// Defaults to [`ReadPreference::Primary`].
+    #[must_use]
+    pub const fn with_read_preference(mut self, preference: ReadPreference) -> Self {
+        self.read_preference = preference;
+
+        self
+    }
+
+    /// Get a reference to the replica connection pool if one is registered.
+    pub const fn replica(&self) -> Option<&Pool> {
+        self.replica.as_ref()
+    }
+
+    /// Get entries while routing reads according to the given
+    /// [`ReadPreference`] instead of the cache's default preference.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, ReadPreference, RedisCache};
+    /// # use twilight_model::id::Id;
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let cache = cache.with_read_preference(ReadPreference::ReplicaPreferred);
+    ///
+    /// // Stale data is not acceptable for this read
+    /// let user = cache
+    ///     .read_from(ReadPreference::Primary)
+    ///     .user(Id::new(123))
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    pub const fn read_from(&self, preference: ReadPreference) -> ReadRoute<'_, C> {
+        ReadRoute::new(self, preference)
+    }
+
+    /// Apply runtime overrides on top of the type-level [`CacheConfig`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::{CacheConfig, ConfigOverrides}, RedisCache};
+    /// # async fn example<C: CacheConfig>() -> Result<(), Box<dyn std::error::Error>> {
+    /// let overrides = ConfigOverrides::from_env()?;
+    ///
+    /// let cache = RedisCache::<C>::new("redis://127.0.0.1:6379")
+    ///     .await?
+    ///     .with_overrides(overrides);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn with_overrides(mut self, overrides: ConfigOverrides) -> Self {
+        self.overrides = overrides;
+
+        self
+    }
+
+    /// Get a reference to the runtime overrides of the config.
+    pub const fn overrides(&self) -> &ConfigOverrides {
+        &self.overrides
+    }
+
     /// Update the cache with an [`Event`] from the gateway.
     #[instrument(skip_all, fields(event = ?event.kind()))]
     pub async fn update(&self, event: &Event) -> CacheResult<()> {
+        #[cfg(feature = "opentelemetry")]
+        otel::attach_context();
+
+        self.process(event, None).await
+    }
+
+    /// Update the cache with an [`Event`] from the gateway and advise whether
+    /// redis is struggling to keep up.
+    ///
+    /// Returns [`Pressure::High`] if any value of [`RedisCache::pressure`]
+    /// exceeds the configured [`CacheConfig::PRESSURE_THRESHOLDS`]. Callers
+    /// may then shed less important events such as presence updates or typing
+    /// starts until the pressure is back to [`Pressure::Normal`].
+    pub async fn update_with_pressure(&self, event: &Event) -> CacheResult<Pressure> {
+        self.update(event).await?;
+
+        let pressure = C::PRESSURE_THRESHOLDS.map_or(Pressure::Normal, |thresholds| {
+            self.pressure().level(&thresholds)
+        });
+
+        Ok(pressure)
+    }
+
+    /// Update the cache with an [`Event`] from the gateway unless the event's
+    /// sequence was already applied.
+    ///
+    /// The last applied sequence is tracked per shard in redis so that
+    /// replaying events, e.g. from an external queue after a crash, does not
+    /// apply them twice. Since sequences start anew with every session,
+    /// [`Event::Ready`] always resets the tracked sequence of its shard.
+    ///
+    /// If `sequence` is `None`, this is the same as [`RedisCache::update`].
+    /// Otherwise, the shard's writer lease is checked if
+    /// [`CacheConfig::WRITER_LEASE`] is set. In shadow mode, neither the
+    /// writer lease nor the tracked sequence are used, see
+    /// [`CacheConfig::SHADOW`].
+    ///
+    /// Returns `false` if the event was skipped.
+    #[instrument(skip_all, fields(event = ?event.kind(), ?sequence))]
+    pub async fn update_with_meta(
+        &self,
+        event: &Event,
+        sequence: Option<EventSequence>,
+    ) -> CacheResult<bool> {
+        #[cfg(feature = "opentelemetry")]
+        otel::attach_context();
+
+        if let Some(sequence) = sequence {
+            self.check_writer(sequence.shard_id).await?;
+
+            if !matches!(event, Event::Ready(_)) && self.is_applied(sequence).await? {
+                trace!("Event sequence already applied; skipping update");
+
+                return Ok(false);
+            }
+        }
+
+        self.process(event, sequence).await?;
+
+        Ok(true)
+    }
+
+    async fn process(&self, event: &Event, sequence: Option<EventSequence>) -> CacheResult<()> {
+        let res = self.process_event(event, sequence).await;
+        self.pressure.record_outcome(res.is_ok());
+
+        res
+    }
+
+    async fn process_event(
+        &self,
+        event: &Event,
+        sequence: Option<EventSequence>,
+    ) -> CacheResult<()> {
+        let start = self.clock.now();
+
         let mut pipe = Pipe::new(self);
+        self.fill_pipe(&mut pipe, event, sequence).await?;
+        self.apply_views(&mut pipe, event);
+
+        if !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
 
+        let elapsed = self.clock.now().duration_since(start).unwrap_or_default();
+        self.update_counters.record(elapsed);
+
+        #[cfg(feature = "metrics")]
+        metrics::record_update_duration(elapsed);
+
+        Ok(())
+    }
+
+    /// Add the writes of an event to the pipeline.
+    ///
+    /// Reads that the writes depend on are executed right away.
+    #[allow(clippy::too_many_lines)]
+    async fn fill_pipe(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        event: &Event,
+        sequence: Option<EventSequence>,
+    ) -> CacheResult<()> {
         #[allow(clippy::match_same_arms)]
         match event {
             Event::AutoModerationActionExecution(_) => {}
-            Event::AutoModerationRuleCreate(_) => {}
-            Event::AutoModerationRuleDelete(_) => {}
-            Event::AutoModerationRuleUpdate(_) => {}
-            Event::BanAdd(event) => self.store_user(&mut pipe, &event.user)?,
-            Event::BanRemove(event) => self.store_user(&mut pipe, &event.user)?,
-            Event::ChannelCreate(event) => self.store_channel(&mut pipe, event)?,
-            Event::ChannelDelete(event) => self.delete_channel(&mut pipe, event.guild_id, event.id),
+            Event::AutoModerationRuleCreate(event) => {
+                self.store_auto_moderation_rule(pipe, event)?;
+            }
+            Event::AutoModerationRuleDelete(event) => {
+                self.delete_auto_moderation_rule(pipe, event.guild_id, event.id);
+            }
+            Event::AutoModerationRuleUpdate(event) => {
+                self.store_auto_moderation_rule(pipe, event)?;
+            }
+            Event::BanAdd(event) => self.store_ban(pipe, event.guild_id, &event.user)?,
+            Event::BanRemove(event) => {
+                self.delete_ban(pipe, event.guild_id, &event.user)?;
+            }
+            Event::ChannelCreate(event) => self.store_channel(pipe, event)?,
+            Event::ChannelDelete(event) => {
+                self.delete_channel(pipe, event.guild_id, event.id).await?;
+            }
             Event::ChannelPinsUpdate(event) => {
-                self.store_channel_pins_update(&mut pipe, event).await?;
+                self.store_channel_pins_update(pipe, event).await?;
             }
-            Event::ChannelUpdate(event) => self.store_channel(&mut pipe, event)?,
+            Event::ChannelUpdate(event) => self.store_channel_update(pipe, event).await?,
             Event::CommandPermissionsUpdate(_) => {}
             Event::GatewayClose(_) => {}
             Event::GatewayHeartbeat(_) => {}
             Event::GatewayHeartbeatAck => {}
             Event::GatewayHello(_) => {}
-            Event::GatewayInvalidateSession(_) => {}
-            Event::GatewayReconnect => {}
+            Event::GatewayInvalidateSession(_) | Event::GatewayReconnect => {
+                self.handle_stale_guilds().await?;
+            }
             Event::GiftCodeUpdate => {}
             Event::GuildAuditLogEntryCreate(_) => {}
-            Event::GuildCreate(event) => self.store_guild(&mut pipe, event)?,
+            Event::GuildCreate(event) => {
+                Self::drop_guild_tombstone(pipe, event.id);
+                self.store_guild(pipe, event)?;
+            }
             Event::GuildDelete(event) => {
                 if event.unavailable {
-                    self.store_unavailable_guild(&mut pipe, event.id).await?;
+                    self.store_unavailable_guild(pipe, event.id).await?;
+                } else if let Some(duration) = C::Guild::TOMBSTONE_DURATION {
+                    self.tombstone_guild(pipe, event.id, duration).await?;
                 } else {
-                    self.delete_guild(&mut pipe, event.id).await?;
+                    self.delete_guild(pipe, event.id).await?;
                 }
             }
             Event::GuildEmojisUpdate(event) => {
-                self.store_emojis(&mut pipe, event.guild_id, &event.emojis)?;
+                self.store_guild_emojis_update(pipe, event).await?;
+            }
+            Event::GuildIntegrationsUpdate(event) => {
+                // The event does not contain the integrations so the cached
+                // ones can't be trusted anymore
+                self.delete_guild_integrations(pipe, event.guild_id).await?;
+
+                if let Some(hook) = C::Integration::on_integrations_update() {
+                    hook(event);
+                }
             }
-            Event::GuildIntegrationsUpdate(_) => {}
             Event::GuildScheduledEventCreate(event) => {
                 if let Some(ref user) = event.creator {
-                    self.store_user(&mut pipe, user)?;
+                    self.store_user(pipe, user)?;
                 }
+
+                self.store_scheduled_event(pipe, event)?;
             }
             Event::GuildScheduledEventDelete(event) => {
                 if let Some(ref user) = event.creator {
-                    self.store_user(&mut pipe, user)?;
+                    self.store_user(pipe, user)?;
                 }
+
+                self.delete_scheduled_event(pipe, event.guild_id, event.id);
             }
             Event::GuildScheduledEventUpdate(event) => {
                 if let Some(ref user) = event.creator {
-                    self.store_user(&mut pipe, user)?;
+                    self.store_user(pipe, user)?;
                 }
+
+                self.store_scheduled_event(pipe, event)?;
+            }
+            Event::GuildScheduledEventUserAdd(event) => {
+                self.store_scheduled_event_user_count(pipe, event.guild_scheduled_event_id, 1)
+                    .await?;
+            }
+            Event::GuildScheduledEventUserRemove(event) => {
+                self.store_scheduled_event_user_count(pipe, event.guild_scheduled_event_id, -1)
+                    .await?;
             }
-            Event::GuildScheduledEventUserAdd(_) => {}
-            Event::GuildScheduledEventUserRemove(_) => {}
             Event::GuildStickersUpdate(event) => {
-                self.store_stickers(&mut pipe, event.guild_id, &event.stickers)?;
+                self.store_guild_stickers_update(pipe, event).await?;
             }
-            Event::GuildUpdate(event) => self.store_guild_update(&mut pipe, event).await?,
+            Event::GuildUpdate(event) => self.store_guild_update(pipe, event).await?,
             Event::IntegrationCreate(event) => {
                 if let Some(guild_id) = event.guild_id {
-                    self.store_integration(&mut pipe, guild_id, event)?;
+                    self.store_integration(pipe, guild_id, event)?;
                 }
             }
             Event::IntegrationDelete(event) => {
-                self.delete_integration(&mut pipe, event.guild_id, event.id);
+                self.delete_integration(pipe, event.guild_id, event.id);
             }
             Event::IntegrationUpdate(event) => {
                 if let Some(guild_id) = event.guild_id {
-                    self.store_integration(&mut pipe, guild_id, event)?;
+                    self.store_integration(pipe, guild_id, event)?;
                 }
             }
-            Event::InteractionCreate(event) => self.store_interaction(&mut pipe, event).await?,
-            Event::InviteCreate(event) => {
-                if let Some(ref user) = event.inviter {
-                    self.store_user(&mut pipe, user)?;
-                }
-
-                if let Some(ref user) = event.target_user {
-                    self.store_partial_user(&mut pipe, user).await?;
-                }
+            Event::InteractionCreate(event) => self.store_interaction(pipe, event).await?,
+            Event::InviteCreate(event) => self.store_invite(pipe, event).await?,
+            Event::InviteDelete(event) => {
+                self.delete_invite(pipe, event.guild_id, event.channel_id, &event.code);
             }
-            Event::InviteDelete(_) => {}
             Event::MemberAdd(event) => {
-                self.store_member(&mut pipe, event.guild_id, &event.member)?;
+                self.store_member(pipe, event.guild_id, &event.member)?;
             }
             Event::MemberRemove(event) => {
-                self.delete_member(&mut pipe, event.guild_id, event.user.id)
+                self.delete_member(pipe, event.guild_id, event.user.id)
                     .await?;
             }
-            Event::MemberUpdate(event) => self.store_member_update(&mut pipe, event).await?,
+            Event::MemberUpdate(event) => self.store_member_update(pipe, event).await?,
             Event::MemberChunk(event) => {
-                self.store_members(&mut pipe, event.guild_id, &event.members)?;
-                self.store_presences(&mut pipe, event.guild_id, &event.presences)?;
+                self.store_members(pipe, event.guild_id, &event.members)?;
+                self.store_presences(pipe, event.guild_id, &event.presences)?;
             }
-            Event::MessageCreate(event) => self.store_message(&mut pipe, event).await?,
+            Event::MessageCreate(event) => self.store_message(pipe, event).await?,
             Event::MessageDelete(event) => {
-                self.delete_message(&mut pipe, event.id, event.channel_id);
+                self.delete_message(pipe, event.id, event.channel_id);
+                self.remove_from_starboard(pipe, event.guild_id, &[event.id]);
             }
             Event::MessageDeleteBulk(event) => {
-                self.delete_messages(&mut pipe, &event.ids, event.channel_id);
+                self.delete_messages(pipe, &event.ids, event.channel_id);
+                self.remove_from_starboard(pipe, event.guild_id, &event.ids);
             }
-            Event::MessageUpdate(event) => self.store_message_update(&mut pipe, event).await?,
-            Event::PresenceUpdate(event) => self.store_presence(&mut pipe, event)?,
+            Event::MessageUpdate(event) => self.store_message_update(pipe, event).await?,
+            Event::PresenceUpdate(event) => self.store_presence_update(pipe, event).await?,
             Event::PresencesReplace => {}
             Event::ReactionAdd(event) => {
                 if let (Some(guild_id), Some(member)) = (event.guild_id, &event.member) {
-                    self.store_member(&mut pipe, guild_id, member)?;
+                    self.store_member(pipe, guild_id, member)?;
                 }
 
-                self.handle_reaction(&mut pipe, ReactionEvent::Add(event))
+                self.handle_reaction(pipe, ReactionEvent::Add(event))
                     .await?;
             }
             Event::ReactionRemove(event) => {
                 if let (Some(guild_id), Some(member)) = (event.guild_id, &event.member) {
-                    self.store_member(&mut pipe, guild_id, member)?;
+                    self.store_member(pipe, guild_id, member)?;
                 }
 
-                self.handle_reaction(&mut pipe, ReactionEvent::Remove(event))
+                self.handle_reaction(pipe, ReactionEvent::Remove(event))
                     .await?;
             }
             Event::ReactionRemoveAll(event) => {
-                self.handle_reaction(&mut pipe, ReactionEvent::RemoveAll(event))
+                self.handle_reaction(pipe, ReactionEvent::RemoveAll(event))
                     .await?;
             }
             Event::ReactionRemoveEmoji(event) => {
-                self.handle_reaction(&mut pipe, ReactionEvent::RemoveEmoji(event))
+                self.handle_reaction(pipe, ReactionEvent::RemoveEmoji(event))
                     .await?;
             }
             Event::Ready(event) => {
-                self.store_unavailable_guilds(&mut pipe, &event.guilds)
-                    .await?;
-                self.store_current_user(&mut pipe, &event.user)?;
+                self.store_unavailable_guilds(pipe, &event.guilds).await?;
+                self.store_current_user(pipe, &event.user)?;
             }
             Event::Resumed => {}
-            Event::RoleCreate(event) => self.store_role(&mut pipe, event.guild_id, &event.role)?,
-            Event::RoleDelete(event) => self.delete_role(&mut pipe, event.guild_id, event.role_id),
-            Event::RoleUpdate(event) => self.store_role(&mut pipe, event.guild_id, &event.role)?,
-            Event::StageInstanceCreate(event) => self.store_stage_instance(&mut pipe, event)?,
+            Event::RoleCreate(event) => self.store_role(pipe, event.guild_id, &event.role)?,
+            Event::RoleDelete(event) => self.delete_role(pipe, event.guild_id, event.role_id),
+            Event::RoleUpdate(event) => self.store_role(pipe, event.guild_id, &event.role)?,
+            Event::StageInstanceCreate(event) => self.store_stage_instance(pipe, event)?,
             Event::StageInstanceDelete(event) => {
-                self.delete_stage_instance(&mut pipe, event.guild_id, event.id);
+                self.delete_stage_instance(pipe, event.guild_id, event.id);
             }
-            Event::StageInstanceUpdate(event) => self.store_stage_instance(&mut pipe, event)?,
-            Event::ThreadCreate(event) => self.store_channel(&mut pipe, event)?,
+            Event::StageInstanceUpdate(event) => {
+                self.store_stage_instance_update(pipe, event).await?;
+            }
+            Event::ThreadCreate(event) => self.store_channel(pipe, event)?,
             Event::ThreadDelete(event) => {
-                self.delete_channel(&mut pipe, Some(event.guild_id), event.id);
+                self.delete_channel(pipe, Some(event.guild_id), event.id)
+                    .await?;
             }
             Event::ThreadListSync(event) => {
-                self.store_channels(&mut pipe, event.guild_id, &event.threads)?;
+                self.store_channels(pipe, event.guild_id, &event.threads)?;
+                self.store_thread_members(pipe, &event.members)?;
             }
             Event::ThreadMemberUpdate(event) => {
+                self.store_thread_member(pipe, &event.member)?;
+
                 if let Some(ref presence) = event.presence {
-                    self.store_presence(&mut pipe, presence)?;
+                    self.store_presence(pipe, presence)?;
                     if let Some(ref member) = event.member.member {
-                        self.store_member(&mut pipe, presence.guild_id, member)?;
+                        self.store_member(pipe, presence.guild_id, member)?;
                     }
                 }
             }
-            Event::ThreadMembersUpdate(_) => {}
-            Event::ThreadUpdate(event) => self.store_channel(&mut pipe, event)?,
+            Event::ThreadMembersUpdate(event) => {
+                self.store_thread_members_update(pipe, event)?;
+            }
+            Event::ThreadUpdate(event) => self.store_channel(pipe, event)?,
             Event::TypingStart(event) => {
                 if let (Some(guild_id), Some(member)) = (event.guild_id, &event.member) {
-                    self.store_member(&mut pipe, guild_id, member)?;
+                    self.store_member(pipe, guild_id, member)?;
                 }
             }
             Event::UnavailableGuild(event) => {
-                self.store_unavailable_guild(&mut pipe, event.id).await?;
+                self.store_unavailable_guild(pipe, event.id).await?;
             }
-            Event::UserUpdate(event) => self.store_current_user(&mut pipe, event)?,
+            Event::UserUpdate(event) => self.store_current_user(pipe, event)?,
             Event::VoiceServerUpdate(_) => {}
             Event::VoiceStateUpdate(event) => {
                 if let Some(guild_id) = event.guild_id {
                     if let Some(channel_id) = event.channel_id {
-                        self.store_voice_state(&mut pipe, channel_id, guild_id, event)?;
+                        self.store_voice_state(pipe, channel_id, guild_id, event)
+                            .await?;
                     } else {
-                        self.delete_voice_state(&mut pipe, guild_id, event.user_id);
+                        self.delete_voice_state(pipe, guild_id, event.user_id);
                     }
                 }
             }
-            Event::WebhooksUpdate(_) => {}
+            Event::WebhooksUpdate(event) => self.store_webhooks_update(pipe, event),
         };
 
-        if !pipe.is_empty() {
-            pipe.query::<()>().await?;
+        // Deleted guilds must not be tracked again
+        if !matches!(event, Event::GuildDelete(_)) {
+            if let Some(guild_id) = event.guild_id() {
+                self.store_guild_activity(pipe, guild_id);
+            }
+        }
+
+        if let Some(sequence) = sequence {
+            let reset = matches!(event, Event::Ready(_));
+            Self::store_sequence(pipe, sequence, reset);
         }
 
         Ok(())
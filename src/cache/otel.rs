@@ -0,0 +1,40 @@
+use tracing::Span;
+
+/// Tracing target of client spans around round trips to redis.
+#[cfg(feature = "opentelemetry")]
+const REDIS_TARGET: &str = "redlight::redis";
+
+/// Use the current OpenTelemetry context as parent of the current span.
+///
+/// Spans already inherit the context of parent tracing spans so this covers
+/// callers that propagate their trace through OpenTelemetry's context
+/// directly, e.g. after extracting it from an incoming request.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn attach_context() {
+    use opentelemetry::{trace::TraceContextExt, Context};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let cx = Context::current();
+
+    if cx.span().span_context().is_valid() {
+        Span::current().set_parent(cx);
+    }
+}
+
+/// Span of a single round trip to redis, recorded as client span.
+#[cfg(feature = "opentelemetry")]
+pub(crate) fn client_span(operation: &'static str) -> Span {
+    tracing::debug_span!(
+        target: REDIS_TARGET,
+        "redis",
+        otel.kind = "client",
+        db.system = "redis",
+        db.operation = operation,
+    )
+}
+
+/// Client spans are only recorded with the `opentelemetry` feature.
+#[cfg(not(feature = "opentelemetry"))]
+pub(crate) const fn client_span(_operation: &'static str) -> Span {
+    Span::none()
+}
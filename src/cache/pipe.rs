@@ -1,19 +1,96 @@
-use std::time::Duration;
+use std::{mem, time::Duration};
 
 use rkyv::util::AlignedVec;
-use tracing::{instrument, trace};
+use tracing::{instrument, trace, Instrument};
 
+#[cfg(feature = "local_cache")]
+use crate::cache::local::LocalLayer;
+#[cfg(feature = "serde-mirror")]
+use crate::config::EntityKind;
 use crate::{
-    config::{CacheConfig, Cacheable},
-    key::RedisKey,
-    redis::{Cmd, ConnectionState, FromRedisValue, Pipeline, ToRedisArgs},
+    cache::{
+        invalidation::{invalidation_channel, invalidation_payload, ChangeKind},
+        otel,
+        pressure::PressureTracker,
+        IO_TARGET,
+    },
+    config::{CacheConfig, Cacheable, ConfigOverrides, VERSION_LIFETIME},
+    error::CacheError,
+    key::{Keys, Namespace, RedisKey},
+    redis::{Arg, Cmd, ConnectionState, FromRedisValue, Pipeline, ToRedisArgs, Value},
     util::BytesWrap,
     CacheResult, CachedArchive, RedisCache,
 };
 
+/// Sets an entry only if the given version is not smaller than the stored one.
+///
+/// Versions are zero-padded so that they can be compared as strings without
+/// losing precision.
+///
+/// KEYS: entry, version
+/// ARGV: version, bytes, expire milliseconds (0 for none), version expire
+/// milliseconds
+const SET_VERSIONED_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[2])
+
+if current and current > ARGV[1] then
+    return 0
+end
+
+if ARGV[3] == '0' then
+    redis.call('SET', KEYS[1], ARGV[2])
+else
+    redis.call('SET', KEYS[1], ARGV[2], 'PX', ARGV[3])
+end
+
+redis.call('SET', KEYS[2], ARGV[1], 'PX', ARGV[4])
+
+return 1
+";
+
+/// Copies an entry to the key of its previous version.
+///
+/// KEYS: entry, previous version
+/// ARGV: expire seconds of the previous version
+const KEEP_PREVIOUS_SCRIPT: &str = r"
+local current = redis.call('GET', KEYS[1])
+
+if current then
+    redis.call('SET', KEYS[2], current, 'EX', ARGV[1])
+end
+
+return 1
+";
+
+#[allow(clippy::struct_field_names)]
 pub(crate) struct Pipe<'c, C> {
     conn: ConnectionState<'c, C>,
     pipe: Pipeline,
+    versions: Vec<(RedisKey, u64)>,
+    deferred: Vec<DeferredSet>,
+    overrides: &'c ConfigOverrides,
+    namespace: &'c Namespace,
+    pressure: &'c PressureTracker,
+    #[cfg(feature = "local_cache")]
+    local: Option<&'c LocalLayer>,
+    #[cfg(feature = "local_cache")]
+    local_keys: Vec<Vec<u8>>,
+    /// A feature that was used but can't be applied in cluster mode.
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    unsupported: Option<&'static str>,
+}
+
+/// A write that is only added to the pipeline once it is sent, see
+/// [`Pipe::set_coalesced`].
+struct DeferredSet {
+    key: RedisKey,
+    bytes: Box<[u8]>,
+    expire: Option<Duration>,
 }
 
 impl<'c, C> Pipe<'c, C> {
@@ -21,71 +98,383 @@ impl<'c, C> Pipe<'c, C> {
         Self {
             conn: ConnectionState::new(cache),
             pipe: Pipeline::new(),
+            versions: Vec::new(),
+            deferred: Vec::new(),
+            overrides: &cache.overrides,
+            namespace: &cache.namespace,
+            pressure: &cache.pressure,
+            #[cfg(feature = "local_cache")]
+            local: cache.local.as_ref(),
+            #[cfg(feature = "local_cache")]
+            local_keys: Vec::new(),
+            #[cfg(all(
+                not(feature = "bb8"),
+                not(feature = "deadpool"),
+                not(feature = "multiplexed"),
+                feature = "cluster"
+            ))]
+            unsupported: None,
         }
     }
 
+    /// Wrap all commands of the pipeline into a MULTI/EXEC block.
+    ///
+    /// Not available in cluster mode since keys of a transaction may belong
+    /// to different slots.
+    #[cfg(not(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    )))]
+    pub(crate) fn atomic(&mut self) {
+        self.pipe.atomic();
+    }
+    /// The namespace that keys of the pipeline are rendered in.
+    pub(crate) const fn namespace(&self) -> &'c Namespace {
+        self.namespace
+    }
+
     pub(crate) fn len(&self) -> usize {
-        self.pipe.cmd_iter().count()
+        self.pipe.cmd_iter().count() + self.deferred.len()
     }
 
-    pub(crate) async fn query<T: FromRedisValue>(&mut self) -> CacheResult<T> {
-        trace!(piped = self.len());
+    pub(crate) async fn get_bytes(
+        &mut self,
+        key: impl Keys,
+    ) -> CacheResult<Option<AlignedVec<16>>> {
+        let namespace = self.namespace;
+
+        self.get_rendered_bytes(namespace.key(key)).await
+    }
+
+    async fn get_rendered_bytes(
+        &mut self,
+        key: impl ToRedisArgs,
+    ) -> CacheResult<Option<AlignedVec<16>>> {
+        let conn = self.conn.get().await?;
+
+        let BytesWrap::<AlignedVec<16>>(bytes) = Cmd::get(key).query_async(conn).await?;
+
+        Ok((!bytes.is_empty()).then_some(bytes))
+    }
+
+    /// Retrieve the members of all given sets right away without executing
+    /// the commands buffered so far.
+    pub(crate) async fn set_members<T: FromRedisValue>(
+        &mut self,
+        keys: impl IntoIterator<Item = RedisKey>,
+    ) -> CacheResult<Vec<T>> {
+        let mut pipe = Pipeline::new();
+
+        for key in keys {
+            pipe.smembers(self.namespace.key(key));
+        }
 
         let conn = self.conn.get().await?;
-        let res = self.pipe.query_async(conn).await?;
-        self.pipe.clear();
+        let members: Vec<Vec<T>> = pipe.query_async(conn).await?;
 
-        Ok(res)
+        Ok(members.into_iter().flatten().collect())
+    }
+
+    /// Retrieve a field of a hash right away if the given entry exists.
+    ///
+    /// The commands are sent together without a script so that the keys may
+    /// belong to different slots.
+    pub(crate) async fn hget_if_exists<T: FromRedisValue>(
+        &mut self,
+        entry: RedisKey,
+        key: RedisKey,
+        field: impl ToRedisArgs,
+    ) -> CacheResult<Option<T>> {
+        let mut pipe = Pipeline::new();
+        pipe.exists(self.namespace.key(entry))
+            .hget(self.namespace.key(key), field);
+
+        let conn = self.conn.get().await?;
+        let (exists, value): (bool, Option<T>) = pipe.query_async(conn).await?;
+
+        Ok(value.filter(|_| exists))
+    }
+
+    pub(crate) async fn exists(&mut self, key: impl Keys) -> CacheResult<bool> {
+        let conn = self.conn.get().await?;
+
+        let exists = Cmd::exists(self.namespace.key(key))
+            .query_async(conn)
+            .await?;
+
+        Ok(exists)
+    }
+
+    /// Remove the JSON copy of an entry.
+    #[cfg(feature = "serde-mirror")]
+    pub(crate) fn del_mirror(&mut self, key: &RedisKey) {
+        self.pipe.del(mirror_key(self.namespace, key)).ignore();
+    }
+
+    /// Rewrite the JSON copy of an entry that was updated in-place.
+    ///
+    /// If the type provides no [`Cacheable::mirror_archived`], the copy
+    /// became stale and is removed instead.
+    #[cfg(feature = "serde-mirror")]
+    pub(crate) fn remirror<T: Cacheable>(
+        &mut self,
+        key: &RedisKey,
+        value: &CachedArchive<T>,
+    ) -> Result<(), T::Error> {
+        if !T::MIRROR || self.is_disabled(key) {
+            return Ok(());
+        }
+
+        match T::mirror_archived(value).transpose()? {
+            Some(json) => self.set_mirror::<T>(key, &json),
+            None => self.del_mirror(key),
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate a lua script, ignoring its result.
+    pub(crate) fn eval(&mut self, script: &str, keys: &[RedisKey], args: impl ToRedisArgs) {
+        #[cfg(feature = "local_cache")]
+        for key in keys {
+            self.invalidate_local(key);
+        }
+
+        self.pipe
+            .cmd("EVAL")
+            .arg(script)
+            .arg(keys.len())
+            .arg(self.namespace.key(keys))
+            .arg(args)
+            .ignore();
+    }
+
+    /// Evaluate a lua script right away, bypassing the pipeline.
+    pub(crate) async fn eval_query<T: FromRedisValue>(
+        &mut self,
+        script: &str,
+        keys: &[RedisKey],
+        args: impl ToRedisArgs,
+    ) -> CacheResult<T> {
+        let conn = self.conn.get().await?;
+
+        let res = Cmd::new()
+            .arg("EVAL")
+            .arg(script)
+            .arg(keys.len())
+            .arg(self.namespace.key(keys))
+            .arg(args)
+            .query_async(conn)
+            .await;
+
+        #[cfg(feature = "local_cache")]
+        if let Some(local) = self.local {
+            let rendered: Vec<_> = keys
+                .iter()
+                .filter(|key| local.is_wanted(key))
+                .map(|key| self.namespace.render(key))
+                .collect();
+
+            local.invalidate(&rendered);
+        }
+
+        res.map_err(CacheError::Redis)
     }
 
-    pub(crate) fn del(&mut self, key: impl ToRedisArgs) {
-        self.pipe.del(key).ignore();
+    /// Refresh the expire duration of an entry without rewriting it.
+    pub(crate) fn expire(&mut self, key: RedisKey, duration: Duration) {
+        let duration = self
+            .overrides
+            .apply_expire(key.entity_kind(), Some(duration))
+            .unwrap_or(duration);
+
+        self.pipe
+            .pexpire(self.namespace.key(key), expire_millis(duration))
+            .ignore();
+    }
+
+    /// Set the expire duration of an entry in milliseconds.
+    ///
+    /// Unlike other commands, the response is not ignored.
+    pub(crate) fn pexpire(&mut self, key: RedisKey, duration: Duration) {
+        let millis = usize::try_from(duration.as_millis()).unwrap_or(usize::MAX);
+        self.pipe.pexpire(self.namespace.key(key), millis);
     }
 
     pub(crate) fn is_empty(&self) -> bool {
-        self.pipe.cmd_iter().next().is_none()
+        self.deferred.is_empty() && self.pipe.cmd_iter().next().is_none()
     }
 
-    pub(crate) fn mset<V: ToRedisArgs>(
+    pub(crate) fn hdel(&mut self, key: RedisKey, fields: impl ToRedisArgs) {
+        self.pipe.hdel(self.namespace.key(key), fields).ignore();
+    }
+
+    pub(crate) fn hset(&mut self, key: RedisKey, field: impl ToRedisArgs, value: impl ToRedisArgs) {
+        self.pipe
+            .hset(self.namespace.key(key), field, value)
+            .ignore();
+    }
+
+    pub(crate) fn hset_multiple<F, V>(&mut self, key: RedisKey, items: &[(F, V)])
+    where
+        F: ToRedisArgs,
+        V: ToRedisArgs,
+    {
+        self.pipe
+            .hset_multiple(self.namespace.key(key), items)
+            .ignore();
+    }
+
+    /// Push bytes to the front of a list and trim the list to `len` entries.
+    pub(crate) fn lpush_capped(
         &mut self,
-        items: &[(RedisKey, V)],
+        key: RedisKey,
+        bytes: &[u8],
+        len: usize,
         expire: Option<Duration>,
     ) {
-        self.pipe.mset(items).ignore();
+        let stop = isize::try_from(len).unwrap_or(isize::MAX) - 1;
+
+        let key = self.namespace.key(key);
+
+        self.pipe.lpush(&key, bytes).ignore();
+        self.pipe.ltrim(&key, 0, stop).ignore();
 
         if let Some(duration) = expire {
-            for (key, _) in items {
-                #[allow(clippy::cast_possible_truncation)]
-                self.pipe.expire(key, duration.as_secs() as usize).ignore();
-            }
+            self.pipe.pexpire(key, expire_millis(duration)).ignore();
         }
     }
 
     pub(crate) fn sadd(&mut self, key: RedisKey, member: impl ToRedisArgs) {
-        self.pipe.sadd(key, member).ignore();
+        self.pipe.sadd(self.namespace.key(key), member).ignore();
     }
 
     pub(crate) fn scard(&mut self, key: RedisKey) {
-        self.pipe.scard(key);
+        self.pipe.scard(self.namespace.key(key));
     }
 
-    pub(crate) fn set(&mut self, key: RedisKey, bytes: &[u8], expire: Option<Duration>) {
+    /// Store the JSON copy of an entry if its type provides one.
+    #[cfg(feature = "serde-mirror")]
+    pub(crate) fn mirror<T: Cacheable>(
+        &mut self,
+        key: &RedisKey,
+        value: &T,
+    ) -> Result<(), T::Error> {
+        if !T::MIRROR || self.is_disabled(key) {
+            return Ok(());
+        }
+
+        if let Some(json) = value.mirror().transpose()? {
+            self.set_mirror::<T>(key, &json);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde-mirror")]
+    fn set_mirror<T: Cacheable>(&mut self, key: &RedisKey, json: &[u8]) {
+        let expire = self.overrides.apply_expire(key.entity_kind(), T::expire());
+        let key = mirror_key(self.namespace, key);
+
         if let Some(duration) = expire {
-            #[allow(clippy::cast_possible_truncation)]
-            self.pipe.set_ex(key, bytes, duration.as_secs() as usize);
+            let millis = usize::try_from(duration.as_millis()).unwrap_or(usize::MAX);
+            self.pipe.pset_ex(key, json, millis.max(1));
         } else {
-            self.pipe.set(key, bytes);
+            self.pipe.set(key, json);
         }
 
         self.pipe.ignore();
     }
 
+    /// Remember the version of an entry if its type provides one.
+    ///
+    /// The next [`set`] or [`mset`] of the key will only be applied if the
+    /// version is not older than the stored one.
+    ///
+    /// In cluster mode, the pipeline fails instead since the entry and its
+    /// version belong to different slots.
+    ///
+    /// [`set`]: Pipe::set
+    /// [`mset`]: Pipe::mset
+    pub(crate) fn version<T: Cacheable>(&mut self, key: &RedisKey, value: &T) {
+        if let Some(version) = value.version() {
+            #[cfg(all(
+                not(feature = "bb8"),
+                not(feature = "deadpool"),
+                not(feature = "multiplexed"),
+                feature = "cluster"
+            ))]
+            self.unsupported.get_or_insert("Cacheable::version");
+
+            self.versions.push((key.clone(), version));
+        }
+    }
+
+    /// Remember the key so that its in-process copy is dropped once the
+    /// pipeline was sent, see [`CacheConfig::LOCAL_CACHE`].
+    #[cfg(feature = "local_cache")]
+    fn invalidate_local(&mut self, key: &RedisKey) {
+        if self.local.is_some_and(|local| local.is_wanted(key)) {
+            self.local_keys.push(self.namespace.render(key));
+        }
+    }
+
+    /// Whether the key holds an entity whose kind is disabled through
+    /// [`ConfigOverrides::disable`].
+    fn is_disabled(&self, key: &RedisKey) -> bool {
+        key.entity_kind()
+            .is_some_and(|kind| self.overrides.is_disabled(kind))
+    }
+
+    fn take_version(&mut self, key: &RedisKey) -> Option<u64> {
+        if self.versions.is_empty() {
+            return None;
+        }
+
+        let idx = self.versions.iter().position(|(k, _)| k == key)?;
+
+        Some(self.versions.swap_remove(idx).1)
+    }
+
+    fn set_versioned(
+        &mut self,
+        key: &RedisKey,
+        value: impl ToRedisArgs,
+        version: u64,
+        expire: Option<Duration>,
+    ) {
+        // Sub-millisecond durations must not turn into 0 which would mean
+        // that the entry is kept forever or is rejected by redis
+        let millis = |duration: Duration| {
+            u64::try_from(duration.as_millis()).map_or(u64::MAX, |millis| millis.max(1))
+        };
+
+        let expire_millis = expire.map_or(0, millis);
+        let version_millis = millis(expire.unwrap_or(VERSION_LIFETIME));
+
+        let version_key = self.namespace.render_with(RedisKey::VERSION_PREFIX, key);
+
+        self.pipe
+            .cmd("EVAL")
+            .arg(SET_VERSIONED_SCRIPT)
+            .arg(2)
+            .arg(self.namespace.key(key))
+            .arg(version_key)
+            .arg(format!("{version:020}"))
+            .arg(value)
+            .arg(expire_millis)
+            .arg(version_millis)
+            .ignore();
+    }
+
     pub(crate) fn smembers(&mut self, key: RedisKey) {
-        self.pipe.smembers(key);
+        self.pipe.smembers(self.namespace.key(key));
     }
 
     pub(crate) fn srem(&mut self, key: RedisKey, member: impl ToRedisArgs) {
-        self.pipe.srem(key, member).ignore();
+        self.pipe.srem(self.namespace.key(key), member).ignore();
     }
 
     pub(crate) fn zadd(
@@ -94,27 +483,427 @@ impl<'c, C> Pipe<'c, C> {
         member: impl ToRedisArgs,
         score: impl ToRedisArgs,
     ) {
-        self.pipe.zadd(key, member, score).ignore();
+        self.pipe
+            .zadd(self.namespace.key(key), member, score)
+            .ignore();
+    }
+
+    pub(crate) fn zadd_multiple<S: ToRedisArgs, M: ToRedisArgs>(
+        &mut self,
+        key: RedisKey,
+        items: &[(S, M)],
+    ) {
+        self.pipe
+            .zadd_multiple(self.namespace.key(key), items)
+            .ignore();
     }
 
     pub(crate) fn zrem(&mut self, key: RedisKey, members: impl ToRedisArgs) {
-        self.pipe.zrem(key, members).ignore();
+        self.pipe.zrem(self.namespace.key(key), members).ignore();
     }
 }
 
-impl<'c, C: CacheConfig> Pipe<'c, C> {
+impl<C: CacheConfig> Pipe<'_, C> {
+    pub(crate) async fn query<T: FromRedisValue>(&mut self) -> CacheResult<T> {
+        #[cfg(all(
+            not(feature = "bb8"),
+            not(feature = "deadpool"),
+            not(feature = "multiplexed"),
+            feature = "cluster"
+        ))]
+        if let Some(option) = self.unsupported.take() {
+            self.pipe.clear();
+            self.versions.clear();
+            self.deferred.clear();
+
+            return Err(CacheError::ClusterUnsupported { option });
+        }
+
+        self.flush_deferred();
+
+        trace!(target: IO_TARGET, piped = self.len());
+
+        if C::SHADOW {
+            self.discard_writes();
+
+            if self.is_empty() {
+                return T::from_redis_value(&Value::Bulk(Vec::new())).map_err(CacheError::Redis);
+            }
+        }
+
+        let conn = self.conn.get().await?;
+        let _pending = self.pressure.pending(self.pipe.cmd_iter().count());
+
+        let res = self
+            .pipe
+            .query_async(conn)
+            .instrument(otel::client_span("PIPELINE"))
+            .await;
+
+        // Invalidate even on error since commands may have been applied
+        #[cfg(feature = "local_cache")]
+        if let Some(local) = self.local {
+            local.invalidate(&mem::take(&mut self.local_keys));
+        }
+
+        let res = res?;
+
+        self.pipe.clear();
+
+        Ok(res)
+    }
+
+    pub(crate) fn del(&mut self, keys: impl Keys) {
+        // Deferred writes of the key must not be applied after its deletion
+        self.flush_deferred();
+
+        // Only entries of mirrored kinds have a JSON copy, not their index
+        // sets or meta keys
+        #[cfg(feature = "serde-mirror")]
+        if any_mirrored::<C>() {
+            let mirror_keys: Vec<_> = keys
+                .as_keys()
+                .iter()
+                .filter(|key| key.entity_kind().is_some_and(is_mirrored::<C>))
+                .map(|key| mirror_key(self.namespace, key))
+                .collect();
+
+            if !mirror_keys.is_empty() {
+                self.pipe.del(mirror_keys).ignore();
+            }
+        }
+
+        #[cfg(feature = "attachments")]
+        {
+            let attachment_keys: Vec<_> = keys
+                .as_keys()
+                .iter()
+                .map(|key| attachment_key(self.namespace, key))
+                .collect();
+
+            if !attachment_keys.is_empty() {
+                self.pipe.del(attachment_keys).ignore();
+            }
+        }
+
+        for key in keys.as_keys() {
+            self.publish_invalidation(key, ChangeKind::Deleted);
+        }
+
+        #[cfg(feature = "local_cache")]
+        if self.local.is_some() {
+            let namespace = self.namespace;
+            let rendered = keys.as_keys().iter().map(|key| namespace.render(key));
+            self.local_keys.extend(rendered);
+        }
+
+        self.pipe.del(self.namespace.key(keys)).ignore();
+    }
+
+    /// Publish the changed keys, see [`CacheConfig::PUBLISH_INVALIDATIONS`].
+    fn publish_invalidation(&mut self, key: &RedisKey, kind: ChangeKind) {
+        if !C::PUBLISH_INVALIDATIONS {
+            return;
+        }
+
+        let channel = invalidation_channel(self.namespace);
+        let key = self.namespace.render(key);
+
+        self.pipe
+            .publish(channel, invalidation_payload(kind, &key))
+            .ignore();
+    }
+
+    /// Add a command whose response is ignored.
+    pub(crate) fn add_command(&mut self, cmd: Cmd) {
+        self.pipe.add_command(cmd).ignore();
+    }
+
+    /// Move all commands of another pipeline to the end of this one.
+    pub(crate) fn append(&mut self, mut other: Self) {
+        other.flush_deferred();
+
+        #[cfg(feature = "local_cache")]
+        self.local_keys.append(&mut other.local_keys);
+
+        #[cfg(all(
+            not(feature = "bb8"),
+            not(feature = "deadpool"),
+            not(feature = "multiplexed"),
+            feature = "cluster"
+        ))]
+        if let Some(option) = other.unsupported {
+            self.unsupported.get_or_insert(option);
+        }
+
+        for cmd in other.pipe.cmd_iter() {
+            self.pipe.add_command(cmd.clone()).ignore();
+        }
+    }
+
+    pub(crate) fn mset<B: AsRef<[u8]>>(
+        &mut self,
+        items: &[(RedisKey, BytesWrap<B>)],
+        expire: Option<Duration>,
+    ) {
+        // All items are of the same kind
+        let Some((first, _)) = items.first() else {
+            return;
+        };
+
+        for (key, _) in items {
+            self.discard_deferred(key);
+        }
+
+        if self.is_disabled(first) {
+            for (key, _) in items {
+                self.take_version(key);
+            }
+
+            return;
+        }
+
+        let expire = self.overrides.apply_expire(first.entity_kind(), expire);
+
+        #[cfg(feature = "metrics")]
+        if let Some(kind) = first.entity_kind() {
+            for (_, BytesWrap(bytes)) in items {
+                super::metrics::record_archive_size(kind, bytes.as_ref().len());
+            }
+        }
+
+        for (key, _) in items {
+            self.keep_previous(key);
+            self.publish_invalidation(key, ChangeKind::Stored);
+
+            #[cfg(feature = "local_cache")]
+            self.invalidate_local(key);
+        }
+
+        if !self.versions.is_empty() {
+            let (versioned, unversioned): (Vec<_>, Vec<_>) = items
+                .iter()
+                .map(|(key, value)| (key, value, self.take_version(key)))
+                .partition(|(.., version)| version.is_some());
+
+            for (key, value, version) in versioned {
+                let version = version.expect("partitioned by version");
+                self.set_versioned(key, value, version, expire);
+            }
+
+            let items: Vec<_> = unversioned
+                .into_iter()
+                .map(|(key, value, _)| (key, value))
+                .collect();
+
+            if !items.is_empty() {
+                self.mset_unversioned(&items, expire);
+            }
+
+            return;
+        }
+
+        let items: Vec<_> = items.iter().map(|(key, value)| (key, value)).collect();
+        self.mset_unversioned(&items, expire);
+    }
+
+    pub(crate) fn set(&mut self, key: RedisKey, bytes: &[u8], expire: Option<Duration>) {
+        self.discard_deferred(&key);
+
+        if self.is_disabled(&key) {
+            self.take_version(&key);
+
+            return;
+        }
+
+        let expire = self.overrides.apply_expire(key.entity_kind(), expire);
+
+        #[cfg(feature = "metrics")]
+        if let Some(kind) = key.entity_kind() {
+            super::metrics::record_archive_size(kind, bytes.len());
+        }
+
+        self.keep_previous(&key);
+        self.publish_invalidation(&key, ChangeKind::Stored);
+
+        #[cfg(feature = "local_cache")]
+        self.invalidate_local(&key);
+
+        if let Some(version) = self.take_version(&key) {
+            return self.set_versioned(&key, bytes, version, expire);
+        }
+
+        let key = self.namespace.key(key);
+
+        if let Some(duration) = expire {
+            #[allow(clippy::cast_possible_truncation)]
+            self.pipe.set_ex(key, bytes, duration.as_secs() as usize);
+        } else {
+            self.pipe.set(key, bytes);
+        }
+
+        self.pipe.ignore();
+    }
+
+    fn mset_unversioned<B: AsRef<[u8]>>(
+        &mut self,
+        items: &[(&RedisKey, &BytesWrap<B>)],
+        expire: Option<Duration>,
+    ) {
+        let namespaced: Vec<_> = items
+            .iter()
+            .map(|(key, value)| (self.namespace.key(*key), *value))
+            .collect();
+
+        self.pipe.mset(&namespaced).ignore();
+
+        if let Some(duration) = expire {
+            for (key, _) in namespaced {
+                self.pipe.pexpire(key, expire_millis(duration)).ignore();
+            }
+        }
+    }
+
+    /// Same as [`Pipe::set`] but the write is only added once the pipeline is
+    /// sent.
+    ///
+    /// If the key is written again in the meanwhile, only the last write is
+    /// sent. This avoids sending the same entry multiple times when it occurs
+    /// repeatedly within an event, e.g. the author and mentions of a message.
+    pub(crate) fn set_coalesced(&mut self, key: RedisKey, bytes: &[u8], expire: Option<Duration>) {
+        self.discard_deferred(&key);
+
+        self.deferred.push(DeferredSet {
+            key,
+            bytes: bytes.into(),
+            expire,
+        });
+    }
+
+    /// Remove a deferred write of the key because a later write replaces it.
+    fn discard_deferred(&mut self, key: &RedisKey) {
+        if self.deferred.is_empty() {
+            return;
+        }
+
+        let Some(idx) = self
+            .deferred
+            .iter()
+            .position(|deferred| deferred.key == *key)
+        else {
+            return;
+        };
+
+        self.deferred.remove(idx);
+
+        #[cfg(feature = "metrics")]
+        super::metrics::record_coalesced_writes(1);
+
+        // The version of the replaced write was remembered first
+        self.take_version(key);
+    }
+
+    fn flush_deferred(&mut self) {
+        for DeferredSet { key, bytes, expire } in mem::take(&mut self.deferred) {
+            self.set(key, &bytes, expire);
+        }
+    }
+
+    /// Copy the stored entity under the key to the key of its previous
+    /// version, see [`CacheConfig::PREVIOUS_VERSION_LIFETIME`].
+    fn keep_previous(&mut self, key: &RedisKey) {
+        let Some(lifetime) = C::PREVIOUS_VERSION_LIFETIME else {
+            return;
+        };
+
+        if key.entity_kind().is_none() {
+            return;
+        }
+
+        self.pipe
+            .cmd("EVAL")
+            .arg(KEEP_PREVIOUS_SCRIPT)
+            .arg(2)
+            .arg(self.namespace.key(key))
+            .arg(previous_key(self.namespace, key))
+            .arg(lifetime.as_secs().max(1))
+            .ignore();
+    }
+
+    /// Remove all commands from the pipeline that would modify data, keeping
+    /// only those that read data.
+    ///
+    /// Used in shadow mode, see [`CacheConfig::SHADOW`].
+    fn discard_writes(&mut self) {
+        /// Commands whose responses are used while building the pipeline.
+        const READ_COMMANDS: &[&[u8]] = &[
+            b"EXISTS",
+            b"GET",
+            b"HGET",
+            b"HGETALL",
+            b"MGET",
+            b"PTTL",
+            b"SCARD",
+            b"SISMEMBER",
+            b"SMEMBERS",
+            b"TTL",
+            b"ZCARD",
+            b"ZRANGE",
+        ];
+
+        let mut reads = Pipeline::new();
+        let mut discarded_bytes = 0;
+
+        for cmd in self.pipe.cmd_iter() {
+            let mut args = cmd.args_iter();
+
+            let is_read = matches!(
+                args.next(),
+                Some(Arg::Simple(name)) if READ_COMMANDS.contains(&name)
+            );
+
+            if is_read {
+                reads.add_command(cmd.clone());
+            } else {
+                discarded_bytes += cmd
+                    .args_iter()
+                    .map(|arg| match arg {
+                        Arg::Simple(bytes) => bytes.len(),
+                        Arg::Cursor => 0,
+                    })
+                    .sum::<usize>();
+            }
+        }
+
+        trace!(target: IO_TARGET, discarded_bytes, "Discarded writes in shadow mode");
+
+        #[cfg(feature = "metrics")]
+        crate::cache::metrics::record_shadow_writes(discarded_bytes);
+
+        self.pipe = reads;
+    }
+
     #[instrument(level = "trace", skip_all)]
-    pub(crate) async fn get<T>(&mut self, key: RedisKey) -> CacheResult<Option<CachedArchive<T>>>
+    pub(crate) async fn get<T>(&mut self, key: impl Keys) -> CacheResult<Option<CachedArchive<T>>>
     where
         T: Cacheable,
     {
-        let conn = self.conn.get().await?;
+        let namespace = self.namespace;
 
-        let BytesWrap::<AlignedVec<16>>(bytes) = Cmd::get(key).query_async(conn).await?;
+        self.get_rendered(namespace.key(key)).await
+    }
 
-        if bytes.is_empty() {
+    /// Same as [`Pipe::get`] but the key is rendered already.
+    pub(crate) async fn get_rendered<T>(
+        &mut self,
+        key: impl ToRedisArgs,
+    ) -> CacheResult<Option<CachedArchive<T>>>
+    where
+        T: Cacheable,
+    {
+        let Some(bytes) = self.get_rendered_bytes(key).await? else {
             return Ok(None);
-        }
+        };
 
         #[cfg(feature = "bytecheck")]
         let res = CachedArchive::new(bytes);
@@ -125,3 +914,69 @@ impl<'c, C: CacheConfig> Pipe<'c, C> {
         res.map(Some)
     }
 }
+
+/// Render a key prefixed with [`RedisKey::PREVIOUS_PREFIX`].
+pub(crate) fn previous_key(namespace: &Namespace, key: &RedisKey) -> Vec<u8> {
+    namespace.render_with(RedisKey::PREVIOUS_PREFIX, key)
+}
+
+/// Render a key prefixed with [`RedisKey::JSON_MIRROR_PREFIX`].
+#[cfg(feature = "serde-mirror")]
+fn mirror_key(namespace: &Namespace, key: &RedisKey) -> Vec<u8> {
+    namespace.render_with(RedisKey::JSON_MIRROR_PREFIX, key)
+}
+
+/// Whether entries of the entity kind have a JSON copy, see
+/// [`Cacheable::MIRROR`].
+#[cfg(feature = "serde-mirror")]
+pub(crate) const fn is_mirrored<C: CacheConfig>(kind: EntityKind) -> bool {
+    match kind {
+        EntityKind::AutoModerationRule => C::AutoModerationRule::MIRROR,
+        EntityKind::Channel => C::Channel::MIRROR,
+        EntityKind::CurrentUser => C::CurrentUser::MIRROR,
+        EntityKind::Emoji => C::Emoji::MIRROR,
+        EntityKind::Guild => C::Guild::MIRROR,
+        EntityKind::Integration => C::Integration::MIRROR,
+        EntityKind::Invite => C::Invite::MIRROR,
+        EntityKind::Member => C::Member::MIRROR,
+        EntityKind::Message => C::Message::MIRROR,
+        EntityKind::Presence => C::Presence::MIRROR,
+        EntityKind::Role => C::Role::MIRROR,
+        EntityKind::ScheduledEvent => C::ScheduledEvent::MIRROR,
+        EntityKind::StageInstance => C::StageInstance::MIRROR,
+        EntityKind::Sticker => C::Sticker::MIRROR,
+        EntityKind::ThreadMember => C::ThreadMember::MIRROR,
+        EntityKind::User => C::User::MIRROR,
+        EntityKind::VoiceState => C::VoiceState::MIRROR,
+    }
+}
+
+/// Whether entries of any entity kind have a JSON copy.
+#[cfg(feature = "serde-mirror")]
+const fn any_mirrored<C: CacheConfig>() -> bool {
+    let mut i = 0;
+
+    while i < EntityKind::ALL.len() {
+        if is_mirrored::<C>(EntityKind::ALL[i]) {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// Render a key prefixed with [`RedisKey::ATTACHMENT_PREFIX`].
+/// Milliseconds of an expire duration.
+///
+/// Sub-millisecond durations must not turn into 0 which would delete the
+/// entry right away.
+fn expire_millis(duration: Duration) -> usize {
+    usize::try_from(duration.as_millis()).map_or(usize::MAX, |millis| millis.max(1))
+}
+
+#[cfg(feature = "attachments")]
+pub(crate) fn attachment_key(namespace: &Namespace, key: &RedisKey) -> Vec<u8> {
+    namespace.render_with(RedisKey::ATTACHMENT_PREFIX, key)
+}
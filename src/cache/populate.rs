@@ -0,0 +1,105 @@
+use tracing::{info, instrument};
+use twilight_http::Client;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    cache::pipe::Pipe,
+    config::{CacheConfig, Cacheable},
+    error::CacheError,
+    CacheResult, RedisCache,
+};
+
+/// Maximum amount of members that can be requested at once.
+const MEMBER_PAGE_SIZE: u16 = 1000;
+
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "http")))]
+impl<C: CacheConfig> RedisCache<C> {
+    /// Fetch a guild through the discord API and store it alongside its
+    /// channels and members.
+    ///
+    /// This primes the cache for guilds that the bot is already in without
+    /// waiting for their `GUILD_CREATE` payloads. Roles, emojis, and stickers
+    /// are part of the fetched guild. Members are fetched in pages of 1000
+    /// and only if [`CacheConfig::Member`] or [`CacheConfig::User`] is not
+    /// ignored; fetching them requires the `GUILD_MEMBERS` intent.
+    ///
+    /// Note that presences, voice states, and threads are not available
+    /// through the API and remain uncached until their gateway events arrive.
+    #[instrument(level = "debug", skip_all, fields(guild_id = guild_id.get()))]
+    pub async fn populate_guild(
+        &self,
+        http: &Client,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<()> {
+        let guild = http
+            .guild(guild_id)
+            .await
+            .map_err(|e| CacheError::Http(Box::new(e)))?
+            .model()
+            .await
+            .map_err(CacheError::HttpBody)?;
+
+        let channels = http
+            .guild_channels(guild_id)
+            .await
+            .map_err(|e| CacheError::Http(Box::new(e)))?
+            .models()
+            .await
+            .map_err(CacheError::HttpBody)?;
+
+        let mut pipe = Pipe::new(self);
+
+        self.store_guild(&mut pipe, &guild)?;
+        self.store_channels(&mut pipe, guild_id, &channels)?;
+
+        if !pipe.is_empty() {
+            pipe.query::<()>().await?;
+        }
+
+        let mut count = 0;
+
+        if C::Member::WANTED || C::User::WANTED {
+            let mut after = None;
+
+            loop {
+                let mut request = http
+                    .guild_members(guild_id)
+                    .limit(MEMBER_PAGE_SIZE)
+                    .expect("valid member limit");
+
+                if let Some(user_id) = after {
+                    request = request.after(user_id);
+                }
+
+                let members = request
+                    .await
+                    .map_err(|e| CacheError::Http(Box::new(e)))?
+                    .models()
+                    .await
+                    .map_err(CacheError::HttpBody)?;
+
+                count += members.len();
+                self.store_members(&mut pipe, guild_id, &members)?;
+
+                if !pipe.is_empty() {
+                    pipe.query::<()>().await?;
+                }
+
+                match members.last() {
+                    Some(member) if members.len() == usize::from(MEMBER_PAGE_SIZE) => {
+                        after = Some(member.user.id);
+                    }
+                    Some(_) | None => break,
+                }
+            }
+        }
+
+        info!(
+            channels = channels.len(),
+            members = count,
+            "Populated guild"
+        );
+
+        Ok(())
+    }
+}
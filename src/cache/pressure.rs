@@ -0,0 +1,177 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use crate::config::PressureThresholds;
+
+/// Weight of the latest sample in the recent averages.
+const SMOOTHING: f64 = 0.1;
+
+/// Advisory on whether redis keeps up with the write path.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Pressure {
+    /// Redis keeps up with updates.
+    #[default]
+    Normal,
+    /// At least one value of the [`PressureGauge`] exceeds its threshold.
+    High,
+}
+
+/// Snapshot of how much load the write path currently puts on redis.
+///
+/// Created via [`RedisCache::pressure`](crate::RedisCache::pressure).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PressureGauge {
+    /// Amount of commands that were sent to redis but not yet answered.
+    pub pending_commands: usize,
+    /// Recent average time spent waiting for a connection of the pool.
+    pub pool_wait: Duration,
+    /// Recent fraction of failed updates between `0.0` and `1.0`.
+    pub error_rate: f64,
+}
+
+impl PressureGauge {
+    /// Compare the gauge against the given thresholds.
+    pub fn level(&self, thresholds: &PressureThresholds) -> Pressure {
+        let high = self.pending_commands > thresholds.pending_commands
+            || self.pool_wait > thresholds.pool_wait
+            || self.error_rate > thresholds.error_rate;
+
+        if high {
+            Pressure::High
+        } else {
+            Pressure::Normal
+        }
+    }
+}
+
+/// Collects the values of the [`PressureGauge`].
+#[derive(Default)]
+pub(crate) struct PressureTracker {
+    pending_commands: AtomicUsize,
+    averages: Mutex<Averages>,
+}
+
+#[derive(Default)]
+struct Averages {
+    pool_wait_micros: f64,
+    error_rate: f64,
+}
+
+impl PressureTracker {
+    pub(crate) fn gauge(&self) -> PressureGauge {
+        let averages = self.averages.lock().unwrap_or_else(PoisonError::into_inner);
+
+        PressureGauge {
+            pending_commands: self.pending_commands.load(Ordering::Relaxed),
+            pool_wait: Duration::from_secs_f64(averages.pool_wait_micros.max(0.0) / 1_000_000.0),
+            error_rate: averages.error_rate,
+        }
+    }
+
+    /// Track commands as pending until the returned guard is dropped.
+    pub(crate) fn pending(&self, commands: usize) -> PendingGuard<'_> {
+        self.pending_commands.fetch_add(commands, Ordering::Relaxed);
+
+        PendingGuard {
+            pending_commands: &self.pending_commands,
+            commands,
+        }
+    }
+
+    pub(crate) fn record_pool_wait(&self, elapsed: Duration) {
+        let mut averages = self.averages.lock().unwrap_or_else(PoisonError::into_inner);
+        let micros = elapsed.as_secs_f64() * 1_000_000.0;
+        averages.pool_wait_micros = smooth(averages.pool_wait_micros, micros);
+    }
+
+    pub(crate) fn record_outcome(&self, success: bool) {
+        let mut averages = self.averages.lock().unwrap_or_else(PoisonError::into_inner);
+        let sample = if success { 0.0 } else { 1.0 };
+        averages.error_rate = smooth(averages.error_rate, sample);
+    }
+}
+
+const fn smooth(average: f64, sample: f64) -> f64 {
+    average + SMOOTHING * (sample - average)
+}
+
+pub(crate) struct PendingGuard<'a> {
+    pending_commands: &'a AtomicUsize,
+    commands: usize,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.pending_commands
+            .fetch_sub(self.commands, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Pressure, PressureGauge, PressureTracker};
+    use crate::config::PressureThresholds;
+
+    const THRESHOLDS: PressureThresholds = PressureThresholds {
+        pending_commands: 100,
+        pool_wait: Duration::from_millis(10),
+        error_rate: 0.5,
+    };
+
+    #[test]
+    fn pending_commands() {
+        let tracker = PressureTracker::default();
+
+        let first = tracker.pending(3);
+        let second = tracker.pending(5);
+        assert_eq!(tracker.gauge().pending_commands, 8);
+
+        drop(first);
+        assert_eq!(tracker.gauge().pending_commands, 5);
+
+        drop(second);
+        assert_eq!(tracker.gauge().pending_commands, 0);
+    }
+
+    #[test]
+    fn recent_error_rate() {
+        let tracker = PressureTracker::default();
+
+        for _ in 0..50 {
+            tracker.record_outcome(false);
+        }
+
+        assert!(tracker.gauge().error_rate > 0.99);
+
+        for _ in 0..50 {
+            tracker.record_outcome(true);
+        }
+
+        assert!(tracker.gauge().error_rate < 0.01);
+    }
+
+    #[test]
+    fn level() {
+        let gauge = PressureGauge::default();
+        assert_eq!(gauge.level(&THRESHOLDS), Pressure::Normal);
+
+        let gauge = PressureGauge {
+            pool_wait: Duration::from_millis(20),
+            ..PressureGauge::default()
+        };
+        assert_eq!(gauge.level(&THRESHOLDS), Pressure::High);
+
+        let gauge = PressureGauge {
+            pending_commands: 101,
+            ..PressureGauge::default()
+        };
+        assert_eq!(gauge.level(&THRESHOLDS), Pressure::High);
+    }
+}
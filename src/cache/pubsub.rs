@@ -0,0 +1,233 @@
+use std::{
+    pin::{pin, Pin},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{
+    future::{self, Either},
+    Stream, StreamExt,
+};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tracing::{debug, trace, warn};
+
+use crate::{
+    error::PubSubError,
+    redis::{aio::PubSub, DedicatedConnection, Msg, Pool},
+    CacheResult, RedisCache,
+};
+
+/// Amount of messages that are buffered until the [`Subscription`] is polled.
+const SUBSCRIPTION_CAPACITY: usize = 1024;
+
+const MIN_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Channels and patterns to subscribe to through [`RedisCache::subscribe`].
+#[derive(Clone, Debug, Default)]
+pub struct Topics {
+    channels: Vec<String>,
+    patterns: Vec<String>,
+}
+
+impl Topics {
+    /// Create a new empty [`Topics`].
+    pub const fn new() -> Self {
+        Self {
+            channels: Vec::new(),
+            patterns: Vec::new(),
+        }
+    }
+
+    /// Subscribe to a channel.
+    #[must_use]
+    pub fn channel(mut self, channel: impl Into<String>) -> Self {
+        self.channels.push(channel.into());
+
+        self
+    }
+
+    /// Subscribe to all channels matching a glob-style pattern.
+    #[must_use]
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.patterns.push(pattern.into());
+
+        self
+    }
+}
+
+/// A message received through a [`Subscription`].
+#[derive(Clone, Debug)]
+pub struct PubSubMessage {
+    channel: String,
+    pattern: Option<String>,
+    payload: Vec<u8>,
+}
+
+impl PubSubMessage {
+    fn new(msg: &Msg) -> Self {
+        Self {
+            channel: msg.get_channel_name().to_owned(),
+            pattern: msg.from_pattern().then(|| msg.get_pattern().ok()).flatten(),
+            payload: msg.get_payload_bytes().to_owned(),
+        }
+    }
+
+    /// The channel the message was published to.
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /// The pattern that matched the channel if the message was received
+    /// through a pattern subscription.
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_deref()
+    }
+
+    /// The payload of the message.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Consume the message and return its payload.
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
+
+/// Stream of messages of a subscription created through
+/// [`RedisCache::subscribe`].
+///
+/// The underlying connection is closed once the subscription is dropped.
+pub struct Subscription {
+    rx: Receiver<PubSubMessage>,
+}
+
+impl Subscription {
+    /// Receive the next message.
+    ///
+    /// Returns `None` if the background task stopped which only happens if
+    /// the runtime shuts down.
+    pub async fn recv(&mut self) -> Option<PubSubMessage> {
+        self.rx.recv().await
+    }
+}
+
+impl Stream for Subscription {
+    type Item = PubSubMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl<C> RedisCache<C> {
+    /// Subscribe to redis channels on a dedicated connection.
+    ///
+    /// Messages are forwarded by a background task which reconnects and
+    /// resubscribes automatically if the connection is lost. Messages that
+    /// are published while reconnecting are missed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// use redlight::Topics;
+    ///
+    /// # async fn example<C: CacheConfig>(cache: RedisCache<C>) -> Result<(), redlight::error::CacheError> {
+    /// let topics = Topics::new()
+    ///     .channel("announcements")
+    ///     .pattern("shard:*");
+    ///
+    /// let mut subscription = cache.subscribe(topics).await?;
+    ///
+    /// while let Some(msg) = subscription.recv().await {
+    ///     println!("{}: {:?}", msg.channel(), msg.payload());
+    /// }
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    pub async fn subscribe(&self, topics: Topics) -> CacheResult<Subscription> {
+        let pubsub = connect(&self.pool, &topics).await?;
+        let (tx, rx) = mpsc::channel(SUBSCRIPTION_CAPACITY);
+
+        tokio::spawn(forward(self.pool.clone(), topics, pubsub, tx));
+
+        Ok(Subscription { rx })
+    }
+}
+
+async fn connect(pool: &Pool, topics: &Topics) -> Result<PubSub, PubSubError> {
+    let conn = DedicatedConnection::get(pool)
+        .await
+        .map_err(PubSubError::GetConnection)?;
+
+    let mut pubsub = conn.into_pubsub();
+
+    for channel in topics.channels.iter() {
+        pubsub
+            .subscribe(channel)
+            .await
+            .map_err(PubSubError::Subscribe)?;
+    }
+
+    for pattern in topics.patterns.iter() {
+        pubsub
+            .psubscribe(pattern)
+            .await
+            .map_err(PubSubError::Subscribe)?;
+    }
+
+    Ok(pubsub)
+}
+
+async fn forward(pool: Pool, topics: Topics, pubsub: PubSub, tx: Sender<PubSubMessage>) {
+    let mut closed = pin!(tx.closed());
+    let mut pubsub = Some(pubsub);
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        let Some(current) = pubsub.take() else {
+            match future::select(pin!(connect(&pool, &topics)), closed.as_mut()).await {
+                Either::Left((Ok(reconnected), _)) => {
+                    debug!("Reconnected subscription");
+                    pubsub = Some(reconnected);
+                    backoff = MIN_BACKOFF;
+                }
+                Either::Left((Err(err), _)) => {
+                    warn!(?err, ?backoff, "Failed to reconnect subscription");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+                Either::Right(_) => return,
+            }
+
+            continue;
+        };
+
+        let mut msgs = current.into_on_message();
+
+        loop {
+            match future::select(msgs.next(), closed.as_mut()).await {
+                Either::Left((Some(msg), _)) => {
+                    if tx.send(PubSubMessage::new(&msg)).await.is_err() {
+                        return;
+                    }
+                }
+                Either::Left((None, _)) => {
+                    debug!("Subscription connection closed; reconnecting...");
+
+                    break;
+                }
+                Either::Right(_) => {
+                    trace!("Subscription dropped");
+
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    clock::Clock,
+    config::{RateLimit, RateLimitBehavior, RateLimitedOperation, RateLimits},
+    error::CacheError,
+    CacheResult,
+};
+
+/// Token buckets for each rate limited operation class.
+pub(crate) struct RateLimiter {
+    iteration: Option<Mutex<Bucket>>,
+    stats_scan: Option<Mutex<Bucket>>,
+    maintenance: Option<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: RateLimits, now: SystemTime) -> Self {
+        let bucket =
+            |limit: Option<RateLimit>| limit.map(|limit| Mutex::new(Bucket::new(limit, now)));
+
+        Self {
+            iteration: bucket(limits.iteration),
+            stats_scan: bucket(limits.stats_scan),
+            maintenance: bucket(limits.maintenance),
+        }
+    }
+
+    /// Consume a token for the operation, either waiting for one to become
+    /// available or returning [`CacheError::RateLimited`].
+    pub(crate) async fn acquire(
+        &self,
+        operation: RateLimitedOperation,
+        clock: &dyn Clock,
+    ) -> CacheResult<()> {
+        let bucket = match operation {
+            RateLimitedOperation::Iteration => &self.iteration,
+            RateLimitedOperation::StatsScan => &self.stats_scan,
+            RateLimitedOperation::Maintenance => &self.maintenance,
+        };
+
+        let Some(bucket) = bucket else {
+            return Ok(());
+        };
+
+        loop {
+            let (retry_after, behavior) = {
+                let mut bucket = bucket.lock().unwrap_or_else(PoisonError::into_inner);
+
+                match bucket.try_take(clock.now()) {
+                    Ok(()) => return Ok(()),
+                    Err(retry_after) => (retry_after, bucket.limit.behavior),
+                }
+            };
+
+            match behavior {
+                RateLimitBehavior::Queue => clock.sleep(retry_after).await,
+                RateLimitBehavior::Error => {
+                    return Err(CacheError::RateLimited {
+                        operation,
+                        retry_after,
+                    })
+                }
+            }
+        }
+    }
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: u32,
+    last_refill: SystemTime,
+}
+
+impl Bucket {
+    const fn new(limit: RateLimit, now: SystemTime) -> Self {
+        Self {
+            limit,
+            tokens: limit.capacity,
+            last_refill: now,
+        }
+    }
+
+    /// Take a token or return the duration until the next one is available.
+    fn try_take(&mut self, now: SystemTime) -> Result<(), Duration> {
+        let interval = self.limit.refill_interval;
+        let elapsed = now.duration_since(self.last_refill).unwrap_or_default();
+
+        if interval.is_zero() {
+            return Ok(());
+        }
+
+        let refills = elapsed.as_nanos() / interval.as_nanos();
+
+        if refills > 0 {
+            let refills = u32::try_from(refills).unwrap_or(u32::MAX);
+            self.tokens = self.tokens.saturating_add(refills).min(self.limit.capacity);
+            self.last_refill += interval * refills;
+
+            // Tokens beyond the capacity are lost so the interval restarts
+            if self.tokens == self.limit.capacity {
+                self.last_refill = now;
+            }
+        }
+
+        if self.tokens > 0 {
+            self.tokens -= 1;
+
+            return Ok(());
+        }
+
+        let since_refill = now.duration_since(self.last_refill).unwrap_or_default();
+
+        Err(interval.saturating_sub(since_refill))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::RateLimiter;
+    use crate::{
+        clock::{Clock, MockClock},
+        config::{RateLimit, RateLimitedOperation, RateLimits},
+        error::CacheError,
+    };
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let clock = MockClock::new(SystemTime::UNIX_EPOCH);
+        let interval = Duration::from_secs(10);
+
+        let limits = RateLimits {
+            iteration: Some(RateLimit::new(2, interval)),
+            stats_scan: Some(RateLimit::new(1, interval).erroring()),
+            maintenance: None,
+        };
+
+        let limiter = RateLimiter::new(limits, clock.now());
+
+        // Bursts up to the capacity, then waits for a refill
+        for _ in 0..2 {
+            limiter
+                .acquire(RateLimitedOperation::Iteration, &clock)
+                .await
+                .unwrap();
+        }
+
+        let (res, ()) = tokio::join!(
+            limiter.acquire(RateLimitedOperation::Iteration, &clock),
+            async { clock.advance(interval) },
+        );
+
+        res.unwrap();
+
+        assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + interval);
+
+        limiter
+            .acquire(RateLimitedOperation::StatsScan, &clock)
+            .await
+            .unwrap();
+
+        let err = limiter
+            .acquire(RateLimitedOperation::StatsScan, &clock)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            CacheError::RateLimited {
+                operation: RateLimitedOperation::StatsScan,
+                retry_after,
+            } if retry_after == interval
+        ));
+
+        // Operations without a limit are never limited
+        for _ in 0..3 {
+            limiter
+                .acquire(RateLimitedOperation::Maintenance, &clock)
+                .await
+                .unwrap();
+        }
+    }
+}
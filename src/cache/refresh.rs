@@ -0,0 +1,133 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use futures_util::future::BoxFuture;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use tracing::{debug, trace};
+
+use crate::{key::RedisKey, RedisCache};
+
+/// Refreshes cache entries that are about to expire.
+///
+/// Registered through [`RedisCache::with_refresher`].
+///
+/// # Example
+///
+/// ```no_run
+/// use futures_util::future::BoxFuture;
+/// use redlight::{cache::Refresher, RedisKey};
+///
+/// struct UserRefresher;
+///
+/// impl Refresher for UserRefresher {
+///     fn refresh(&self, key: RedisKey) -> BoxFuture<'_, ()> {
+///         Box::pin(async move {
+///             if let RedisKey::User { id } = key {
+///                 // fetch the user e.g. through twilight-http
+///                 // and store it through `RedisCache::update`
+///             }
+///         })
+///     }
+/// }
+/// ```
+pub trait Refresher: Send + Sync + 'static {
+    /// Fetch the entry of the given key and store it in the cache again.
+    fn refresh(&self, key: RedisKey) -> BoxFuture<'_, ()>;
+}
+
+/// Bounded queue of keys whose entries should be refreshed.
+pub(crate) struct RefreshQueue {
+    threshold: Duration,
+    tx: Sender<RedisKey>,
+    pending: Arc<Mutex<HashSet<RedisKey>>>,
+}
+
+impl RefreshQueue {
+    fn new(threshold: Duration, capacity: usize, refresher: impl Refresher) -> Self {
+        let (tx, rx) = mpsc::channel(capacity.max(1));
+        let pending = Arc::new(Mutex::new(HashSet::new()));
+
+        tokio::spawn(refresh_loop(rx, refresher, Arc::clone(&pending)));
+
+        Self {
+            threshold,
+            tx,
+            pending,
+        }
+    }
+
+    /// Whether the remaining TTL in milliseconds is low enough to refresh.
+    pub(crate) fn is_due(&self, pttl: i64) -> bool {
+        // Negative values indicate missing keys or keys without TTL
+        u64::try_from(pttl).is_ok_and(|pttl| u128::from(pttl) < self.threshold.as_millis())
+    }
+
+    /// Enqueue the key unless it's already pending or the queue is full.
+    pub(crate) fn enqueue(&self, key: RedisKey) {
+        let mut pending = self.pending.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if pending.contains(&key) {
+            return;
+        }
+
+        match self.tx.try_send(key.clone()) {
+            Ok(()) => {
+                pending.insert(key);
+            }
+            Err(TrySendError::Full(key)) => {
+                trace!(?key, "Refresh queue is full; skipping key");
+            }
+            Err(TrySendError::Closed(_)) => debug!("Refresh queue closed"),
+        }
+    }
+}
+
+async fn refresh_loop(
+    mut rx: Receiver<RedisKey>,
+    refresher: impl Refresher,
+    pending: Arc<Mutex<HashSet<RedisKey>>>,
+) {
+    while let Some(key) = rx.recv().await {
+        trace!(?key, "Refreshing entry");
+        refresher.refresh(key.clone()).await;
+
+        pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&key);
+    }
+}
+
+impl<C> RedisCache<C> {
+    /// Refresh entries that are about to expire whenever they are read.
+    ///
+    /// If a getter such as [`RedisCache::user`] finds an entry whose
+    /// remaining TTL is below `threshold`, its key is passed to the
+    /// [`Refresher`] in a background task. This keeps frequently read
+    /// entries warm while rarely read ones still expire.
+    ///
+    /// At most `queue_capacity` keys wait to be refreshed at a time, further
+    /// keys are skipped until the queue has room again.
+    ///
+    /// Reads will additionally request the TTL of the entry which adds a
+    /// small overhead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a tokio runtime.
+    #[must_use]
+    pub fn with_refresher(
+        mut self,
+        threshold: Duration,
+        queue_capacity: usize,
+        refresher: impl Refresher,
+    ) -> Self {
+        let queue = RefreshQueue::new(threshold, queue_capacity, refresher);
+        self.refresh = Some(queue);
+
+        self
+    }
+}
@@ -0,0 +1,252 @@
+use tracing::warn;
+use twilight_model::id::{
+    marker::{
+        AutoModerationRuleMarker, ChannelMarker, EmojiMarker, GuildMarker, IntegrationMarker,
+        MessageMarker, RoleMarker, ScheduledEventMarker, StageMarker, StickerMarker, UserMarker,
+    },
+    Id,
+};
+
+use crate::{
+    config::CacheConfig, error::CacheError, key::RedisKey, redis::Connection, CacheResult,
+    CachedArchive, RedisCache,
+};
+
+/// Which redis instance reads are sent to.
+///
+/// Replicas are registered through [`RedisCache::with_replica`]. Data read
+/// from a replica may be slightly stale since replication is asynchronous.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ReadPreference {
+    /// Always read from the primary.
+    #[default]
+    Primary,
+    /// Always read from the replica.
+    ///
+    /// Reads from the primary if no replica is registered.
+    Replica,
+    /// Read from the replica but fall back to the primary if no replica
+    /// connection can be acquired.
+    ReplicaPreferred,
+}
+
+impl<C> RedisCache<C> {
+    /// Get a connection for reads according to the given preference.
+    pub(crate) async fn read_connection(
+        &self,
+        preference: ReadPreference,
+    ) -> CacheResult<Connection<'_>> {
+        let Some(ref replica) = self.replica else {
+            return self.connection().await;
+        };
+
+        match preference {
+            ReadPreference::Primary => self.connection().await,
+            ReadPreference::Replica => Connection::get(replica)
+                .await
+                .map_err(CacheError::GetConnection),
+            ReadPreference::ReplicaPreferred => match Connection::get(replica).await {
+                Ok(conn) => Ok(conn),
+                Err(err) => {
+                    warn!(?err, "Replica unavailable; reading from primary");
+
+                    self.connection().await
+                }
+            },
+        }
+    }
+}
+
+/// Getters of cache entries that are routed according to a
+/// [`ReadPreference`].
+///
+/// Created via [`RedisCache::read_from`].
+pub struct ReadRoute<'c, C> {
+    cache: &'c RedisCache<C>,
+    preference: ReadPreference,
+}
+
+impl<'c, C> ReadRoute<'c, C> {
+    pub(crate) const fn new(cache: &'c RedisCache<C>, preference: ReadPreference) -> Self {
+        Self { cache, preference }
+    }
+}
+
+impl<C: CacheConfig> ReadRoute<'_, C> {
+    /// Get an auto moderation rule entry.
+    pub async fn auto_moderation_rule(
+        &self,
+        rule_id: Id<AutoModerationRuleMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::AutoModerationRule<'static>>>> {
+        self.cache.get_single_from(self.preference, rule_id).await
+    }
+
+    /// Get a channel entry.
+    pub async fn channel(
+        &self,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Channel<'static>>>> {
+        self.cache
+            .get_single_from(self.preference, channel_id)
+            .await
+    }
+
+    /// Get the current user entry.
+    pub async fn current_user(
+        &self,
+    ) -> CacheResult<Option<CachedArchive<C::CurrentUser<'static>>>> {
+        self.cache
+            .get_single_from(self.preference, RedisKey::CurrentUser)
+            .await
+    }
+
+    /// Get an emoji entry.
+    pub async fn emoji(
+        &self,
+        emoji_id: Id<EmojiMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Emoji<'static>>>> {
+        self.cache.get_single_from(self.preference, emoji_id).await
+    }
+
+    /// Get a guild entry.
+    pub async fn guild(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Guild<'static>>>> {
+        self.cache.get_single_from(self.preference, guild_id).await
+    }
+
+    /// Get an integration entry.
+    pub async fn integration(
+        &self,
+        guild_id: Id<GuildMarker>,
+        integration_id: Id<IntegrationMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Integration<'static>>>> {
+        let key = RedisKey::Integration {
+            guild: guild_id,
+            id: integration_id,
+        };
+
+        self.cache.get_single_from(self.preference, key).await
+    }
+
+    /// Get an invite entry.
+    pub async fn invite(
+        &self,
+        code: &str,
+    ) -> CacheResult<Option<CachedArchive<C::Invite<'static>>>> {
+        let key = RedisKey::Invite { code: code.into() };
+
+        self.cache.get_single_from(self.preference, key).await
+    }
+
+    /// Get a member entry.
+    pub async fn member(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Member<'static>>>> {
+        let key = RedisKey::Member {
+            guild: guild_id,
+            user: user_id,
+        };
+
+        self.cache.get_single_from(self.preference, key).await
+    }
+
+    /// Get a message entry.
+    pub async fn message(
+        &self,
+        msg_id: Id<MessageMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Message<'static>>>> {
+        self.cache.get_single_from(self.preference, msg_id).await
+    }
+
+    /// Get a presence entry.
+    pub async fn presence(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Presence<'static>>>> {
+        let key = RedisKey::Presence {
+            guild: guild_id,
+            user: user_id,
+        };
+
+        self.cache.get_single_from(self.preference, key).await
+    }
+
+    /// Get a role entry.
+    pub async fn role(
+        &self,
+        role_id: Id<RoleMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Role<'static>>>> {
+        self.cache.get_single_from(self.preference, role_id).await
+    }
+
+    /// Get a scheduled event entry.
+    pub async fn scheduled_event(
+        &self,
+        scheduled_event_id: Id<ScheduledEventMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::ScheduledEvent<'static>>>> {
+        self.cache
+            .get_single_from(self.preference, scheduled_event_id)
+            .await
+    }
+
+    /// Get a stage instance entry.
+    pub async fn stage_instance(
+        &self,
+        stage_instance_id: Id<StageMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::StageInstance<'static>>>> {
+        self.cache
+            .get_single_from(self.preference, stage_instance_id)
+            .await
+    }
+
+    /// Get a sticker entry.
+    pub async fn sticker(
+        &self,
+        sticker_id: Id<StickerMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::Sticker<'static>>>> {
+        self.cache
+            .get_single_from(self.preference, sticker_id)
+            .await
+    }
+
+    /// Get a thread member entry.
+    pub async fn thread_member(
+        &self,
+        channel_id: Id<ChannelMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::ThreadMember<'static>>>> {
+        let key = RedisKey::ThreadMember {
+            channel: channel_id,
+            user: user_id,
+        };
+
+        self.cache.get_single_from(self.preference, key).await
+    }
+
+    /// Get a user entry.
+    pub async fn user(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::User<'static>>>> {
+        self.cache.get_single_from(self.preference, user_id).await
+    }
+
+    /// Get a voice state entry.
+    pub async fn voice_state(
+        &self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> CacheResult<Option<CachedArchive<C::VoiceState<'static>>>> {
+        let key = RedisKey::VoiceState {
+            guild: guild_id,
+            user: user_id,
+        };
+
+        self.cache.get_single_from(self.preference, key).await
+    }
+}
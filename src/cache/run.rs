@@ -0,0 +1,132 @@
+use std::pin::pin;
+
+use futures_util::{future, Stream, StreamExt};
+use tokio::sync::mpsc::{self, Receiver};
+use tracing::instrument;
+use twilight_model::gateway::event::Event;
+
+use crate::{config::CacheConfig, error::CacheError, CacheResult, RedisCache};
+
+/// Amount of events that may be buffered for each lane before the stream is
+/// no longer polled.
+const LANE_CAPACITY: usize = 128;
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Update the cache with all [`Event`]s of a stream, processing up to
+    /// `concurrency` events at the same time.
+    ///
+    /// Events of the same guild are applied in the order in which they were
+    /// received. To do so, events are distributed onto `concurrency` lanes by
+    /// their guild id and each lane processes its events one after another.
+    /// Events without guild id share a single lane.
+    ///
+    /// A `concurrency` of `0` is treated as `1`.
+    ///
+    /// Returns once the stream ends and all its events are processed, or on
+    /// the first error in which case the remaining events are not processed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # use twilight_model::gateway::event::Event;
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// #     events: Vec<Event>,
+    /// # ) -> Result<(), Box<dyn std::error::Error>> {
+    /// let events = futures_util::stream::iter(events);
+    ///
+    /// cache.run(events, 8).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(level = "debug", skip_all)]
+    pub async fn run<S>(&self, events: S, concurrency: usize) -> CacheResult<()>
+    where
+        S: Stream<Item = Event>,
+    {
+        let lane_count = concurrency.max(1);
+
+        let (senders, receivers): (Vec<_>, Vec<_>) = (0..lane_count)
+            .map(|_| mpsc::channel(LANE_CAPACITY))
+            .unzip();
+
+        let distribute = async move {
+            let mut events = pin!(events);
+
+            while let Some(event) = events.next().await {
+                let lane = lane(&event, lane_count);
+
+                // Receivers are only dropped once a lane failed in which
+                // case the error is returned already.
+                if senders[lane].send(event).await.is_err() {
+                    break;
+                }
+            }
+
+            Ok::<_, CacheError>(())
+        };
+
+        let lanes = future::try_join_all(receivers.into_iter().map(|rx| self.run_lane(rx)));
+
+        future::try_join(distribute, lanes).await?;
+
+        Ok(())
+    }
+
+    async fn run_lane(&self, mut rx: Receiver<Event>) -> CacheResult<()> {
+        while let Some(event) = rx.recv().await {
+            self.update(&event).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Index of the lane that processes the event.
+// The remainder is smaller than `lane_count` so it fits into usize
+#[allow(clippy::cast_possible_truncation)]
+fn lane(event: &Event, lane_count: usize) -> usize {
+    event
+        .guild_id()
+        .map_or(0, |guild_id| (guild_id.get() % lane_count as u64) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{
+        gateway::{
+            event::Event,
+            payload::incoming::{RoleDelete, TypingStart},
+        },
+        id::Id,
+    };
+
+    use super::lane;
+
+    fn role_delete(guild_id: u64) -> Event {
+        Event::RoleDelete(RoleDelete {
+            guild_id: Id::new(guild_id),
+            role_id: Id::new(1),
+        })
+    }
+
+    #[test]
+    fn same_guild_same_lane() {
+        assert_eq!(lane(&role_delete(123), 4), lane(&role_delete(123), 4));
+        assert_eq!(lane(&role_delete(5), 4), 1);
+        assert_eq!(lane(&role_delete(5), 1), 0);
+    }
+
+    #[test]
+    fn no_guild_first_lane() {
+        let event = Event::TypingStart(Box::new(TypingStart {
+            channel_id: Id::new(1),
+            guild_id: None,
+            member: None,
+            timestamp: 0,
+            user_id: Id::new(2),
+        }));
+
+        assert_eq!(lane(&event, 4), 0);
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+use rkyv::util::AlignedVec;
+use tracing::{info, instrument};
+
+use crate::{
+    config::{CacheConfig, Cacheable, EntityKind},
+    error::CacheError,
+    key::RedisKey,
+    maintenance::{entry_prefix, scan, scan_pattern},
+    redis::{Cmd, Connection, Pipeline},
+    util::BytesWrap,
+    CacheResult, RedisCache,
+};
+
+/// An entity kind whose stored entries were written with a different
+/// [`Cacheable::VERSION`] than the one of the configured type.
+///
+/// Returned by [`RedisCache::check_schema`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// Kind of the outdated entries.
+    pub kind: EntityKind,
+    /// Version that the stored entries were written with; `0` if no version
+    /// was recorded yet.
+    pub stored: u16,
+    /// Version of the configured type.
+    pub current: u16,
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Compare the recorded schema versions of all cached entity kinds with
+    /// their configured [`Cacheable::VERSION`].
+    ///
+    /// Versions are recorded once outdated entries were handled through
+    /// [`RedisCache::flush_outdated`] or [`RedisCache::migrate_with`] so this
+    /// should be checked on startup before processing events.
+    ///
+    /// Kinds that are not cached, i.e. [`Ignore`]d, are skipped.
+    ///
+    /// [`Ignore`]: crate::config::Ignore
+    pub async fn check_schema(&self) -> CacheResult<Vec<SchemaMismatch>> {
+        let mut conn = self.connection().await?;
+
+        let stored: HashMap<String, u16> =
+            Cmd::hgetall(self.namespace.key(RedisKey::SchemaVersions))
+                .query_async(&mut conn)
+                .await?;
+
+        let mismatches = EntityKind::ALL
+            .into_iter()
+            .filter_map(|kind| {
+                let current = schema_version::<C>(kind)?;
+                let stored = stored.get(kind.name()).copied().unwrap_or(0);
+
+                (stored != current).then_some(SchemaMismatch {
+                    kind,
+                    stored,
+                    current,
+                })
+            })
+            .collect();
+
+        Ok(mismatches)
+    }
+
+    /// Delete all entries of entity kinds whose recorded schema version does
+    /// not match the configured [`Cacheable::VERSION`] and record the
+    /// configured version.
+    ///
+    /// Entries are deleted through [`Maintenance::clear`] so the same caveats
+    /// apply. Returns the amount of deleted keys.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// if !cache.check_schema().await?.is_empty() {
+    ///     let deleted = cache.flush_outdated().await?;
+    ///     println!("Deleted {deleted} outdated keys");
+    /// }
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Maintenance::clear`]: crate::maintenance::Maintenance::clear
+    #[instrument(level = "debug", skip_all)]
+    pub async fn flush_outdated(&self) -> CacheResult<usize> {
+        let mut deleted = 0;
+
+        for SchemaMismatch {
+            kind,
+            stored,
+            current,
+        } in self.check_schema().await?
+        {
+            let count = self.maintenance().clear(kind).await?;
+            deleted += count;
+
+            info!(
+                kind = kind.name(),
+                stored, current, count, "Deleted outdated entries"
+            );
+
+            self.record_schema_version(kind, current).await?;
+        }
+
+        #[cfg(feature = "local_cache")]
+        self.clear_local_cache();
+
+        Ok(deleted)
+    }
+
+    /// Transform all entries of the given kind if its recorded schema version
+    /// does not match the configured [`Cacheable::VERSION`], and record the
+    /// configured version.
+    ///
+    /// The function receives the archived bytes of each entry which are
+    /// aligned to 16 bytes so they can be accessed as the previous layout.
+    /// It returns the bytes of the new layout, or `None` to delete the entry.
+    /// Expire durations of transformed entries are kept.
+    ///
+    /// Entries are walked through `SCAN` so the same caveats as for
+    /// [`Maintenance`] apply. Returns the amount of transformed or deleted
+    /// entries.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::{CacheConfig, EntityKind}, RedisCache};
+    /// # use rkyv::{rancor::Error, Archive, Serialize};
+    /// #[derive(Archive)]
+    /// struct OldGuild {
+    ///     name: String,
+    /// }
+    ///
+    /// #[derive(Archive, Serialize)]
+    /// struct NewGuild {
+    ///     name: String,
+    ///     member_count: u64,
+    /// }
+    ///
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// cache
+    ///     .migrate_with(EntityKind::Guild, |bytes| {
+    ///         // SAFETY: all guilds were stored with the previous layout
+    ///         let old = unsafe { rkyv::access_unchecked::<ArchivedOldGuild>(bytes) };
+    ///
+    ///         let new = NewGuild {
+    ///             name: old.name.as_str().to_owned(),
+    ///             member_count: 0,
+    ///         };
+    ///
+    ///         rkyv::to_bytes::<Error>(&new).ok()
+    ///     })
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// [`Maintenance`]: crate::maintenance::Maintenance
+    #[instrument(level = "debug", skip_all, fields(kind = kind.name()))]
+    pub async fn migrate_with<F, B>(&self, kind: EntityKind, mut f: F) -> CacheResult<usize>
+    where
+        F: FnMut(&[u8]) -> Option<B>,
+        B: AsRef<[u8]>,
+    {
+        let Some(current) = schema_version::<C>(kind) else {
+            return Ok(0);
+        };
+
+        let mismatch = self
+            .check_schema()
+            .await?
+            .into_iter()
+            .any(|mismatch| mismatch.kind == kind);
+
+        if !mismatch {
+            return Ok(0);
+        }
+
+        let mut conn = self.connection().await?;
+        let mut migrated = 0;
+
+        if kind == EntityKind::CurrentUser {
+            let keys = [self.namespace.render(&RedisKey::CurrentUser)];
+            migrated += migrate_keys(&mut conn, &keys, &mut f).await?;
+        } else {
+            let pattern = scan_pattern(&self.namespace, entry_prefix(kind));
+            let mut cursor = 0;
+
+            loop {
+                let (next, keys) = scan(&mut conn, cursor, Some(&pattern)).await?;
+                migrated += migrate_keys(&mut conn, &keys, &mut f).await?;
+
+                cursor = next;
+
+                if cursor == 0 {
+                    break;
+                }
+            }
+        }
+
+        info!(
+            kind = kind.name(),
+            current, migrated, "Migrated outdated entries"
+        );
+
+        self.record_schema_version(kind, current).await?;
+
+        #[cfg(feature = "local_cache")]
+        self.clear_local_cache();
+
+        Ok(migrated)
+    }
+
+    async fn record_schema_version(&self, kind: EntityKind, version: u16) -> CacheResult<()> {
+        let mut conn = self.connection().await?;
+
+        Cmd::hset(
+            self.namespace.key(RedisKey::SchemaVersions),
+            kind.name(),
+            version,
+        )
+        .query_async(&mut conn)
+        .await
+        .map_err(CacheError::Redis)
+    }
+}
+
+/// Transform the entries of the given keys, returning the amount of
+/// transformed or deleted entries.
+async fn migrate_keys<F, B>(
+    conn: &mut Connection<'_>,
+    keys: &[Vec<u8>],
+    f: &mut F,
+) -> CacheResult<usize>
+where
+    F: FnMut(&[u8]) -> Option<B>,
+    B: AsRef<[u8]>,
+{
+    if keys.is_empty() {
+        return Ok(0);
+    }
+
+    // Not using MGET since keys may belong to different cluster slots
+    let mut pipe = Pipeline::new();
+
+    for key in keys {
+        pipe.get(key);
+    }
+
+    let entries: Vec<BytesWrap<AlignedVec<16>>> = pipe.query_async(conn).await?;
+
+    let mut pipe = Pipeline::new();
+    let mut migrated = 0;
+
+    for (key, BytesWrap(bytes)) in keys.iter().zip(entries) {
+        // Expired in the meanwhile
+        if bytes.is_empty() {
+            continue;
+        }
+
+        if let Some(new) = f(bytes.as_slice()) {
+            pipe.cmd("SET")
+                .arg(key)
+                .arg(new.as_ref())
+                .arg("KEEPTTL")
+                .ignore();
+        } else {
+            pipe.del(key).ignore();
+        }
+
+        migrated += 1;
+    }
+
+    if migrated > 0 {
+        pipe.query_async::<_, ()>(conn).await?;
+    }
+
+    Ok(migrated)
+}
+
+/// The configured schema version of the entity kind if it is cached.
+fn schema_version<C: CacheConfig>(kind: EntityKind) -> Option<u16> {
+    fn version<T: Cacheable>() -> Option<u16> {
+        T::WANTED.then_some(T::VERSION)
+    }
+
+    match kind {
+        EntityKind::AutoModerationRule => version::<C::AutoModerationRule<'static>>(),
+        EntityKind::Channel => version::<C::Channel<'static>>(),
+        EntityKind::CurrentUser => version::<C::CurrentUser<'static>>(),
+        EntityKind::Emoji => version::<C::Emoji<'static>>(),
+        EntityKind::Guild => version::<C::Guild<'static>>(),
+        EntityKind::Integration => version::<C::Integration<'static>>(),
+        EntityKind::Invite => version::<C::Invite<'static>>(),
+        EntityKind::Member => version::<C::Member<'static>>(),
+        EntityKind::Message => version::<C::Message<'static>>(),
+        EntityKind::Presence => version::<C::Presence<'static>>(),
+        EntityKind::Role => version::<C::Role<'static>>(),
+        EntityKind::ScheduledEvent => version::<C::ScheduledEvent<'static>>(),
+        EntityKind::StageInstance => version::<C::StageInstance<'static>>(),
+        EntityKind::Sticker => version::<C::Sticker<'static>>(),
+        EntityKind::ThreadMember => version::<C::ThreadMember<'static>>(),
+        EntityKind::User => version::<C::User<'static>>(),
+        EntityKind::VoiceState => version::<C::VoiceState<'static>>(),
+    }
+}
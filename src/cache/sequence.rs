@@ -0,0 +1,76 @@
+use tracing::instrument;
+
+use crate::{
+    cache::pipe::Pipe, config::CacheConfig, error::CacheError, key::RedisKey, redis::Cmd,
+    CacheResult, RedisCache,
+};
+
+/// Stores the sequence of a shard if it's larger than the current one or if
+/// the sequence should be reset.
+///
+/// KEYS: shard sequence
+/// ARGV: sequence, reset (0 or 1)
+const STORE_SEQUENCE_SCRIPT: &str = r"
+local current = tonumber(redis.call('GET', KEYS[1]) or -1)
+
+if ARGV[2] == '1' or tonumber(ARGV[1]) > current then
+    redis.call('SET', KEYS[1], ARGV[1])
+end
+";
+
+/// Position of a gateway event within its shard's session.
+///
+/// Used by [`RedisCache::update_with_meta`] to skip events that were already
+/// applied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EventSequence {
+    /// Id of the shard that received the event.
+    pub shard_id: u32,
+    /// Sequence number of the event as sent by the gateway.
+    pub sequence: u64,
+}
+
+impl EventSequence {
+    /// Create a new [`EventSequence`].
+    pub const fn new(shard_id: u32, sequence: u64) -> Self {
+        Self { shard_id, sequence }
+    }
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Whether an event with the given sequence was already applied.
+    ///
+    /// Always `false` in shadow mode since the stored sequence belongs to the
+    /// live cache.
+    #[instrument(level = "trace", skip(self))]
+    pub(crate) async fn is_applied(&self, sequence: EventSequence) -> CacheResult<bool> {
+        if C::SHADOW {
+            return Ok(false);
+        }
+
+        let mut conn = self.connection().await?;
+
+        let key = RedisKey::ShardSequence {
+            shard: sequence.shard_id,
+        };
+
+        let current: Option<u64> = Cmd::get(self.namespace.key(key))
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        Ok(current.is_some_and(|current| sequence.sequence <= current))
+    }
+
+    pub(crate) fn store_sequence(pipe: &mut Pipe<'_, C>, sequence: EventSequence, reset: bool) {
+        let key = RedisKey::ShardSequence {
+            shard: sequence.shard_id,
+        };
+
+        pipe.eval(
+            STORE_SEQUENCE_SCRIPT,
+            &[key],
+            (sequence.sequence, u8::from(reset)),
+        );
+    }
+}
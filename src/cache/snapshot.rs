@@ -0,0 +1,217 @@
+use std::collections::HashSet;
+
+use rkyv::util::AlignedVec;
+use tracing::{instrument, trace, Instrument};
+use twilight_model::id::{
+    marker::{ChannelMarker, EmojiMarker, GuildMarker, RoleMarker},
+    Id,
+};
+
+use super::{get::into_archive, otel};
+use crate::{
+    config::{CacheConfig, Cacheable},
+    error::CacheError,
+    key::{Namespace, RedisKey},
+    redis::{cmd, Connection, Pipeline},
+    util::{convert_ids, BytesWrap},
+    CacheResult, CachedArchive, RedisCache,
+};
+
+/// Maximum amount of attempts to read a snapshot before giving up.
+const MAX_ATTEMPTS: usize = 5;
+
+/// A guild alongside its channels, roles, and emojis as they were cached at
+/// the same point in time.
+///
+/// Created through [`RedisCache::guild_snapshot`] or
+/// [`RedisCache::guild_snapshot_with_payloads`].
+#[cfg_attr(
+    all(docsrs, not(doctest)),
+    doc(cfg(any(feature = "bb8", feature = "deadpool")))
+)]
+pub struct GuildSnapshot<C: CacheConfig> {
+    /// The guild entry.
+    pub guild: Option<CachedArchive<C::Guild<'static>>>,
+    /// Ids of the guild's cached channels.
+    pub channel_ids: HashSet<Id<ChannelMarker>>,
+    /// Ids of the guild's cached roles.
+    pub role_ids: HashSet<Id<RoleMarker>>,
+    /// Ids of the guild's cached emojis.
+    pub emoji_ids: HashSet<Id<EmojiMarker>>,
+    /// Entries of the guild's channels.
+    ///
+    /// Only filled by [`RedisCache::guild_snapshot_with_payloads`].
+    pub channels: Vec<CachedArchive<C::Channel<'static>>>,
+    /// Entries of the guild's roles.
+    ///
+    /// Only filled by [`RedisCache::guild_snapshot_with_payloads`].
+    pub roles: Vec<CachedArchive<C::Role<'static>>>,
+    /// Entries of the guild's emojis.
+    ///
+    /// Only filled by [`RedisCache::guild_snapshot_with_payloads`].
+    pub emojis: Vec<CachedArchive<C::Emoji<'static>>>,
+}
+
+#[cfg_attr(
+    all(docsrs, not(doctest)),
+    doc(cfg(any(feature = "bb8", feature = "deadpool")))
+)]
+impl<C: CacheConfig> RedisCache<C> {
+    /// Read a guild entry alongside the ids of its channels, roles, and
+    /// emojis such that all of them reflect the same point in time.
+    ///
+    /// The involved keys are watched while reading and the read is retried
+    /// if any of them changed in the meantime. This is useful when combining
+    /// the entries, e.g. to compute permissions, which would otherwise risk
+    /// mixing a guild with roles of a later update.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CacheError::SnapshotContention`] if the keys changed during
+    /// each of five attempts.
+    pub async fn guild_snapshot(&self, guild_id: Id<GuildMarker>) -> CacheResult<GuildSnapshot<C>> {
+        self.read_guild_snapshot(guild_id, false).await
+    }
+
+    /// Same as [`RedisCache::guild_snapshot`] but additionally reads the
+    /// entries of the guild's channels, roles, and emojis.
+    ///
+    /// The entries are watched as well so they're consistent with the ids.
+    pub async fn guild_snapshot_with_payloads(
+        &self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<GuildSnapshot<C>> {
+        self.read_guild_snapshot(guild_id, true).await
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn read_guild_snapshot(
+        &self,
+        guild_id: Id<GuildMarker>,
+        payloads: bool,
+    ) -> CacheResult<GuildSnapshot<C>> {
+        #[cfg(feature = "opentelemetry")]
+        otel::attach_context();
+
+        // Watching requires a connection that isn't shared with other tasks
+        // until the read is done
+        let mut conn = self.connection().await?;
+
+        for _ in 0..MAX_ATTEMPTS {
+            match Self::try_read_guild_snapshot(&mut conn, &self.namespace, guild_id, payloads)
+                .await
+            {
+                Ok(Some(snapshot)) => return Ok(snapshot),
+                Ok(None) => trace!("Guild changed while reading its snapshot, retrying"),
+                Err(err) => {
+                    // Don't hand a connection with watched keys back to the pool
+                    let _: Result<(), _> = cmd("UNWATCH").query_async(&mut conn).await;
+
+                    return Err(err);
+                }
+            }
+        }
+
+        Err(CacheError::SnapshotContention)
+    }
+
+    /// Returns `None` if a watched key changed.
+    async fn try_read_guild_snapshot(
+        conn: &mut Connection<'_>,
+        namespace: &Namespace,
+        guild_id: Id<GuildMarker>,
+        payloads: bool,
+    ) -> CacheResult<Option<GuildSnapshot<C>>> {
+        let guild_key = namespace.key(RedisKey::Guild { id: guild_id });
+        let channels_key = namespace.key(RedisKey::GuildChannels { id: guild_id });
+        let roles_key = namespace.key(RedisKey::GuildRoles { id: guild_id });
+        let emojis_key = namespace.key(RedisKey::GuildEmojis { id: guild_id });
+
+        cmd("WATCH")
+            .arg(&guild_key)
+            .arg(&channels_key)
+            .arg(&roles_key)
+            .arg(&emojis_key)
+            .query_async::<_, ()>(conn)
+            .await?;
+
+        let (BytesWrap(guild), channel_ids, role_ids, emoji_ids): (
+            BytesWrap<AlignedVec<16>>,
+            HashSet<u64>,
+            HashSet<u64>,
+            HashSet<u64>,
+        ) = Pipeline::new()
+            .get(&guild_key)
+            .smembers(&channels_key)
+            .smembers(&roles_key)
+            .smembers(&emojis_key)
+            .query_async(conn)
+            .instrument(otel::client_span("GET SMEMBERS"))
+            .await?;
+
+        let channel_ids = convert_ids(channel_ids);
+        let role_ids = convert_ids(role_ids);
+        let emoji_ids = convert_ids(emoji_ids);
+
+        let (channels, roles, emojis) = if payloads {
+            let channels = channel_ids.iter().map(|&id| RedisKey::Channel { id });
+            let roles = role_ids.iter().map(|&id| RedisKey::Role { id });
+            let emojis = emoji_ids.iter().map(|&id| RedisKey::Emoji { id });
+
+            (
+                watch_entries(conn, namespace, channels.collect()).await?,
+                watch_entries(conn, namespace, roles.collect()).await?,
+                watch_entries(conn, namespace, emojis.collect()).await?,
+            )
+        } else {
+            (Vec::new(), Vec::new(), Vec::new())
+        };
+
+        // An empty transaction only succeeds if no watched key changed
+        let exec: Option<()> = Pipeline::new()
+            .atomic()
+            .cmd("PING")
+            .query_async(conn)
+            .await?;
+
+        if exec.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(GuildSnapshot {
+            guild: into_archive(guild)?,
+            channel_ids,
+            role_ids,
+            emoji_ids,
+            channels,
+            roles,
+            emojis,
+        }))
+    }
+}
+
+/// Watch the keys and fetch their entries, skipping missing ones.
+async fn watch_entries<V: Cacheable>(
+    conn: &mut Connection<'_>,
+    namespace: &Namespace,
+    keys: Vec<RedisKey>,
+) -> CacheResult<Vec<CachedArchive<V>>> {
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let keys = namespace.key(keys);
+
+    cmd("WATCH").arg(&keys).query_async::<_, ()>(conn).await?;
+
+    let entries: Vec<BytesWrap<AlignedVec<16>>> = cmd("MGET")
+        .arg(&keys)
+        .query_async(conn)
+        .instrument(otel::client_span("MGET"))
+        .await?;
+
+    entries
+        .into_iter()
+        .filter_map(|BytesWrap(bytes)| into_archive(bytes).transpose())
+        .collect()
+}
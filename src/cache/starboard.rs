@@ -0,0 +1,67 @@
+use twilight_model::id::{
+    marker::{GuildMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    cache::pipe::Pipe,
+    config::{CacheConfig, ICachedMessage},
+    error::CacheError,
+    key::RedisKey,
+    redis::Cmd,
+    CacheResult, RedisCache,
+};
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Get the ids and scores of a guild's highest ranked messages, ordered
+    /// by descending score.
+    ///
+    /// Messages are only ranked if [`ICachedMessage::starboard_score`] is
+    /// specified. Since messages may expire on their own, the returned ids
+    /// are not guaranteed to still be cached.
+    pub async fn top_messages(
+        &self,
+        guild_id: Id<GuildMarker>,
+        count: usize,
+    ) -> CacheResult<Vec<(Id<MessageMarker>, f64)>> {
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.connection().await?;
+        let key = RedisKey::GuildStarboard { id: guild_id };
+
+        let stop = isize::try_from(count - 1).unwrap_or(isize::MAX);
+
+        let entries: Vec<(u64, f64)> = Cmd::zrevrange_withscores(self.namespace.key(key), 0, stop)
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        let top = entries
+            .into_iter()
+            .filter_map(|(id, score)| Id::new_checked(id).map(|id| (id, score)))
+            .collect();
+
+        Ok(top)
+    }
+
+    pub(crate) fn remove_from_starboard(
+        &self,
+        pipe: &mut Pipe<'_, C>,
+        guild_id: Option<Id<GuildMarker>>,
+        msg_ids: &[Id<MessageMarker>],
+    ) {
+        if C::Message::starboard_score().is_none() || msg_ids.is_empty() {
+            return;
+        }
+
+        let Some(guild_id) = guild_id else {
+            return;
+        };
+
+        let key = RedisKey::GuildStarboard { id: guild_id };
+        let msg_ids: Vec<_> = msg_ids.iter().map(|id| id.get()).collect();
+        pipe.zrem(key, msg_ids);
+    }
+}
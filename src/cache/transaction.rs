@@ -0,0 +1,166 @@
+use tracing::instrument;
+use twilight_model::{
+    channel::Channel,
+    guild::{Member, Role},
+    id::{
+        marker::{ChannelMarker, GuildMarker, MessageMarker, RoleMarker, UserMarker},
+        Id,
+    },
+    user::User,
+};
+
+use crate::{cache::pipe::Pipe, config::CacheConfig, CacheResult, RedisCache};
+
+/// A set of cache mutations that is applied atomically.
+///
+/// Created through [`RedisCache::transaction`]. Operations are only queued
+/// up and sent to redis inside a single MULTI/EXEC block once the
+/// transaction's closure returns successfully.
+///
+/// Only operations that do not need to read from the cache can be part of a
+/// transaction since reads would not be answered before the block executes.
+pub struct Transaction<'c, C> {
+    cache: &'c RedisCache<C>,
+    pipe: Pipe<'c, C>,
+}
+
+impl<C: CacheConfig> Transaction<'_, C> {
+    /// Store a user.
+    pub fn store_user(&mut self, user: &User) -> CacheResult<&mut Self> {
+        self.cache.store_user(&mut self.pipe, user)?;
+
+        Ok(self)
+    }
+
+    /// Store a guild member and its user.
+    pub fn store_member(
+        &mut self,
+        guild_id: Id<GuildMarker>,
+        member: &Member,
+    ) -> CacheResult<&mut Self> {
+        self.cache.store_member(&mut self.pipe, guild_id, member)?;
+
+        Ok(self)
+    }
+
+    /// Store a channel.
+    pub fn store_channel(&mut self, channel: &Channel) -> CacheResult<&mut Self> {
+        self.cache.store_channel(&mut self.pipe, channel)?;
+
+        Ok(self)
+    }
+
+    /// Store a guild role.
+    pub fn store_role(&mut self, guild_id: Id<GuildMarker>, role: &Role) -> CacheResult<&mut Self> {
+        self.cache.store_role(&mut self.pipe, guild_id, role)?;
+
+        Ok(self)
+    }
+
+    /// Delete a message.
+    pub fn delete_message(
+        &mut self,
+        channel_id: Id<ChannelMarker>,
+        msg_id: Id<MessageMarker>,
+    ) -> &mut Self {
+        self.cache
+            .delete_message(&mut self.pipe, msg_id, channel_id);
+
+        self
+    }
+
+    /// Delete a guild role.
+    pub fn delete_role(&mut self, guild_id: Id<GuildMarker>, role_id: Id<RoleMarker>) -> &mut Self {
+        self.cache.delete_role(&mut self.pipe, guild_id, role_id);
+
+        self
+    }
+
+    /// Delete a user's voice state in a guild.
+    pub fn delete_voice_state(
+        &mut self,
+        guild_id: Id<GuildMarker>,
+        user_id: Id<UserMarker>,
+    ) -> &mut Self {
+        self.cache
+            .delete_voice_state(&mut self.pipe, guild_id, user_id);
+
+        self
+    }
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Apply multiple cache mutations atomically.
+    ///
+    /// The closure queues up operations on the given [`Transaction`]. If it
+    /// returns an error, nothing is sent to redis. Otherwise all operations
+    /// are executed within a single MULTI/EXEC block so that other clients
+    /// observe either none or all of them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # use twilight_model::{guild::Member, id::Id};
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// #     member: Member,
+    /// # ) -> Result<(), Box<dyn std::error::Error>> {
+    /// cache
+    ///     .transaction(|tx| {
+    ///         tx.store_member(Id::new(2), &member)?
+    ///             .delete_voice_state(Id::new(1), member.user.id);
+    ///
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// # Ok(()) }
+    /// ```
+    #[cfg(not(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    )))]
+    #[instrument(level = "debug", skip_all)]
+    pub async fn transaction<F>(&self, f: F) -> CacheResult<()>
+    where
+        F: FnOnce(&mut Transaction<'_, C>) -> CacheResult<()>,
+    {
+        let mut tx = Transaction {
+            cache: self,
+            pipe: Pipe::new(self),
+        };
+
+        f(&mut tx)?;
+
+        if tx.pipe.is_empty() {
+            return Ok(());
+        }
+
+        tx.pipe.atomic();
+        tx.pipe.query::<()>().await
+    }
+
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    /// Transactions are not supported in cluster mode since the keys of their
+    /// operations may belong to different slots.
+    ///
+    /// Always returns [`CacheError::TransactionUnsupported`].
+    ///
+    /// [`CacheError::TransactionUnsupported`]: crate::error::CacheError::TransactionUnsupported
+    // Async to keep the signature of the non-cluster variant
+    #[allow(clippy::unused_async)]
+    #[instrument(level = "debug", skip_all)]
+    pub async fn transaction<F>(&self, _: F) -> CacheResult<()>
+    where
+        F: FnOnce(&mut Transaction<'_, C>) -> CacheResult<()>,
+    {
+        Err(crate::error::CacheError::TransactionUnsupported)
+    }
+}
@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use tracing::warn;
+use twilight_model::gateway::event::Event;
+
+use super::{custom::custom_key, pipe::Pipe};
+use crate::{
+    config::CacheConfig,
+    key::{Namespace, Namespaced, RedisKey},
+    redis::Cmd,
+    RedisCache,
+};
+
+/// A user-defined projection of gateway events such as a leaderboard or a
+/// counter.
+///
+/// Registered through [`RedisCache::with_view`].
+///
+/// # Example
+///
+/// ```no_run
+/// use redlight::cache::{CacheBatch, DerivedView};
+/// use twilight_model::gateway::event::Event;
+///
+/// struct MessageLeaderboard;
+///
+/// impl DerivedView for MessageLeaderboard {
+///     fn apply(&self, event: &Event, batch: &mut CacheBatch<'_>) {
+///         if let Event::MessageCreate(msg) = event {
+///             let Some(guild_id) = msg.guild_id else { return };
+///             let user_id = msg.author.id.to_string();
+///
+///             batch.zincr_by("leaderboard", &guild_id.to_string(), &user_id, 1.0);
+///         }
+///     }
+/// }
+/// ```
+pub trait DerivedView: Send + Sync + 'static {
+    /// Queue up the writes that the event causes for this view.
+    fn apply(&self, event: &Event, batch: &mut CacheBatch<'_>);
+}
+
+/// Writes of [`DerivedView`]s that are sent alongside the cache's own writes
+/// of an event.
+///
+/// All keys are the same as for [`RedisCache::set_custom`], i.e. a namespace
+/// and a key which must not contain `:`. Writes to keys that do contain `:`
+/// are skipped. Unlike entries of [`RedisCache::set_custom`], keys written
+/// through counters or sets hold values of the respective redis type.
+pub struct CacheBatch<'n> {
+    key_prefix: &'n Namespace,
+    cmds: Vec<Cmd>,
+}
+
+impl<'n> CacheBatch<'n> {
+    const fn new(key_prefix: &'n Namespace) -> Self {
+        Self {
+            key_prefix,
+            cmds: Vec::new(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        namespace: &str,
+        key: &str,
+        cmd: impl FnOnce(Namespaced<'n, RedisKey>) -> Cmd,
+    ) {
+        match custom_key(namespace, key) {
+            Ok(key) => self.cmds.push(cmd(self.key_prefix.key(key))),
+            Err(err) => warn!(?err, "Skipping write of derived view"),
+        }
+    }
+
+    /// Set the bytes of a key, optionally with an expire duration.
+    pub fn set(&mut self, namespace: &str, key: &str, bytes: &[u8], expire: Option<Duration>) {
+        self.push(namespace, key, |key| match expire {
+            Some(duration) => {
+                let millis = usize::try_from(duration.as_millis()).unwrap_or(usize::MAX);
+
+                Cmd::pset_ex(key, bytes, millis.max(1))
+            }
+            None => Cmd::set(key, bytes),
+        });
+    }
+
+    /// Delete a key.
+    pub fn del(&mut self, namespace: &str, key: &str) {
+        self.push(namespace, key, Cmd::del);
+    }
+
+    /// Increment the counter of a key.
+    pub fn incr_by(&mut self, namespace: &str, key: &str, delta: i64) {
+        self.push(namespace, key, |key| Cmd::incr(key, delta));
+    }
+
+    /// Increment the counter of a field within the hash of a key.
+    pub fn hincr_by(&mut self, namespace: &str, key: &str, field: &str, delta: i64) {
+        self.push(namespace, key, |key| Cmd::hincr(key, field, delta));
+    }
+
+    /// Increment the score of a member within the sorted set of a key.
+    pub fn zincr_by(&mut self, namespace: &str, key: &str, member: &str, delta: f64) {
+        self.push(namespace, key, |key| Cmd::zincr(key, member, delta));
+    }
+
+    /// Add a member to the set of a key.
+    pub fn sadd(&mut self, namespace: &str, key: &str, member: &str) {
+        self.push(namespace, key, |key| Cmd::sadd(key, member));
+    }
+
+    /// Remove a member from the set of a key.
+    pub fn srem(&mut self, namespace: &str, key: &str, member: &str) {
+        self.push(namespace, key, |key| Cmd::srem(key, member));
+    }
+}
+
+impl<C> RedisCache<C> {
+    /// Register a [`DerivedView`] that is applied on every event passed to
+    /// [`RedisCache::update`].
+    ///
+    /// Views are applied after the cache queued up its own writes for the
+    /// event. If any view writes something, all writes of the event are sent
+    /// within a single MULTI/EXEC block so that other clients observe the
+    /// cache and its views in a consistent state. With the `cluster` feature,
+    /// the writes are still sent together but are not atomic.
+    ///
+    /// Views are applied for events of a [`BatchedUpdater`] as well whose
+    /// writes are sent together but not atomically.
+    ///
+    /// [`BatchedUpdater`]: crate::cache::BatchedUpdater
+    #[must_use]
+    pub fn with_view(mut self, view: impl DerivedView) -> Self {
+        self.views.push(Box::new(view));
+
+        self
+    }
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    pub(crate) fn apply_views(&self, pipe: &mut Pipe<'_, C>, event: &Event) {
+        let mut batch = CacheBatch::new(&self.namespace);
+
+        for view in self.views.iter() {
+            view.apply(event, &mut batch);
+        }
+
+        if batch.cmds.is_empty() {
+            return;
+        }
+
+        // Keys of a transaction may belong to different slots
+        #[cfg(not(all(
+            not(feature = "bb8"),
+            not(feature = "deadpool"),
+            not(feature = "multiplexed"),
+            feature = "cluster"
+        )))]
+        pipe.atomic();
+
+        for cmd in batch.cmds {
+            pipe.add_command(cmd);
+        }
+    }
+}
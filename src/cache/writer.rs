@@ -0,0 +1,146 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use tracing::{error, instrument};
+
+use crate::{
+    clock::Clock,
+    config::{CacheConfig, WriterConflictBehavior},
+    error::CacheError,
+    key::RedisKey,
+    redis::Cmd,
+    util::instance_id,
+    CacheResult, RedisCache,
+};
+
+/// Claims or refreshes the writer lease of a shard.
+///
+/// Returns the id of the current holder if it's another writer.
+///
+/// KEYS: shard writer
+/// ARGV: writer id, lease in milliseconds
+const CLAIM_SCRIPT: &str = r"
+local holder = redis.call('GET', KEYS[1])
+
+if holder and holder ~= ARGV[1] then
+    return holder
+end
+
+redis.call('SET', KEYS[1], ARGV[1], 'PX', ARGV[2])
+
+return false
+";
+
+/// Amount of heartbeats within the lease duration.
+const HEARTBEATS_PER_LEASE: u32 = 3;
+
+/// Tracks the writer leases of shards for this process.
+pub(crate) struct WriterLeases {
+    writer_id: String,
+    checks: Mutex<HashMap<u32, LeaseCheck>>,
+}
+
+/// Outcome of the latest heartbeat of a shard.
+struct LeaseCheck {
+    at: SystemTime,
+    /// Whether the lease is held by another process.
+    is_foreign: bool,
+}
+
+impl WriterLeases {
+    pub(crate) fn new(clock: &dyn Clock) -> Self {
+        Self {
+            writer_id: instance_id(clock),
+            checks: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<C: CacheConfig> RedisCache<C> {
+    /// Ensure that this process may write events of the shard.
+    ///
+    /// Heartbeats are only sent every so often so most events don't require
+    /// a round trip. Shadow caches never claim the lease since they don't
+    /// write anything.
+    #[instrument(level = "trace", skip(self))]
+    pub(crate) async fn check_writer(&self, shard: u32) -> CacheResult<()> {
+        let Some(lease) = C::WRITER_LEASE.filter(|_| !C::SHADOW) else {
+            return Ok(());
+        };
+
+        let now = self.clock.now();
+        let heartbeat_interval = lease.duration / HEARTBEATS_PER_LEASE;
+
+        let cached = {
+            let checks = self
+                .writer_leases
+                .checks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            checks
+                .get(&shard)
+                .filter(|check| {
+                    now.duration_since(check.at)
+                        .is_ok_and(|elapsed| elapsed < heartbeat_interval)
+                })
+                .map(|check| check.is_foreign)
+        };
+
+        let is_foreign = if let Some(is_foreign) = cached {
+            is_foreign
+        } else {
+            let mut conn = self.connection().await?;
+
+            let lease_ms = u64::try_from(lease.duration.as_millis())
+                .unwrap_or(u64::MAX)
+                .max(1);
+
+            let foreign_holder: Option<String> = Cmd::new()
+                .arg("EVAL")
+                .arg(CLAIM_SCRIPT)
+                .arg(1)
+                .arg(self.namespace.key(RedisKey::ShardWriter { shard }))
+                .arg(&self.writer_leases.writer_id)
+                .arg(lease_ms)
+                .query_async(&mut conn)
+                .await
+                .map_err(CacheError::Redis)?;
+
+            if let Some(ref holder) = foreign_holder {
+                error!(
+                    shard,
+                    holder,
+                    writer_id = self.writer_leases.writer_id,
+                    "Events of the shard are written by another process as well"
+                );
+
+                #[cfg(feature = "metrics")]
+                crate::cache::metrics::record_duplicate_writer(shard);
+            }
+
+            let is_foreign = foreign_holder.is_some();
+            let check = LeaseCheck {
+                at: now,
+                is_foreign,
+            };
+
+            self.writer_leases
+                .checks
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(shard, check);
+
+            is_foreign
+        };
+
+        if is_foreign && lease.behavior == WriterConflictBehavior::Reject {
+            return Err(CacheError::DuplicateWriter { shard });
+        }
+
+        Ok(())
+    }
+}
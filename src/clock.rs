@@ -0,0 +1,134 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use tokio::sync::watch;
+
+/// Future returned by [`Clock::sleep`].
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Source of time for the cache.
+///
+/// All time access of the cache, e.g. the interval of the metrics loop, goes
+/// through this trait so that tests can replace it with a [`MockClock`] and
+/// run deterministically.
+///
+/// By default, [`SystemClock`] is used. A custom clock can be provided through
+/// [`RedisCache::new_with_clock`].
+///
+/// [`RedisCache::new_with_clock`]: crate::RedisCache::new_with_clock
+pub trait Clock: Send + Sync + 'static {
+    /// The current point in time.
+    fn now(&self) -> SystemTime;
+
+    /// Wait until the given duration has passed.
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// [`Clock`] based on the system time and tokio's timer.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// [`Clock`] whose time only moves when told to.
+///
+/// Sleeping waits until the clock was moved past the deadline through
+/// [`advance`] or [`set`]. Clones share the same time so a clone can be kept
+/// to control the clock after handing it to the cache.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+///
+/// use redlight::clock::{Clock, MockClock};
+///
+/// let clock = MockClock::new(SystemTime::UNIX_EPOCH);
+/// clock.advance(Duration::from_secs(5));
+///
+/// assert_eq!(clock.now(), SystemTime::UNIX_EPOCH + Duration::from_secs(5));
+/// ```
+///
+/// [`advance`]: MockClock::advance
+/// [`set`]: MockClock::set
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    now: Arc<watch::Sender<SystemTime>>,
+}
+
+impl MockClock {
+    /// Create a new [`MockClock`] starting at the given time.
+    pub fn new(start: SystemTime) -> Self {
+        Self {
+            now: Arc::new(watch::Sender::new(start)),
+        }
+    }
+
+    /// Move the clock forward by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        self.now.send_modify(|now| *now += duration);
+    }
+
+    /// Set the clock to the given time.
+    pub fn set(&self, now: SystemTime) {
+        self.now.send_replace(now);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(SystemTime::UNIX_EPOCH)
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.borrow()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let deadline = self.now() + duration;
+        let mut rx = self.now.subscribe();
+
+        Box::pin(async move {
+            // Only fails if all clones of the clock were dropped in which
+            // case it won't move anymore anyway
+            let _ = rx.wait_for(|now| *now >= deadline).await;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mock_clock_sleep_waits_for_advance() {
+        let clock = MockClock::default();
+        let sleep = tokio::spawn(clock.sleep(Duration::from_secs(20)));
+
+        clock.advance(Duration::from_secs(10));
+        tokio::task::yield_now().await;
+        assert!(!sleep.is_finished());
+
+        clock.advance(Duration::from_secs(10));
+        sleep.await.unwrap();
+
+        assert_eq!(
+            clock.now(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(20)
+        );
+    }
+}
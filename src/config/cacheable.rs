@@ -1,8 +1,11 @@
 use std::time::Duration;
 
 use rkyv::{
+    api::high::{to_bytes_in_with_alloc, HighSerializer},
     rancor::{Fallible, Source},
-    Archive,
+    ser::allocator::{Arena, ArenaHandle},
+    util::AlignedVec,
+    Archive, Serialize,
 };
 
 use super::CheckedArchive;
@@ -57,6 +60,36 @@ pub trait Cacheable: Archive + Fallible<Error: Source> + CheckedArchive + Sized
     /// [`Ignore`](crate::config::Ignore).
     const WANTED: bool = true;
 
+    /// Version of the archived layout of this type.
+    ///
+    /// Bump it whenever the fields of the type change. Entries that were
+    /// stored with a previous layout may otherwise fail validation or, without
+    /// the `bytecheck` feature, be misinterpreted. Outdated entries are
+    /// detected through [`RedisCache::check_schema`] and can then be removed
+    /// through [`RedisCache::flush_outdated`] or transformed through
+    /// [`RedisCache::migrate_with`].
+    ///
+    /// Defaults to `0`.
+    ///
+    /// [`RedisCache::check_schema`]: crate::RedisCache::check_schema
+    /// [`RedisCache::flush_outdated`]: crate::RedisCache::flush_outdated
+    /// [`RedisCache::migrate_with`]: crate::RedisCache::migrate_with
+    const VERSION: u16 = 0;
+
+    #[cfg(feature = "serde-mirror")]
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "serde-mirror")))]
+    /// Whether entries of this type have a JSON copy through [`mirror`].
+    ///
+    /// Must be set to `true` for [`mirror`] and [`mirror_archived`] to be
+    /// used. Only JSON copies of types that set it are removed alongside
+    /// their entries.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`mirror`]: Cacheable::mirror
+    /// [`mirror_archived`]: Cacheable::mirror_archived
+    const MIRROR: bool = false;
+
     /// Duration until the cache entry expires and is removed.
     ///
     /// `None` indicates that it will never expire.
@@ -82,12 +115,123 @@ pub trait Cacheable: Archive + Fallible<Error: Source> + CheckedArchive + Sized
     /// potentially improve performance.
     ///
     /// Unless implemented manually, the default serializer will just use
-    /// [`serialize_one`] repeatedly.
+    /// [`serialize_one`] repeatedly. For types that serialize through rkyv's
+    /// high-level API, [`SerializeWithArena`] is a ready-made alternative.
     ///
     /// [`serialize_one`]: Cacheable::serialize_one
     fn serialize_many() -> impl SerializeMany<Self> {
         SerializeOneByOne
     }
+
+    /// Optionally provide a version of this entry to protect it from being
+    /// overwritten by older data.
+    ///
+    /// Gateway events may arrive out of order, e.g. after a reconnect or when
+    /// multiple shards write to the same cache. If a version is provided, it
+    /// is stored alongside the entry and subsequent writes of the entry with
+    /// a smaller version are skipped. The comparison happens atomically
+    /// within redis so concurrent writers cannot regress an entry.
+    ///
+    /// A version could be a timestamp like a message's `edited_timestamp`.
+    ///
+    /// The version is kept for as long as the entry if it expires, and for
+    /// [`VERSION_LIFETIME`] otherwise. Entries updated in-place, e.g. through
+    /// [`ICachedMessage::on_message_update`], don't check or bump the version.
+    ///
+    /// Versions are not supported with the `cluster` feature since the entry
+    /// and its version belong to different slots. Writing a value that
+    /// provides a version fails with `CacheError::ClusterUnsupported` then.
+    ///
+    /// Returns `None` by default, meaning writes are never skipped.
+    ///
+    /// [`VERSION_LIFETIME`]: crate::config::VERSION_LIFETIME
+    /// [`ICachedMessage::on_message_update`]: crate::config::ICachedMessage::on_message_update
+    fn version(&self) -> Option<u64> {
+        None
+    }
+
+    #[cfg(feature = "serde-mirror")]
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "serde-mirror")))]
+    /// Optionally serialize this type into JSON which will be stored in
+    /// addition to the archived bytes.
+    ///
+    /// The JSON is stored under the same key as the entry, prefixed by
+    /// `JSON:`, and expires alongside it. This allows services that don't
+    /// know about [`rkyv`] to read from the cache.
+    ///
+    /// Only used if [`MIRROR`] is `true`. Returns `None` by default, meaning
+    /// no JSON will be stored. If the type implements [`serde::Serialize`],
+    /// [`to_json`] can be used.
+    ///
+    /// Entries which are updated in-place, e.g. through
+    /// [`ICachedMessage::on_message_update`], use [`mirror_archived`]
+    /// instead.
+    ///
+    /// [`MIRROR`]: Cacheable::MIRROR
+    /// [`to_json`]: crate::config::to_json
+    /// [`mirror_archived`]: Cacheable::mirror_archived
+    /// [`ICachedMessage::on_message_update`]: crate::config::ICachedMessage::on_message_update
+    fn mirror(&self) -> Option<Result<Vec<u8>, Self::Error>> {
+        None
+    }
+
+    #[cfg(feature = "serde-mirror")]
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "serde-mirror")))]
+    /// Optionally serialize the archived form of this type into JSON.
+    ///
+    /// Used to rewrite the JSON copy of [`mirror`] after an entry was
+    /// updated in-place. If this returns `None`, the now stale JSON copy is
+    /// removed instead.
+    ///
+    /// Returns `None` by default.
+    ///
+    /// [`mirror`]: Cacheable::mirror
+    fn mirror_archived(_archived: &Self::Archived) -> Option<Result<Vec<u8>, Self::Error>> {
+        None
+    }
+}
+
+/// How long the version of a non-expiring entry is kept.
+///
+/// See [`Cacheable::version`].
+pub const VERSION_LIFETIME: Duration = Duration::from_hours(24);
+
+#[cfg(feature = "serde-mirror")]
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "serde-mirror")))]
+/// Serialize a value into JSON, intended for [`Cacheable::mirror`].
+///
+/// # Example
+/// ```
+/// # use std::time::Duration;
+/// use redlight::config::{to_json, Cacheable};
+/// use rkyv::{rancor::Fallible, util::AlignedVec, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize, serde::Serialize)]
+/// struct CachedRole {
+///     name: String,
+/// }
+///
+/// impl Cacheable for CachedRole {
+///     # /*
+///     // ...
+///     # */
+///     # type Bytes = AlignedVec;
+///     # fn expire() -> Option<Duration> { None }
+///     # fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> { rkyv::to_bytes(self) }
+///
+///     const MIRROR: bool = true;
+///
+///     fn mirror(&self) -> Option<Result<Vec<u8>, Self::Error>> {
+///         Some(to_json(self))
+///     }
+/// }
+///
+/// impl Fallible for CachedRole {
+///     type Error = rkyv::rancor::Error;
+/// }
+/// ```
+pub fn to_json<T: serde::Serialize + ?Sized, E: Source>(value: &T) -> Result<Vec<u8>, E> {
+    serde_json::to_vec(value).map_err(Source::new)
 }
 
 /// A serializer to serialize multiple instances in a row.
@@ -108,3 +252,157 @@ impl<C: Cacheable> SerializeMany<C> for SerializeOneByOne {
         next.serialize_one()
     }
 }
+
+/// A [`SerializeMany`] implementation that reuses its scratch space across
+/// serializations and pre-sizes the resulting buffers.
+///
+/// The scratch arena grows to the largest size required so far and is kept
+/// for subsequent serializations. The capacity of each resulting buffer is
+/// based on a moving average of recent payload sizes so that most
+/// serializations don't need to reallocate.
+///
+/// Besides being returned from [`Cacheable::serialize_many`], it can also be
+/// used directly when serializing many entries manually.
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// use redlight::config::{Cacheable, SerializeMany, SerializeWithArena};
+/// use rkyv::{rancor::Fallible, util::AlignedVec, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct CachedMember {
+///     roles: Vec<u64>,
+/// }
+///
+/// impl Cacheable for CachedMember {
+///     type Bytes = AlignedVec<16>;
+///
+///     fn expire() -> Option<Duration> {
+///         None
+///     }
+///
+///     fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+///         rkyv::to_bytes(self)
+///     }
+///
+///     fn serialize_many() -> impl SerializeMany<Self> {
+///         SerializeWithArena::<16>::new()
+///     }
+/// }
+///
+/// impl Fallible for CachedMember {
+///     type Error = rkyv::rancor::Error;
+/// }
+/// ```
+pub struct SerializeWithArena<const A: usize = 16> {
+    arena: Arena,
+    average_len: usize,
+}
+
+impl<const A: usize> SerializeWithArena<A> {
+    /// Weight of the previous average when including a new payload size.
+    const SMOOTHING: usize = 8;
+
+    /// Create a new serializer without any size estimate.
+    pub fn new() -> Self {
+        Self::with_len_hint(0)
+    }
+
+    /// Create a new serializer that expects payloads of roughly the given
+    /// length in bytes.
+    pub fn with_len_hint(len: usize) -> Self {
+        Self {
+            arena: Arena::new(),
+            average_len: len,
+        }
+    }
+
+    /// The current moving average of payload lengths in bytes.
+    pub const fn average_len(&self) -> usize {
+        self.average_len
+    }
+
+    const fn record_len(&mut self, len: usize) {
+        self.average_len = if self.average_len == 0 {
+            len
+        } else {
+            self.average_len - self.average_len / Self::SMOOTHING + len / Self::SMOOTHING
+        };
+    }
+}
+
+impl<const A: usize> Default for SerializeWithArena<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C, const A: usize> SerializeMany<C> for SerializeWithArena<A>
+where
+    C: Cacheable
+        + for<'a> Serialize<HighSerializer<AlignedVec<A>, ArenaHandle<'a>, <C as Fallible>::Error>>,
+{
+    type Bytes = AlignedVec<A>;
+
+    fn serialize_next(&mut self, next: &C) -> Result<Self::Bytes, C::Error> {
+        // Leave some room so that slightly larger payloads still fit
+        let capacity = self.average_len + self.average_len / 4;
+        let writer = AlignedVec::with_capacity(capacity);
+        let bytes = to_bytes_in_with_alloc(next, writer, self.arena.acquire())?;
+        self.record_len(bytes.len());
+
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rkyv::{rancor::Error, util::AlignedVec, Archive, Serialize};
+
+    use super::{Cacheable, SerializeMany, SerializeWithArena};
+
+    #[derive(Archive, Serialize)]
+    struct Entry {
+        values: Vec<u64>,
+    }
+
+    impl Cacheable for Entry {
+        type Bytes = AlignedVec<16>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl rkyv::rancor::Fallible for Entry {
+        type Error = Error;
+    }
+
+    #[test]
+    fn test_serialize_with_arena() -> Result<(), Error> {
+        let mut serializer = SerializeWithArena::<16>::new();
+
+        for len in [4, 8, 16, 8] {
+            let entry = Entry {
+                values: (0..len).collect(),
+            };
+
+            let many = serializer.serialize_next(&entry)?;
+            let one = entry.serialize_one()?;
+
+            assert_eq!(many.as_slice(), one.as_slice());
+        }
+
+        assert!(serializer.average_len() > 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,23 @@
+/// Limits for the sampled comparison of index sets with their entries.
+///
+/// See [`CacheConfig::DRIFT_ALARM`](crate::config::CacheConfig::DRIFT_ALARM).
+///
+/// # Example
+///
+/// ```
+/// use redlight::config::DriftAlarm;
+///
+/// const DRIFT_ALARM: DriftAlarm = DriftAlarm {
+///     sample_size: 100,
+///     threshold: 0.05,
+/// };
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DriftAlarm {
+    /// Amount of random ids that are sampled from each index set per metrics
+    /// interval.
+    pub sample_size: usize,
+    /// Fraction of sampled ids without entry between `0.0` and `1.0` above
+    /// which an alarm is raised.
+    pub threshold: f64,
+}
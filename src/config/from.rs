@@ -1,23 +1,37 @@
+use std::time::Duration;
+
 use twilight_model::{
-    channel::{message::Sticker, Channel, Message, StageInstance},
+    channel::{message::Sticker, thread::ThreadMember, Channel, Message, StageInstance},
     gateway::{
         payload::incoming::{
-            invite_create::PartialUser, ChannelPinsUpdate, GuildUpdate, MemberUpdate, MessageUpdate,
+            invite_create::PartialUser, ChannelPinsUpdate, GuildEmojisUpdate,
+            GuildIntegrationsUpdate, GuildStickersUpdate, GuildUpdate, InviteCreate, MemberUpdate,
+            MessageUpdate, StageInstanceUpdate, WebhooksUpdate,
         },
         presence::Presence,
     },
-    guild::{Emoji, Guild, GuildIntegration, Member, PartialMember, Role},
+    guild::{
+        auto_moderation::AutoModerationRule, scheduled_event::GuildScheduledEvent, Emoji, Guild,
+        GuildIntegration, Member, PartialMember, Role,
+    },
     id::{
         marker::{ChannelMarker, GuildMarker},
         Id,
     },
     user::{CurrentUser, User},
+    util::Timestamp,
     voice::VoiceState,
 };
 
 use super::{Cacheable, ReactionEvent};
 use crate::CachedArchive;
 
+/// Create a type from an [`AutoModerationRule`] reference.
+pub trait ICachedAutoModerationRule<'a>: Cacheable {
+    /// Create an instance from an [`AutoModerationRule`] reference.
+    fn from_auto_moderation_rule(rule: &'a AutoModerationRule) -> Self;
+}
+
 /// Create a type from a [`Channel`] reference.
 pub trait ICachedChannel<'a>: Cacheable {
     /// Create an instance from a [`Channel`] reference.
@@ -33,11 +47,44 @@ pub trait ICachedChannel<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - the [`ChannelPinsUpdate`] event
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn on_pins_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &ChannelPinsUpdate) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, &ChannelPinsUpdate) -> Result<(), Self::Error>> {
+        None
+    }
+
+    /// Specify a function that is called on [`ChannelUpdate`] events before
+    /// the channel is overwritten.
+    ///
+    /// The returned function receives the currently cached entry and the
+    /// updated channel so that changes to e.g. its permission overwrites can
+    /// be detected, for instance to invalidate computed permissions only when
+    /// needed. It is not called if the channel is not cached yet.
+    ///
+    /// Returns `None` by default.
+    ///
+    /// [`ChannelUpdate`]: twilight_model::gateway::payload::incoming::ChannelUpdate
+    fn on_permission_update() -> Option<fn(&CachedArchive<Self>, &Channel)> {
+        None
+    }
+
+    /// Specify a function that is called on [`WebhooksUpdate`] events.
+    ///
+    /// The event only contains the channel and guild id so webhooks are not
+    /// cached. Instead, the returned function can be used to invalidate or
+    /// re-fetch webhooks that are stored elsewhere. Webhooks stored under
+    /// [`RedisKey::ChannelWebhooks`] are deleted regardless.
+    ///
+    /// Returns `None` by default.
+    ///
+    /// [`RedisKey::ChannelWebhooks`]: crate::RedisKey::ChannelWebhooks
+    fn on_webhooks_update() -> Option<fn(&WebhooksUpdate)> {
+        None
+    }
 }
 
 /// Create a type from a [`CurrentUser`] reference.
@@ -54,6 +101,44 @@ pub trait ICachedEmoji<'a>: Cacheable {
 
 /// Create a type from a [`Guild`] reference.
 pub trait ICachedGuild<'a>: Cacheable {
+    /// Whether the ids of banned users should be tracked for each guild.
+    ///
+    /// If enabled, [`BanAdd`] and [`BanRemove`] events keep a set of banned
+    /// user ids up to date. Since the gateway does not provide the initial
+    /// list of bans, it should be backfilled through
+    /// [`RedisCache::store_guild_bans`].
+    ///
+    /// [`BanAdd`]: twilight_model::gateway::payload::incoming::BanAdd
+    /// [`BanRemove`]: twilight_model::gateway::payload::incoming::BanRemove
+    /// [`RedisCache::store_guild_bans`]: crate::RedisCache::store_guild_bans
+    const CACHE_BANS: bool = false;
+
+    /// How long the entries of a guild are retained after the bot was
+    /// removed from it.
+    ///
+    /// If set, a [`GuildDelete`] event moves the guild's entries into
+    /// tombstone keys that expire after the given duration instead of deleting
+    /// them right away. Until then, they can be restored through
+    /// [`RedisCache::restore_guild_tombstone`], e.g. when the bot is re-invited
+    /// shortly after.
+    ///
+    /// [`GuildDelete`]: twilight_model::gateway::payload::incoming::GuildDelete
+    /// [`RedisCache::restore_guild_tombstone`]: crate::RedisCache::restore_guild_tombstone
+    const TOMBSTONE_DURATION: Option<Duration> = None;
+
+    /// How long a guild may go without events before it's considered stale.
+    ///
+    /// If set, the time of the latest event of each guild is tracked. When a
+    /// [`GatewayInvalidateSession`] or [`GatewayReconnect`] event is received,
+    /// the guilds without events within the given duration are passed to
+    /// [`ICachedGuild::on_stale_guilds`]. They can also be requested through
+    /// [`RedisCache::stale_guild_ids`].
+    ///
+    /// [`GatewayInvalidateSession`]: twilight_model::gateway::event::Event::GatewayInvalidateSession
+    /// [`GatewayReconnect`]: twilight_model::gateway::event::Event::GatewayReconnect
+    /// [`RedisCache::stale_guild_ids`]: crate::RedisCache::stale_guild_ids
+    const STALENESS_THRESHOLD: Option<Duration> = None;
+
     /// Create an instance from a [`Guild`] reference.
     fn from_guild(guild: &'a Guild) -> Self;
 
@@ -67,21 +152,136 @@ pub trait ICachedGuild<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - the [`GuildUpdate`] event
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn on_guild_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+        None
+    }
+
+    /// Specify how [`GuildEmojisUpdate`] events affect the cached guild.
+    ///
+    /// The emojis themselves are stored regardless so this is only of
+    /// interest if the guild entry contains emoji data, e.g. a compact list
+    /// of emoji ids.
+    ///
+    /// If the event is not of interest, return `None`.
+    /// Otherwise, return a function that updates the currently cached guild.
+    ///
+    /// The returned function should take two arguments:
+    ///   - a mutable reference to the current entry which must be updated
+    ///     either through [`CachedArchive::update_archive`] or
+    ///     [`CachedArchive::update_by_deserializing`].
+    ///   - the [`GuildEmojisUpdate`] event containing the full list of emojis
+    ///
+    /// Returns `None` by default.
+    // Abstracting the type through a type definition would likely cause
+    // more confusion than do good so we'll allow the complexity.
+    #[allow(clippy::type_complexity)]
+    fn on_emojis_update(
+    ) -> Option<fn(&mut CachedArchive<Self>, &GuildEmojisUpdate) -> Result<(), Self::Error>> {
+        None
+    }
+
+    /// Specify how [`GuildStickersUpdate`] events affect the cached guild.
+    ///
+    /// The stickers themselves are stored regardless so this is only of
+    /// interest if the guild entry contains sticker data.
+    ///
+    /// If the event is not of interest, return `None`.
+    /// Otherwise, return a function that updates the currently cached guild.
+    ///
+    /// The returned function should take two arguments:
+    ///   - a mutable reference to the current entry which must be updated
+    ///     either through [`CachedArchive::update_archive`] or
+    ///     [`CachedArchive::update_by_deserializing`].
+    ///   - the [`GuildStickersUpdate`] event containing the full list of
+    ///     stickers
+    ///
+    /// Returns `None` by default.
+    // Abstracting the type through a type definition would likely cause
+    // more confusion than do good so we'll allow the complexity.
+    #[allow(clippy::type_complexity)]
+    fn on_stickers_update(
+    ) -> Option<fn(&mut CachedArchive<Self>, &GuildStickersUpdate) -> Result<(), Self::Error>> {
+        None
+    }
+
+    /// Specify how stale guilds are handled after the gateway session was
+    /// invalidated or reconnected.
+    ///
+    /// Events may have been missed in the meanwhile so the returned function
+    /// is called with the ids of all guilds that did not receive an event
+    /// within [`ICachedGuild::STALENESS_THRESHOLD`], least recently active
+    /// first. It can be used to re-hydrate the guilds, e.g. by requesting
+    /// their members through the gateway or fetching them from the REST API.
+    ///
+    /// Only called if [`ICachedGuild::STALENESS_THRESHOLD`] is set and there
+    /// are stale guilds.
+    ///
+    /// Returns `None` by default.
+    fn on_stale_guilds() -> Option<fn(&[Id<GuildMarker>])> {
+        None
+    }
 }
 
 /// Create a type from a [`GuildIntegration`] reference.
 pub trait ICachedIntegration<'a>: Cacheable {
     /// Create an instance from a [`GuildIntegration`] reference.
     fn from_integration(integration: &'a GuildIntegration) -> Self;
+
+    /// Specify how [`GuildIntegrationsUpdate`] events are handled.
+    ///
+    /// The event only contains the guild id so the cache can't update the
+    /// integrations on its own. Instead, it removes all cached integrations
+    /// of the guild so that none of them are stale. Afterwards, the returned
+    /// function is called and can be used to re-sync the integrations, e.g.
+    /// by fetching them from the REST API and passing them to
+    /// `RedisCache::store_guild_integrations`.
+    ///
+    /// Returns `None` by default.
+    fn on_integrations_update() -> Option<fn(&GuildIntegrationsUpdate)> {
+        None
+    }
+}
+
+/// Create a type from an [`InviteCreate`] reference.
+pub trait ICachedInvite<'a>: Cacheable {
+    /// Create an instance from an [`InviteCreate`] reference.
+    fn from_invite(invite: &'a InviteCreate) -> Self;
 }
 
 /// Create a type from a [`Member`] reference.
 pub trait ICachedMember<'a>: Cacheable {
+    /// Whether the names of members should be indexed per guild.
+    ///
+    /// If enabled, the lowercase username, global name, and nickname of
+    /// each member are stored in a sorted set so that members can be looked
+    /// up by name prefix through [`RedisCacheIter::search_members`], e.g. to
+    /// autocomplete usernames, without scanning every member entry. This
+    /// costs a lua script evaluation per stored member.
+    ///
+    /// [`RedisCacheIter::search_members`]: crate::iter::RedisCacheIter::search_members
+    const INDEX_NAMES: bool = false;
+
+    /// Whether partial members should only update members that are already
+    /// cached.
+    ///
+    /// Partial members are part of events such as `MessageCreate` or
+    /// `InteractionCreate` and lack data like the member's user. By default,
+    /// receiving one marks the user as a member of the guild, e.g. for
+    /// [`RedisCache::guild_member_ids`], even if no full member was cached
+    /// yet. If enabled, partial members of uncached members are ignored so
+    /// that guild memberships are only established by full members such as
+    /// those of `MemberAdd` events. This costs an additional round trip per
+    /// partial member.
+    ///
+    /// [`RedisCache::guild_member_ids`]: crate::RedisCache::guild_member_ids
+    const PARTIALS_UPDATE_ONLY: bool = false;
+
     /// Create an instance from a [`Member`] reference.
     fn from_member(guild_id: Id<GuildMarker>, member: &'a Member) -> Self;
 
@@ -95,11 +295,15 @@ pub trait ICachedMember<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - a reference to the [`PartialMember`] instance
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn update_via_partial(
-    ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>> {
+        None
+    }
 
     /// Specify how [`MemberUpdate`] events are handled.
     ///
@@ -111,15 +315,73 @@ pub trait ICachedMember<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - the [`MemberUpdate`] event
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn on_member_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>> {
+        None
+    }
+
+    /// Specify whether the boosters of a guild are tracked.
+    ///
+    /// If `None`, boosters are not tracked.
+    /// Otherwise, return a function that provides the time since when a
+    /// cached member boosts their guild. Boosters are then kept in a set per
+    /// guild based on the `premium_since` field of received members which can
+    /// be retrieved through [`RedisCache::guild_boosters`].
+    ///
+    /// On [`MemberUpdate`] events, the function is applied to the cached
+    /// member to skip writes if the member's boost status did not change.
+    ///
+    /// [`RedisCache::guild_boosters`]: crate::RedisCache::guild_boosters
+    #[allow(clippy::type_complexity)]
+    fn premium_since() -> Option<fn(&CachedArchive<Self>) -> Option<Timestamp>> {
+        None
+    }
 }
 
 /// Create a type from a [`Message`] reference.
 pub trait ICachedMessage<'a>: Cacheable {
+    /// Whether storing a [`Message`] should be skipped if it is already
+    /// cached with the exact same serialized bytes.
+    ///
+    /// If enabled, a hash of the serialized bytes is kept in the message's
+    /// meta entry and compared before writing. This costs an additional read
+    /// per message but avoids redundant writes e.g. when the gateway replays
+    /// events after a resume.
+    const SKIP_UNCHANGED: bool = false;
+
+    /// Maximum amount of serialized bytes of cached messages per channel.
+    ///
+    /// If set, the size of each message is tracked and whenever a new message
+    /// exceeds a channel's budget, the channel's oldest messages are evicted
+    /// until it fits again. Compared to limiting the amount of messages, this
+    /// prevents busy channels with large messages e.g. due to embeds from
+    /// taking up a disproportionate amount of memory.
+    ///
+    /// Sizes are tracked when messages are created, in-place updates do not
+    /// adjust them. Messages that were cached before the budget was set are
+    /// not accounted for and will be evicted first.
+    const CHANNEL_BYTE_BUDGET: Option<usize> = None;
+
+    /// Amount of previous versions to keep per message.
+    ///
+    /// If non-zero, the cached message is pushed onto a list before it is
+    /// modified through [`on_message_update`] and the list is trimmed to the
+    /// given length. Previous versions can be retrieved through
+    /// [`RedisCache::message_history`], e.g. to show the original content of
+    /// an edited message. The list shares the message's expire duration and
+    /// is removed alongside it.
+    ///
+    /// Defaults to `0` i.e. no history is kept.
+    ///
+    /// [`on_message_update`]: ICachedMessage::on_message_update
+    /// [`RedisCache::message_history`]: crate::RedisCache::message_history
+    const EDIT_HISTORY: usize = 0;
+
     /// Create an instance from a [`Message`] reference.
     fn from_message(message: &'a Message) -> Self;
 
@@ -133,11 +395,15 @@ pub trait ICachedMessage<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - the [`MessageUpdate`] event
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn on_message_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>> {
+        None
+    }
 
     /// Specify how reaction events are handled.
     ///
@@ -149,29 +415,138 @@ pub trait ICachedMessage<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - a [`ReactionEvent`]
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn on_reaction_event(
-    ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>> {
+        None
+    }
+
+    /// Specify how messages are ranked on their guild's starboard.
+    ///
+    /// If `None`, no starboard is maintained.
+    /// Otherwise, return a function that extracts a score e.g. the star count
+    /// from a message after it was updated through [`on_reaction_event`].
+    /// Messages for which the function returns `None` are removed from the
+    /// starboard.
+    ///
+    /// The highest ranked messages of a guild can then be retrieved through
+    /// [`RedisCache::top_messages`].
+    ///
+    /// [`on_reaction_event`]: ICachedMessage::on_reaction_event
+    /// [`RedisCache::top_messages`]: crate::RedisCache::top_messages
+    #[allow(clippy::type_complexity)]
+    fn starboard_score() -> Option<fn(&CachedArchive<Self>) -> Option<f64>> {
+        None
+    }
 }
 
 /// Create a type from a [`Presence`] reference.
 pub trait ICachedPresence<'a>: Cacheable {
+    /// Whether storing a [`Presence`] of a [`PresenceUpdate`] event should be
+    /// skipped if it is already cached with the exact same serialized bytes.
+    ///
+    /// Presence updates are sent very frequently while often not changing any
+    /// of the cached fields. If enabled, a hash of the serialized bytes is
+    /// stored alongside the entry and compared before writing. This costs an
+    /// additional read per update but avoids rewriting unchanged entries;
+    /// their expiration is still refreshed.
+    ///
+    /// [`PresenceUpdate`]: twilight_model::gateway::payload::incoming::PresenceUpdate
+    const SKIP_UNCHANGED: bool = false;
+
+    /// Whether the time of the latest presence of each member should be
+    /// stored.
+    ///
+    /// Presences silently go stale if events are missed. If enabled, their
+    /// age can be retrieved through [`RedisCache::presence_age`] to detect
+    /// and ignore ancient entries.
+    ///
+    /// [`RedisCache::presence_age`]: crate::RedisCache::presence_age
+    const TRACK_FRESHNESS: bool = false;
+
     /// Create an instance from a [`Presence`] reference.
     fn from_presence(presence: &'a Presence) -> Self;
 }
 
 /// Create a type from a [`Role`] reference.
 pub trait ICachedRole<'a>: Cacheable {
+    /// Whether the id of the guild that a role belongs to should be stored.
+    ///
+    /// If enabled, the guild id of a role can be retrieved through
+    /// [`RedisCache::role_guild_id`]. The mapping is always stored if roles
+    /// expire since it's required to clean up after them.
+    ///
+    /// [`RedisCache::role_guild_id`]: crate::RedisCache::role_guild_id
+    const STORE_GUILD_ID: bool = false;
+
     /// Create an instance from a [`Role`] reference.
     fn from_role(role: &'a Role) -> Self;
 }
 
+/// Create a type from a [`GuildScheduledEvent`] reference.
+pub trait ICachedScheduledEvent<'a>: Cacheable {
+    /// Create an instance from a [`GuildScheduledEvent`] reference.
+    fn from_scheduled_event(scheduled_event: &'a GuildScheduledEvent) -> Self;
+
+    /// Specify how [`GuildScheduledEventUserAdd`] and
+    /// [`GuildScheduledEventUserRemove`] events are handled.
+    ///
+    /// If `None` is returned, the events are ignored. Otherwise, return a
+    /// function that updates the user count of the currently cached
+    /// scheduled event in-place. If the scheduled event is not cached,
+    /// nothing happens.
+    ///
+    /// The returned function should take two arguments:
+    ///   - a mutable reference to the current entry which must be updated
+    ///     either through [`CachedArchive::update_archive`] or
+    ///     [`CachedArchive::update_by_deserializing`].
+    ///   - the change of the user count, i.e. `1` if a user was added and `-1`
+    ///     if a user was removed
+    ///
+    /// Returns `None` by default.
+    ///
+    /// [`GuildScheduledEventUserAdd`]: twilight_model::gateway::payload::incoming::GuildScheduledEventUserAdd
+    /// [`GuildScheduledEventUserRemove`]: twilight_model::gateway::payload::incoming::GuildScheduledEventUserRemove
+    // Abstracting the type through a type definition would likely cause
+    // more confusion than do good so we'll allow the complexity.
+    #[allow(clippy::type_complexity)]
+    fn on_user_count_update() -> Option<fn(&mut CachedArchive<Self>, i64) -> Result<(), Self::Error>>
+    {
+        None
+    }
+}
+
 /// Create a type from a [`StageInstance`] reference.
 pub trait ICachedStageInstance<'a>: Cacheable {
     /// Create an instance from a [`StageInstance`] reference.
     fn from_stage_instance(stage_instance: &'a StageInstance) -> Self;
+
+    /// Specify how [`StageInstanceUpdate`] events are handled.
+    ///
+    /// If `None` is returned, the cached stage instance is overwritten with
+    /// the updated one. Otherwise, return a function that updates the
+    /// currently cached stage instance in-place, e.g. its topic or privacy
+    /// level, without serializing it from scratch. If the stage instance is
+    /// not cached, it is stored as usual.
+    ///
+    /// The returned function should take two arguments:
+    ///   - a mutable reference to the current entry which must be updated
+    ///     either through [`CachedArchive::update_archive`] or
+    ///     [`CachedArchive::update_by_deserializing`].
+    ///   - the [`StageInstanceUpdate`] event
+    ///
+    /// Returns `None` by default.
+    // Abstracting the type through a type definition would likely cause
+    // more confusion than do good so we'll allow the complexity.
+    #[allow(clippy::type_complexity)]
+    fn on_stage_update(
+    ) -> Option<fn(&mut CachedArchive<Self>, &StageInstanceUpdate) -> Result<(), Self::Error>> {
+        None
+    }
 }
 
 /// Create a type from a [`Sticker`] reference.
@@ -180,6 +555,12 @@ pub trait ICachedSticker<'a>: Cacheable {
     fn from_sticker(sticker: &'a Sticker) -> Self;
 }
 
+/// Create a type from a [`ThreadMember`] reference.
+pub trait ICachedThreadMember<'a>: Cacheable {
+    /// Create an instance from a [`ThreadMember`] reference.
+    fn from_thread_member(thread_member: &'a ThreadMember) -> Self;
+}
+
 /// Create a type from a [`User`] reference.
 pub trait ICachedUser<'a>: Cacheable {
     /// Create an instance from a [`User`] reference.
@@ -195,19 +576,58 @@ pub trait ICachedUser<'a>: Cacheable {
     ///     either through [`CachedArchive::update_archive`] or
     ///     [`CachedArchive::update_by_deserializing`].
     ///   - a reference to the [`PartialUser`] instance
+    ///
+    /// Returns `None` by default.
     // Abstracting the type through a type definition would likely cause
     // more confusion than do good so we'll allow the complexity.
     #[allow(clippy::type_complexity)]
     fn update_via_partial(
-    ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>>;
+    ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>> {
+        None
+    }
 }
 
 /// Create a type from a [`VoiceState`] reference.
 pub trait ICachedVoiceState<'a>: Cacheable {
+    /// Whether the time of the latest voice state of each member should be
+    /// stored.
+    ///
+    /// If enabled, the age of a voice state can be retrieved through
+    /// [`RedisCache::voice_state_age`].
+    ///
+    /// [`RedisCache::voice_state_age`]: crate::RedisCache::voice_state_age
+    const TRACK_FRESHNESS: bool = false;
+
     /// Create an instance from a [`VoiceState`] reference.
     fn from_voice_state(
         channel_id: Id<ChannelMarker>,
         guild_id: Id<GuildMarker>,
         voice_state: &'a VoiceState,
     ) -> Self;
+
+    /// Specify how [`VoiceStateUpdate`] events are handled for voice states
+    /// that are already cached.
+    ///
+    /// If the event is not of interest, return `None` and the voice state will
+    /// be serialized from scratch through [`from_voice_state`].
+    /// Otherwise, return a function that updates the currently cached voice
+    /// state.
+    ///
+    /// The returned function should take two arguments:
+    ///   - a mutable reference to the current entry which must be updated
+    ///     either through [`CachedArchive::update_archive`] or
+    ///     [`CachedArchive::update_by_deserializing`].
+    ///   - the [`VoiceState`] of the [`VoiceStateUpdate`] event
+    ///
+    /// Returns `None` by default.
+    ///
+    /// [`VoiceStateUpdate`]: twilight_model::gateway::payload::incoming::VoiceStateUpdate
+    /// [`from_voice_state`]: ICachedVoiceState::from_voice_state
+    // Abstracting the type through a type definition would likely cause
+    // more confusion than do good so we'll allow the complexity.
+    #[allow(clippy::type_complexity)]
+    fn on_voice_state_update(
+    ) -> Option<fn(&mut CachedArchive<Self>, &VoiceState) -> Result<(), Self::Error>> {
+        None
+    }
 }
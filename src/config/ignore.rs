@@ -5,14 +5,12 @@ use rkyv::{
     Archive, Place,
 };
 use twilight_model::{
-    channel::{message::Sticker, Channel, Message, StageInstance},
-    gateway::{
-        payload::incoming::{
-            invite_create::PartialUser, ChannelPinsUpdate, GuildUpdate, MemberUpdate, MessageUpdate,
-        },
-        presence::Presence,
+    channel::{message::Sticker, thread::ThreadMember, Channel, Message, StageInstance},
+    gateway::{payload::incoming::InviteCreate, presence::Presence},
+    guild::{
+        auto_moderation::AutoModerationRule, scheduled_event::GuildScheduledEvent, Emoji, Guild,
+        GuildIntegration, Member, Role,
     },
-    guild::{Emoji, Guild, GuildIntegration, Member, PartialMember, Role},
     id::{
         marker::{ChannelMarker, GuildMarker},
         Id,
@@ -21,14 +19,11 @@ use twilight_model::{
     voice::VoiceState,
 };
 
-use super::ReactionEvent;
-use crate::{
-    config::{
-        Cacheable, ICachedChannel, ICachedCurrentUser, ICachedEmoji, ICachedGuild,
-        ICachedIntegration, ICachedMember, ICachedMessage, ICachedPresence, ICachedRole,
-        ICachedStageInstance, ICachedSticker, ICachedUser, ICachedVoiceState,
-    },
-    CachedArchive,
+use crate::config::{
+    Cacheable, ICachedAutoModerationRule, ICachedChannel, ICachedCurrentUser, ICachedEmoji,
+    ICachedGuild, ICachedIntegration, ICachedInvite, ICachedMember, ICachedMessage,
+    ICachedPresence, ICachedRole, ICachedScheduledEvent, ICachedStageInstance, ICachedSticker,
+    ICachedThreadMember, ICachedUser, ICachedVoiceState,
 };
 
 /// Struct to indicate that a type should not be cached.
@@ -37,14 +32,15 @@ use crate::{
 /// [`CacheConfig`](crate::config::CacheConfig).
 pub struct Ignore;
 
-impl ICachedChannel<'_> for Ignore {
-    fn from_channel(_: &'_ Channel) -> Self {
+impl ICachedAutoModerationRule<'_> for Ignore {
+    fn from_auto_moderation_rule(_: &'_ AutoModerationRule) -> Self {
         Self
     }
+}
 
-    fn on_pins_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &ChannelPinsUpdate) -> Result<(), Self::Error>> {
-        None
+impl ICachedChannel<'_> for Ignore {
+    fn from_channel(_: &'_ Channel) -> Self {
+        Self
     }
 }
 
@@ -66,14 +62,15 @@ impl ICachedIntegration<'_> for Ignore {
     }
 }
 
-impl ICachedGuild<'_> for Ignore {
-    fn from_guild(_: &'_ Guild) -> Self {
+impl ICachedInvite<'_> for Ignore {
+    fn from_invite(_: &'_ InviteCreate) -> Self {
         Self
     }
+}
 
-    fn on_guild_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
-        None
+impl ICachedGuild<'_> for Ignore {
+    fn from_guild(_: &'_ Guild) -> Self {
+        Self
     }
 }
 
@@ -81,32 +78,12 @@ impl ICachedMember<'_> for Ignore {
     fn from_member(_: Id<GuildMarker>, _: &'_ Member) -> Self {
         Self
     }
-
-    fn update_via_partial(
-    ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>> {
-        None
-    }
-
-    fn on_member_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>> {
-        None
-    }
 }
 
 impl ICachedMessage<'_> for Ignore {
     fn from_message(_: &'_ Message) -> Self {
         Self
     }
-
-    fn on_message_update(
-    ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>> {
-        None
-    }
-
-    fn on_reaction_event(
-    ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>> {
-        None
-    }
 }
 
 impl ICachedPresence<'_> for Ignore {
@@ -121,6 +98,12 @@ impl ICachedRole<'_> for Ignore {
     }
 }
 
+impl ICachedScheduledEvent<'_> for Ignore {
+    fn from_scheduled_event(_: &'_ GuildScheduledEvent) -> Self {
+        Self
+    }
+}
+
 impl ICachedStageInstance<'_> for Ignore {
     fn from_stage_instance(_: &StageInstance) -> Self {
         Self
@@ -133,14 +116,15 @@ impl ICachedSticker<'_> for Ignore {
     }
 }
 
-impl ICachedUser<'_> for Ignore {
-    fn from_user(_: &User) -> Self {
+impl ICachedThreadMember<'_> for Ignore {
+    fn from_thread_member(_: &'_ ThreadMember) -> Self {
         Self
     }
+}
 
-    fn update_via_partial(
-    ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>> {
-        None
+impl ICachedUser<'_> for Ignore {
+    fn from_user(_: &User) -> Self {
+        Self
     }
 }
 
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+use super::EntityKind;
+
+/// Bounded in-process layer in front of redis for frequently read entries.
+///
+/// See [`CacheConfig::LOCAL_CACHE`](crate::config::CacheConfig::LOCAL_CACHE).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use redlight::config::{EntityKind, LocalCache};
+///
+/// const LOCAL_CACHE: LocalCache = LocalCache {
+///     capacity: 64,
+///     lifetime: Duration::from_secs(30),
+///     kinds: &[EntityKind::CurrentUser, EntityKind::Guild],
+/// };
+/// ```
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "local_cache")))]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LocalCache {
+    /// Maximum amount of entries that are held in memory.
+    ///
+    /// Entries are spread across up to 16 shards that split the capacity
+    /// between them. Once a shard is full, its least recently used entry is
+    /// evicted.
+    pub capacity: usize,
+    /// Duration after which an entry is fetched from redis again.
+    ///
+    /// Bounds how long changes are missed that did not go through this
+    /// process' pipelines, e.g. writes of other processes or expirations.
+    pub lifetime: Duration,
+    /// Entity kinds whose entries are held in memory.
+    pub kinds: &'static [EntityKind],
+}
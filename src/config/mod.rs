@@ -1,22 +1,42 @@
 mod cacheable;
 mod checked;
+mod drift;
 mod from;
+mod overrides;
+mod pressure;
+mod rate_limit;
 mod reaction_event;
+mod scratch;
+mod writer;
+
+#[cfg(feature = "local_cache")]
+mod local_cache;
 
 // pub but hidden for `cargo rdme`
 #[doc(hidden)]
 pub mod ignore;
 
+#[cfg(feature = "serde-mirror")]
+pub use self::cacheable::to_json;
+#[cfg(feature = "local_cache")]
+pub use self::local_cache::LocalCache;
 pub use self::{
-    cacheable::{Cacheable, SerializeMany},
+    cacheable::{Cacheable, SerializeMany, SerializeWithArena, VERSION_LIFETIME},
     checked::CheckedArchive,
+    drift::DriftAlarm,
     from::{
-        ICachedChannel, ICachedCurrentUser, ICachedEmoji, ICachedGuild, ICachedIntegration,
-        ICachedMember, ICachedMessage, ICachedPresence, ICachedRole, ICachedStageInstance,
-        ICachedSticker, ICachedUser, ICachedVoiceState,
+        ICachedAutoModerationRule, ICachedChannel, ICachedCurrentUser, ICachedEmoji, ICachedGuild,
+        ICachedIntegration, ICachedInvite, ICachedMember, ICachedMessage, ICachedPresence,
+        ICachedRole, ICachedScheduledEvent, ICachedStageInstance, ICachedSticker,
+        ICachedThreadMember, ICachedUser, ICachedVoiceState,
     },
     ignore::Ignore,
-    reaction_event::ReactionEvent,
+    overrides::{ConfigOverrides, EntityKind},
+    pressure::PressureThresholds,
+    rate_limit::{RateLimit, RateLimitBehavior, RateLimitedOperation, RateLimits},
+    reaction_event::{emoji_key, ReactionEvent},
+    scratch::archived_size,
+    writer::{WriterConflictBehavior, WriterLease},
 };
 
 /// Configuration for a [`RedisCache`](crate::RedisCache).
@@ -26,21 +46,29 @@ pub use self::{
 ///
 /// If an associated type should not be cached, use [`Ignore`].
 ///
+/// Associated types can't have defaults so every type must be specified.
+/// Adding a new one, as done for `AutoModerationRule`, `Invite`,
+/// `ScheduledEvent`, and `ThreadMember`, is thus a breaking change which
+/// requires existing configurations to specify it, e.g. as [`Ignore`].
+///
 /// # Example
 ///
 /// ```
 /// # use std::{time::Duration};
-/// # use redlight::{CachedArchive, config::ReactionEvent};
 /// # use rkyv::{Archive, Serialize};
-/// # use twilight_model::{
-/// #     channel::{message::Message, Channel},
-/// #     gateway::payload::incoming::{ChannelPinsUpdate, MessageUpdate}
-/// # };
-/// use redlight::config::{CacheConfig, Cacheable, ICachedChannel, ICachedMessage, Ignore};
-/// use redlight::rkyv_util::{id::IdRkyv, util::BitflagsRkyv};
-/// use rkyv::with::{Map, InlineAsBox};
-/// use rkyv::rancor::Fallible;
-/// use twilight_model::{channel::ChannelFlags, id::{Id, marker::ChannelMarker}};
+/// # use twilight_model::channel::{message::Message, Channel};
+/// use redlight::{
+///     config::{CacheConfig, Cacheable, ICachedChannel, ICachedMessage, Ignore},
+///     rkyv_util::{id::IdRkyv, util::BitflagsRkyv},
+/// };
+/// use rkyv::{
+///     rancor::Fallible,
+///     with::{InlineAsBox, Map},
+/// };
+/// use twilight_model::{
+///     channel::ChannelFlags,
+///     id::{marker::ChannelMarker, Id},
+/// };
 ///
 /// struct Config;
 ///
@@ -49,17 +77,21 @@ pub use self::{
 ///     // Only if the `metrics` feature is enabled
 ///     const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(30);
 ///
+///     type AutoModerationRule<'a> = Ignore;
 ///     type Channel<'a> = CachedChannel; // <-
 ///     type CurrentUser<'a> = Ignore;
 ///     type Emoji<'a> = Ignore;
 ///     type Guild<'a> = Ignore;
 ///     type Integration<'a> = Ignore;
+///     type Invite<'a> = Ignore;
 ///     type Member<'a> = Ignore;
 ///     type Message<'a> = CachedMessage<'a>; // <-
 ///     type Presence<'a> = Ignore;
 ///     type Role<'a> = Ignore;
+///     type ScheduledEvent<'a> = Ignore;
 ///     type StageInstance<'a> = Ignore;
 ///     type Sticker<'a> = Ignore;
+///     type ThreadMember<'a> = Ignore;
 ///     type User<'a> = Ignore;
 ///     type VoiceState<'a> = Ignore;
 /// }
@@ -77,8 +109,6 @@ pub use self::{
 ///     // ...
 ///     # */
 ///     # fn from_channel(_: &'a Channel) -> Self { unimplemented!() }
-///     # fn on_pins_update() -> Option<fn(&mut CachedArchive<Self>, &ChannelPinsUpdate)
-///     #     -> Result<(), Self::Error>> { None }
 /// }
 ///
 /// impl Cacheable for CachedChannel {
@@ -105,10 +135,6 @@ pub use self::{
 ///     // ...
 ///     # */
 ///     # fn from_message(_: &'a Message) -> Self { unimplemented!() }
-///     # fn on_message_update() -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate)
-///     #     -> Result<(), Self::Error>> { None }
-///     # fn on_reaction_event() -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>)
-///     #     -> Result<(), Self::Error>> { None }
 /// }
 ///
 /// impl Cacheable for CachedMessage<'_> {
@@ -128,20 +154,204 @@ pub trait CacheConfig: Send + Sync + 'static {
     #[cfg(feature = "metrics")]
     /// The interval duration until metrics are updated.
     ///
+    /// If multiple instances share the same redis, only one of them reports
+    /// collection sizes at a time. Leadership is handed over once the leader
+    /// misses a few intervals.
+    ///
     /// The suggested duration is 30 seconds.
     const METRICS_INTERVAL_DURATION: std::time::Duration;
 
+    /// Rate limits of expensive operations such as iterating over full
+    /// collections.
+    ///
+    /// Protects redis from being saturated by e.g. a buggy dashboard that
+    /// iterates over all users in a loop.
+    ///
+    /// Defaults to [`RateLimits::NONE`].
+    const RATE_LIMITS: RateLimits = RateLimits::NONE;
+
+    /// Thresholds that decide when redis is considered to be struggling.
+    ///
+    /// If set, [`RedisCache::update_with_pressure`] returns
+    /// [`Pressure::High`] once any value of [`RedisCache::pressure`] exceeds
+    /// its threshold. Gateway consumers can then shed less important events
+    /// such as presence updates instead of queueing them.
+    ///
+    /// Defaults to `None` i.e. pressure is always reported as
+    /// [`Pressure::Normal`].
+    ///
+    /// [`RedisCache::update_with_pressure`]: crate::RedisCache::update_with_pressure
+    /// [`RedisCache::pressure`]: crate::RedisCache::pressure
+    /// [`Pressure::High`]: crate::Pressure::High
+    /// [`Pressure::Normal`]: crate::Pressure::Normal
+    const PRESSURE_THRESHOLDS: Option<PressureThresholds> = None;
+
+    /// Whether the cache runs in shadow mode.
+    ///
+    /// In shadow mode, [`RedisCache::update`] performs all serialization and
+    /// builds pipelines as usual but discards all writes before sending them
+    /// to redis. Reads that are required to build the pipelines are still
+    /// performed. This allows load-testing a new configuration in production
+    /// without affecting the live cache.
+    ///
+    /// Shadow caches also don't claim [`CacheConfig::WRITER_LEASE`] and don't
+    /// skip events in [`RedisCache::update_with_meta`] since both the lease
+    /// and the tracked sequences belong to the live cache.
+    ///
+    /// With the `metrics` feature, the amount of discarded bytes is recorded
+    /// in the `shadow_write_bytes` counter and the processing time of events
+    /// is recorded in the `update_duration` histogram as usual.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`RedisCache::update`]: crate::RedisCache::update
+    /// [`RedisCache::update_with_meta`]: crate::RedisCache::update_with_meta
+    const SHADOW: bool = false;
+
+    /// Sampling rate of getter hit and miss counters.
+    ///
+    /// If non-zero, every n-th read through getters such as
+    /// [`RedisCache::user`] increments a counter of hits or misses for its
+    /// entity kind by n. The counters are stored in redis so they are shared
+    /// across instances and can be inspected through
+    /// [`RedisCacheStats::read_counts`] to decide which entity types are
+    /// worth caching.
+    ///
+    /// Defaults to `0` i.e. reads are not counted.
+    ///
+    /// [`RedisCache::user`]: crate::RedisCache::user
+    /// [`RedisCacheStats::read_counts`]: crate::stats::RedisCacheStats::read_counts
+    const READ_COUNTER_SAMPLING: u32 = 0;
+
+    /// How long the previous version of an overwritten entity is kept.
+    ///
+    /// If set, writing an entity such as a user or a member first copies the
+    /// currently stored entry to a separate key which expires after the given
+    /// duration. The previous version can then be retrieved through
+    /// [`RedisCache::previous`], e.g. to see what an update changed or to
+    /// debug unexpected overwrites.
+    ///
+    /// Note that every write of an entity requires an additional command and
+    /// that recently updated entities take up twice the memory.
+    ///
+    /// Defaults to `None` i.e. previous versions are not kept.
+    ///
+    /// [`RedisCache::previous`]: crate::RedisCache::previous
+    const PREVIOUS_VERSION_LIFETIME: Option<std::time::Duration> = None;
+
+    /// Lease that detects multiple processes writing events of the same
+    /// shard, e.g. after a failed deploy left an old process running.
+    ///
+    /// If set, [`RedisCache::update_with_meta`] claims the shard of each event
+    /// for this process through a heartbeat key in redis. If the key is held
+    /// by another process, an error is logged and, with the `metrics` feature,
+    /// the `duplicate_writers` counter is incremented. Depending on the
+    /// lease's [`WriterConflictBehavior`], the event is then applied anyway or
+    /// rejected.
+    ///
+    /// The lease is released once the holding process did not write events
+    /// of the shard for the lease's duration. Events without sequence are not
+    /// checked.
+    ///
+    /// Defaults to `None` i.e. writers are not tracked.
+    ///
+    /// [`RedisCache::update_with_meta`]: crate::RedisCache::update_with_meta
+    const WRITER_LEASE: Option<WriterLease> = None;
+
+    /// Whether written and deleted keys are published to other processes.
+    ///
+    /// If enabled, every entry that is stored or deleted while processing
+    /// events additionally publishes its key so that processes holding
+    /// in-memory copies can drop them, see
+    /// [`RedisCache::subscribe_invalidations`].
+    ///
+    /// Note that every write and delete requires an additional command.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`RedisCache::subscribe_invalidations`]: crate::RedisCache::subscribe_invalidations
+    const PUBLISH_INVALIDATIONS: bool = false;
+
+    /// Prefix of all keys written and read by the cache.
+    ///
+    /// Allows multiple bots to share a redis instance without their keys
+    /// colliding. If set, keys are rendered as `{prefix}:{key}`, e.g.
+    /// `mybot:USER:123`, and expire events of keys outside of the prefix are
+    /// ignored. Pub/sub channels of [`RedisCache::subscribe`] are not
+    /// prefixed.
+    ///
+    /// Each cache renders keys within its own prefix so caches with
+    /// different prefixes may be used within the same process. The prefix
+    /// may be at most 32 bytes long, otherwise creating the cache fails with
+    /// [`CacheError::InvalidKeyPrefix`].
+    ///
+    /// Defaults to `""` i.e. keys are not prefixed.
+    ///
+    /// [`RedisCache::subscribe`]: crate::RedisCache::subscribe
+    /// [`CacheError::InvalidKeyPrefix`]: crate::error::CacheError::InvalidKeyPrefix
+    const KEY_PREFIX: &'static str = "";
+
+    /// Whether the status of presences is tracked on its own.
+    ///
+    /// If enabled, a single byte of each user's status is kept in a hash per
+    /// guild which can be read through [`RedisCache::presence_status`]. Users
+    /// are removed from the hash once they go offline or leave the guild.
+    ///
+    /// This is independent of [`CacheConfig::Presence`] so bots that only
+    /// need to know whether users are online, idle, or do not disturb can
+    /// set the presence type to [`Ignore`] and avoid storing full presences
+    /// including their activities.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// [`RedisCache::presence_status`]: crate::RedisCache::presence_status
+    const PRESENCE_STATUS: bool = false;
+
+    #[cfg(feature = "metrics")]
+    /// Alarm for index sets that drifted apart from their entries.
+    ///
+    /// If set, the metrics loop samples random ids of index sets such as all
+    /// user ids each interval and checks whether their entries still exist.
+    /// The fraction of ids without entry is reported in the `index_drift`
+    /// gauge per entity kind. Once it exceeds the alarm's threshold, a
+    /// warning is logged and the `index_drift_alarms` counter is incremented,
+    /// hinting at a bug in cascading deletes or expire bookkeeping.
+    ///
+    /// Defaults to `None` i.e. index sets are not checked.
+    const DRIFT_ALARM: Option<DriftAlarm> = None;
+
+    #[cfg(feature = "local_cache")]
+    /// In-process layer that keeps recently read entries of hot entity kinds
+    /// such as the current user or the bot's own guilds in memory.
+    ///
+    /// If set, getters such as [`RedisCache::current_user`] or
+    /// [`RedisCache::guild`] first look up the entry in a bounded LRU and only
+    /// query redis on a miss. Entries are dropped as soon as this process
+    /// writes or deletes their key, and after the layer's
+    /// [`lifetime`](LocalCache::lifetime) to also pick up changes made
+    /// elsewhere.
+    ///
+    /// Defaults to `None` i.e. every read goes to redis.
+    ///
+    /// [`RedisCache::current_user`]: crate::RedisCache::current_user
+    /// [`RedisCache::guild`]: crate::RedisCache::guild
+    const LOCAL_CACHE: Option<LocalCache> = None;
+
+    type AutoModerationRule<'a>: ICachedAutoModerationRule<'a>;
     type Channel<'a>: ICachedChannel<'a>;
     type CurrentUser<'a>: ICachedCurrentUser<'a>;
     type Emoji<'a>: ICachedEmoji<'a>;
     type Guild<'a>: ICachedGuild<'a>;
     type Integration<'a>: ICachedIntegration<'a>;
+    type Invite<'a>: ICachedInvite<'a>;
     type Member<'a>: ICachedMember<'a>;
     type Message<'a>: ICachedMessage<'a>;
     type Presence<'a>: ICachedPresence<'a>;
     type Role<'a>: ICachedRole<'a>;
+    type ScheduledEvent<'a>: ICachedScheduledEvent<'a>;
     type StageInstance<'a>: ICachedStageInstance<'a>;
     type Sticker<'a>: ICachedSticker<'a>;
+    type ThreadMember<'a>: ICachedThreadMember<'a>;
     type User<'a>: ICachedUser<'a>;
     type VoiceState<'a>: ICachedVoiceState<'a>;
 }
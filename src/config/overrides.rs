@@ -0,0 +1,278 @@
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    time::Duration,
+};
+
+use crate::error::OverrideError;
+
+/// Prefix of environment variables that override expire durations, followed
+/// by the uppercase name of an [`EntityKind`], e.g. `REDLIGHT_EXPIRE_MEMBER`.
+const EXPIRE_VAR_PREFIX: &str = "REDLIGHT_EXPIRE_";
+
+/// Environment variable containing comma-separated names of disabled
+/// [`EntityKind`]s, e.g. `member,presence`.
+const DISABLE_VAR: &str = "REDLIGHT_DISABLE";
+
+/// The kinds of entities that are cached through a [`CacheConfig`].
+///
+/// [`CacheConfig`]: crate::config::CacheConfig
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EntityKind {
+    AutoModerationRule,
+    Channel,
+    CurrentUser,
+    Emoji,
+    Guild,
+    Integration,
+    Invite,
+    Member,
+    Message,
+    Presence,
+    Role,
+    ScheduledEvent,
+    StageInstance,
+    Sticker,
+    ThreadMember,
+    User,
+    VoiceState,
+}
+
+impl EntityKind {
+    /// All entity kinds.
+    pub const ALL: [Self; 17] = [
+        Self::AutoModerationRule,
+        Self::Channel,
+        Self::CurrentUser,
+        Self::Emoji,
+        Self::Guild,
+        Self::Integration,
+        Self::Invite,
+        Self::Member,
+        Self::Message,
+        Self::Presence,
+        Self::Role,
+        Self::ScheduledEvent,
+        Self::StageInstance,
+        Self::Sticker,
+        Self::ThreadMember,
+        Self::User,
+        Self::VoiceState,
+    ];
+
+    /// The snake case name of the kind, e.g. `"stage_instance"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::AutoModerationRule => "auto_moderation_rule",
+            Self::Channel => "channel",
+            Self::CurrentUser => "current_user",
+            Self::Emoji => "emoji",
+            Self::Guild => "guild",
+            Self::Integration => "integration",
+            Self::Invite => "invite",
+            Self::Member => "member",
+            Self::Message => "message",
+            Self::Presence => "presence",
+            Self::Role => "role",
+            Self::ScheduledEvent => "scheduled_event",
+            Self::StageInstance => "stage_instance",
+            Self::Sticker => "sticker",
+            Self::ThreadMember => "thread_member",
+            Self::User => "user",
+            Self::VoiceState => "voice_state",
+        }
+    }
+
+    /// Parse a kind from its name, ignoring ASCII case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Runtime overrides of a [`CacheConfig`].
+///
+/// The type-level config decides which entities can be cached and how.
+/// Overrides are applied on top of it so that caching behavior can be tuned
+/// per deployment without recompiling.
+///
+/// Overrides are registered through [`RedisCache::with_overrides`] and
+/// affect all entries that are written afterwards.
+///
+/// [`CacheConfig::KEY_PREFIX`] can not be overridden. By the time overrides
+/// are registered, the cache already listens to expire events within its key
+/// prefix so a different prefix at runtime would leave the listener handling
+/// the keys of the old one.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use redlight::config::{ConfigOverrides, EntityKind};
+///
+/// let overrides = ConfigOverrides::new()
+///     .expire(EntityKind::Member, Duration::from_secs(3600))
+///     .disable(EntityKind::Presence);
+/// ```
+///
+/// [`CacheConfig`]: crate::config::CacheConfig
+/// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
+/// [`RedisCache::with_overrides`]: crate::RedisCache::with_overrides
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigOverrides {
+    expire: HashMap<EntityKind, Duration>,
+    disabled: HashSet<EntityKind>,
+}
+
+impl ConfigOverrides {
+    /// Create empty overrides that keep the type-level config as is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read overrides from environment variables.
+    ///
+    /// - `REDLIGHT_EXPIRE_<KIND>` overrides the expire duration in seconds of
+    ///   an entity kind, e.g. `REDLIGHT_EXPIRE_MEMBER=3600`.
+    /// - `REDLIGHT_DISABLE` contains comma-separated names of entity kinds that
+    ///   won't be cached, e.g. `REDLIGHT_DISABLE=presence,voice_state`.
+    ///
+    /// Kind names are those of [`EntityKind::name`], ignoring ASCII case.
+    pub fn from_env() -> Result<Self, OverrideError> {
+        Self::from_vars(env::vars())
+    }
+
+    fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Result<Self, OverrideError> {
+        let mut overrides = Self::new();
+
+        for (name, value) in vars {
+            if name == DISABLE_VAR {
+                let names = value.split(',').map(str::trim).filter(|s| !s.is_empty());
+
+                for kind_name in names {
+                    let Some(kind) = EntityKind::from_name(kind_name) else {
+                        return Err(OverrideError {
+                            name,
+                            value: value.clone(),
+                        });
+                    };
+
+                    overrides.disabled.insert(kind);
+                }
+            } else if let Some(kind_name) = name.strip_prefix(EXPIRE_VAR_PREFIX) {
+                let kind = EntityKind::from_name(kind_name);
+                let secs = value.trim().parse().ok();
+
+                let (Some(kind), Some(secs)) = (kind, secs) else {
+                    return Err(OverrideError { name, value });
+                };
+
+                overrides.expire.insert(kind, Duration::from_secs(secs));
+            }
+        }
+
+        Ok(overrides)
+    }
+
+    /// Override the expire duration of entries of the given kind.
+    ///
+    /// Only takes effect if [`Cacheable::expire`] of the kind's type is
+    /// `Some` since bookkeeping for expired entries is only set up in that
+    /// case.
+    ///
+    /// [`Cacheable::expire`]: crate::config::Cacheable::expire
+    #[must_use]
+    pub fn expire(mut self, kind: EntityKind, duration: Duration) -> Self {
+        self.expire.insert(kind, duration);
+
+        self
+    }
+
+    /// Don't cache entries of the given kind even if the type-level config
+    /// would.
+    ///
+    /// Collections of ids, e.g. the ids of a guild's members, are still kept
+    /// up to date.
+    #[must_use]
+    pub fn disable(mut self, kind: EntityKind) -> Self {
+        self.disabled.insert(kind);
+
+        self
+    }
+
+    /// Whether entries of the given kind are not cached.
+    pub fn is_disabled(&self, kind: EntityKind) -> bool {
+        self.disabled.contains(&kind)
+    }
+
+    /// The expire duration that applies to entries of the given kind.
+    pub(crate) fn apply_expire(
+        &self,
+        kind: Option<EntityKind>,
+        expire: Option<Duration>,
+    ) -> Option<Duration> {
+        match (kind, expire) {
+            (Some(kind), Some(expire)) => Some(self.expire.get(&kind).copied().unwrap_or(expire)),
+            (None, _) | (_, None) => expire,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ConfigOverrides, EntityKind};
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_vars() {
+        let overrides = ConfigOverrides::from_vars(vars(&[
+            ("REDLIGHT_EXPIRE_MEMBER", "3600"),
+            ("REDLIGHT_EXPIRE_voice_state", " 60 "),
+            ("REDLIGHT_DISABLE", "presence, STAGE_INSTANCE"),
+            ("UNRELATED", "value"),
+        ]))
+        .unwrap();
+
+        let expected = ConfigOverrides::new()
+            .expire(EntityKind::Member, Duration::from_hours(1))
+            .expire(EntityKind::VoiceState, Duration::from_mins(1))
+            .disable(EntityKind::Presence)
+            .disable(EntityKind::StageInstance);
+
+        assert_eq!(overrides, expected);
+    }
+
+    #[test]
+    fn reject_invalid_vars() {
+        let unknown_kind = vars(&[("REDLIGHT_EXPIRE_THREAD", "60")]);
+        assert!(ConfigOverrides::from_vars(unknown_kind).is_err());
+
+        let invalid_secs = vars(&[("REDLIGHT_EXPIRE_USER", "1h")]);
+        assert!(ConfigOverrides::from_vars(invalid_secs).is_err());
+
+        let unknown_disabled = vars(&[("REDLIGHT_DISABLE", "member,thread")]);
+        assert!(ConfigOverrides::from_vars(unknown_disabled).is_err());
+    }
+
+    #[test]
+    fn apply_expire_only_if_expiring() {
+        let overrides = ConfigOverrides::new().expire(EntityKind::Role, Duration::from_secs(5));
+
+        let expire = overrides.apply_expire(Some(EntityKind::Role), Some(Duration::from_mins(1)));
+        assert_eq!(expire, Some(Duration::from_secs(5)));
+
+        assert_eq!(overrides.apply_expire(Some(EntityKind::Role), None), None);
+
+        let expire = overrides.apply_expire(None, Some(Duration::from_mins(1)));
+        assert_eq!(expire, Some(Duration::from_mins(1)));
+    }
+}
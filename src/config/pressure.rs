@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+/// Limits above which the write path of the cache is considered under high
+/// pressure.
+///
+/// See [`CacheConfig::PRESSURE_THRESHOLDS`](crate::config::CacheConfig::PRESSURE_THRESHOLDS).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use redlight::config::PressureThresholds;
+///
+/// const THRESHOLDS: PressureThresholds = PressureThresholds {
+///     pending_commands: 10_000,
+///     pool_wait: Duration::from_millis(50),
+///     error_rate: 0.05,
+/// };
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PressureThresholds {
+    /// Amount of commands that were sent to redis but not yet answered.
+    pub pending_commands: usize,
+    /// Recent average time spent waiting for a connection of the pool.
+    pub pool_wait: Duration,
+    /// Recent fraction of failed updates between `0.0` and `1.0`.
+    pub error_rate: f64,
+}
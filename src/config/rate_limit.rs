@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+/// Classes of expensive operations that can be rate limited.
+///
+/// See [`CacheConfig::RATE_LIMITS`](crate::config::CacheConfig::RATE_LIMITS).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RateLimitedOperation {
+    /// Iterating over a full collection, e.g. [`RedisCacheIter::users`].
+    ///
+    /// [`RedisCacheIter::users`]: crate::iter::RedisCacheIter::users
+    Iteration,
+    /// Sampling entries of a collection, e.g. [`RedisCacheStats::user_ttls`].
+    ///
+    /// [`RedisCacheStats::user_ttls`]: crate::stats::RedisCacheStats::user_ttls
+    StatsScan,
+    /// A batch of keys processed by a maintenance operation, e.g.
+    /// [`Maintenance::prune_orphans`].
+    ///
+    /// [`Maintenance::prune_orphans`]: crate::maintenance::Maintenance::prune_orphans
+    Maintenance,
+}
+
+/// What happens when a rate limited operation has no tokens left.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RateLimitBehavior {
+    /// Wait until a token becomes available.
+    #[default]
+    Queue,
+    /// Fail with [`CacheError::RateLimited`].
+    ///
+    /// [`CacheError::RateLimited`]: crate::error::CacheError::RateLimited
+    Error,
+}
+
+/// Token bucket limiting how often an operation class may be performed.
+///
+/// The bucket starts full and regains one token per refill interval up to its
+/// capacity. Each operation consumes one token.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum amount of tokens i.e. the allowed burst size.
+    pub capacity: u32,
+    /// Duration until a token is regained.
+    pub refill_interval: Duration,
+    /// What happens when no token is left.
+    pub behavior: RateLimitBehavior,
+}
+
+impl RateLimit {
+    /// Create a new [`RateLimit`] that queues operations once no token is
+    /// left.
+    pub const fn new(capacity: u32, refill_interval: Duration) -> Self {
+        Self {
+            capacity,
+            refill_interval,
+            behavior: RateLimitBehavior::Queue,
+        }
+    }
+
+    /// Fail operations with [`CacheError::RateLimited`] instead of queueing
+    /// them once no token is left.
+    ///
+    /// [`CacheError::RateLimited`]: crate::error::CacheError::RateLimited
+    #[must_use]
+    pub const fn erroring(mut self) -> Self {
+        self.behavior = RateLimitBehavior::Error;
+
+        self
+    }
+}
+
+/// Rate limits for each [`RateLimitedOperation`].
+///
+/// Operations without a rate limit are never limited.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use redlight::config::{RateLimit, RateLimits};
+///
+/// const RATE_LIMITS: RateLimits = RateLimits {
+///     // Allow bursts of 5 iterations and one more every 10 seconds
+///     iteration: Some(RateLimit::new(5, Duration::from_secs(10))),
+///     // Error if stats are sampled more than once a minute
+///     stats_scan: Some(RateLimit::new(1, Duration::from_secs(60)).erroring()),
+///     // Process at most 10 maintenance batches per second
+///     maintenance: Some(RateLimit::new(10, Duration::from_millis(100))),
+/// };
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Limit for [`RateLimitedOperation::Iteration`].
+    pub iteration: Option<RateLimit>,
+    /// Limit for [`RateLimitedOperation::StatsScan`].
+    pub stats_scan: Option<RateLimit>,
+    /// Limit for [`RateLimitedOperation::Maintenance`].
+    pub maintenance: Option<RateLimit>,
+}
+
+impl RateLimits {
+    /// No operation is rate limited.
+    pub const NONE: Self = Self {
+        iteration: None,
+        stats_scan: None,
+        maintenance: None,
+    };
+
+    /// The rate limit of the given operation class.
+    pub const fn get(&self, operation: RateLimitedOperation) -> Option<RateLimit> {
+        match operation {
+            RateLimitedOperation::Iteration => self.iteration,
+            RateLimitedOperation::StatsScan => self.stats_scan,
+            RateLimitedOperation::Maintenance => self.maintenance,
+        }
+    }
+}
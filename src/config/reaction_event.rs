@@ -1,9 +1,12 @@
+use std::borrow::Cow;
+
 use twilight_model::{
+    channel::message::ReactionType,
     gateway::payload::incoming::{
         ReactionAdd, ReactionRemove, ReactionRemoveAll, ReactionRemoveEmoji,
     },
     id::{
-        marker::{ChannelMarker, MessageMarker},
+        marker::{ChannelMarker, GuildMarker, MessageMarker, UserMarker},
         Id,
     },
 };
@@ -21,7 +24,7 @@ pub enum ReactionEvent<'a> {
     RemoveEmoji(&'a ReactionRemoveEmoji),
 }
 
-impl ReactionEvent<'_> {
+impl<'a> ReactionEvent<'a> {
     /// The message id of the [`ReactionEvent`].
     pub fn message_id(self) -> Id<MessageMarker> {
         match self {
@@ -41,4 +44,137 @@ impl ReactionEvent<'_> {
             ReactionEvent::RemoveEmoji(event) => event.channel_id,
         }
     }
+
+    /// The guild id of the [`ReactionEvent`].
+    pub fn guild_id(self) -> Option<Id<GuildMarker>> {
+        match self {
+            ReactionEvent::Add(event) => event.guild_id,
+            ReactionEvent::Remove(event) => event.guild_id,
+            ReactionEvent::RemoveAll(event) => event.guild_id,
+            ReactionEvent::RemoveEmoji(event) => Some(event.guild_id),
+        }
+    }
+
+    /// The id of the user that added or removed a reaction.
+    ///
+    /// Returns `None` if reactions of multiple users were removed.
+    pub fn user_id(self) -> Option<Id<UserMarker>> {
+        match self {
+            ReactionEvent::Add(event) => Some(event.user_id),
+            ReactionEvent::Remove(event) => Some(event.user_id),
+            ReactionEvent::RemoveAll(_) | ReactionEvent::RemoveEmoji(_) => None,
+        }
+    }
+
+    /// The emoji of the [`ReactionEvent`].
+    ///
+    /// Returns `None` if all reactions were removed.
+    pub fn emoji(self) -> Option<&'a ReactionType> {
+        match self {
+            ReactionEvent::Add(event) => Some(&event.emoji),
+            ReactionEvent::Remove(event) => Some(&event.emoji),
+            ReactionEvent::RemoveAll(_) => None,
+            ReactionEvent::RemoveEmoji(event) => Some(&event.emoji),
+        }
+    }
+
+    /// The canonical key of the event's emoji, see [`emoji_key`].
+    ///
+    /// Returns `None` if all reactions were removed.
+    pub fn emoji_key(self) -> Option<Cow<'a, str>> {
+        self.emoji().map(emoji_key)
+    }
+
+    /// Whether reactions with the emoji of the given [key] are affected by
+    /// the event.
+    ///
+    /// Always `true` if all reactions were removed. Comparing against a key
+    /// instead of an emoji allows matching reactions of a cached message
+    /// whose archived emoji is stored as key.
+    ///
+    /// [key]: emoji_key
+    pub fn matches_emoji(self, key: &str) -> bool {
+        self.emoji().is_none_or(|emoji| match emoji {
+            ReactionType::Custom { id, .. } => key.parse::<u64>().is_ok_and(|key| key == id.get()),
+            ReactionType::Unicode { name } => name == key,
+        })
+    }
+}
+
+/// Canonical string representation of an emoji.
+///
+/// Unicode emojis are represented by their name i.e. the emoji itself while
+/// custom emojis are represented by their id since their name may change.
+pub fn emoji_key(emoji: &ReactionType) -> Cow<'_, str> {
+    match emoji {
+        ReactionType::Custom { id, .. } => Cow::Owned(id.to_string()),
+        ReactionType::Unicode { name } => Cow::Borrowed(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twilight_model::{
+        channel::message::ReactionType,
+        gateway::{
+            payload::incoming::{ReactionAdd, ReactionRemoveAll},
+            GatewayReaction,
+        },
+        id::Id,
+    };
+
+    use super::{emoji_key, ReactionEvent};
+
+    fn reaction_add(emoji: ReactionType) -> ReactionAdd {
+        ReactionAdd(GatewayReaction {
+            channel_id: Id::new(1),
+            emoji,
+            guild_id: None,
+            member: None,
+            message_author_id: None,
+            message_id: Id::new(2),
+            user_id: Id::new(3),
+        })
+    }
+
+    #[test]
+    fn keys() {
+        let unicode = ReactionType::Unicode {
+            name: "⭐".to_owned(),
+        };
+
+        let custom = ReactionType::Custom {
+            animated: false,
+            id: Id::new(42),
+            name: Some("star".to_owned()),
+        };
+
+        assert_eq!(emoji_key(&unicode), "⭐");
+        assert_eq!(emoji_key(&custom), "42");
+    }
+
+    #[test]
+    fn matches_emoji() {
+        let add = reaction_add(ReactionType::Custom {
+            animated: true,
+            id: Id::new(42),
+            name: None,
+        });
+
+        let event = ReactionEvent::Add(&add);
+        assert!(event.matches_emoji("42"));
+        assert!(!event.matches_emoji("43"));
+        assert!(!event.matches_emoji("⭐"));
+        assert_eq!(event.user_id(), Some(Id::new(3)));
+
+        let remove_all = ReactionRemoveAll {
+            channel_id: Id::new(1),
+            guild_id: None,
+            message_id: Id::new(2),
+        };
+
+        let event = ReactionEvent::RemoveAll(&remove_all);
+        assert!(event.matches_emoji("⭐"));
+        assert_eq!(event.emoji_key(), None);
+    }
 }
@@ -0,0 +1,69 @@
+use rkyv::Archive;
+
+/// Size in bytes of the archived form of `T`.
+///
+/// This is the amount of bytes required to serialize a `T` that contains no
+/// out-of-line data such as strings or vectors, e.g. when choosing the length
+/// of a fixed-size array for [`Cacheable::Bytes`].
+///
+/// [`Cacheable::Bytes`]: crate::config::Cacheable::Bytes
+pub const fn archived_size<T: Archive>() -> usize {
+    std::mem::size_of::<T::Archived>()
+}
+
+/// Asserts at compile time that a type can be serialized into a buffer of the
+/// given length.
+///
+/// When serializing into a fixed-size buffer, a buffer that is too small only
+/// shows up as a serialization error at runtime. With this macro, the
+/// compilation fails instead and the error message contains the required
+/// length, e.g. `expected an array with a size of 16, found one with a size of
+/// 24` means that the buffer should be 24 bytes long.
+///
+/// Only the archived form of the type is accounted for so types containing
+/// out-of-line data such as strings or vectors require additional space.
+///
+/// # Example
+///
+/// ```
+/// use redlight::assert_fits_scratch;
+/// use rkyv::Archive;
+///
+/// #[derive(Archive)]
+/// struct CachedMember {
+///     pending: bool,
+///     joined_at: i64,
+/// }
+///
+/// assert_fits_scratch!(CachedMember, 16);
+/// ```
+///
+/// ```compile_fail
+/// # use redlight::assert_fits_scratch;
+/// # use rkyv::Archive;
+/// #[derive(Archive)]
+/// struct CachedMember {
+///     pending: bool,
+///     joined_at: i64,
+/// }
+///
+/// // error: expected an array with a size of 8, found one with a size of 16
+/// assert_fits_scratch!(CachedMember, 8);
+/// ```
+#[macro_export]
+macro_rules! assert_fits_scratch {
+    ($ty:ty, $len:expr $(,)?) => {
+        const _: () = {
+            const BUFFER_LEN: usize = $len;
+            const ARCHIVED_LEN: usize = $crate::config::archived_size::<$ty>();
+            const SUGGESTED_LEN: usize = if ARCHIVED_LEN > BUFFER_LEN {
+                ARCHIVED_LEN
+            } else {
+                BUFFER_LEN
+            };
+
+            // Mismatching lengths cause a compile error mentioning both
+            let _: [(); BUFFER_LEN] = [(); SUGGESTED_LEN];
+        };
+    };
+}
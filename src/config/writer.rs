@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+/// What happens when another process already writes events of a shard.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WriterConflictBehavior {
+    /// Log an error but apply the event anyway.
+    #[default]
+    Warn,
+    /// Log an error and fail with [`CacheError::DuplicateWriter`] instead of
+    /// applying the event.
+    ///
+    /// [`CacheError::DuplicateWriter`]: crate::error::CacheError::DuplicateWriter
+    Reject,
+}
+
+/// Lease on the shards whose events a process writes into the cache.
+///
+/// See [`CacheConfig::WRITER_LEASE`](crate::config::CacheConfig::WRITER_LEASE).
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use redlight::config::WriterLease;
+///
+/// const WRITER_LEASE: WriterLease = WriterLease::new(Duration::from_secs(30)).rejecting();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WriterLease {
+    /// Duration without events after which another process may take over a
+    /// shard.
+    pub duration: Duration,
+    /// What happens when another process holds the lease of a shard.
+    pub behavior: WriterConflictBehavior,
+}
+
+impl WriterLease {
+    /// Create a new [`WriterLease`] that only logs conflicts.
+    pub const fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            behavior: WriterConflictBehavior::Warn,
+        }
+    }
+
+    /// Reject events of shards whose lease is held by another process.
+    #[must_use]
+    pub const fn rejecting(mut self) -> Self {
+        self.behavior = WriterConflictBehavior::Reject;
+
+        self
+    }
+}
@@ -1,13 +1,30 @@
+use std::time::Duration;
+
 use rkyv::rancor::{BoxedError, Source};
 use thiserror::Error as ThisError;
 
-use crate::redis::RedisError;
+use crate::{config::RateLimitedOperation, redis::RedisError};
 
 #[cfg(feature = "bb8")]
-type DedicatedConnectionError = RedisError;
+pub(crate) type DedicatedConnectionError = RedisError;
 
 #[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
-type DedicatedConnectionError = deadpool_redis::PoolError;
+pub(crate) type DedicatedConnectionError = deadpool_redis::PoolError;
+
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    feature = "multiplexed"
+))]
+pub(crate) type DedicatedConnectionError = RedisError;
+
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    not(feature = "multiplexed"),
+    feature = "cluster"
+))]
+pub(crate) type DedicatedConnectionError = RedisError;
 
 /// Represents all the ways something can fail.
 #[derive(Debug, ThisError)]
@@ -30,6 +47,42 @@ pub enum CacheError {
     /// Failed to get a connection.
     GetConnection(#[source] deadpool_redis::PoolError),
 
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        feature = "multiplexed"
+    ))]
+    #[error("failed to create redis client")]
+    /// Failed to create redis client.
+    CreatePool(#[source] RedisError),
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        feature = "multiplexed"
+    ))]
+    #[error("failed to get a connection")]
+    /// Failed to get a connection.
+    GetConnection(#[source] RedisError),
+
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    #[error("failed to create redis cluster client")]
+    /// Failed to create redis cluster client.
+    CreatePool(#[source] RedisError),
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    #[error("failed to get a connection")]
+    /// Failed to get a connection.
+    GetConnection(#[source] RedisError),
+
     #[cfg(feature = "bytecheck")]
     #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "bytecheck")))]
     #[error("cached bytes did not correspond to the cached type")]
@@ -42,26 +95,111 @@ pub enum CacheError {
     /// Failed to serialize sessions.
     SerializeSessions(#[source] BoxedError),
 
+    #[cfg(feature = "http")]
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "http")))]
+    #[error("failed to request the discord API")]
+    /// Failed to request the discord API.
+    Http(#[source] Box<twilight_http::Error>),
+    #[cfg(feature = "http")]
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "http")))]
+    #[error("failed to deserialize response of the discord API")]
+    /// Failed to deserialize a response of the discord API.
+    HttpBody(#[source] twilight_http::response::DeserializeBodyError),
+
+    #[error("cold store operation failed")]
+    /// A [`ColdStore`](crate::cache::ColdStore) failed to store or load
+    /// messages.
+    ColdStore(#[source] crate::cache::ColdStoreError),
+    #[error("failed to deserialize cached entry")]
+    /// Failed to deserialize a cached entry into an owned value.
+    Deserialization(#[source] BoxedError),
+    #[error("events of shard {shard} are written by another process")]
+    /// Another process holds the writer lease of the shard.
+    ///
+    /// See [`CacheConfig::WRITER_LEASE`](crate::config::CacheConfig::WRITER_LEASE).
+    DuplicateWriter { shard: u32 },
     #[error(transparent)]
     /// Expire-related error.
     Expire(#[from] ExpireError),
+    #[error("custom namespace {namespace:?} or key {key:?} contains `:`")]
+    /// The namespace or key of user-defined data contains `:` which is used
+    /// as separator within redis keys.
+    ///
+    /// See [`RedisCache::set_custom`](crate::RedisCache::set_custom).
+    InvalidCustomKey { namespace: Box<str>, key: Box<str> },
+    #[error("key prefix {prefix:?} is longer than 32 bytes")]
+    /// The key prefix is longer than 32 bytes.
+    ///
+    /// See [`CacheConfig::KEY_PREFIX`](crate::config::CacheConfig::KEY_PREFIX).
+    InvalidKeyPrefix { prefix: &'static str },
     #[error("received invalid response from redis")]
     /// Received invalid response from redis
     InvalidResponse,
+    #[error("{operation:?} operation is rate limited, retry after {retry_after:?}")]
+    /// An expensive operation exceeded its configured rate limit.
+    ///
+    /// See [`CacheConfig::RATE_LIMITS`](crate::config::CacheConfig::RATE_LIMITS).
+    RateLimited {
+        operation: RateLimitedOperation,
+        retry_after: Duration,
+    },
     #[error(transparent)]
     /// Meta-related error.
     Meta(#[from] MetaError),
+    #[error(transparent)]
+    /// Pub/sub-related error.
+    PubSub(#[from] PubSubError),
     #[error("redis error")]
     /// Redis error.
     Redis(#[from] RedisError),
     #[error(transparent)]
     /// Serialization-related error.
     Serialization(#[from] SerializeError),
+    #[error("keys kept changing while reading a snapshot")]
+    /// The watched keys of a snapshot changed during each attempt to read it.
+    ///
+    /// See [`RedisCache::guild_snapshot`](crate::RedisCache::guild_snapshot).
+    #[cfg(any(feature = "bb8", feature = "deadpool"))]
+    SnapshotContention,
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    #[error("transactions are not supported in cluster mode")]
+    /// Transactions can't be atomic in cluster mode since their keys may
+    /// belong to different slots.
+    ///
+    /// See [`RedisCache::transaction`](crate::RedisCache::transaction).
+    TransactionUnsupported,
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    #[error("{option} is not supported in cluster mode")]
+    /// The config enables an option, or a cached value provides a version,
+    /// whose scripts access keys of different slots at once.
+    ///
+    /// See [`ClusterPool`](crate::ClusterPool).
+    ClusterUnsupported { option: &'static str },
     #[error("failed to update entry")]
     /// Failed to update entry.
     Update(#[from] UpdateError),
 }
 
+#[derive(Debug, ThisError)]
+#[error("invalid value {value:?} for config override {name}")]
+/// An environment variable contained an invalid config override.
+///
+/// See [`ConfigOverrides::from_env`](crate::config::ConfigOverrides::from_env).
+pub struct OverrideError {
+    pub name: String,
+    pub value: String,
+}
+
 #[derive(Debug, ThisError)]
 #[error("failed to serialize {kind:?}")]
 /// Failed to serialize some type.
@@ -85,17 +223,23 @@ impl SerializeError {
 ///
 /// Used in [`SerializeError`].
 pub enum SerializeErrorKind {
+    Attachment,
+    AutoModerationRule,
     Channel,
     CurrentUser,
+    Custom,
     Emoji,
     Guild,
     Integration,
+    Invite,
     Member,
     Message,
     Presence,
     Role,
+    ScheduledEvent,
     StageInstance,
     Sticker,
+    ThreadMember,
     User,
     VoiceState,
 }
@@ -130,6 +274,9 @@ pub enum UpdateErrorKind {
     PartialMember,
     PartialUser,
     Reaction,
+    ScheduledEvent,
+    StageInstance,
+    VoiceState,
 }
 
 #[derive(Debug, ThisError)]
@@ -193,16 +340,20 @@ impl MetaError {
 ///
 /// Used in [`MetaError`].
 pub enum MetaErrorKind {
+    AutoModerationRule,
     Channel,
     Emoji,
     Guild,
     Integration,
+    Invite,
     Member,
     Message,
     Presence,
     Role,
+    ScheduledEvent,
     StageInstance,
     Sticker,
+    ThreadMember,
     User,
     VoiceState,
 }
@@ -235,3 +386,14 @@ pub enum ExpireError {
     /// Cached bytes did not correspond to the expected meta type.
     Validation(#[source] BoxedError),
 }
+
+#[derive(Debug, ThisError)]
+/// Pub/sub-related error.
+pub enum PubSubError {
+    #[error("failed to get a connection")]
+    /// Failed to get a connection.
+    GetConnection(#[source] DedicatedConnectionError),
+    #[error("failed to subscribe")]
+    /// Failed to subscribe to a channel or pattern.
+    Subscribe(#[source] RedisError),
+}
@@ -0,0 +1,1146 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::{Error as IoError, ErrorKind, Result as IoResult},
+    net::SocketAddr,
+    str,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tracing::{debug, trace};
+
+/// In-memory stand-in for a redis server.
+///
+/// Speaks the redis protocol on a local port and implements the subset of
+/// commands that the cache relies on for plain reads and writes, i.e. strings,
+/// sets, sorted sets, hashes, lists, expiration, and pipelines.
+///
+/// The fake is meant for tests of configs that stick to those plain reads
+/// and writes. It does not evaluate scripts; `EVAL` replies with an error so
+/// everything built on scripts fails against it, e.g. event sequences of
+/// [`RedisCache::update_with_meta`], [`CacheConfig::PREVIOUS_VERSION_LIFETIME`],
+/// tombstones, member name indexes, message byte budgets, skipping unchanged
+/// presences, and deleting the members of a thread. Neither does it support
+/// pub/sub, keyspace notifications, or key scans so configs whose types
+/// [expire], invalidations, and [`Maintenance`] still require a real server,
+/// and so do the crate's own doctests.
+///
+/// The server stops once the [`FakeRedis`] is dropped.
+///
+/// [`RedisCache::update_with_meta`]: crate::RedisCache::update_with_meta
+/// [`CacheConfig::PREVIOUS_VERSION_LIFETIME`]: crate::config::CacheConfig::PREVIOUS_VERSION_LIFETIME
+/// [expire]: crate::config::Cacheable::expire
+/// [`Maintenance`]: crate::maintenance::Maintenance
+///
+/// # Example
+///
+/// ```
+/// # use std::time::Duration;
+/// # use redlight::config::{CacheConfig, Ignore};
+/// use redlight::{fake::FakeRedis, RedisCache};
+///
+/// # struct Config;
+/// # impl CacheConfig for Config {
+/// #     #[cfg(feature = "metrics")]
+/// #     const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(30);
+/// #     type AutoModerationRule<'a> = Ignore;
+/// #     type Channel<'a> = Ignore;
+/// #     type CurrentUser<'a> = Ignore;
+/// #     type Emoji<'a> = Ignore;
+/// #     type Guild<'a> = Ignore;
+/// #     type Integration<'a> = Ignore;
+/// #     type Invite<'a> = Ignore;
+/// #     type Member<'a> = Ignore;
+/// #     type Message<'a> = Ignore;
+/// #     type Presence<'a> = Ignore;
+/// #     type Role<'a> = Ignore;
+/// #     type ScheduledEvent<'a> = Ignore;
+/// #     type StageInstance<'a> = Ignore;
+/// #     type Sticker<'a> = Ignore;
+/// #     type ThreadMember<'a> = Ignore;
+/// #     type User<'a> = Ignore;
+/// #     type VoiceState<'a> = Ignore;
+/// # }
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let fake = FakeRedis::start().await?;
+/// let cache = RedisCache::<Config>::new(&fake.url()).await?;
+///
+/// assert!(cache.guild_ids().await?.is_empty());
+/// # Ok(()) }
+/// ```
+pub struct FakeRedis {
+    addr: SocketAddr,
+    store: Arc<Mutex<Store>>,
+    listener: JoinHandle<()>,
+}
+
+impl FakeRedis {
+    /// Start listening on a free local port.
+    pub async fn start() -> IoResult<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let store = Arc::new(Mutex::new(Store::default()));
+
+        debug!(%addr, "Started fake redis");
+
+        let listener = tokio::spawn(accept_loop(listener, Arc::clone(&store)));
+
+        Ok(Self {
+            addr,
+            store,
+            listener,
+        })
+    }
+
+    /// The address that the server listens on.
+    pub const fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The url to connect to the server, e.g. through [`RedisCache::new`].
+    ///
+    /// [`RedisCache::new`]: crate::RedisCache::new
+    pub fn url(&self) -> String {
+        format!("redis://{}", self.addr)
+    }
+
+    /// Remove all stored keys.
+    pub fn flush(&self) {
+        self.store
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .clear();
+    }
+}
+
+impl Drop for FakeRedis {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+async fn accept_loop(listener: TcpListener, store: Arc<Mutex<Store>>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(serve(stream, Arc::clone(&store)));
+            }
+            Err(err) => debug!(?err, "Fake redis failed to accept connection"),
+        }
+    }
+}
+
+async fn serve(stream: TcpStream, store: Arc<Mutex<Store>>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+
+    // Commands of an open transaction
+    let mut queued: Option<Vec<Vec<Vec<u8>>>> = None;
+    let mut out = Vec::new();
+
+    loop {
+        let args = match read_command(&mut reader).await {
+            Ok(Some(args)) if !args.is_empty() => args,
+            Ok(Some(_)) => continue,
+            Ok(None) => return,
+            Err(err) => {
+                debug!(?err, "Fake redis failed to read command");
+
+                return;
+            }
+        };
+
+        trace!(command = %String::from_utf8_lossy(&args[0]));
+
+        let name = args[0].to_ascii_uppercase();
+
+        let reply = match (name.as_slice(), queued.as_mut()) {
+            (b"MULTI", None) => {
+                queued = Some(Vec::new());
+
+                Reply::Status("OK")
+            }
+            (b"EXEC", Some(_)) => {
+                let mut store = store.lock().unwrap_or_else(PoisonError::into_inner);
+
+                let replies = queued
+                    .take()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|args| store.execute(args))
+                    .collect();
+
+                Reply::Array(replies)
+            }
+            (b"DISCARD", Some(_)) => {
+                queued = None;
+
+                Reply::Status("OK")
+            }
+            (b"MULTI", Some(_)) => Reply::error("ERR MULTI calls can not be nested"),
+            (b"EXEC" | b"DISCARD", None) => Reply::error("ERR command without MULTI"),
+            (_, Some(queued)) => {
+                queued.push(args);
+
+                Reply::Status("QUEUED")
+            }
+            (_, None) => store
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .execute(&args),
+        };
+
+        out.clear();
+        reply.write(&mut out);
+
+        if let Err(err) = writer.write_all(&out).await {
+            debug!(?err, "Fake redis failed to write reply");
+
+            return;
+        }
+    }
+}
+
+/// Read a command either as array of bulk strings or as inline command.
+async fn read_command<R>(reader: &mut R) -> IoResult<Option<Vec<Vec<u8>>>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = Vec::new();
+
+    if reader.read_until(b'\n', &mut line).await? == 0 {
+        return Ok(None);
+    }
+
+    let line = trim_crlf(&line);
+
+    let Some(len) = line.strip_prefix(b"*") else {
+        let args = line
+            .split(u8::is_ascii_whitespace)
+            .filter(|arg| !arg.is_empty())
+            .map(<[u8]>::to_vec)
+            .collect();
+
+        return Ok(Some(args));
+    };
+
+    let len = parse_len(len)?;
+    let mut args = Vec::with_capacity(len);
+
+    for _ in 0..len {
+        let mut header = Vec::new();
+        reader.read_until(b'\n', &mut header).await?;
+
+        let Some(len) = trim_crlf(&header).strip_prefix(b"$") else {
+            return Err(IoError::new(ErrorKind::InvalidData, "expected bulk string"));
+        };
+
+        let len = parse_len(len)?;
+
+        // Include the trailing CRLF
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).await?;
+        arg.truncate(len);
+
+        args.push(arg);
+    }
+
+    Ok(Some(args))
+}
+
+fn trim_crlf(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+fn parse_len(bytes: &[u8]) -> IoResult<usize> {
+    str::from_utf8(bytes)
+        .ok()
+        .and_then(|len| len.parse().ok())
+        .ok_or_else(|| IoError::new(ErrorKind::InvalidData, "invalid length"))
+}
+
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Status(&'static str),
+    Error(String),
+    Int(i64),
+    Bulk(Vec<u8>),
+    Nil,
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    fn error(msg: impl Into<String>) -> Self {
+        Self::Error(msg.into())
+    }
+
+    fn wrong_type() -> Self {
+        Self::error("WRONGTYPE Operation against a key holding the wrong kind of value")
+    }
+
+    fn wrong_arity() -> Self {
+        Self::error("ERR wrong number of arguments")
+    }
+
+    fn syntax() -> Self {
+        Self::error("ERR syntax error")
+    }
+
+    fn not_integer() -> Self {
+        Self::error("ERR value is not an integer or out of range")
+    }
+
+    fn count(count: usize) -> Self {
+        Self::Int(i64::try_from(count).unwrap_or(i64::MAX))
+    }
+
+    fn bulks(values: impl IntoIterator<Item = Vec<u8>>) -> Self {
+        Self::Array(values.into_iter().map(Self::Bulk).collect())
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Status(status) => {
+                out.push(b'+');
+                out.extend_from_slice(status.as_bytes());
+            }
+            Self::Error(msg) => {
+                out.push(b'-');
+                out.extend_from_slice(msg.as_bytes());
+            }
+            Self::Int(int) => out.extend_from_slice(format!(":{int}").as_bytes()),
+            Self::Bulk(bytes) => {
+                out.extend_from_slice(format!("${}\r\n", bytes.len()).as_bytes());
+                out.extend_from_slice(bytes);
+            }
+            Self::Nil => out.extend_from_slice(b"$-1"),
+            Self::Array(replies) => {
+                out.extend_from_slice(format!("*{}\r\n", replies.len()).as_bytes());
+
+                for reply in replies {
+                    reply.write(out);
+                }
+
+                // Nested replies already wrote their CRLF
+                return;
+            }
+        }
+
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+#[derive(Default)]
+struct Store {
+    entries: HashMap<Vec<u8>, Entry>,
+}
+
+struct Entry {
+    value: Value,
+    expires_at: Option<Instant>,
+}
+
+enum Value {
+    String(Vec<u8>),
+    List(VecDeque<Vec<u8>>),
+    Set(HashSet<Vec<u8>>),
+    SortedSet(HashMap<Vec<u8>, f64>),
+    Hash(HashMap<Vec<u8>, Vec<u8>>),
+}
+
+macro_rules! typed {
+    ($fn:ident, $variant:ident, $ty:ty) => {
+        /// Get the value of the key, creating an empty one if missing.
+        fn $fn(&mut self, key: &[u8]) -> Result<&mut $ty, Reply> {
+            self.purge(key);
+
+            let entry = self.entries.entry(key.to_vec()).or_insert_with(|| Entry {
+                value: Value::$variant(Default::default()),
+                expires_at: None,
+            });
+
+            match entry.value {
+                Value::$variant(ref mut value) => Ok(value),
+                _ => Err(Reply::wrong_type()),
+            }
+        }
+    };
+}
+
+impl Store {
+    typed!(list_mut, List, VecDeque<Vec<u8>>);
+    typed!(set_mut, Set, HashSet<Vec<u8>>);
+    typed!(zset_mut, SortedSet, HashMap<Vec<u8>, f64>);
+    typed!(hash_mut, Hash, HashMap<Vec<u8>, Vec<u8>>);
+
+    /// Remove the key if it expired.
+    fn purge(&mut self, key: &[u8]) {
+        let expired = self
+            .entries
+            .get(key)
+            .and_then(|entry| entry.expires_at)
+            .is_some_and(|expires_at| expires_at <= Instant::now());
+
+        if expired {
+            self.entries.remove(key);
+        }
+    }
+
+    fn get(&mut self, key: &[u8]) -> Option<&mut Entry> {
+        self.purge(key);
+
+        self.entries.get_mut(key)
+    }
+
+    /// Remove the key if its collection became empty.
+    fn remove_if_empty(&mut self, key: &[u8]) {
+        let is_empty = self
+            .entries
+            .get(key)
+            .is_some_and(|entry| match entry.value {
+                Value::String(_) => false,
+                Value::List(ref list) => list.is_empty(),
+                Value::Set(ref set) => set.is_empty(),
+                Value::SortedSet(ref zset) => zset.is_empty(),
+                Value::Hash(ref hash) => hash.is_empty(),
+            });
+
+        if is_empty {
+            self.entries.remove(key);
+        }
+    }
+
+    fn set_string(&mut self, key: &[u8], value: Vec<u8>, expires_at: Option<Instant>) {
+        let entry = Entry {
+            value: Value::String(value),
+            expires_at,
+        };
+
+        self.entries.insert(key.to_vec(), entry);
+    }
+
+    fn get_string(&mut self, key: &[u8]) -> Reply {
+        match self.get(key).map(|entry| &entry.value) {
+            Some(Value::String(value)) => Reply::Bulk(value.clone()),
+            Some(_) => Reply::wrong_type(),
+            None => Reply::Nil,
+        }
+    }
+
+    fn expire(&mut self, key: &[u8], duration: Option<Duration>) -> Reply {
+        match self.get(key) {
+            Some(entry) => {
+                entry.expires_at = duration.map(|duration| Instant::now() + duration);
+
+                Reply::Int(1)
+            }
+            None => Reply::Int(0),
+        }
+    }
+
+    fn pttl(&mut self, key: &[u8]) -> Option<i64> {
+        let entry = self.get(key)?;
+
+        let pttl = entry.expires_at.map_or(-1, |expires_at| {
+            let remaining = expires_at.saturating_duration_since(Instant::now());
+
+            i64::try_from(remaining.as_millis()).unwrap_or(i64::MAX)
+        });
+
+        Some(pttl)
+    }
+
+    /// Members of a sorted set ordered by score and then member.
+    fn sorted(&mut self, key: &[u8]) -> Result<Vec<(Vec<u8>, f64)>, Reply> {
+        let zset = match self.get(key).map(|entry| &entry.value) {
+            Some(Value::SortedSet(zset)) => zset,
+            Some(_) => return Err(Reply::wrong_type()),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut members: Vec<_> = zset
+            .iter()
+            .map(|(member, score)| (member.clone(), *score))
+            .collect();
+
+        members.sort_by(|(a_member, a_score), (b_member, b_score)| {
+            a_score
+                .total_cmp(b_score)
+                .then_with(|| a_member.cmp(b_member))
+        });
+
+        Ok(members)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn execute(&mut self, args: &[Vec<u8>]) -> Reply {
+        let name = args[0].to_ascii_uppercase();
+        let args = &args[1..];
+
+        let res = match name.as_slice() {
+            b"PING" => Ok(args
+                .first()
+                .map_or(Reply::Status("PONG"), |msg| Reply::Bulk(msg.clone()))),
+            b"SELECT" | b"CLIENT" | b"READONLY" => Ok(Reply::Status("OK")),
+            b"FLUSHDB" | b"FLUSHALL" => {
+                self.entries.clear();
+
+                Ok(Reply::Status("OK"))
+            }
+            b"GET" => arity(args, 1).map(|()| self.get_string(&args[0])),
+            b"MGET" => Ok(Reply::Array(
+                args.iter().map(|key| self.get_string(key)).collect(),
+            )),
+            b"SET" => self.cmd_set(args),
+            b"SETEX" => arity(args, 3).and_then(|()| {
+                let secs = parse_int::<u64>(&args[1])?;
+                let expires_at = Instant::now() + Duration::from_secs(secs);
+                self.set_string(&args[0], args[2].clone(), Some(expires_at));
+
+                Ok(Reply::Status("OK"))
+            }),
+            b"PSETEX" => arity(args, 3).and_then(|()| {
+                let millis = parse_int::<u64>(&args[1])?;
+                let expires_at = Instant::now() + Duration::from_millis(millis);
+                self.set_string(&args[0], args[2].clone(), Some(expires_at));
+
+                Ok(Reply::Status("OK"))
+            }),
+            b"MSET" => {
+                if args.is_empty() || !args.len().is_multiple_of(2) {
+                    return Reply::wrong_arity();
+                }
+
+                for pair in args.chunks_exact(2) {
+                    self.set_string(&pair[0], pair[1].clone(), None);
+                }
+
+                Ok(Reply::Status("OK"))
+            }
+            b"DEL" => {
+                let removed = args
+                    .iter()
+                    .filter(|key| {
+                        self.purge(key);
+
+                        self.entries.remove(key.as_slice()).is_some()
+                    })
+                    .count();
+
+                Ok(Reply::count(removed))
+            }
+            b"EXISTS" => {
+                let existing = args.iter().filter(|key| self.get(key).is_some()).count();
+
+                Ok(Reply::count(existing))
+            }
+            b"EXPIRE" => arity(args, 2).and_then(|()| {
+                let secs = parse_int(&args[1])?;
+
+                Ok(self.expire(&args[0], Some(Duration::from_secs(secs))))
+            }),
+            b"PEXPIRE" => arity(args, 2).and_then(|()| {
+                let millis = parse_int(&args[1])?;
+
+                Ok(self.expire(&args[0], Some(Duration::from_millis(millis))))
+            }),
+            b"PERSIST" => arity(args, 1).map(|()| self.expire(&args[0], None)),
+            b"PTTL" => arity(args, 1).map(|()| Reply::Int(self.pttl(&args[0]).unwrap_or(-2))),
+            b"TTL" => arity(args, 1).map(|()| {
+                let ttl = self
+                    .pttl(&args[0])
+                    .map_or(-2, |pttl| if pttl < 0 { pttl } else { pttl / 1000 });
+
+                Reply::Int(ttl)
+            }),
+            b"SADD" => min_arity(args, 2).and_then(|()| {
+                let set = self.set_mut(&args[0])?;
+                let added = args[1..]
+                    .iter()
+                    .filter(|member| set.insert((*member).clone()))
+                    .count();
+
+                Ok(Reply::count(added))
+            }),
+            b"SREM" => min_arity(args, 2).and_then(|()| {
+                let set = self.set_mut(&args[0])?;
+                let removed = args[1..]
+                    .iter()
+                    .filter(|member| set.remove(*member))
+                    .count();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::count(removed))
+            }),
+            b"SMEMBERS" => arity(args, 1).and_then(|()| {
+                let members = self.set_mut(&args[0])?.iter().cloned().collect::<Vec<_>>();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::bulks(members))
+            }),
+            b"SCARD" => arity(args, 1).and_then(|()| {
+                let len = self.set_mut(&args[0])?.len();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::count(len))
+            }),
+            b"SISMEMBER" => arity(args, 2).and_then(|()| {
+                let contains = self.set_mut(&args[0])?.contains(&args[1]);
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::Int(i64::from(contains)))
+            }),
+            b"ZADD" => self.cmd_zadd(args),
+            b"ZREM" => min_arity(args, 2).and_then(|()| {
+                let zset = self.zset_mut(&args[0])?;
+                let removed = args[1..]
+                    .iter()
+                    .filter(|member| zset.remove(*member).is_some())
+                    .count();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::count(removed))
+            }),
+            b"ZCARD" => arity(args, 1).and_then(|()| {
+                let len = self.zset_mut(&args[0])?.len();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::count(len))
+            }),
+            b"ZSCORE" => arity(args, 2).and_then(|()| {
+                let score = self.zset_mut(&args[0])?.get(&args[1]).copied();
+                self.remove_if_empty(&args[0]);
+
+                Ok(score.map_or(Reply::Nil, |score| Reply::Bulk(format_score(score))))
+            }),
+            b"ZRANGE" => self.cmd_zrange(args, false),
+            b"ZREVRANGE" => self.cmd_zrange(args, true),
+            b"ZRANGEBYSCORE" => self.cmd_zrangebyscore(args),
+            b"ZRANGEBYLEX" => self.cmd_zrangebylex(args),
+            b"HSET" => {
+                if args.len() < 3 || args.len().is_multiple_of(2) {
+                    return Reply::wrong_arity();
+                }
+
+                self.hash_mut(&args[0]).map(|hash| {
+                    let added = args[1..]
+                        .chunks_exact(2)
+                        .filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none())
+                        .count();
+
+                    Reply::count(added)
+                })
+            }
+            b"HGET" => arity(args, 2).and_then(|()| {
+                let value = self.hash_mut(&args[0])?.get(&args[1]).cloned();
+                self.remove_if_empty(&args[0]);
+
+                Ok(value.map_or(Reply::Nil, Reply::Bulk))
+            }),
+            b"HDEL" => min_arity(args, 2).and_then(|()| {
+                let hash = self.hash_mut(&args[0])?;
+                let removed = args[1..]
+                    .iter()
+                    .filter(|field| hash.remove(*field).is_some())
+                    .count();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::count(removed))
+            }),
+            b"HGETALL" => arity(args, 1).and_then(|()| {
+                let pairs = self
+                    .hash_mut(&args[0])?
+                    .iter()
+                    .flat_map(|(field, value)| [field.clone(), value.clone()])
+                    .collect::<Vec<_>>();
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::bulks(pairs))
+            }),
+            b"HINCRBY" => arity(args, 3).and_then(|()| {
+                let incr = parse_int::<i64>(&args[2])?;
+                let hash = self.hash_mut(&args[0])?;
+
+                let current = match hash.get(&args[1]) {
+                    Some(value) => parse_int::<i64>(value)?,
+                    None => 0,
+                };
+
+                let value = current.checked_add(incr).ok_or_else(Reply::not_integer)?;
+                hash.insert(args[1].clone(), value.to_string().into_bytes());
+
+                Ok(Reply::Int(value))
+            }),
+            b"LPUSH" => min_arity(args, 2).and_then(|()| {
+                let list = self.list_mut(&args[0])?;
+
+                for value in &args[1..] {
+                    list.push_front(value.clone());
+                }
+
+                Ok(Reply::count(list.len()))
+            }),
+            b"LRANGE" => arity(args, 3).and_then(|()| {
+                let start = parse_int(&args[1])?;
+                let stop = parse_int(&args[2])?;
+                let list = self.list_mut(&args[0])?;
+
+                let values: Vec<_> = match range(list.len(), start, stop) {
+                    Some((start, stop)) => list.range(start..=stop).cloned().collect(),
+                    None => Vec::new(),
+                };
+
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::bulks(values))
+            }),
+            b"LTRIM" => arity(args, 3).and_then(|()| {
+                let start = parse_int(&args[1])?;
+                let stop = parse_int(&args[2])?;
+                let list = self.list_mut(&args[0])?;
+
+                match range(list.len(), start, stop) {
+                    Some((start, stop)) => {
+                        list.truncate(stop + 1);
+                        list.drain(..start);
+                    }
+                    None => list.clear(),
+                }
+
+                self.remove_if_empty(&args[0]);
+
+                Ok(Reply::Status("OK"))
+            }),
+            b"EVAL" | b"EVALSHA" | b"SCRIPT" => {
+                Err(Reply::error("ERR scripts are not supported by the fake"))
+            }
+            _ => Err(Reply::error(format!(
+                "ERR unknown command '{}'",
+                String::from_utf8_lossy(&name)
+            ))),
+        };
+
+        res.unwrap_or_else(|reply| reply)
+    }
+
+    fn cmd_set(&mut self, args: &[Vec<u8>]) -> Result<Reply, Reply> {
+        min_arity(args, 2)?;
+
+        let mut expires_at = None;
+        let mut keep_ttl = false;
+        let mut only_missing = false;
+        let mut only_existing = false;
+        let mut options = args[2..].iter();
+
+        while let Some(option) = options.next() {
+            match option.to_ascii_uppercase().as_slice() {
+                b"EX" => {
+                    let secs = parse_int(options.next().ok_or_else(Reply::syntax)?)?;
+                    expires_at = Some(Instant::now() + Duration::from_secs(secs));
+                }
+                b"PX" => {
+                    let millis = parse_int(options.next().ok_or_else(Reply::syntax)?)?;
+                    expires_at = Some(Instant::now() + Duration::from_millis(millis));
+                }
+                b"KEEPTTL" => keep_ttl = true,
+                b"NX" => only_missing = true,
+                b"XX" => only_existing = true,
+                _ => return Err(Reply::syntax()),
+            }
+        }
+
+        let existing = self.get(&args[0]);
+
+        if (only_missing && existing.is_some()) || (only_existing && existing.is_none()) {
+            return Ok(Reply::Nil);
+        }
+
+        if keep_ttl {
+            expires_at = existing.and_then(|entry| entry.expires_at);
+        }
+
+        self.set_string(&args[0], args[1].clone(), expires_at);
+
+        Ok(Reply::Status("OK"))
+    }
+
+    fn cmd_zadd(&mut self, args: &[Vec<u8>]) -> Result<Reply, Reply> {
+        if args.len() < 3 || args.len().is_multiple_of(2) {
+            return Err(Reply::wrong_arity());
+        }
+
+        let pairs = args[1..]
+            .chunks_exact(2)
+            .map(|pair| parse_score(&pair[0]).map(|score| (score, &pair[1])))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let zset = self.zset_mut(&args[0])?;
+
+        let added = pairs
+            .into_iter()
+            .filter(|(score, member)| zset.insert((*member).clone(), *score).is_none())
+            .count();
+
+        Ok(Reply::count(added))
+    }
+
+    fn cmd_zrange(&mut self, args: &[Vec<u8>], rev: bool) -> Result<Reply, Reply> {
+        min_arity(args, 3)?;
+
+        let start = parse_int(&args[1])?;
+        let stop = parse_int(&args[2])?;
+
+        let with_scores = match &args[3..] {
+            [] => false,
+            [option] if option.eq_ignore_ascii_case(b"WITHSCORES") => true,
+            _ => return Err(Reply::syntax()),
+        };
+
+        let mut members = self.sorted(&args[0])?;
+
+        if rev {
+            members.reverse();
+        }
+
+        let members = match range(members.len(), start, stop) {
+            Some((start, stop)) => members.drain(start..=stop).collect(),
+            None => Vec::new(),
+        };
+
+        Ok(members_reply(members, with_scores))
+    }
+
+    fn cmd_zrangebyscore(&mut self, args: &[Vec<u8>]) -> Result<Reply, Reply> {
+        min_arity(args, 3)?;
+
+        let min = ScoreBound::parse(&args[1])?;
+        let max = ScoreBound::parse(&args[2])?;
+        let (with_scores, limit) = parse_range_options(&args[3..], true)?;
+
+        let members = self
+            .sorted(&args[0])?
+            .into_iter()
+            .filter(|(_, score)| min.allows_above(*score) && max.allows_below(*score));
+
+        Ok(members_reply(apply_limit(members, limit), with_scores))
+    }
+
+    fn cmd_zrangebylex(&mut self, args: &[Vec<u8>]) -> Result<Reply, Reply> {
+        min_arity(args, 3)?;
+
+        let min = LexBound::parse(&args[1])?;
+        let max = LexBound::parse(&args[2])?;
+        let (_, limit) = parse_range_options(&args[3..], false)?;
+
+        let members = self
+            .sorted(&args[0])?
+            .into_iter()
+            .filter(|(member, _)| min.allows_above(member) && max.allows_below(member));
+
+        Ok(members_reply(apply_limit(members, limit), false))
+    }
+}
+
+fn arity(args: &[Vec<u8>], expected: usize) -> Result<(), Reply> {
+    if args.len() == expected {
+        Ok(())
+    } else {
+        Err(Reply::wrong_arity())
+    }
+}
+
+fn min_arity(args: &[Vec<u8>], min: usize) -> Result<(), Reply> {
+    if args.len() >= min {
+        Ok(())
+    } else {
+        Err(Reply::wrong_arity())
+    }
+}
+
+fn parse_int<T: str::FromStr>(bytes: &[u8]) -> Result<T, Reply> {
+    str::from_utf8(bytes)
+        .ok()
+        .and_then(|int| int.parse().ok())
+        .ok_or_else(Reply::not_integer)
+}
+
+fn parse_score(bytes: &[u8]) -> Result<f64, Reply> {
+    match bytes {
+        b"+inf" | b"inf" => Ok(f64::INFINITY),
+        b"-inf" => Ok(f64::NEG_INFINITY),
+        _ => str::from_utf8(bytes)
+            .ok()
+            .and_then(|score| score.parse().ok())
+            .ok_or_else(|| Reply::error("ERR value is not a valid float")),
+    }
+}
+
+fn format_score(score: f64) -> Vec<u8> {
+    score.to_string().into_bytes()
+}
+
+/// Convert a redis range with potentially negative indices into an inclusive
+/// range of valid indices.
+fn range(len: usize, start: i64, stop: i64) -> Option<(usize, usize)> {
+    let len = i64::try_from(len).ok()?;
+    let normalize = |idx: i64| if idx < 0 { len + idx } else { idx };
+
+    let start = normalize(start).max(0);
+    let stop = normalize(stop).min(len - 1);
+
+    if start > stop {
+        return None;
+    }
+
+    Some((usize::try_from(start).ok()?, usize::try_from(stop).ok()?))
+}
+
+/// Offset and optional count of a `LIMIT` option.
+type Limit = (usize, Option<usize>);
+
+/// Parse `[WITHSCORES] [LIMIT offset count]`.
+fn parse_range_options(
+    options: &[Vec<u8>],
+    allow_scores: bool,
+) -> Result<(bool, Option<Limit>), Reply> {
+    let mut with_scores = false;
+    let mut limit = None;
+    let mut options = options.iter();
+
+    while let Some(option) = options.next() {
+        match option.to_ascii_uppercase().as_slice() {
+            b"WITHSCORES" if allow_scores => with_scores = true,
+            b"LIMIT" => {
+                let offset = parse_int::<usize>(options.next().ok_or_else(Reply::syntax)?)?;
+                let count = parse_int::<i64>(options.next().ok_or_else(Reply::syntax)?)?;
+
+                // A negative count returns all remaining members
+                limit = Some((offset, usize::try_from(count).ok()));
+            }
+            _ => return Err(Reply::syntax()),
+        }
+    }
+
+    Ok((with_scores, limit))
+}
+
+fn apply_limit(
+    members: impl Iterator<Item = (Vec<u8>, f64)>,
+    limit: Option<Limit>,
+) -> Vec<(Vec<u8>, f64)> {
+    match limit {
+        Some((offset, Some(count))) => members.skip(offset).take(count).collect(),
+        Some((offset, None)) => members.skip(offset).collect(),
+        None => members.collect(),
+    }
+}
+
+fn members_reply(members: Vec<(Vec<u8>, f64)>, with_scores: bool) -> Reply {
+    if with_scores {
+        let values = members
+            .into_iter()
+            .flat_map(|(member, score)| [member, format_score(score)]);
+
+        Reply::bulks(values)
+    } else {
+        Reply::bulks(members.into_iter().map(|(member, _)| member))
+    }
+}
+
+enum ScoreBound {
+    Inclusive(f64),
+    Exclusive(f64),
+}
+
+impl ScoreBound {
+    fn parse(bytes: &[u8]) -> Result<Self, Reply> {
+        match bytes.strip_prefix(b"(") {
+            Some(score) => parse_score(score).map(Self::Exclusive),
+            None => parse_score(bytes).map(Self::Inclusive),
+        }
+    }
+
+    fn allows_above(&self, score: f64) -> bool {
+        match *self {
+            Self::Inclusive(min) => score >= min,
+            Self::Exclusive(min) => score > min,
+        }
+    }
+
+    fn allows_below(&self, score: f64) -> bool {
+        match *self {
+            Self::Inclusive(max) => score <= max,
+            Self::Exclusive(max) => score < max,
+        }
+    }
+}
+
+enum LexBound {
+    Unbounded,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    fn parse(bytes: &[u8]) -> Result<Self, Reply> {
+        match bytes.split_first() {
+            Some((b'-' | b'+', [])) => Ok(Self::Unbounded),
+            Some((b'[', member)) => Ok(Self::Inclusive(member.to_vec())),
+            Some((b'(', member)) => Ok(Self::Exclusive(member.to_vec())),
+            _ => Err(Reply::error("ERR min or max not valid string range item")),
+        }
+    }
+
+    fn allows_above(&self, member: &[u8]) -> bool {
+        match self {
+            Self::Unbounded => true,
+            Self::Inclusive(min) => member >= min.as_slice(),
+            Self::Exclusive(min) => member > min.as_slice(),
+        }
+    }
+
+    fn allows_below(&self, member: &[u8]) -> bool {
+        match self {
+            Self::Unbounded => true,
+            Self::Inclusive(max) => member <= max.as_slice(),
+            Self::Exclusive(max) => member < max.as_slice(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reply, Store};
+
+    fn execute(store: &mut Store, args: &[&str]) -> Reply {
+        let args: Vec<_> = args.iter().map(|arg| arg.as_bytes().to_vec()).collect();
+
+        store.execute(&args)
+    }
+
+    fn bulk(value: &str) -> Reply {
+        Reply::Bulk(value.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn strings() {
+        let mut store = Store::default();
+
+        assert_eq!(execute(&mut store, &["SET", "a", "1"]), Reply::Status("OK"));
+        assert_eq!(execute(&mut store, &["GET", "a"]), bulk("1"));
+        assert_eq!(execute(&mut store, &["PTTL", "a"]), Reply::Int(-1));
+
+        execute(&mut store, &["MSET", "b", "2", "c", "3"]);
+
+        let expected = Reply::Array(vec![bulk("2"), Reply::Nil, bulk("3")]);
+        assert_eq!(execute(&mut store, &["MGET", "b", "x", "c"]), expected);
+
+        assert_eq!(execute(&mut store, &["DEL", "a", "b", "x"]), Reply::Int(2));
+        assert_eq!(execute(&mut store, &["GET", "a"]), Reply::Nil);
+        assert_eq!(execute(&mut store, &["PTTL", "a"]), Reply::Int(-2));
+    }
+
+    #[test]
+    fn expiration() {
+        let mut store = Store::default();
+
+        execute(&mut store, &["SET", "a", "1", "PX", "0"]);
+        assert_eq!(execute(&mut store, &["GET", "a"]), Reply::Nil);
+
+        execute(&mut store, &["PSETEX", "c", "0", "3"]);
+        assert_eq!(execute(&mut store, &["GET", "c"]), Reply::Nil);
+
+        execute(&mut store, &["SETEX", "b", "60", "2"]);
+        assert!(matches!(
+            execute(&mut store, &["TTL", "b"]),
+            Reply::Int(59 | 60)
+        ));
+
+        assert_eq!(execute(&mut store, &["PERSIST", "b"]), Reply::Int(1));
+        assert_eq!(execute(&mut store, &["TTL", "b"]), Reply::Int(-1));
+    }
+
+    #[test]
+    fn sets() {
+        let mut store = Store::default();
+
+        assert_eq!(
+            execute(&mut store, &["SADD", "s", "1", "2", "2"]),
+            Reply::Int(2)
+        );
+        assert_eq!(execute(&mut store, &["SCARD", "s"]), Reply::Int(2));
+        assert_eq!(execute(&mut store, &["SREM", "s", "1", "2"]), Reply::Int(2));
+        assert_eq!(execute(&mut store, &["EXISTS", "s"]), Reply::Int(0));
+
+        execute(&mut store, &["SET", "a", "1"]);
+        assert!(matches!(
+            execute(&mut store, &["SADD", "a", "1"]),
+            Reply::Error(_)
+        ));
+    }
+
+    #[test]
+    fn sorted_sets() {
+        let mut store = Store::default();
+
+        execute(&mut store, &["ZADD", "z", "3", "c", "1", "a", "2", "b"]);
+
+        let expected = Reply::Array(vec![bulk("a"), bulk("b"), bulk("c")]);
+        assert_eq!(execute(&mut store, &["ZRANGE", "z", "0", "-1"]), expected);
+
+        let expected = Reply::Array(vec![bulk("c"), bulk("3")]);
+        let reply = execute(&mut store, &["ZREVRANGE", "z", "0", "0", "WITHSCORES"]);
+        assert_eq!(reply, expected);
+
+        let expected = Reply::Array(vec![bulk("b"), bulk("c")]);
+        let reply = execute(&mut store, &["ZRANGEBYSCORE", "z", "(1", "+inf"]);
+        assert_eq!(reply, expected);
+
+        let expected = Reply::Array(vec![bulk("b")]);
+        let reply = execute(
+            &mut store,
+            &["ZRANGEBYLEX", "z", "(a", "+", "LIMIT", "0", "1"],
+        );
+        assert_eq!(reply, expected);
+    }
+
+    #[test]
+    fn hashes_and_lists() {
+        let mut store = Store::default();
+
+        assert_eq!(
+            execute(&mut store, &["HINCRBY", "h", "f", "3"]),
+            Reply::Int(3)
+        );
+        assert_eq!(
+            execute(&mut store, &["HINCRBY", "h", "f", "2"]),
+            Reply::Int(5)
+        );
+        assert_eq!(execute(&mut store, &["HDEL", "h", "f"]), Reply::Int(1));
+
+        execute(&mut store, &["LPUSH", "l", "1", "2", "3"]);
+        execute(&mut store, &["LTRIM", "l", "0", "1"]);
+
+        let expected = Reply::Array(vec![bulk("3"), bulk("2")]);
+        assert_eq!(execute(&mut store, &["LRANGE", "l", "0", "-1"]), expected);
+    }
+
+    #[test]
+    fn scripts_are_rejected() {
+        let mut store = Store::default();
+
+        assert!(matches!(
+            execute(&mut store, &["EVAL", "return 1", "0"]),
+            Reply::Error(_)
+        ));
+    }
+}
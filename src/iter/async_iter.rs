@@ -102,7 +102,7 @@ impl<'c, T: Cacheable> AsyncIter<'c, T> {
     }
 }
 
-impl<'c, T: Cacheable> Stream for AsyncIter<'c, T> {
+impl<T: Cacheable> Stream for AsyncIter<'_, T> {
     type Item = CacheResult<CachedArchive<T>>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
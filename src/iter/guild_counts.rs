@@ -0,0 +1,181 @@
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+    vec::IntoIter,
+};
+
+use futures_util::{future::BoxFuture, stream::StreamExt, FutureExt, Stream};
+use rkyv::util::AlignedVec;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::{
+    config::Cacheable,
+    error::CacheError,
+    key::{Namespace, RedisKey},
+    redis::{Connection, FromRedisValue, Pipeline, RedisResult, Value},
+    util::{convert_ids_vec, BytesWrap},
+    CacheResult, CachedArchive,
+};
+
+/// Amount of guilds that are requested through a single pipeline.
+const CHUNK_SIZE: usize = 32;
+
+/// A guild entry alongside the amount of its cached members and channels.
+pub type GuildWithCounts<T> = (CachedArchive<T>, usize, usize);
+
+/// An iterator that fetches cached guild entries alongside the amount of
+/// their cached members and channels.
+///
+/// Guilds are requested in chunks so that entries and counts of multiple
+/// guilds are fetched in a single round trip.
+///
+/// The items are of type [`GuildWithCounts`] wrapped in a [`Result`].
+pub struct GuildCountsIter<'c, T> {
+    buffered: VecDeque<CacheResult<GuildWithCounts<T>>>,
+    state: State<'c>,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'c, T: Cacheable> GuildCountsIter<'c, T> {
+    pub(crate) fn new(conn: Connection<'c>, namespace: &'c Namespace, ids: Vec<u64>) -> Self {
+        let ids: Vec<Id<GuildMarker>> = convert_ids_vec(ids);
+
+        let chunks = Chunks {
+            conn,
+            namespace,
+            ids: ids.into_iter(),
+        };
+
+        Self {
+            buffered: VecDeque::new(),
+            state: State::Idle(Box::new(chunks)),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Retrieve the next item from the cache.
+    pub async fn next_item(&mut self) -> Option<CacheResult<GuildWithCounts<T>>> {
+        self.next().await
+    }
+
+    fn buffer(&mut self, values: &[Value]) {
+        for chunk in values.chunks_exact(3) {
+            let bytes = match Option::<BytesWrap<AlignedVec<16>>>::from_redis_value(&chunk[0]) {
+                Ok(Some(BytesWrap(bytes))) if !bytes.is_empty() => bytes,
+                Ok(_) => continue,
+                Err(err) => {
+                    self.buffered.push_back(Err(CacheError::Redis(err)));
+
+                    continue;
+                }
+            };
+
+            let counts = usize::from_redis_value(&chunk[1]).and_then(|members| {
+                usize::from_redis_value(&chunk[2]).map(|channels| (members, channels))
+            });
+
+            let (members, channels) = match counts {
+                Ok(counts) => counts,
+                Err(err) => {
+                    self.buffered.push_back(Err(CacheError::Redis(err)));
+
+                    continue;
+                }
+            };
+
+            #[cfg(feature = "bytecheck")]
+            let archived_res = CachedArchive::new(bytes);
+
+            #[cfg(not(feature = "bytecheck"))]
+            let archived_res = Ok(CachedArchive::new_unchecked(bytes));
+
+            let item = archived_res.map(|archived| (archived, members, channels));
+            self.buffered.push_back(item);
+        }
+    }
+}
+
+impl<T: Cacheable> Stream for GuildCountsIter<'_, T> {
+    type Item = CacheResult<GuildWithCounts<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(item) = this.buffered.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+
+            match mem::replace(&mut this.state, State::Completed) {
+                State::Idle(chunks) => {
+                    if chunks.ids.as_slice().is_empty() {
+                        return Poll::Ready(None);
+                    }
+
+                    this.state = State::InFlight(chunks.fetch().boxed());
+                }
+                State::InFlight(mut fut) => match fut.poll_unpin(cx) {
+                    Poll::Ready((chunks, res)) => {
+                        this.state = State::Idle(chunks);
+
+                        match res {
+                            Ok(values) => this.buffer(&values),
+                            Err(err) => this.buffered.push_back(Err(CacheError::Redis(err))),
+                        }
+                    }
+                    Poll::Pending => {
+                        this.state = State::InFlight(fut);
+
+                        return Poll::Pending;
+                    }
+                },
+                State::Completed => return Poll::Ready(None),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let max = match self.state {
+            State::Idle(ref chunks) => Some(chunks.ids.len()),
+            State::InFlight(_) => None,
+            State::Completed => Some(0),
+        };
+
+        (0, max.map(|max| max + self.buffered.len()))
+    }
+}
+
+// The iterator is never pinned structurally.
+impl<T> Unpin for GuildCountsIter<'_, T> {}
+
+enum State<'c> {
+    Idle(Box<Chunks<'c>>),
+    InFlight(BoxFuture<'c, (Box<Chunks<'c>>, RedisResult<Vec<Value>>)>),
+    Completed,
+}
+
+struct Chunks<'c> {
+    conn: Connection<'c>,
+    namespace: &'c Namespace,
+    ids: IntoIter<Id<GuildMarker>>,
+}
+
+impl Chunks<'_> {
+    /// Request the entries and counts of the next chunk of guilds.
+    async fn fetch(mut self: Box<Self>) -> (Box<Self>, RedisResult<Vec<Value>>) {
+        let mut pipe = Pipeline::new();
+
+        for guild_id in self.ids.by_ref().take(CHUNK_SIZE) {
+            pipe.get(self.namespace.key(RedisKey::Guild { id: guild_id }))
+                .scard(self.namespace.key(RedisKey::GuildMembers { id: guild_id }))
+                .scard(self.namespace.key(RedisKey::GuildChannels { id: guild_id }));
+        }
+
+        let res = pipe.query_async(&mut self.conn).await;
+
+        (self, res)
+    }
+}
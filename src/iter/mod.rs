@@ -1,4 +1,9 @@
 mod async_iter;
+mod guild_counts;
+mod paged;
+mod stream;
+
+use std::collections::HashSet;
 
 use itoa::Buffer;
 use twilight_model::id::{
@@ -6,11 +11,16 @@ use twilight_model::id::{
     Id,
 };
 
-pub use self::async_iter::AsyncIter;
+pub use self::{
+    async_iter::AsyncIter,
+    guild_counts::{GuildCountsIter, GuildWithCounts},
+    paged::PagedIter,
+    stream::StreamIter,
+};
 use crate::{
-    config::{CacheConfig, Cacheable},
+    config::{CacheConfig, Cacheable, RateLimitedOperation},
     error::CacheError,
-    key::RedisKey,
+    key::{Namespace, RedisKey},
     redis::Cmd,
     CacheResult, RedisCache,
 };
@@ -19,7 +29,8 @@ use crate::{
 ///
 /// The iteration order of all iterators is arbitrary, except for
 /// [`RedisCacheIter::channel_messages`] whose order is the message timestamp
-/// i.e. from most recent to oldest.
+/// i.e. from most recent to oldest, and [`RedisCacheIter::search_members`]
+/// whose order is the matching name.
 pub struct RedisCacheIter<'c, C> {
     cache: &'c RedisCache<C>,
 }
@@ -36,6 +47,17 @@ impl<'c, C> RedisCacheIter<'c, C> {
 }
 
 impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
+    /// Iterate over all cached auto moderation rule entries.
+    pub async fn auto_moderation_rules(
+        self,
+    ) -> CacheResult<AsyncIter<'c, C::AutoModerationRule<'static>>> {
+        self.iter_all(
+            RedisKey::AutoModerationRules,
+            RedisKey::AUTO_MODERATION_RULE_PREFIX,
+        )
+        .await
+    }
+
     /// Iterate over all cached channel entries.
     pub async fn channels(self) -> CacheResult<AsyncIter<'c, C::Channel<'static>>> {
         self.iter_all(RedisKey::Channels, RedisKey::CHANNEL_PREFIX)
@@ -55,17 +77,41 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
 
         let mut conn = self.cache.connection().await?;
 
-        let ids: Vec<u64> = Cmd::zrange(key, 0, -1)
+        let ids: Vec<u64> = Cmd::zrange(self.cache.namespace().key(key), 0, -1)
             .query_async(&mut conn)
             .await
             .map_err(CacheError::Redis)?;
 
-        let key_prefix = key_prefix_simple(RedisKey::MESSAGE_PREFIX);
+        let key_prefix = key_prefix_simple(self.cache.namespace(), RedisKey::MESSAGE_PREFIX);
         let iter = AsyncIter::new(conn, ids, key_prefix);
 
         Ok(iter)
     }
 
+    /// Iterate over all cached thread member entries of a thread.
+    pub async fn channel_thread_members(
+        self,
+        channel_id: Id<ChannelMarker>,
+    ) -> CacheResult<AsyncIter<'c, C::ThreadMember<'static>>> {
+        let key = RedisKey::ChannelThreadMembers {
+            channel: channel_id,
+        };
+
+        let mut conn = self.cache.connection().await?;
+
+        let ids: Vec<u64> =
+            RedisCache::<C>::get_ids_static(self.cache.namespace().key(key), &mut conn).await?;
+
+        let (key_prefix, buf) = key_prefix_buffered(
+            self.cache.namespace(),
+            RedisKey::THREAD_MEMBER_PREFIX,
+            channel_id,
+        );
+        let iter = AsyncIter::new_with_buf(conn, ids, key_prefix, buf);
+
+        Ok(iter)
+    }
+
     /// Iterate over all cached emoji entries.
     pub async fn emojis(self) -> CacheResult<AsyncIter<'c, C::Emoji<'static>>> {
         self.iter_all(RedisKey::Emojis, RedisKey::EMOJI_PREFIX)
@@ -78,6 +124,27 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
             .await
     }
 
+    /// Iterate over all cached guild entries alongside the amount of their
+    /// cached members and channels.
+    ///
+    /// Entries and counts are fetched in chunks of multiple guilds per round
+    /// trip which is useful to e.g. list guilds with their sizes.
+    pub async fn guilds_with_counts(self) -> CacheResult<GuildCountsIter<'c, C::Guild<'static>>> {
+        self.cache
+            .rate_limit(RateLimitedOperation::Iteration)
+            .await?;
+
+        let mut conn = self.cache.connection().await?;
+
+        let ids: Vec<u64> = RedisCache::<C>::get_ids_static(
+            self.cache.namespace().key(RedisKey::Guilds),
+            &mut conn,
+        )
+        .await?;
+
+        Ok(GuildCountsIter::new(conn, self.cache.namespace(), ids))
+    }
+
     /// Iterate over all cached message entries.
     pub async fn messages(self) -> CacheResult<AsyncIter<'c, C::Message<'static>>> {
         self.iter_all(RedisKey::Messages, RedisKey::MESSAGE_PREFIX)
@@ -89,6 +156,12 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
         self.iter_all(RedisKey::Roles, RedisKey::ROLE_PREFIX).await
     }
 
+    /// Iterate over all cached scheduled event entries.
+    pub async fn scheduled_events(self) -> CacheResult<AsyncIter<'c, C::ScheduledEvent<'static>>> {
+        self.iter_all(RedisKey::ScheduledEvents, RedisKey::SCHEDULED_EVENT_PREFIX)
+            .await
+    }
+
     /// Iterate over all cached stage instance entries.
     pub async fn stage_instances(self) -> CacheResult<AsyncIter<'c, C::StageInstance<'static>>> {
         self.iter_all(RedisKey::StageInstances, RedisKey::STAGE_INSTANCE_PREFIX)
@@ -106,6 +179,98 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
         self.iter_all(RedisKey::Users, RedisKey::USER_PREFIX).await
     }
 
+    /// Iterate over all cached user entries in pages of up to `page_size`
+    /// entries.
+    ///
+    /// Unlike [`RedisCacheIter::users`], ids are not all loaded upfront and
+    /// each page is fetched in a single round trip. Use
+    /// [`PagedIter::with_delay`] to wait between pages, e.g. for maintenance
+    /// jobs that walk all users without putting too much load onto redis.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let mut pages = cache
+    ///     .iter()
+    ///     .users_paged(1000)
+    ///     .await?
+    ///     .with_delay(Duration::from_millis(100));
+    ///
+    /// while let Some(page) = pages.next_page().await {
+    ///     for user in page? {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn users_paged(
+        self,
+        page_size: usize,
+    ) -> CacheResult<PagedIter<'c, C::User<'static>>> {
+        let key_prefix = key_prefix_simple(self.cache.namespace(), RedisKey::USER_PREFIX);
+
+        self.paged_all(RedisKey::Users, key_prefix, page_size).await
+    }
+
+    /// Stream all cached user entries, fetching them in chunks of up to
+    /// `chunk_size` entries.
+    ///
+    /// Unlike [`RedisCacheIter::users`], ids are not all loaded upfront which
+    /// keeps memory usage flat even for millions of users.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use redlight::{config::CacheConfig, RedisCache};
+    /// # async fn example<C: CacheConfig>(
+    /// #     cache: RedisCache<C>,
+    /// # ) -> Result<(), redlight::error::CacheError> {
+    /// let mut users = cache.iter().users_stream(1000).await?;
+    ///
+    /// while let Some(user) = users.next_item().await {
+    ///     let user = user?;
+    ///     // ...
+    /// }
+    /// # Ok(()) }
+    /// ```
+    pub async fn users_stream(
+        self,
+        chunk_size: usize,
+    ) -> CacheResult<StreamIter<'c, C::User<'static>>> {
+        self.users_paged(chunk_size).await.map(StreamIter::new)
+    }
+
+    /// Stream all cached message entries, fetching them in chunks of up to
+    /// `chunk_size` entries.
+    ///
+    /// See [`RedisCacheIter::users_stream`].
+    pub async fn messages_stream(
+        self,
+        chunk_size: usize,
+    ) -> CacheResult<StreamIter<'c, C::Message<'static>>> {
+        let key_prefix = key_prefix_simple(self.cache.namespace(), RedisKey::MESSAGE_PREFIX);
+
+        self.paged_all(RedisKey::Messages, key_prefix, chunk_size)
+            .await
+            .map(StreamIter::new)
+    }
+
+    /// Iterate over all cached auto moderation rule entries of a guild.
+    pub async fn guild_auto_moderation_rules(
+        self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<AsyncIter<'c, C::AutoModerationRule<'static>>> {
+        let key = RedisKey::GuildAutoModerationRules { id: guild_id };
+
+        self.iter_guild_simple(key, RedisKey::AUTO_MODERATION_RULE_PREFIX)
+            .await
+    }
+
     /// Iterate over all cached channel entries of a guild.
     pub async fn guild_channels(
         self,
@@ -148,6 +313,70 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
             .await
     }
 
+    /// Stream all cached member entries of a guild, fetching them in chunks of
+    /// up to `chunk_size` entries.
+    ///
+    /// See [`RedisCacheIter::users_stream`].
+    pub async fn guild_members_stream(
+        self,
+        guild_id: Id<GuildMarker>,
+        chunk_size: usize,
+    ) -> CacheResult<StreamIter<'c, C::Member<'static>>> {
+        let key = RedisKey::GuildMembers { id: guild_id };
+        let (key_prefix, _) =
+            key_prefix_buffered(self.cache.namespace(), RedisKey::MEMBER_PREFIX, guild_id);
+
+        self.paged_all(key, key_prefix, chunk_size)
+            .await
+            .map(StreamIter::new)
+    }
+
+    /// Iterate over the cached member entries of a guild whose username,
+    /// global name, or nickname starts with the given prefix, ignoring case.
+    ///
+    /// The items are ordered by their matching name. Members are only found
+    /// if [`ICachedMember::INDEX_NAMES`] is enabled.
+    ///
+    /// [`ICachedMember::INDEX_NAMES`]: crate::config::ICachedMember::INDEX_NAMES
+    pub async fn search_members(
+        self,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+    ) -> CacheResult<AsyncIter<'c, C::Member<'static>>> {
+        let key = RedisKey::GuildMemberNames { id: guild_id };
+
+        let mut min = Vec::with_capacity(prefix.len() + 2);
+        min.push(b'[');
+        min.extend_from_slice(prefix.to_lowercase().as_bytes());
+
+        // No byte of a UTF-8 string is 0xff so this bounds all names that
+        // start with the prefix
+        let mut max = min.clone();
+        max.push(0xff);
+
+        let mut conn = self.cache.connection().await?;
+
+        let entries: Vec<Vec<u8>> = Cmd::zrangebylex(self.cache.namespace().key(key), min, max)
+            .query_async(&mut conn)
+            .await
+            .map_err(CacheError::Redis)?;
+
+        // Members may match through multiple names
+        let mut seen = HashSet::with_capacity(entries.len());
+
+        let ids: Vec<u64> = entries
+            .iter()
+            .filter_map(|entry| parse_name_entry(entry))
+            .filter(|&id| seen.insert(id))
+            .collect();
+
+        let (key_prefix, buf) =
+            key_prefix_buffered(self.cache.namespace(), RedisKey::MEMBER_PREFIX, guild_id);
+        let iter = AsyncIter::new_with_buf(conn, ids, key_prefix, buf);
+
+        Ok(iter)
+    }
+
     /// Iterate over all cached presence entries of a guild.
     pub async fn guild_presences(
         self,
@@ -169,6 +398,17 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
         self.iter_guild_simple(key, RedisKey::ROLE_PREFIX).await
     }
 
+    /// Iterate over all cached scheduled event entries of a guild.
+    pub async fn guild_scheduled_events(
+        self,
+        guild_id: Id<GuildMarker>,
+    ) -> CacheResult<AsyncIter<'c, C::ScheduledEvent<'static>>> {
+        let key = RedisKey::GuildScheduledEvents { id: guild_id };
+
+        self.iter_guild_simple(key, RedisKey::SCHEDULED_EVENT_PREFIX)
+            .await
+    }
+
     /// Iterate over all cached stage instance entries of a guild.
     pub async fn guild_stage_instances(
         self,
@@ -206,16 +446,43 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
         key: RedisKey,
         prefix: &'static [u8],
     ) -> CacheResult<AsyncIter<'c, T>> {
+        self.cache
+            .rate_limit(RateLimitedOperation::Iteration)
+            .await?;
+
         let mut conn = self.cache.connection().await?;
 
-        let ids: Vec<u64> = RedisCache::<C>::get_ids_static(key, &mut conn).await?;
+        let ids: Vec<u64> =
+            RedisCache::<C>::get_ids_static(self.cache.namespace().key(key), &mut conn).await?;
 
-        let key_prefix = key_prefix_simple(prefix);
+        let key_prefix = key_prefix_simple(self.cache.namespace(), prefix);
         let iter = AsyncIter::new(conn, ids, key_prefix);
 
         Ok(iter)
     }
 
+    async fn paged_all<T: Cacheable>(
+        self,
+        key: RedisKey,
+        key_prefix: Vec<u8>,
+        page_size: usize,
+    ) -> CacheResult<PagedIter<'c, T>> {
+        self.cache
+            .rate_limit(RateLimitedOperation::Iteration)
+            .await?;
+
+        let conn = self.cache.connection().await?;
+        let iter = PagedIter::new(
+            conn,
+            self.cache.clock(),
+            self.cache.namespace().key(key),
+            key_prefix,
+            page_size,
+        );
+
+        Ok(iter)
+    }
+
     async fn iter_guild_simple<T: Cacheable>(
         self,
         key: RedisKey,
@@ -223,9 +490,10 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
     ) -> CacheResult<AsyncIter<'c, T>> {
         let mut conn = self.cache.connection().await?;
 
-        let ids: Vec<u64> = RedisCache::<C>::get_ids_static(key, &mut conn).await?;
+        let ids: Vec<u64> =
+            RedisCache::<C>::get_ids_static(self.cache.namespace().key(key), &mut conn).await?;
 
-        let key_prefix = key_prefix_simple(prefix);
+        let key_prefix = key_prefix_simple(self.cache.namespace(), prefix);
         let iter = AsyncIter::new(conn, ids, key_prefix);
 
         Ok(iter)
@@ -239,39 +507,61 @@ impl<'c, C: CacheConfig> RedisCacheIter<'c, C> {
     ) -> CacheResult<AsyncIter<'c, T>> {
         let mut conn = self.cache.connection().await?;
 
-        let ids: Vec<u64> = RedisCache::<C>::get_ids_static(key, &mut conn).await?;
+        let ids: Vec<u64> =
+            RedisCache::<C>::get_ids_static(self.cache.namespace().key(key), &mut conn).await?;
 
-        let (key_prefix, buf) = key_prefix_buffered(prefix, guild_id);
+        let (key_prefix, buf) = key_prefix_buffered(self.cache.namespace(), prefix, guild_id);
         let iter = AsyncIter::new_with_buf(conn, ids, key_prefix, buf);
 
         Ok(iter)
     }
 }
 
-impl<'c, C> Clone for RedisCacheIter<'c, C> {
+impl<C> Clone for RedisCacheIter<'_, C> {
     fn clone(&self) -> Self {
         *self
     }
 }
 
-impl<'c, C> Copy for RedisCacheIter<'c, C> {}
+impl<C> Copy for RedisCacheIter<'_, C> {}
+
+fn key_prefix_simple(namespace: &Namespace, prefix: &'static [u8]) -> Vec<u8> {
+    let namespace = namespace.as_bytes();
 
-fn key_prefix_simple(prefix: &'static [u8]) -> Vec<u8> {
-    let mut key_prefix = Vec::with_capacity(prefix.len() + 1);
+    let mut key_prefix = Vec::with_capacity(namespace.len() + prefix.len() + 1);
+    key_prefix.extend_from_slice(namespace);
     key_prefix.extend_from_slice(prefix);
     key_prefix.push(b':');
 
     key_prefix
 }
 
-fn key_prefix_buffered(prefix: &'static [u8], guild_id: Id<GuildMarker>) -> (Vec<u8>, Buffer) {
+/// Parse the user id of a [`RedisKey::GuildMemberNames`] entry, i.e. the
+/// digits after the last null byte.
+fn parse_name_entry(entry: &[u8]) -> Option<u64> {
+    let idx = entry.iter().rposition(|&byte| byte == 0)?;
+
+    std::str::from_utf8(&entry[idx + 1..]).ok()?.parse().ok()
+}
+
+/// Key prefix of entries that are keyed by a parent id such as a guild id,
+/// e.g. `MEMBER:{guild}:`.
+fn key_prefix_buffered<T>(
+    namespace: &Namespace,
+    prefix: &'static [u8],
+    parent_id: Id<T>,
+) -> (Vec<u8>, Buffer) {
     let mut buf = Buffer::new();
-    let guild_id = buf.format(guild_id.get());
+    let parent_id = buf.format(parent_id.get());
+
+    let namespace = namespace.as_bytes();
 
-    let mut key_prefix = Vec::with_capacity(prefix.len() + 1 + 2 * (guild_id.len() + 1));
+    let mut key_prefix =
+        Vec::with_capacity(namespace.len() + prefix.len() + 1 + 2 * (parent_id.len() + 1));
+    key_prefix.extend_from_slice(namespace);
     key_prefix.extend_from_slice(prefix);
     key_prefix.push(b':');
-    key_prefix.extend_from_slice(guild_id.as_bytes());
+    key_prefix.extend_from_slice(parent_id.as_bytes());
     key_prefix.push(b':');
 
     (key_prefix, buf)
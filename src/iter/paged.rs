@@ -0,0 +1,230 @@
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::{future::BoxFuture, stream::StreamExt, FutureExt, Stream};
+use itoa::Buffer;
+use rkyv::util::AlignedVec;
+
+use crate::{
+    clock::{Clock, Sleep},
+    config::Cacheable,
+    error::CacheError,
+    key::{Namespaced, RedisKey},
+    redis::{cmd, Cmd, Connection, RedisResult},
+    util::BytesWrap,
+    CacheResult, CachedArchive,
+};
+
+/// An iterator that fetches cached entries in pages.
+///
+/// Ids are scanned incrementally and the entries of a page are fetched
+/// through a single `MGET` so that even huge collections can be walked
+/// without keeping all of their ids in memory. Optionally, a delay between
+/// pages spreads out the load on redis.
+///
+/// Pages hold at most the requested amount of entries and may hold fewer if
+/// entries expired in the meantime. Since ids are scanned while the
+/// collection may change, entries that are added or removed during the
+/// iteration may or may not be included, and in rare cases an entry may be
+/// included more than once.
+///
+/// The items are of type `Vec<CachedArchive<T>>` wrapped in a [`Result`].
+pub struct PagedIter<'c, T> {
+    state: State<'c>,
+    delay: Option<Duration>,
+    clock: &'c dyn Clock,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<'c, T: Cacheable> PagedIter<'c, T> {
+    pub(crate) fn new(
+        conn: Connection<'c>,
+        clock: &'c dyn Clock,
+        key: Namespaced<'c, RedisKey>,
+        key_prefix: Vec<u8>,
+        page_size: usize,
+    ) -> Self {
+        let pages = Pages {
+            conn,
+            key,
+            key_prefix,
+            page_size: page_size.max(1),
+            cursor: 0,
+            scanned: false,
+            ids: VecDeque::new(),
+        };
+
+        Self {
+            state: State::Idle(Box::new(pages)),
+            delay: None,
+            clock,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Wait for the given duration before fetching each page after the
+    /// first one.
+    #[must_use]
+    pub const fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+
+        self
+    }
+
+    /// Retrieve the next page from the cache.
+    pub async fn next_page(&mut self) -> Option<CacheResult<Vec<CachedArchive<T>>>> {
+        self.next().await
+    }
+}
+
+impl<T: Cacheable> Stream for PagedIter<'_, T> {
+    type Item = CacheResult<Vec<CachedArchive<T>>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            match mem::replace(&mut this.state, State::Completed) {
+                State::Idle(pages) => {
+                    if pages.is_exhausted() {
+                        return Poll::Ready(None);
+                    }
+
+                    let sleep = this
+                        .delay
+                        .filter(|_| pages.scanned)
+                        .map(|delay| this.clock.sleep(delay));
+
+                    this.state = State::InFlight(pages.fetch(sleep).boxed());
+                }
+                State::InFlight(mut fut) => match fut.poll_unpin(cx) {
+                    Poll::Ready((pages, res)) => {
+                        this.state = State::Idle(pages);
+
+                        match res {
+                            // Skip pages whose entries all expired
+                            Ok(values) if values.iter().all(Option::is_none) => {}
+                            Ok(values) => return Poll::Ready(Some(to_page(values))),
+                            // Fetching again would likely fail again
+                            Err(err) => {
+                                this.state = State::Completed;
+
+                                return Poll::Ready(Some(Err(CacheError::Redis(err))));
+                            }
+                        }
+                    }
+                    Poll::Pending => {
+                        this.state = State::InFlight(fut);
+
+                        return Poll::Pending;
+                    }
+                },
+                State::Completed => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+// The iterator is never pinned structurally.
+impl<T> Unpin for PagedIter<'_, T> {}
+
+// The bindings are needed for the cfg attributes
+#[allow(clippy::let_and_return)]
+fn to_page<T: Cacheable>(values: Values) -> CacheResult<Vec<CachedArchive<T>>> {
+    values
+        .into_iter()
+        .flatten()
+        .filter(|BytesWrap(bytes)| !bytes.is_empty())
+        .map(|BytesWrap(bytes)| {
+            #[cfg(feature = "bytecheck")]
+            let archived_res = CachedArchive::new(bytes);
+
+            #[cfg(not(feature = "bytecheck"))]
+            let archived_res = Ok(CachedArchive::new_unchecked(bytes));
+
+            archived_res
+        })
+        .collect()
+}
+
+type Values = Vec<Option<BytesWrap<AlignedVec<16>>>>;
+
+enum State<'c> {
+    Idle(Box<Pages<'c>>),
+    InFlight(BoxFuture<'c, (Box<Pages<'c>>, RedisResult<Values>)>),
+    Completed,
+}
+
+struct Pages<'c> {
+    conn: Connection<'c>,
+    key: Namespaced<'c, RedisKey>,
+    key_prefix: Vec<u8>,
+    page_size: usize,
+    cursor: u64,
+    scanned: bool,
+    ids: VecDeque<u64>,
+}
+
+impl Pages<'_> {
+    /// Whether all ids have been scanned and fetched.
+    fn is_exhausted(&self) -> bool {
+        self.scanned && self.cursor == 0 && self.ids.is_empty()
+    }
+
+    /// Scan ids until a page is filled and request their entries.
+    async fn fetch(mut self: Box<Self>, sleep: Option<Sleep>) -> (Box<Self>, RedisResult<Values>) {
+        if let Some(sleep) = sleep {
+            sleep.await;
+        }
+
+        let res = self.fetch_page().await;
+
+        (self, res)
+    }
+
+    async fn fetch_page(&mut self) -> RedisResult<Values> {
+        while self.ids.len() < self.page_size && !(self.scanned && self.cursor == 0) {
+            let (cursor, ids): (u64, Vec<u64>) = cmd("SSCAN")
+                .arg(&self.key)
+                .cursor_arg(self.cursor)
+                .arg("COUNT")
+                .arg(self.page_size)
+                .query_async(&mut self.conn)
+                .await?;
+
+            self.cursor = cursor;
+            self.scanned = true;
+            self.ids.extend(ids);
+        }
+
+        let len = self.ids.len().min(self.page_size);
+
+        if len == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut buf = Buffer::new();
+
+        let keys: Vec<Vec<u8>> = self
+            .ids
+            .drain(..len)
+            .map(|id| {
+                let id = buf.format(id);
+
+                let mut key = Vec::with_capacity(self.key_prefix.len() + id.len());
+                key.extend_from_slice(&self.key_prefix);
+                key.extend_from_slice(id.as_bytes());
+
+                key
+            })
+            .collect();
+
+        Cmd::mget(keys).query_async(&mut self.conn).await
+    }
+}
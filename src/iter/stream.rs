@@ -0,0 +1,71 @@
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+    vec::IntoIter,
+};
+
+use futures_util::{stream::StreamExt, Stream};
+
+use super::PagedIter;
+use crate::{config::Cacheable, CacheResult, CachedArchive};
+
+/// An iterator that streams cached entries one by one while fetching them in
+/// chunks.
+///
+/// Ids are scanned incrementally and the entries of each chunk are fetched
+/// through a single `MGET`, same as for [`PagedIter`], so neither all ids nor
+/// all entries are held in memory at once. The same caveats about entries
+/// that change during the iteration apply.
+///
+/// The items are of type [`CachedArchive`] wrapped in a [`Result`].
+pub struct StreamIter<'c, T> {
+    pages: PagedIter<'c, T>,
+    buffered: IntoIter<CachedArchive<T>>,
+}
+
+impl<'c, T: Cacheable> StreamIter<'c, T> {
+    pub(crate) fn new(pages: PagedIter<'c, T>) -> Self {
+        Self {
+            pages,
+            buffered: Vec::new().into_iter(),
+        }
+    }
+
+    /// Wait for the given duration before fetching each chunk after the
+    /// first one.
+    #[must_use]
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.pages = self.pages.with_delay(delay);
+
+        self
+    }
+
+    /// Retrieve the next item from the cache.
+    pub async fn next_item(&mut self) -> Option<CacheResult<CachedArchive<T>>> {
+        self.next().await
+    }
+}
+
+impl<T: Cacheable> Stream for StreamIter<'_, T> {
+    type Item = CacheResult<CachedArchive<T>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(item) = this.buffered.next() {
+                return Poll::Ready(Some(Ok(item)));
+            }
+
+            match ready!(this.pages.poll_next_unpin(cx)) {
+                Some(Ok(page)) => this.buffered = page.into_iter(),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+// The iterator is never pinned structurally.
+impl<T> Unpin for StreamIter<'_, T> {}
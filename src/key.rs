@@ -1,38 +1,217 @@
-use std::borrow::Cow;
+use std::str::FromStr;
 
 use itoa::Buffer;
 use twilight_model::id::{
     marker::{
-        ChannelMarker, EmojiMarker, GuildMarker, IntegrationMarker, MessageMarker, RoleMarker,
-        StageMarker, StickerMarker, UserMarker,
+        AutoModerationRuleMarker, ChannelMarker, EmojiMarker, GuildMarker, IntegrationMarker,
+        MessageMarker, RoleMarker, ScheduledEventMarker, StageMarker, StickerMarker, UserMarker,
     },
     Id,
 };
 
-use crate::redis::{RedisWrite, ToRedisArgs};
+use crate::{
+    config::EntityKind,
+    error::CacheError,
+    redis::{RedisWrite, ToRedisArgs},
+};
+
+/// Amount of digits of the largest `u64`.
+const MAX_ID_LEN: usize = 20;
+
+/// Maximum length of [`CacheConfig::KEY_PREFIX`].
+///
+/// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
+pub(crate) const MAX_KEY_PREFIX_LEN: usize = 32;
+
+/// The key prefix of a cache followed by a `:`, or empty if there is none.
+///
+/// All keys of a cache are rendered within its namespace so that caches of
+/// different key prefixes can share a redis instance.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Namespace(Box<str>);
+
+impl Namespace {
+    /// Create the namespace of the given key prefix.
+    ///
+    /// Fails if the prefix is too long.
+    pub(crate) fn new(prefix: &'static str) -> Result<Self, CacheError> {
+        if prefix.len() > MAX_KEY_PREFIX_LEN {
+            return Err(CacheError::InvalidKeyPrefix { prefix });
+        }
+
+        if prefix.is_empty() {
+            Ok(Self::default())
+        } else {
+            Ok(Self(format!("{prefix}:").into()))
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+
+    /// Render the given keys within the namespace when passed to `redis`
+    /// commands.
+    pub(crate) const fn key<K: Keys>(&self, keys: K) -> Namespaced<'_, K> {
+        Namespaced {
+            namespace: self,
+            keys,
+        }
+    }
+
+    /// Render the key within the namespace.
+    pub(crate) fn render(&self, key: &RedisKey) -> Vec<u8> {
+        self.render_with(&[], key)
+    }
+
+    /// Render the key within the namespace with an additional segment in
+    /// front of it, e.g. `{namespace}PREVIOUS:USER:123`.
+    pub(crate) fn render_with(&self, segment: &[u8], key: &RedisKey) -> Vec<u8> {
+        let mut prefix = Vec::with_capacity(self.0.len() + segment.len() + 1);
+        prefix.extend_from_slice(self.as_bytes());
+
+        if !segment.is_empty() {
+            prefix.extend_from_slice(segment);
+            prefix.push(b':');
+        }
+
+        let mut args = Vec::with_capacity(1);
+        key.write_arg(&prefix, &mut args);
+
+        args.pop().unwrap_or_default()
+    }
+
+    /// Parse a rendered key of this namespace.
+    pub(crate) fn parse(&self, bytes: &[u8]) -> Option<RedisKey> {
+        RedisKey::parse(bytes.strip_prefix(self.as_bytes())?)
+    }
+}
+
+/// Keys that are rendered within a [`Namespace`].
+///
+/// Created through [`Namespace::key`].
+pub(crate) struct Namespaced<'n, K> {
+    namespace: &'n Namespace,
+    keys: K,
+}
+
+impl<K: Keys> ToRedisArgs for Namespaced<'_, K> {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        for key in self.keys.as_keys() {
+            key.write_arg(self.namespace.as_bytes(), out);
+        }
+    }
+
+    fn is_single_arg(&self) -> bool {
+        self.keys.is_single()
+    }
+}
+
+/// One or many [`RedisKey`]s that can be rendered within a [`Namespace`].
+pub(crate) trait Keys {
+    fn as_keys(&self) -> &[RedisKey];
+
+    fn is_single(&self) -> bool {
+        false
+    }
+}
+
+impl Keys for RedisKey {
+    fn as_keys(&self) -> &[RedisKey] {
+        std::slice::from_ref(self)
+    }
+
+    fn is_single(&self) -> bool {
+        true
+    }
+}
+
+impl Keys for [RedisKey] {
+    fn as_keys(&self) -> &[RedisKey] {
+        self
+    }
+}
+
+impl<const N: usize> Keys for [RedisKey; N] {
+    fn as_keys(&self) -> &[RedisKey] {
+        self
+    }
+}
+
+impl Keys for Vec<RedisKey> {
+    fn as_keys(&self) -> &[RedisKey] {
+        self
+    }
+}
+
+impl<K: Keys + ?Sized> Keys for &K {
+    fn as_keys(&self) -> &[RedisKey] {
+        (**self).as_keys()
+    }
+
+    fn is_single(&self) -> bool {
+        (**self).is_single()
+    }
+}
 
 /// Keys for storing and loading data from redis.
 ///
 /// Implements `redis::ToRedisArgs` so it can be passed as argument
-/// to `redis` commands.
+/// to `redis` commands. Keys are rendered without
+/// [`CacheConfig::KEY_PREFIX`] so it has to be prepended manually when
+/// accessing keys of a cache with a key prefix.
 ///
 /// Each variant is documented with the kind of data it points to.
+///
+/// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[non_exhaustive]
 pub enum RedisKey {
+    /// Serialized `CacheConfig::AutoModerationRule`
+    AutoModerationRule { id: Id<AutoModerationRuleMarker> },
+    /// Serialized `AutoModerationRuleMeta`.
+    ///
+    /// Used for bookkeeping on expire events.
+    AutoModerationRuleMeta { id: Id<AutoModerationRuleMarker> },
+    /// Set of auto moderation rule ids
+    AutoModerationRules,
     /// Serialized `CacheConfig::Channel`
     Channel { id: Id<ChannelMarker> },
+    /// Guild id of a guild channel
+    ChannelGuild { id: Id<ChannelMarker> },
+    /// Hash of message ids to the length of their serialized bytes, as well
+    /// as their sum in the `total` field
+    ChannelMessageBytes { channel: Id<ChannelMarker> },
     /// Sorted set of message ids ordered by timestamp i.e. most recent to
     /// oldest
     ChannelMessages { channel: Id<ChannelMarker> },
+    /// Set of invite codes
+    ChannelInvites { id: Id<ChannelMarker> },
     /// Serialized `ChannelMeta`.
     ///
     /// Used for bookkeeping on expire events.
     ChannelMeta { id: Id<ChannelMarker> },
+    /// Set of user ids of thread members
+    ChannelThreadMembers { channel: Id<ChannelMarker> },
+    /// Webhooks of a channel, stored by the user rather than the cache.
+    ///
+    /// Deleted on `WebhooksUpdate` events.
+    ChannelWebhooks { channel: Id<ChannelMarker> },
     /// Set of channel ids
     Channels,
     /// Serialized `CacheConfig::CurrentUser`
     CurrentUser,
+    /// Serialized user-defined data, see [`RedisCache::custom`].
+    ///
+    /// [`RedisCache::custom`]: crate::RedisCache::custom
+    Custom { namespace: Box<str>, key: Box<str> },
     /// Serialized `CacheConfig::Emoji`
     Emoji { id: Id<EmojiMarker> },
     /// Serialized `EmojiMeta`.
@@ -43,22 +222,90 @@ pub enum RedisKey {
     Emojis,
     /// Serialized `CacheConfig::Guild`
     Guild { id: Id<GuildMarker> },
+    /// Sorted set of guild ids, scored by the unix timestamp in seconds of
+    /// their latest event
+    ///
+    /// Only tracked if [`ICachedGuild::STALENESS_THRESHOLD`] is set.
+    ///
+    /// [`ICachedGuild::STALENESS_THRESHOLD`]: crate::config::ICachedGuild::STALENESS_THRESHOLD
+    GuildActivity,
+    /// Set of auto moderation rule ids
+    GuildAutoModerationRules { id: Id<GuildMarker> },
+    /// Set of user ids
+    GuildBans { id: Id<GuildMarker> },
+    /// Unix timestamp in seconds of the last ban list backfill
+    GuildBansSnapshot { id: Id<GuildMarker> },
+    /// Set of user ids of members that boost the guild
+    GuildBoosters { id: Id<GuildMarker> },
     /// Set of channel ids
     GuildChannels { id: Id<GuildMarker> },
     /// Set of emoji ids
     GuildEmojis { id: Id<GuildMarker> },
     /// Set of integration ids
     GuildIntegrations { id: Id<GuildMarker> },
+    /// Set of invite codes
+    GuildInvites { id: Id<GuildMarker> },
+    /// Sorted set of lowercase member names, each followed by a null byte
+    /// and the user id, ordered lexicographically
+    ///
+    /// Only tracked if [`ICachedMember::INDEX_NAMES`] is enabled.
+    ///
+    /// [`ICachedMember::INDEX_NAMES`]: crate::config::ICachedMember::INDEX_NAMES
+    GuildMemberNames { id: Id<GuildMarker> },
+    /// Hash of user ids to their newline-separated indexed names
+    ///
+    /// Only tracked if [`ICachedMember::INDEX_NAMES`] is enabled.
+    ///
+    /// [`ICachedMember::INDEX_NAMES`]: crate::config::ICachedMember::INDEX_NAMES
+    GuildMemberNamesByUser { id: Id<GuildMarker> },
     /// Set of user ids
     GuildMembers { id: Id<GuildMarker> },
+    /// Sorted set of zero-padded user ids, ordered lexicographically
+    GuildMembersOrdered { id: Id<GuildMarker> },
+    /// Hash of user ids to the hash of their serialized presence
+    ///
+    /// Only tracked if [`ICachedPresence::SKIP_UNCHANGED`] is enabled.
+    ///
+    /// [`ICachedPresence::SKIP_UNCHANGED`]: crate::config::ICachedPresence::SKIP_UNCHANGED
+    GuildPresenceHashes { id: Id<GuildMarker> },
+    /// Sorted set of user ids, scored by the unix timestamp in seconds of
+    /// their latest presence
+    ///
+    /// Only tracked if [`ICachedPresence::TRACK_FRESHNESS`] is enabled.
+    ///
+    /// [`ICachedPresence::TRACK_FRESHNESS`]: crate::config::ICachedPresence::TRACK_FRESHNESS
+    GuildPresenceStamps { id: Id<GuildMarker> },
+    /// Hash of user ids to a single byte of their status
+    ///
+    /// Only tracked if [`CacheConfig::PRESENCE_STATUS`] is enabled.
+    ///
+    /// [`CacheConfig::PRESENCE_STATUS`]: crate::config::CacheConfig::PRESENCE_STATUS
+    GuildPresenceStatus { id: Id<GuildMarker> },
     /// Set of user ids
     GuildPresences { id: Id<GuildMarker> },
     /// Set of role ids
     GuildRoles { id: Id<GuildMarker> },
+    /// Set of scheduled event ids
+    GuildScheduledEvents { id: Id<GuildMarker> },
     /// Set of stage instance ids
     GuildStageInstances { id: Id<GuildMarker> },
+    /// Sorted set of message ids, scored by [`ICachedMessage::starboard_score`]
+    ///
+    /// [`ICachedMessage::starboard_score`]: crate::config::ICachedMessage::starboard_score
+    GuildStarboard { id: Id<GuildMarker> },
     /// Set of sticker ids
     GuildStickers { id: Id<GuildMarker> },
+    /// Hash of moved keys to their remaining TTL in milliseconds
+    ///
+    /// Used to restore a deleted guild.
+    GuildTombstone { id: Id<GuildMarker> },
+    /// Sorted set of user ids, scored by the unix timestamp in seconds of
+    /// their latest voice state
+    ///
+    /// Only tracked if [`ICachedVoiceState::TRACK_FRESHNESS`] is enabled.
+    ///
+    /// [`ICachedVoiceState::TRACK_FRESHNESS`]: crate::config::ICachedVoiceState::TRACK_FRESHNESS
+    GuildVoiceStateStamps { id: Id<GuildMarker> },
     /// Set of user ids
     GuildVoiceStates { id: Id<GuildMarker> },
     /// Set of guild ids
@@ -68,6 +315,12 @@ pub enum RedisKey {
         guild: Id<GuildMarker>,
         id: Id<IntegrationMarker>,
     },
+    /// Serialized `CacheConfig::Invite`
+    Invite { code: Box<str> },
+    /// Serialized `InviteMeta`.
+    ///
+    /// Used for bookkeeping on expire events.
+    InviteMeta { code: Box<str> },
     /// Serialized `CacheConfig::Member`
     Member {
         guild: Id<GuildMarker>,
@@ -75,17 +328,26 @@ pub enum RedisKey {
     },
     /// Serialized `CacheConfig::Message`
     Message { id: Id<MessageMarker> },
+    /// Hash of message ids to the user id of their author
+    MessageAuthors,
+    /// List of previous serialized `CacheConfig::Message`, most recent first
+    MessageHistory { id: Id<MessageMarker> },
     /// Serialized `MessageMeta`.
     ///
-    /// Used for bookkeeping on expire events.
+    /// Used for bookkeeping on expire events and to detect unchanged messages.
     MessageMeta { id: Id<MessageMarker> },
     /// Set of message ids
     Messages,
+    #[cfg(feature = "metrics")]
+    /// Identifier of the instance that currently publishes collection sizes
+    MetricsLeader,
     /// Serialized `CacheConfig::Presence`
     Presence {
         guild: Id<GuildMarker>,
         user: Id<UserMarker>,
     },
+    /// Hash of sampled getter hits and misses per entity kind
+    ReadCounters,
     /// Serialized `CacheConfig::Role`
     Role { id: Id<RoleMarker> },
     /// Serialized `RoleMeta`.
@@ -94,9 +356,23 @@ pub enum RedisKey {
     RoleMeta { id: Id<RoleMarker> },
     /// Set of role ids
     Roles,
+    /// Serialized `CacheConfig::ScheduledEvent`
+    ScheduledEvent { id: Id<ScheduledEventMarker> },
+    /// Serialized `ScheduledEventMeta`.
+    ///
+    /// Used for bookkeeping on expire events.
+    ScheduledEventMeta { id: Id<ScheduledEventMarker> },
+    /// Set of scheduled event ids
+    ScheduledEvents,
+    /// Hash of the stored `Cacheable::VERSION` per entity kind
+    SchemaVersions,
     #[cfg(feature = "cold_resume")]
     /// Serialized `SessionsWrapper`
     Sessions,
+    /// Sequence of the last applied event of a shard
+    ShardSequence { shard: u32 },
+    /// Id of the process that currently writes events of a shard
+    ShardWriter { shard: u32 },
     /// Serialized `CacheConfig::StageInstance`
     StageInstance { id: Id<StageMarker> },
     /// Serialized `StageInstanceMeta`.
@@ -113,6 +389,11 @@ pub enum RedisKey {
     StickerMeta { id: Id<StickerMarker> },
     /// Set of sticker ids
     Stickers,
+    /// Serialized `CacheConfig::ThreadMember`
+    ThreadMember {
+        channel: Id<ChannelMarker>,
+        user: Id<UserMarker>,
+    },
     /// Set of guild ids
     UnavailableGuilds,
     /// Serialized `CacheConfig::User`
@@ -129,47 +410,973 @@ pub enum RedisKey {
 }
 
 impl RedisKey {
+    #[cfg(feature = "attachments")]
+    pub(crate) const ATTACHMENT_PREFIX: &'static [u8] = b"ATTACHMENT";
+    pub(crate) const AUTO_MODERATION_RULE_PREFIX: &'static [u8] = b"AUTO_MODERATION_RULE";
+    pub(crate) const AUTO_MODERATION_RULE_META_PREFIX: &'static [u8] = b"AUTO_MODERATION_RULE_META";
+    pub(crate) const AUTO_MODERATION_RULES_PREFIX: &'static [u8] = b"AUTO_MODERATION_RULES";
     pub(crate) const CHANNEL_PREFIX: &'static [u8] = b"CHANNEL";
+    pub(crate) const CHANNEL_GUILD_PREFIX: &'static [u8] = b"CHANNEL_GUILD";
+    pub(crate) const CHANNEL_MESSAGE_BYTES_PREFIX: &'static [u8] = b"CHANNEL_MESSAGE_BYTES";
     pub(crate) const CHANNEL_MESSAGES_PREFIX: &'static [u8] = b"CHANNEL_MESSAGES_META";
+    pub(crate) const CHANNEL_INVITES_PREFIX: &'static [u8] = b"CHANNEL_INVITES";
     pub(crate) const CHANNEL_META_PREFIX: &'static [u8] = b"CHANNEL_META";
+    pub(crate) const CHANNEL_THREAD_MEMBERS_PREFIX: &'static [u8] = b"CHANNEL_THREAD_MEMBERS";
+    pub(crate) const CHANNEL_WEBHOOKS_PREFIX: &'static [u8] = b"CHANNEL_WEBHOOKS";
     pub(crate) const CHANNELS_PREFIX: &'static [u8] = b"CHANNELS";
     pub(crate) const CURRENT_USER_PREFIX: &'static [u8] = b"CURRENT_USER";
+    pub(crate) const CUSTOM_PREFIX: &'static [u8] = b"CUSTOM";
     pub(crate) const EMOJI_PREFIX: &'static [u8] = b"EMOJI";
     pub(crate) const EMOJI_META_PREFIX: &'static [u8] = b"EMOJI_META";
     pub(crate) const EMOJIS_PREFIX: &'static [u8] = b"EMOJIS";
     pub(crate) const GUILD_PREFIX: &'static [u8] = b"GUILD";
+    pub(crate) const GUILD_ACTIVITY_PREFIX: &'static [u8] = b"GUILD_ACTIVITY";
+    pub(crate) const GUILD_AUTO_MODERATION_RULES_PREFIX: &'static [u8] =
+        b"GUILD_AUTO_MODERATION_RULES";
+    pub(crate) const GUILD_BANS_PREFIX: &'static [u8] = b"GUILD_BANS";
+    pub(crate) const GUILD_BANS_SNAPSHOT_PREFIX: &'static [u8] = b"GUILD_BANS_SNAPSHOT";
+    pub(crate) const GUILD_BOOSTERS_PREFIX: &'static [u8] = b"GUILD_BOOSTERS";
     pub(crate) const GUILD_CHANNELS_PREFIX: &'static [u8] = b"GUILD_CHANNELS";
     pub(crate) const GUILD_EMOJIS_PREFIX: &'static [u8] = b"GUILD_EMOJIS";
     pub(crate) const GUILD_INTEGRATIONS_PREFIX: &'static [u8] = b"GUILD_INTEGRATIONS";
+    pub(crate) const GUILD_INVITES_PREFIX: &'static [u8] = b"GUILD_INVITES";
+    pub(crate) const GUILD_MEMBER_NAMES_PREFIX: &'static [u8] = b"GUILD_MEMBER_NAMES";
+    pub(crate) const GUILD_MEMBER_NAMES_BY_USER_PREFIX: &'static [u8] =
+        b"GUILD_MEMBER_NAMES_BY_USER";
     pub(crate) const GUILD_MEMBERS_PREFIX: &'static [u8] = b"GUILD_MEMBERS";
+    pub(crate) const GUILD_MEMBERS_ORDERED_PREFIX: &'static [u8] = b"GUILD_MEMBERS_ORDERED";
+    pub(crate) const GUILD_PRESENCE_HASHES_PREFIX: &'static [u8] = b"GUILD_PRESENCE_HASHES";
+    pub(crate) const GUILD_PRESENCE_STAMPS_PREFIX: &'static [u8] = b"GUILD_PRESENCE_STAMPS";
+    pub(crate) const GUILD_PRESENCE_STATUS_PREFIX: &'static [u8] = b"GUILD_PRESENCE_STATUS";
     pub(crate) const GUILD_PRESENCES_PREFIX: &'static [u8] = b"GUILD_PRESENCES";
     pub(crate) const GUILD_ROLES_PREFIX: &'static [u8] = b"GUILD_ROLES";
+    pub(crate) const GUILD_SCHEDULED_EVENTS_PREFIX: &'static [u8] = b"GUILD_SCHEDULED_EVENTS";
     pub(crate) const GUILD_STAGE_INSTANCES_PREFIX: &'static [u8] = b"GUILD_STAGE_INSTANCES";
+    pub(crate) const GUILD_STARBOARD_PREFIX: &'static [u8] = b"GUILD_STARBOARD";
     pub(crate) const GUILD_STICKERS_PREFIX: &'static [u8] = b"GUILD_STICKERS";
+    pub(crate) const GUILD_TOMBSTONE_PREFIX: &'static [u8] = b"GUILD_TOMBSTONE";
+    pub(crate) const GUILD_VOICE_STATE_STAMPS_PREFIX: &'static [u8] = b"GUILD_VOICE_STATE_STAMPS";
     pub(crate) const GUILD_VOICE_STATES_PREFIX: &'static [u8] = b"GUILD_VOICE_STATES";
     pub(crate) const GUILDS_PREFIX: &'static [u8] = b"GUILDS";
     pub(crate) const INTEGRATION_PREFIX: &'static [u8] = b"INTEGRATION";
+    #[cfg(feature = "serde-mirror")]
+    pub(crate) const JSON_MIRROR_PREFIX: &'static [u8] = b"JSON";
+    pub(crate) const INVITE_PREFIX: &'static [u8] = b"INVITE";
+    pub(crate) const INVITE_META_PREFIX: &'static [u8] = b"INVITE_META";
     pub(crate) const MEMBER_PREFIX: &'static [u8] = b"MEMBER";
     pub(crate) const MESSAGE_PREFIX: &'static [u8] = b"MESSAGE";
+    pub(crate) const MESSAGE_AUTHORS_PREFIX: &'static [u8] = b"MESSAGE_AUTHORS";
+    pub(crate) const MESSAGE_HISTORY_PREFIX: &'static [u8] = b"MESSAGE_HISTORY";
     pub(crate) const MESSAGE_META_PREFIX: &'static [u8] = b"MESSAGE_META";
     pub(crate) const MESSAGES_PREFIX: &'static [u8] = b"MESSAGES";
+    #[cfg(feature = "metrics")]
+    pub(crate) const METRICS_LEADER_PREFIX: &'static [u8] = b"METRICS_LEADER";
     pub(crate) const PRESENCE_PREFIX: &'static [u8] = b"PRESENCE";
+    pub(crate) const PREVIOUS_PREFIX: &'static [u8] = b"PREVIOUS";
+    pub(crate) const READ_COUNTERS_PREFIX: &'static [u8] = b"READ_COUNTERS";
     pub(crate) const ROLE_PREFIX: &'static [u8] = b"ROLE";
     pub(crate) const ROLE_META_PREFIX: &'static [u8] = b"ROLE_META";
     pub(crate) const ROLES_PREFIX: &'static [u8] = b"ROLES";
+    pub(crate) const SCHEDULED_EVENT_PREFIX: &'static [u8] = b"SCHEDULED_EVENT";
+    pub(crate) const SCHEDULED_EVENT_META_PREFIX: &'static [u8] = b"SCHEDULED_EVENT_META";
+    pub(crate) const SCHEDULED_EVENTS_PREFIX: &'static [u8] = b"SCHEDULED_EVENTS";
+    pub(crate) const SCHEMA_VERSIONS_PREFIX: &'static [u8] = b"SCHEMA_VERSIONS";
     #[cfg(feature = "cold_resume")]
     pub(crate) const SESSIONS_PREFIX: &'static [u8] = b"SESSIONS";
+    pub(crate) const SHARD_SEQUENCE_PREFIX: &'static [u8] = b"SHARD_SEQUENCE";
+    pub(crate) const SHARD_WRITER_PREFIX: &'static [u8] = b"SHARD_WRITER";
     pub(crate) const STAGE_INSTANCE_PREFIX: &'static [u8] = b"STAGE_INSTANCE";
     pub(crate) const STAGE_INSTANCE_META_PREFIX: &'static [u8] = b"STAGE_INSTANCE_META";
     pub(crate) const STAGE_INSTANCES_PREFIX: &'static [u8] = b"STAGE_INSTANCES";
     pub(crate) const STICKER_PREFIX: &'static [u8] = b"STICKER";
     pub(crate) const STICKER_META_PREFIX: &'static [u8] = b"STICKER_META";
     pub(crate) const STICKERS_PREFIX: &'static [u8] = b"STICKERS";
+    pub(crate) const THREAD_MEMBER_PREFIX: &'static [u8] = b"THREAD_MEMBER";
+    pub(crate) const TOMBSTONE_PREFIX: &'static [u8] = b"TOMBSTONE";
     pub(crate) const UNAVAILABLE_GUILDS_PREFIX: &'static [u8] = b"UNAVAILABLE_GUILDS";
     pub(crate) const USER_PREFIX: &'static [u8] = b"USER";
     pub(crate) const USER_GUILDS_PREFIX: &'static [u8] = b"USER_GUILDS";
     pub(crate) const USERS_PREFIX: &'static [u8] = b"USERS";
+    pub(crate) const VERSION_PREFIX: &'static [u8] = b"VERSION";
     pub(crate) const VOICE_STATE_PREFIX: &'static [u8] = b"VOICE_STATE";
+
+    const SCHEMA: &'static [KeySchema] = &[
+        KeySchema::new(
+            "AutoModerationRule",
+            Self::AUTO_MODERATION_RULE_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "AutoModerationRuleMeta",
+            Self::AUTO_MODERATION_RULE_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "AutoModerationRules",
+            Self::AUTO_MODERATION_RULES_PREFIX,
+            &[],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "Channel",
+            Self::CHANNEL_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ChannelGuild",
+            Self::CHANNEL_GUILD_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ChannelMessageBytes",
+            Self::CHANNEL_MESSAGE_BYTES_PREFIX,
+            &["channel"],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new(
+            "ChannelMessages",
+            Self::CHANNEL_MESSAGES_PREFIX,
+            &["channel"],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "ChannelInvites",
+            Self::CHANNEL_INVITES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "ChannelMeta",
+            Self::CHANNEL_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ChannelThreadMembers",
+            Self::CHANNEL_THREAD_MEMBERS_PREFIX,
+            &["channel"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "ChannelWebhooks",
+            Self::CHANNEL_WEBHOOKS_PREFIX,
+            &["channel"],
+            KeyValueType::String,
+        ),
+        KeySchema::new("Channels", Self::CHANNELS_PREFIX, &[], KeyValueType::Set),
+        KeySchema::new(
+            "CurrentUser",
+            Self::CURRENT_USER_PREFIX,
+            &[],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "Custom",
+            Self::CUSTOM_PREFIX,
+            &["namespace", "key"],
+            KeyValueType::String,
+        ),
+        KeySchema::new("Emoji", Self::EMOJI_PREFIX, &["id"], KeyValueType::String),
+        KeySchema::new(
+            "EmojiMeta",
+            Self::EMOJI_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new("Emojis", Self::EMOJIS_PREFIX, &[], KeyValueType::Set),
+        KeySchema::new("Guild", Self::GUILD_PREFIX, &["id"], KeyValueType::String),
+        KeySchema::new(
+            "GuildActivity",
+            Self::GUILD_ACTIVITY_PREFIX,
+            &[],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "GuildAutoModerationRules",
+            Self::GUILD_AUTO_MODERATION_RULES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildBans",
+            Self::GUILD_BANS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildBansSnapshot",
+            Self::GUILD_BANS_SNAPSHOT_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "GuildBoosters",
+            Self::GUILD_BOOSTERS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildChannels",
+            Self::GUILD_CHANNELS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildEmojis",
+            Self::GUILD_EMOJIS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildIntegrations",
+            Self::GUILD_INTEGRATIONS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildInvites",
+            Self::GUILD_INVITES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildMemberNames",
+            Self::GUILD_MEMBER_NAMES_PREFIX,
+            &["id"],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "GuildMemberNamesByUser",
+            Self::GUILD_MEMBER_NAMES_BY_USER_PREFIX,
+            &["id"],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new(
+            "GuildMembers",
+            Self::GUILD_MEMBERS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildMembersOrdered",
+            Self::GUILD_MEMBERS_ORDERED_PREFIX,
+            &["id"],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "GuildPresenceHashes",
+            Self::GUILD_PRESENCE_HASHES_PREFIX,
+            &["id"],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new(
+            "GuildPresenceStamps",
+            Self::GUILD_PRESENCE_STAMPS_PREFIX,
+            &["id"],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "GuildPresenceStatus",
+            Self::GUILD_PRESENCE_STATUS_PREFIX,
+            &["id"],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new(
+            "GuildPresences",
+            Self::GUILD_PRESENCES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildRoles",
+            Self::GUILD_ROLES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildScheduledEvents",
+            Self::GUILD_SCHEDULED_EVENTS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildStageInstances",
+            Self::GUILD_STAGE_INSTANCES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildStarboard",
+            Self::GUILD_STARBOARD_PREFIX,
+            &["id"],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "GuildStickers",
+            Self::GUILD_STICKERS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "GuildTombstone",
+            Self::GUILD_TOMBSTONE_PREFIX,
+            &["id"],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new(
+            "GuildVoiceStateStamps",
+            Self::GUILD_VOICE_STATE_STAMPS_PREFIX,
+            &["id"],
+            KeyValueType::SortedSet,
+        ),
+        KeySchema::new(
+            "GuildVoiceStates",
+            Self::GUILD_VOICE_STATES_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new("Guilds", Self::GUILDS_PREFIX, &[], KeyValueType::Set),
+        KeySchema::new(
+            "Integration",
+            Self::INTEGRATION_PREFIX,
+            &["guild", "id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "Invite",
+            Self::INVITE_PREFIX,
+            &["code"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "InviteMeta",
+            Self::INVITE_META_PREFIX,
+            &["code"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "Member",
+            Self::MEMBER_PREFIX,
+            &["guild", "user"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "Message",
+            Self::MESSAGE_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "MessageAuthors",
+            Self::MESSAGE_AUTHORS_PREFIX,
+            &[],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new(
+            "MessageHistory",
+            Self::MESSAGE_HISTORY_PREFIX,
+            &["id"],
+            KeyValueType::List,
+        ),
+        KeySchema::new(
+            "MessageMeta",
+            Self::MESSAGE_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new("Messages", Self::MESSAGES_PREFIX, &[], KeyValueType::Set),
+        #[cfg(feature = "metrics")]
+        KeySchema::new(
+            "MetricsLeader",
+            Self::METRICS_LEADER_PREFIX,
+            &[],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "Presence",
+            Self::PRESENCE_PREFIX,
+            &["guild", "user"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ReadCounters",
+            Self::READ_COUNTERS_PREFIX,
+            &[],
+            KeyValueType::Hash,
+        ),
+        KeySchema::new("Role", Self::ROLE_PREFIX, &["id"], KeyValueType::String),
+        KeySchema::new(
+            "RoleMeta",
+            Self::ROLE_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new("Roles", Self::ROLES_PREFIX, &[], KeyValueType::Set),
+        KeySchema::new(
+            "ScheduledEvent",
+            Self::SCHEDULED_EVENT_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ScheduledEventMeta",
+            Self::SCHEDULED_EVENT_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ScheduledEvents",
+            Self::SCHEDULED_EVENTS_PREFIX,
+            &[],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "SchemaVersions",
+            Self::SCHEMA_VERSIONS_PREFIX,
+            &[],
+            KeyValueType::Hash,
+        ),
+        #[cfg(feature = "cold_resume")]
+        KeySchema::new("Sessions", Self::SESSIONS_PREFIX, &[], KeyValueType::String),
+        KeySchema::new(
+            "ShardSequence",
+            Self::SHARD_SEQUENCE_PREFIX,
+            &["shard"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "ShardWriter",
+            Self::SHARD_WRITER_PREFIX,
+            &["shard"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "StageInstance",
+            Self::STAGE_INSTANCE_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "StageInstanceMeta",
+            Self::STAGE_INSTANCE_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "StageInstances",
+            Self::STAGE_INSTANCES_PREFIX,
+            &[],
+            KeyValueType::Set,
+        ),
+        KeySchema::new(
+            "Sticker",
+            Self::STICKER_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "StickerMeta",
+            Self::STICKER_META_PREFIX,
+            &["id"],
+            KeyValueType::String,
+        ),
+        KeySchema::new("Stickers", Self::STICKERS_PREFIX, &[], KeyValueType::Set),
+        KeySchema::new(
+            "ThreadMember",
+            Self::THREAD_MEMBER_PREFIX,
+            &["channel", "user"],
+            KeyValueType::String,
+        ),
+        KeySchema::new(
+            "UnavailableGuilds",
+            Self::UNAVAILABLE_GUILDS_PREFIX,
+            &[],
+            KeyValueType::Set,
+        ),
+        KeySchema::new("User", Self::USER_PREFIX, &["id"], KeyValueType::String),
+        KeySchema::new(
+            "UserGuilds",
+            Self::USER_GUILDS_PREFIX,
+            &["id"],
+            KeyValueType::Set,
+        ),
+        KeySchema::new("Users", Self::USERS_PREFIX, &[], KeyValueType::Set),
+        KeySchema::new(
+            "VoiceState",
+            Self::VOICE_STATE_PREFIX,
+            &["guild", "user"],
+            KeyValueType::String,
+        ),
+    ];
+
+    /// Description of the layout of every key variant.
+    ///
+    /// Useful to generate external tools such as backups or dashboards
+    /// against the cache's keyspace instead of hardcoding key names.
+    ///
+    /// Keys consist of their prefix followed by their segments, all
+    /// separated by `:`, e.g. `MEMBER:{guild}:{user}`. If
+    /// [`CacheConfig::KEY_PREFIX`] is set, it precedes all keys, e.g.
+    /// `{key prefix}:MEMBER:{guild}:{user}`.
+    ///
+    /// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
+    pub const fn schema() -> &'static [KeySchema] {
+        Self::SCHEMA
+    }
+
+    /// Upper bound of the rendered length of keys, including the key prefix.
+    ///
+    /// Only invite keys with unusually long codes and custom keys with long
+    /// namespaces or keys may exceed it.
+    pub const MAX_LEN: usize =
+        MAX_KEY_PREFIX_LEN + 1 + max_prefix_len(Self::SCHEMA) + 2 * (1 + MAX_ID_LEN);
+
+    /// Render the key into the buffer and return the amount of written
+    /// bytes.
+    ///
+    /// Unlike going through `redis::ToRedisArgs`, this does not allocate.
+    /// Just like it, the key is rendered without [`CacheConfig::KEY_PREFIX`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer is too short for the rendered key. A buffer of
+    /// [`RedisKey::MAX_LEN`] bytes suffices for all keys other than invite
+    /// keys with unusually long codes and custom keys with long namespaces or
+    /// keys.
+    ///
+    /// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
+    pub fn to_bytes(&self, buf: &mut [u8]) -> usize {
+        self.to_bytes_in(b"", buf)
+    }
+
+    /// Render the key within the namespace into the buffer and return the
+    /// amount of written bytes.
+    pub(crate) fn to_bytes_in(&self, namespace: &[u8], buf: &mut [u8]) -> usize {
+        let mut writer = SliceWriter { buf, len: 0 };
+        writer.push(namespace);
+
+        match self.parts() {
+            Parts::Prefix(prefix) => writer.push(prefix),
+            Parts::Id(prefix, id) => {
+                writer.push(prefix);
+                writer.push_id(id);
+            }
+            Parts::Str(prefix, s) => {
+                writer.push(prefix);
+                writer.push(b":");
+                writer.push(s.as_bytes());
+            }
+            Parts::Strs(prefix, a, b) => {
+                writer.push(prefix);
+                writer.push(b":");
+                writer.push(a.as_bytes());
+                writer.push(b":");
+                writer.push(b.as_bytes());
+            }
+            Parts::GuildId(prefix, guild, id) => {
+                writer.push(prefix);
+                writer.push_id(guild);
+                writer.push_id(id);
+            }
+        }
+
+        writer.len
+    }
+
+    // One arm per key variant
+    #[allow(clippy::too_many_lines)]
+    fn parts(&self) -> Parts<'_> {
+        match self {
+            Self::AutoModerationRule { id } => {
+                Parts::Id(Self::AUTO_MODERATION_RULE_PREFIX, id.get())
+            }
+            Self::AutoModerationRuleMeta { id } => {
+                Parts::Id(Self::AUTO_MODERATION_RULE_META_PREFIX, id.get())
+            }
+            Self::AutoModerationRules => Parts::Prefix(Self::AUTO_MODERATION_RULES_PREFIX),
+            Self::Channel { id } => Parts::Id(Self::CHANNEL_PREFIX, id.get()),
+            Self::ChannelGuild { id } => Parts::Id(Self::CHANNEL_GUILD_PREFIX, id.get()),
+            Self::ChannelMessageBytes { channel } => {
+                Parts::Id(Self::CHANNEL_MESSAGE_BYTES_PREFIX, channel.get())
+            }
+            Self::ChannelMessages { channel } => {
+                Parts::Id(Self::CHANNEL_MESSAGES_PREFIX, channel.get())
+            }
+            Self::ChannelInvites { id } => Parts::Id(Self::CHANNEL_INVITES_PREFIX, id.get()),
+            Self::ChannelMeta { id } => Parts::Id(Self::CHANNEL_META_PREFIX, id.get()),
+            Self::ChannelThreadMembers { channel } => {
+                Parts::Id(Self::CHANNEL_THREAD_MEMBERS_PREFIX, channel.get())
+            }
+            Self::ChannelWebhooks { channel } => {
+                Parts::Id(Self::CHANNEL_WEBHOOKS_PREFIX, channel.get())
+            }
+            Self::Channels => Parts::Prefix(Self::CHANNELS_PREFIX),
+            Self::CurrentUser => Parts::Prefix(Self::CURRENT_USER_PREFIX),
+            Self::Custom { namespace, key } => Parts::Strs(Self::CUSTOM_PREFIX, namespace, key),
+            Self::Emoji { id } => Parts::Id(Self::EMOJI_PREFIX, id.get()),
+            Self::EmojiMeta { id } => Parts::Id(Self::EMOJI_META_PREFIX, id.get()),
+            Self::Emojis => Parts::Prefix(Self::EMOJIS_PREFIX),
+            Self::Guild { id } => Parts::Id(Self::GUILD_PREFIX, id.get()),
+            Self::GuildActivity => Parts::Prefix(Self::GUILD_ACTIVITY_PREFIX),
+            Self::GuildAutoModerationRules { id } => {
+                Parts::Id(Self::GUILD_AUTO_MODERATION_RULES_PREFIX, id.get())
+            }
+            Self::GuildBans { id } => Parts::Id(Self::GUILD_BANS_PREFIX, id.get()),
+            Self::GuildBansSnapshot { id } => Parts::Id(Self::GUILD_BANS_SNAPSHOT_PREFIX, id.get()),
+            Self::GuildBoosters { id } => Parts::Id(Self::GUILD_BOOSTERS_PREFIX, id.get()),
+            Self::GuildChannels { id } => Parts::Id(Self::GUILD_CHANNELS_PREFIX, id.get()),
+            Self::GuildEmojis { id } => Parts::Id(Self::GUILD_EMOJIS_PREFIX, id.get()),
+            Self::GuildIntegrations { id } => Parts::Id(Self::GUILD_INTEGRATIONS_PREFIX, id.get()),
+            Self::GuildInvites { id } => Parts::Id(Self::GUILD_INVITES_PREFIX, id.get()),
+            Self::GuildMemberNames { id } => Parts::Id(Self::GUILD_MEMBER_NAMES_PREFIX, id.get()),
+            Self::GuildMemberNamesByUser { id } => {
+                Parts::Id(Self::GUILD_MEMBER_NAMES_BY_USER_PREFIX, id.get())
+            }
+            Self::GuildMembers { id } => Parts::Id(Self::GUILD_MEMBERS_PREFIX, id.get()),
+            Self::GuildMembersOrdered { id } => {
+                Parts::Id(Self::GUILD_MEMBERS_ORDERED_PREFIX, id.get())
+            }
+            Self::GuildPresenceHashes { id } => {
+                Parts::Id(Self::GUILD_PRESENCE_HASHES_PREFIX, id.get())
+            }
+            Self::GuildPresenceStamps { id } => {
+                Parts::Id(Self::GUILD_PRESENCE_STAMPS_PREFIX, id.get())
+            }
+            Self::GuildPresenceStatus { id } => {
+                Parts::Id(Self::GUILD_PRESENCE_STATUS_PREFIX, id.get())
+            }
+            Self::GuildPresences { id } => Parts::Id(Self::GUILD_PRESENCES_PREFIX, id.get()),
+            Self::GuildRoles { id } => Parts::Id(Self::GUILD_ROLES_PREFIX, id.get()),
+            Self::GuildScheduledEvents { id } => {
+                Parts::Id(Self::GUILD_SCHEDULED_EVENTS_PREFIX, id.get())
+            }
+            Self::GuildStageInstances { id } => {
+                Parts::Id(Self::GUILD_STAGE_INSTANCES_PREFIX, id.get())
+            }
+            Self::GuildStarboard { id } => Parts::Id(Self::GUILD_STARBOARD_PREFIX, id.get()),
+            Self::GuildStickers { id } => Parts::Id(Self::GUILD_STICKERS_PREFIX, id.get()),
+            Self::GuildTombstone { id } => Parts::Id(Self::GUILD_TOMBSTONE_PREFIX, id.get()),
+            Self::GuildVoiceStateStamps { id } => {
+                Parts::Id(Self::GUILD_VOICE_STATE_STAMPS_PREFIX, id.get())
+            }
+            Self::GuildVoiceStates { id } => Parts::Id(Self::GUILD_VOICE_STATES_PREFIX, id.get()),
+            Self::Guilds => Parts::Prefix(Self::GUILDS_PREFIX),
+            Self::Integration { guild, id } => {
+                Parts::GuildId(Self::INTEGRATION_PREFIX, guild.get(), id.get())
+            }
+            Self::Invite { code } => Parts::Str(Self::INVITE_PREFIX, code),
+            Self::InviteMeta { code } => Parts::Str(Self::INVITE_META_PREFIX, code),
+            Self::Member { user, guild } => {
+                Parts::GuildId(Self::MEMBER_PREFIX, guild.get(), user.get())
+            }
+            Self::Message { id } => Parts::Id(Self::MESSAGE_PREFIX, id.get()),
+            Self::MessageAuthors => Parts::Prefix(Self::MESSAGE_AUTHORS_PREFIX),
+            Self::MessageHistory { id } => Parts::Id(Self::MESSAGE_HISTORY_PREFIX, id.get()),
+            Self::MessageMeta { id } => Parts::Id(Self::MESSAGE_META_PREFIX, id.get()),
+            Self::Messages => Parts::Prefix(Self::MESSAGES_PREFIX),
+            #[cfg(feature = "metrics")]
+            Self::MetricsLeader => Parts::Prefix(Self::METRICS_LEADER_PREFIX),
+            Self::Presence { guild, user } => {
+                Parts::GuildId(Self::PRESENCE_PREFIX, guild.get(), user.get())
+            }
+            Self::ReadCounters => Parts::Prefix(Self::READ_COUNTERS_PREFIX),
+            Self::Role { id } => Parts::Id(Self::ROLE_PREFIX, id.get()),
+            Self::RoleMeta { id } => Parts::Id(Self::ROLE_META_PREFIX, id.get()),
+            Self::Roles => Parts::Prefix(Self::ROLES_PREFIX),
+            Self::ScheduledEvent { id } => Parts::Id(Self::SCHEDULED_EVENT_PREFIX, id.get()),
+            Self::ScheduledEventMeta { id } => {
+                Parts::Id(Self::SCHEDULED_EVENT_META_PREFIX, id.get())
+            }
+            Self::ScheduledEvents => Parts::Prefix(Self::SCHEDULED_EVENTS_PREFIX),
+            Self::SchemaVersions => Parts::Prefix(Self::SCHEMA_VERSIONS_PREFIX),
+            #[cfg(feature = "cold_resume")]
+            Self::Sessions => Parts::Prefix(Self::SESSIONS_PREFIX),
+            Self::ShardSequence { shard } => {
+                Parts::Id(Self::SHARD_SEQUENCE_PREFIX, u64::from(*shard))
+            }
+            Self::ShardWriter { shard } => Parts::Id(Self::SHARD_WRITER_PREFIX, u64::from(*shard)),
+            Self::StageInstance { id } => Parts::Id(Self::STAGE_INSTANCE_PREFIX, id.get()),
+            Self::StageInstanceMeta { id } => Parts::Id(Self::STAGE_INSTANCE_META_PREFIX, id.get()),
+            Self::StageInstances => Parts::Prefix(Self::STAGE_INSTANCES_PREFIX),
+            Self::Sticker { id } => Parts::Id(Self::STICKER_PREFIX, id.get()),
+            Self::StickerMeta { id } => Parts::Id(Self::STICKER_META_PREFIX, id.get()),
+            Self::Stickers => Parts::Prefix(Self::STICKERS_PREFIX),
+            Self::ThreadMember { channel, user } => {
+                Parts::GuildId(Self::THREAD_MEMBER_PREFIX, channel.get(), user.get())
+            }
+            Self::UnavailableGuilds => Parts::Prefix(Self::UNAVAILABLE_GUILDS_PREFIX),
+            Self::User { id } => Parts::Id(Self::USER_PREFIX, id.get()),
+            Self::UserGuilds { id } => Parts::Id(Self::USER_GUILDS_PREFIX, id.get()),
+            Self::Users => Parts::Prefix(Self::USERS_PREFIX),
+            Self::VoiceState { guild, user } => {
+                Parts::GuildId(Self::VOICE_STATE_PREFIX, guild.get(), user.get())
+            }
+        }
+    }
+
+    /// Parse a rendered key.
+    ///
+    /// Returns `None` if the bytes do not correspond to any key.
+    // One arm per key variant
+    #[allow(clippy::too_many_lines)]
+    pub(crate) fn parse(bytes: &[u8]) -> Option<Self> {
+        fn parse_int<T: FromStr>(bytes: &[u8]) -> Option<T> {
+            std::str::from_utf8(bytes).ok()?.parse().ok()
+        }
+
+        fn parse_id<T>(bytes: &[u8]) -> Option<Id<T>> {
+            parse_int(bytes).and_then(Id::new_checked)
+        }
+
+        fn parse_str(bytes: &[u8]) -> Option<Box<str>> {
+            std::str::from_utf8(bytes).ok().map(Box::from)
+        }
+
+        let mut split = bytes.split(|&byte| byte == b':');
+        let prefix = split.next()?;
+        let segments: Vec<&[u8]> = split.collect();
+
+        let key = match (prefix, segments.as_slice()) {
+            (Self::AUTO_MODERATION_RULE_PREFIX, [id]) => {
+                Self::AutoModerationRule { id: parse_id(id)? }
+            }
+            (Self::AUTO_MODERATION_RULE_META_PREFIX, [id]) => {
+                Self::AutoModerationRuleMeta { id: parse_id(id)? }
+            }
+            (Self::AUTO_MODERATION_RULES_PREFIX, []) => Self::AutoModerationRules,
+            (Self::CHANNEL_PREFIX, [id]) => Self::Channel { id: parse_id(id)? },
+            (Self::CHANNEL_GUILD_PREFIX, [id]) => Self::ChannelGuild { id: parse_id(id)? },
+            (Self::CHANNEL_MESSAGE_BYTES_PREFIX, [id]) => Self::ChannelMessageBytes {
+                channel: parse_id(id)?,
+            },
+            (Self::CHANNEL_MESSAGES_PREFIX, [id]) => Self::ChannelMessages {
+                channel: parse_id(id)?,
+            },
+            (Self::CHANNEL_INVITES_PREFIX, [id]) => Self::ChannelInvites { id: parse_id(id)? },
+            (Self::CHANNEL_META_PREFIX, [id]) => Self::ChannelMeta { id: parse_id(id)? },
+            (Self::CHANNEL_THREAD_MEMBERS_PREFIX, [id]) => Self::ChannelThreadMembers {
+                channel: parse_id(id)?,
+            },
+            (Self::CHANNEL_WEBHOOKS_PREFIX, [id]) => Self::ChannelWebhooks {
+                channel: parse_id(id)?,
+            },
+            (Self::CHANNELS_PREFIX, []) => Self::Channels,
+            (Self::CURRENT_USER_PREFIX, []) => Self::CurrentUser,
+            (Self::CUSTOM_PREFIX, [a, b]) => Self::Custom {
+                namespace: parse_str(a)?,
+                key: parse_str(b)?,
+            },
+            (Self::EMOJI_PREFIX, [id]) => Self::Emoji { id: parse_id(id)? },
+            (Self::EMOJI_META_PREFIX, [id]) => Self::EmojiMeta { id: parse_id(id)? },
+            (Self::EMOJIS_PREFIX, []) => Self::Emojis,
+            (Self::GUILD_PREFIX, [id]) => Self::Guild { id: parse_id(id)? },
+            (Self::GUILD_ACTIVITY_PREFIX, []) => Self::GuildActivity,
+            (Self::GUILD_AUTO_MODERATION_RULES_PREFIX, [id]) => {
+                Self::GuildAutoModerationRules { id: parse_id(id)? }
+            }
+            (Self::GUILD_BANS_PREFIX, [id]) => Self::GuildBans { id: parse_id(id)? },
+            (Self::GUILD_BANS_SNAPSHOT_PREFIX, [id]) => {
+                Self::GuildBansSnapshot { id: parse_id(id)? }
+            }
+            (Self::GUILD_BOOSTERS_PREFIX, [id]) => Self::GuildBoosters { id: parse_id(id)? },
+            (Self::GUILD_CHANNELS_PREFIX, [id]) => Self::GuildChannels { id: parse_id(id)? },
+            (Self::GUILD_EMOJIS_PREFIX, [id]) => Self::GuildEmojis { id: parse_id(id)? },
+            (Self::GUILD_INTEGRATIONS_PREFIX, [id]) => {
+                Self::GuildIntegrations { id: parse_id(id)? }
+            }
+            (Self::GUILD_INVITES_PREFIX, [id]) => Self::GuildInvites { id: parse_id(id)? },
+            (Self::GUILD_MEMBER_NAMES_PREFIX, [id]) => Self::GuildMemberNames { id: parse_id(id)? },
+            (Self::GUILD_MEMBER_NAMES_BY_USER_PREFIX, [id]) => {
+                Self::GuildMemberNamesByUser { id: parse_id(id)? }
+            }
+            (Self::GUILD_MEMBERS_PREFIX, [id]) => Self::GuildMembers { id: parse_id(id)? },
+            (Self::GUILD_MEMBERS_ORDERED_PREFIX, [id]) => {
+                Self::GuildMembersOrdered { id: parse_id(id)? }
+            }
+            (Self::GUILD_PRESENCE_HASHES_PREFIX, [id]) => {
+                Self::GuildPresenceHashes { id: parse_id(id)? }
+            }
+            (Self::GUILD_PRESENCE_STAMPS_PREFIX, [id]) => {
+                Self::GuildPresenceStamps { id: parse_id(id)? }
+            }
+            (Self::GUILD_PRESENCE_STATUS_PREFIX, [id]) => {
+                Self::GuildPresenceStatus { id: parse_id(id)? }
+            }
+            (Self::GUILD_PRESENCES_PREFIX, [id]) => Self::GuildPresences { id: parse_id(id)? },
+            (Self::GUILD_ROLES_PREFIX, [id]) => Self::GuildRoles { id: parse_id(id)? },
+            (Self::GUILD_SCHEDULED_EVENTS_PREFIX, [id]) => {
+                Self::GuildScheduledEvents { id: parse_id(id)? }
+            }
+            (Self::GUILD_STAGE_INSTANCES_PREFIX, [id]) => {
+                Self::GuildStageInstances { id: parse_id(id)? }
+            }
+            (Self::GUILD_STARBOARD_PREFIX, [id]) => Self::GuildStarboard { id: parse_id(id)? },
+            (Self::GUILD_STICKERS_PREFIX, [id]) => Self::GuildStickers { id: parse_id(id)? },
+            (Self::GUILD_TOMBSTONE_PREFIX, [id]) => Self::GuildTombstone { id: parse_id(id)? },
+            (Self::GUILD_VOICE_STATE_STAMPS_PREFIX, [id]) => {
+                Self::GuildVoiceStateStamps { id: parse_id(id)? }
+            }
+            (Self::GUILD_VOICE_STATES_PREFIX, [id]) => Self::GuildVoiceStates { id: parse_id(id)? },
+            (Self::GUILDS_PREFIX, []) => Self::Guilds,
+            (Self::INTEGRATION_PREFIX, [guild, id]) => Self::Integration {
+                guild: parse_id(guild)?,
+                id: parse_id(id)?,
+            },
+            (Self::INVITE_PREFIX, [s]) => Self::Invite {
+                code: parse_str(s)?,
+            },
+            (Self::INVITE_META_PREFIX, [s]) => Self::InviteMeta {
+                code: parse_str(s)?,
+            },
+            (Self::MEMBER_PREFIX, [guild, id]) => Self::Member {
+                guild: parse_id(guild)?,
+                user: parse_id(id)?,
+            },
+            (Self::MESSAGE_PREFIX, [id]) => Self::Message { id: parse_id(id)? },
+            (Self::MESSAGE_AUTHORS_PREFIX, []) => Self::MessageAuthors,
+            (Self::MESSAGE_HISTORY_PREFIX, [id]) => Self::MessageHistory { id: parse_id(id)? },
+            (Self::MESSAGE_META_PREFIX, [id]) => Self::MessageMeta { id: parse_id(id)? },
+            (Self::MESSAGES_PREFIX, []) => Self::Messages,
+            #[cfg(feature = "metrics")]
+            (Self::METRICS_LEADER_PREFIX, []) => Self::MetricsLeader,
+            (Self::PRESENCE_PREFIX, [guild, id]) => Self::Presence {
+                guild: parse_id(guild)?,
+                user: parse_id(id)?,
+            },
+            (Self::READ_COUNTERS_PREFIX, []) => Self::ReadCounters,
+            (Self::ROLE_PREFIX, [id]) => Self::Role { id: parse_id(id)? },
+            (Self::ROLE_META_PREFIX, [id]) => Self::RoleMeta { id: parse_id(id)? },
+            (Self::ROLES_PREFIX, []) => Self::Roles,
+            (Self::SCHEDULED_EVENT_PREFIX, [id]) => Self::ScheduledEvent { id: parse_id(id)? },
+            (Self::SCHEDULED_EVENT_META_PREFIX, [id]) => {
+                Self::ScheduledEventMeta { id: parse_id(id)? }
+            }
+            (Self::SCHEDULED_EVENTS_PREFIX, []) => Self::ScheduledEvents,
+            (Self::SCHEMA_VERSIONS_PREFIX, []) => Self::SchemaVersions,
+            #[cfg(feature = "cold_resume")]
+            (Self::SESSIONS_PREFIX, []) => Self::Sessions,
+            (Self::SHARD_SEQUENCE_PREFIX, [id]) => Self::ShardSequence {
+                shard: parse_int(id)?,
+            },
+            (Self::SHARD_WRITER_PREFIX, [id]) => Self::ShardWriter {
+                shard: parse_int(id)?,
+            },
+            (Self::STAGE_INSTANCE_PREFIX, [id]) => Self::StageInstance { id: parse_id(id)? },
+            (Self::STAGE_INSTANCE_META_PREFIX, [id]) => {
+                Self::StageInstanceMeta { id: parse_id(id)? }
+            }
+            (Self::STAGE_INSTANCES_PREFIX, []) => Self::StageInstances,
+            (Self::STICKER_PREFIX, [id]) => Self::Sticker { id: parse_id(id)? },
+            (Self::STICKER_META_PREFIX, [id]) => Self::StickerMeta { id: parse_id(id)? },
+            (Self::STICKERS_PREFIX, []) => Self::Stickers,
+            (Self::THREAD_MEMBER_PREFIX, [channel, id]) => Self::ThreadMember {
+                channel: parse_id(channel)?,
+                user: parse_id(id)?,
+            },
+            (Self::UNAVAILABLE_GUILDS_PREFIX, []) => Self::UnavailableGuilds,
+            (Self::USER_PREFIX, [id]) => Self::User { id: parse_id(id)? },
+            (Self::USER_GUILDS_PREFIX, [id]) => Self::UserGuilds { id: parse_id(id)? },
+            (Self::USERS_PREFIX, []) => Self::Users,
+            (Self::VOICE_STATE_PREFIX, [guild, id]) => Self::VoiceState {
+                guild: parse_id(guild)?,
+                user: parse_id(id)?,
+            },
+            _ => return None,
+        };
+
+        Some(key)
+    }
+
+    /// The entity kind if the key holds a single cached entity.
+    pub(crate) const fn entity_kind(&self) -> Option<EntityKind> {
+        let kind = match self {
+            Self::AutoModerationRule { .. } => EntityKind::AutoModerationRule,
+            Self::Channel { .. } => EntityKind::Channel,
+            Self::CurrentUser => EntityKind::CurrentUser,
+            Self::Emoji { .. } => EntityKind::Emoji,
+            Self::Guild { .. } => EntityKind::Guild,
+            Self::Integration { .. } => EntityKind::Integration,
+            Self::Invite { .. } => EntityKind::Invite,
+            Self::Member { .. } => EntityKind::Member,
+            Self::Message { .. } => EntityKind::Message,
+            Self::Presence { .. } => EntityKind::Presence,
+            Self::Role { .. } => EntityKind::Role,
+            Self::ScheduledEvent { .. } => EntityKind::ScheduledEvent,
+            Self::StageInstance { .. } => EntityKind::StageInstance,
+            Self::Sticker { .. } => EntityKind::Sticker,
+            Self::ThreadMember { .. } => EntityKind::ThreadMember,
+            Self::User { .. } => EntityKind::User,
+            Self::VoiceState { .. } => EntityKind::VoiceState,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+}
+
+/// Description of a [`RedisKey`] variant, see [`RedisKey::schema`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct KeySchema {
+    /// Name of the variant.
+    pub name: &'static str,
+    /// Prefix of the key.
+    pub prefix: &'static str,
+    /// Names of the segments that follow the prefix, e.g. ids.
+    pub segments: &'static [&'static str],
+    /// Type of the value stored under the key.
+    pub value_type: KeyValueType,
+}
+
+impl KeySchema {
+    const fn new(
+        name: &'static str,
+        prefix: &'static [u8],
+        segments: &'static [&'static str],
+        value_type: KeyValueType,
+    ) -> Self {
+        let Ok(prefix) = std::str::from_utf8(prefix) else {
+            panic!("key prefixes must be valid UTF-8");
+        };
+
+        Self {
+            name,
+            prefix,
+            segments,
+            value_type,
+        }
+    }
+
+    /// Amount of segments that follow the prefix.
+    pub const fn arity(&self) -> usize {
+        self.segments.len()
+    }
+}
+
+/// Redis type of the value stored under a key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum KeyValueType {
+    /// Plain bytes, generally serialized data
+    String,
+    /// Unordered set
+    Set,
+    /// Sorted set
+    SortedSet,
+    /// Hash of fields to values
+    Hash,
+    /// List of values
+    List,
+}
+
+impl From<Id<AutoModerationRuleMarker>> for RedisKey {
+    fn from(id: Id<AutoModerationRuleMarker>) -> Self {
+        Self::AutoModerationRule { id }
+    }
 }
 
 impl From<Id<ChannelMarker>> for RedisKey {
@@ -202,6 +1409,12 @@ impl From<Id<RoleMarker>> for RedisKey {
     }
 }
 
+impl From<Id<ScheduledEventMarker>> for RedisKey {
+    fn from(id: Id<ScheduledEventMarker>) -> Self {
+        Self::ScheduledEvent { id }
+    }
+}
+
 impl From<Id<StageMarker>> for RedisKey {
     fn from(id: Id<StageMarker>) -> Self {
         Self::StageInstance { id }
@@ -220,92 +1433,247 @@ impl From<Id<UserMarker>> for RedisKey {
     }
 }
 
+impl RedisKey {
+    /// Render the key within the namespace as a single argument.
+    fn write_arg<W>(&self, namespace: &[u8], out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        // Invite codes and custom keys are not bounded in length
+        let len = namespace.len() + self.parts().max_len();
+
+        if len > Self::MAX_LEN {
+            let mut vec = vec![0; len];
+            let len = self.to_bytes_in(namespace, &mut vec);
+
+            out.write_arg(&vec[..len]);
+        } else {
+            let mut buf = [0; Self::MAX_LEN];
+            let len = self.to_bytes_in(namespace, &mut buf);
+
+            out.write_arg(&buf[..len]);
+        }
+    }
+}
+
 impl ToRedisArgs for RedisKey {
     fn write_redis_args<W>(&self, out: &mut W)
     where
         W: ?Sized + RedisWrite,
     {
-        fn name_id<T>(name: &[u8], id: Id<T>) -> Cow<'static, [u8]> {
-            fn inner(name: &[u8], id: u64) -> Cow<'static, [u8]> {
-                let mut buf = Buffer::new();
-                let id = buf.format(id).as_bytes();
+        self.write_arg(b"", out);
+    }
+}
 
-                let mut vec = Vec::with_capacity(name.len() + 1 + id.len());
-                vec.extend_from_slice(name);
-                vec.push(b':');
-                vec.extend_from_slice(id);
+/// Components of a rendered key.
+enum Parts<'a> {
+    /// Only the prefix
+    Prefix(&'static [u8]),
+    /// Prefix followed by an id
+    Id(&'static [u8], u64),
+    /// Prefix followed by a string
+    Str(&'static [u8], &'a str),
+    /// Prefix followed by two strings
+    Strs(&'static [u8], &'a str, &'a str),
+    /// Prefix followed by a guild id and another id
+    GuildId(&'static [u8], u64, u64),
+}
 
-                Cow::Owned(vec)
-            }
+impl Parts<'_> {
+    /// Upper bound of the rendered length.
+    const fn max_len(&self) -> usize {
+        match self {
+            Self::Prefix(prefix) => prefix.len(),
+            Self::Id(prefix, _) => prefix.len() + 1 + MAX_ID_LEN,
+            Self::Str(prefix, s) => prefix.len() + 1 + s.len(),
+            Self::Strs(prefix, a, b) => prefix.len() + 2 + a.len() + b.len(),
+            Self::GuildId(prefix, ..) => prefix.len() + 2 * (1 + MAX_ID_LEN),
+        }
+    }
+}
+
+/// Writes into a byte slice, panicking if the slice is too short.
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
 
-            inner(name, id.get())
+impl SliceWriter<'_> {
+    fn push(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    /// Push a `:` separator followed by the id.
+    fn push_id(&mut self, id: u64) {
+        self.push(b":");
+        self.push(Buffer::new().format(id).as_bytes());
+    }
+}
+
+/// Length of the longest prefix in the schema.
+const fn max_prefix_len(schema: &[KeySchema]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+
+    while i < schema.len() {
+        if schema[i].prefix.len() > max {
+            max = schema[i].prefix.len();
         }
 
-        fn name_guild_id<T>(name: &[u8], guild: Id<GuildMarker>, id: Id<T>) -> Cow<'static, [u8]> {
-            fn inner(name: &[u8], guild: Id<GuildMarker>, id: u64) -> Cow<'static, [u8]> {
-                let mut buf = Buffer::new();
-                let guild = buf.format(guild.get()).as_bytes();
+        i += 1;
+    }
+
+    max
+}
 
-                let mut vec = Vec::with_capacity(name.len() + 1 + (guild.len() + 1) * 2);
-                vec.extend_from_slice(name);
-                vec.push(b':');
-                vec.extend_from_slice(guild);
-                vec.push(b':');
-                let id = buf.format(id).as_bytes();
-                vec.extend_from_slice(id);
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
 
-                Cow::Owned(vec)
-            }
+    use twilight_model::id::Id;
+
+    use super::{Namespace, RedisKey};
+    use crate::redis::ToRedisArgs;
+
+    #[test]
+    fn long_key_prefix_is_rejected() {
+        const PREFIX: &str = "0123456789abcdef0123456789abcdef+";
+
+        assert!(Namespace::new(PREFIX).is_err());
+    }
+
+    #[test]
+    fn namespaced_keys() {
+        let bot = Namespace::new("bot").unwrap();
+        let other = Namespace::new("other").unwrap();
+        let key = RedisKey::User { id: Id::new(1) };
+
+        assert_eq!(bot.render(&key), b"bot:USER:1");
+        assert_eq!(other.render(&key), b"other:USER:1");
+        assert_eq!(Namespace::default().render(&key), b"USER:1");
+        assert_eq!(bot.key(&key).to_redis_args(), [b"bot:USER:1".to_vec()]);
+        assert_eq!(bot.parse(b"bot:USER:1"), Some(key.clone()));
+        assert_eq!(bot.parse(b"other:USER:1"), None);
+    }
+
+    #[test]
+    fn schema_is_unique() {
+        let schema = RedisKey::schema();
+
+        let names: HashSet<_> = schema.iter().map(|entry| entry.name).collect();
+        assert_eq!(names.len(), schema.len());
+
+        let prefixes: HashSet<_> = schema.iter().map(|entry| entry.prefix).collect();
+        assert_eq!(prefixes.len(), schema.len());
+    }
+
+    #[test]
+    fn schema_matches_keys() {
+        let keys = [
+            ("Channels", RedisKey::Channels),
+            (
+                "Member",
+                RedisKey::Member {
+                    guild: Id::new(1),
+                    user: Id::new(2),
+                },
+            ),
+            ("ShardSequence", RedisKey::ShardSequence { shard: 3 }),
+            ("ShardWriter", RedisKey::ShardWriter { shard: 4 }),
+            (
+                "Custom",
+                RedisKey::Custom {
+                    namespace: "settings".into(),
+                    key: "5".into(),
+                },
+            ),
+        ];
+
+        for (name, key) in keys {
+            let entry = RedisKey::schema()
+                .iter()
+                .find(|entry| entry.name == name)
+                .unwrap();
+
+            let args = key.to_redis_args();
+            let encoded = std::str::from_utf8(&args[0]).unwrap();
+            let mut split = encoded.split(':');
 
-            inner(name, guild, id.get())
+            assert_eq!(split.next(), Some(entry.prefix));
+            assert_eq!(split.count(), entry.arity());
         }
+    }
 
-        let bytes = match self {
-            Self::Channel { id } => name_id(Self::CHANNEL_PREFIX, *id),
-            Self::ChannelMessages { channel } => name_id(Self::CHANNEL_MESSAGES_PREFIX, *channel),
-            Self::ChannelMeta { id } => name_id(Self::CHANNEL_META_PREFIX, *id),
-            Self::Channels => Cow::Borrowed(Self::CHANNELS_PREFIX),
-            Self::CurrentUser => Cow::Borrowed(Self::CURRENT_USER_PREFIX),
-            Self::Emoji { id } => name_id(Self::EMOJI_PREFIX, *id),
-            Self::EmojiMeta { id } => name_id(Self::EMOJI_META_PREFIX, *id),
-            Self::Emojis => Cow::Borrowed(Self::EMOJIS_PREFIX),
-            Self::Guild { id } => name_id(Self::GUILD_PREFIX, *id),
-            Self::GuildChannels { id } => name_id(Self::GUILD_CHANNELS_PREFIX, *id),
-            Self::GuildEmojis { id } => name_id(Self::GUILD_EMOJIS_PREFIX, *id),
-            Self::GuildIntegrations { id } => name_id(Self::GUILD_INTEGRATIONS_PREFIX, *id),
-            Self::GuildMembers { id } => name_id(Self::GUILD_MEMBERS_PREFIX, *id),
-            Self::GuildPresences { id } => name_id(Self::GUILD_PRESENCES_PREFIX, *id),
-            Self::GuildRoles { id } => name_id(Self::GUILD_ROLES_PREFIX, *id),
-            Self::GuildStageInstances { id } => name_id(Self::GUILD_STAGE_INSTANCES_PREFIX, *id),
-            Self::GuildStickers { id } => name_id(Self::GUILD_STICKERS_PREFIX, *id),
-            Self::GuildVoiceStates { id } => name_id(Self::GUILD_VOICE_STATES_PREFIX, *id),
-            Self::Guilds => Cow::Borrowed(Self::GUILDS_PREFIX),
-            Self::Integration { guild, id } => name_guild_id(Self::INTEGRATION_PREFIX, *guild, *id),
-            Self::Member { user, guild } => name_guild_id(Self::MEMBER_PREFIX, *guild, *user),
-            Self::Message { id } => name_id(Self::MESSAGE_PREFIX, *id),
-            Self::MessageMeta { id } => name_id(Self::MESSAGE_META_PREFIX, *id),
-            Self::Messages => Cow::Borrowed(Self::MESSAGES_PREFIX),
-            Self::Presence { guild, user } => name_guild_id(Self::PRESENCE_PREFIX, *guild, *user),
-            Self::Role { id } => name_id(Self::ROLE_PREFIX, *id),
-            Self::RoleMeta { id } => name_id(Self::ROLE_META_PREFIX, *id),
-            Self::Roles => Cow::Borrowed(Self::ROLES_PREFIX),
-            #[cfg(feature = "cold_resume")]
-            Self::Sessions => Cow::Borrowed(Self::SESSIONS_PREFIX),
-            Self::StageInstance { id } => name_id(Self::STAGE_INSTANCE_PREFIX, *id),
-            Self::StageInstanceMeta { id } => name_id(Self::STAGE_INSTANCE_META_PREFIX, *id),
-            Self::StageInstances => Cow::Borrowed(Self::STAGE_INSTANCES_PREFIX),
-            Self::Sticker { id } => name_id(Self::STICKER_PREFIX, *id),
-            Self::StickerMeta { id } => name_id(Self::STICKER_META_PREFIX, *id),
-            Self::Stickers => Cow::Borrowed(Self::STICKERS_PREFIX),
-            Self::UnavailableGuilds => Cow::Borrowed(Self::UNAVAILABLE_GUILDS_PREFIX),
-            Self::User { id } => name_id(Self::USER_PREFIX, *id),
-            Self::UserGuilds { id } => name_id(Self::USER_GUILDS_PREFIX, *id),
-            Self::Users => Cow::Borrowed(Self::USERS_PREFIX),
-            Self::VoiceState { guild, user } => {
-                name_guild_id(Self::VOICE_STATE_PREFIX, *guild, *user)
-            }
-        };
+    #[test]
+    fn parse_roundtrips() {
+        let keys = [
+            RedisKey::Users,
+            RedisKey::User { id: Id::new(1) },
+            RedisKey::GuildMembersOrdered {
+                id: Id::new(u64::MAX),
+            },
+            RedisKey::VoiceState {
+                guild: Id::new(2),
+                user: Id::new(3),
+            },
+            RedisKey::ShardSequence { shard: u32::MAX },
+            RedisKey::Invite { code: "abc".into() },
+            RedisKey::Custom {
+                namespace: "settings".into(),
+                key: "4".into(),
+            },
+        ];
+
+        for key in keys {
+            let args = key.to_redis_args();
+
+            assert_eq!(RedisKey::parse(&args[0]), Some(key));
+        }
 
-        out.write_arg(bytes.as_ref());
+        assert_eq!(RedisKey::parse(b"USER"), None);
+        assert_eq!(RedisKey::parse(b"USER:0"), None);
+        assert_eq!(RedisKey::parse(b"UNKNOWN:1"), None);
+    }
+
+    #[test]
+    fn to_bytes_matches_args() {
+        let long_code = "a".repeat(RedisKey::MAX_LEN);
+
+        let keys = [
+            RedisKey::Users,
+            RedisKey::GuildMembersOrdered {
+                id: Id::new(u64::MAX),
+            },
+            RedisKey::VoiceState {
+                guild: Id::new(u64::MAX),
+                user: Id::new(u64::MAX),
+            },
+            RedisKey::ShardSequence { shard: u32::MAX },
+            RedisKey::Invite { code: "abc".into() },
+            RedisKey::InviteMeta {
+                code: long_code.as_str().into(),
+            },
+            RedisKey::Custom {
+                namespace: "settings".into(),
+                key: long_code.as_str().into(),
+            },
+        ];
+
+        for key in keys {
+            let args = key.to_redis_args();
+
+            if args[0].len() <= RedisKey::MAX_LEN {
+                let mut buf = [0; RedisKey::MAX_LEN];
+                let len = key.to_bytes(&mut buf);
+
+                assert_eq!(&buf[..len], args[0].as_slice());
+            } else {
+                assert!(matches!(
+                    key,
+                    RedisKey::InviteMeta { .. } | RedisKey::Custom { .. }
+                ));
+            }
+        }
     }
 }
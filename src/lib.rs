@@ -83,11 +83,33 @@
 //! | `default` | Enables the `bb8` and `bytecheck` feature |
 //! | `bb8` | Uses [`bb8`] as underlying connection pool | [`bb8-redis`]
 //! | `deadpool` | Uses [`deadpool`] as underlying connection pool | [`deadpool-redis`]
+//! | `multiplexed` | Uses a single auto-reconnecting multiplexed connection instead of a connection pool. Only used if neither `bb8` nor `deadpool` are enabled. | [`redis`]
+//! | `cluster` | Connects to a redis cluster and routes commands to the node owning their keys. Only used if none of `bb8`, `deadpool`, or `multiplexed` are enabled. | [`redis`]
+//! | `attachments` | Enables the methods `RedisCache::set_attachment` and `RedisCache::get_attachment` to attach custom payloads to cached entries which take on their remaining TTL and are deleted alongside them. |
 //! | `bytecheck` | Always validate data when fetched from the cache. This adds a performance penalty but ensures that stored data always matches the defined types. | `rkyv/bytecheck`
 //! | `cold_resume` | Enables the methods `RedisCache::freeze` and `RedisCache::defrost` to store and load discord gateway sessions. | [`twilight-gateway`]
+//! | `fake-redis` | Enables the `fake` module containing `FakeRedis`, an in-memory stand-in for a redis server so that tests of configs that only rely on plain reads and writes can run without a redis instance. Scripts, pub/sub, and keyspace notifications are not supported. |
+//! | `http` | Enables the method `RedisCache::populate_guild` to prime the cache with a guild's channels and members fetched through the discord API. | [`twilight-http`]
+//! | `inmemory` | Enables the method `RedisCache::import_inmemory` to prime the cache from an existing in-memory cache. | [`twilight-cache-inmemory`]
+//! | `local_cache` | Enables `CacheConfig::LOCAL_CACHE` to keep entries of hot entity kinds such as the current user in a bounded in-process LRU in front of redis. |
 //! | `metrics` | Starts a background task that updates metrics in an interval. Metrics will be recorded in the global recorder which should be set before creating a cache instance. | [`metrics`]
+//! | `opentelemetry` | Attaches the current OpenTelemetry context to spans of updates and getters and records each round trip to redis as a client span. | [`opentelemetry`], [`tracing-opentelemetry`]
+//! | `serde` | Derives `Serialize` for plain data types such as `StatsSnapshot` so they can be dumped to dashboards. | [`serde`]
+//! | `serde-mirror` | Additionally stores a JSON copy of entities whose type opts into it through `Cacheable::mirror` so that non-Rust services can read the cache. | [`serde`], [`serde_json`]
+//! | `time` | Enables `ArchivedTimestamp::to_offsetdatetime` to convert archived timestamps into [`time`]'s `OffsetDateTime`. | [`time`]
 //!
-//! Either the `bb8` or `deadpool` feature *must* be enabled.
+//! One of the `bb8`, `deadpool`, `multiplexed`, or `cluster` features *must* be
+//! enabled.
+//!
+//! # Logging
+//!
+//! Logs are emitted through [`tracing`]. Verbose logs about the data written to
+//! redis, e.g. the amount of bytes for each stored entry, use the dedicated
+//! target `redlight::io` so they can be filtered separately, e.g. through
+//! `RUST_LOG=redlight=trace,redlight::io=off`. Spans of entity updates include
+//! a `guild_id` field where it is known. With the `opentelemetry` feature,
+//! round trips to redis are recorded as client spans with the target
+//! `redlight::redis`.
 //!
 //! [twilight]: https://github.com/twilight-rs/twilight
 //! [examples]: https://github.com/MaxOhn/redlight/tree/main/examples
@@ -104,8 +126,16 @@
 //! [`bb8-redis`]: https://docs.rs/bb8-redis/latest/bb8_redis/
 //! [`deadpool`]: https://docs.rs/deadpool/latest/deadpool/
 //! [`deadpool-redis`]: https://docs.rs/deadpool-redis/latest/deadpool_redis/
+//! [`redis`]: https://docs.rs/redis/latest/redis/
 //! [`twilight-gateway`]: https://docs.rs/twilight-gateway/latest/twilight_gateway/
+//! [`twilight-cache-inmemory`]: https://docs.rs/twilight-cache-inmemory/latest/twilight_cache_inmemory/
+//! [`twilight-http`]: https://docs.rs/twilight-http/latest/twilight_http/
 //! [`metrics`]: https://docs.rs/metrics/latest/metrics/
+//! [`tracing`]: https://docs.rs/tracing/latest/tracing/
+//! [`opentelemetry`]: https://docs.rs/opentelemetry/latest/opentelemetry/
+//! [`tracing-opentelemetry`]: https://docs.rs/tracing-opentelemetry/latest/tracing_opentelemetry/
+//! [`serde_json`]: https://docs.rs/serde_json/latest/serde_json/
+//! [`time`]: https://docs.rs/time/latest/time/
 
 #![cfg_attr(all(docsrs, not(doctest)), feature(doc_cfg))]
 #![deny(rustdoc::broken_intra_doc_links, rustdoc::missing_crate_level_docs)]
@@ -119,49 +149,191 @@
     clippy::unit_arg
 )]
 
-#[cfg(not(any(feature = "bb8", feature = "deadpool")))]
-compile_error!("either of the features `bb8` and `deadpool` *must* be enabled");
+#[cfg(not(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+)))]
+compile_error!(
+    "one of the features `bb8`, `deadpool`, `multiplexed`, and `cluster` *must* be enabled"
+);
 
 // pub but hidden for `cargo rdme`
 #[doc(hidden)]
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 pub mod cache;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 mod key;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 mod util;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 mod value;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
+/// Utilities for archived cached types.
+pub mod archive_utils;
+
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
+/// Abstraction over time access of the cache.
+pub mod clock;
+
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 /// Types and traits to configure the cache.
 pub mod config;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 /// Types related to errors.
 pub mod error;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(all(
+    feature = "fake-redis",
+    any(
+        feature = "bb8",
+        feature = "deadpool",
+        feature = "multiplexed",
+        feature = "cluster"
+    )
+))]
+#[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "fake-redis")))]
+/// In-memory stand-in for a redis server.
+pub mod fake;
+
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 /// Types related to iteration of cache entries.
 pub mod iter;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
+/// Administrative operations to inspect and repair cached data.
+pub mod maintenance;
+
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 /// Types to help implement rkyv traits.
 pub mod rkyv_util;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 /// Types related to statistics of the cache.
 pub mod stats;
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 /// Re-export of redis types and traits.
 pub(crate) mod redis;
 
+#[cfg(all(
+    feature = "cold_resume",
+    any(
+        feature = "bb8",
+        feature = "deadpool",
+        feature = "multiplexed",
+        feature = "cluster"
+    )
+))]
+pub use self::cache::DefrostedSessions;
 #[cfg(any(feature = "bb8", feature = "deadpool"))]
-pub use self::{cache::RedisCache, key::RedisKey, value::CachedArchive};
+pub use self::cache::GuildSnapshot;
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    not(feature = "multiplexed"),
+    feature = "cluster"
+))]
+pub use self::redis::cluster::{ClusterConnection, ClusterPool};
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    feature = "multiplexed"
+))]
+pub use self::redis::multiplexed::{MultiplexedConnection, MultiplexedPool};
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
+pub use self::{
+    cache::{
+        BatchedUpdater, CacheBatch, CacheConfigGroup, ChangeKind, ColdStore, DerivedView,
+        EventSequence, Invalidations, Pressure, PressureGauge, PubSubMessage, ReadPreference,
+        ReadRoute, RedisCache, Refresher, SchemaMismatch, Subscription, Topics, Transaction,
+        UpdateCache,
+    },
+    key::{KeySchema, KeyValueType, RedisKey},
+    util::BytesWrap,
+    value::{CachedArchive, DeserializeCache},
+};
 
-#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[cfg(any(
+    feature = "bb8",
+    feature = "deadpool",
+    feature = "multiplexed",
+    feature = "cluster"
+))]
 type CacheResult<T> = Result<T, error::CacheError>;
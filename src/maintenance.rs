@@ -0,0 +1,529 @@
+use std::collections::BTreeMap;
+
+use itoa::Buffer;
+
+use crate::{
+    config::{EntityKind, RateLimitedOperation},
+    error::CacheError,
+    key::{Namespace, RedisKey},
+    redis::{cmd, Cmd, Connection, ConnectionState, Pipeline},
+    CacheResult, RedisCache,
+};
+
+/// Amount of keys that are requested per `SCAN` or `SSCAN` round trip.
+const BATCH_SIZE: usize = 500;
+
+type Progress<'c> = Box<dyn FnMut(usize) + Send + 'c>;
+
+/// Administrative operations to inspect and repair the cached data.
+///
+/// All operations walk keys incrementally through `SCAN` so they don't block
+/// redis but they still touch every key they cover, i.e. they're meant to be
+/// run occasionally from maintenance scripts rather than alongside regular
+/// cache usage. Each batch is subject to the configured
+/// [`RateLimitedOperation::Maintenance`] limit. When using the `cluster`
+/// feature, key scans only cover the keys of the node that the cluster
+/// connection routes them to.
+///
+/// Created via [`RedisCache::maintenance`].
+pub struct Maintenance<'c, C> {
+    cache: &'c RedisCache<C>,
+    conn: ConnectionState<'c, C>,
+    progress: Option<Progress<'c>>,
+}
+
+/// How well the index set of an [`EntityKind`] matches its entries.
+///
+/// Returned by [`Maintenance::integrity_report`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct IndexIntegrity {
+    /// Amount of ids in the index set.
+    pub indexed: usize,
+    /// Amount of ids in the index set whose entry is missing.
+    pub orphaned: usize,
+    /// Amount of entries whose id is missing in the index set.
+    pub unindexed: usize,
+}
+
+impl IndexIntegrity {
+    /// Whether the index set and the entries match.
+    pub const fn is_consistent(&self) -> bool {
+        self.orphaned == 0 && self.unindexed == 0
+    }
+}
+
+impl<'c, C> Maintenance<'c, C> {
+    pub(crate) const fn new(cache: &'c RedisCache<C>) -> Self {
+        Self {
+            cache,
+            conn: ConnectionState::new(cache),
+            progress: None,
+        }
+    }
+
+    /// Call the given function after each processed batch with the amount of
+    /// keys that the current operation processed so far.
+    #[must_use]
+    pub fn with_progress(mut self, progress: impl FnMut(usize) + Send + 'c) -> Self {
+        self.progress = Some(Box::new(progress));
+
+        self
+    }
+
+    /// Remove the ids from the index set of the given kind whose entry no
+    /// longer exists.
+    ///
+    /// Returns the amount of removed ids. Kinds without a global index set,
+    /// e.g. members, are skipped and return `0`.
+    pub async fn prune_orphans(&mut self, kind: EntityKind) -> CacheResult<usize> {
+        let Some(index) = global_index(kind) else {
+            return Ok(0);
+        };
+
+        let namespace = self.cache.namespace();
+        let index = namespace.key(index);
+        let prefix = entry_prefix(kind);
+        let mut cursor = 0;
+        let mut processed = 0;
+        let mut pruned = 0;
+
+        loop {
+            self.cache
+                .rate_limit(RateLimitedOperation::Maintenance)
+                .await?;
+
+            let conn = self.conn.get().await?;
+
+            let (next, ids): (u64, Vec<u64>) = cmd("SSCAN")
+                .arg(&index)
+                .cursor_arg(cursor)
+                .arg("COUNT")
+                .arg(BATCH_SIZE)
+                .query_async(conn)
+                .await?;
+
+            let orphans = missing_entries(conn, namespace, prefix, &ids).await?;
+
+            if !orphans.is_empty() {
+                let removed: usize = Cmd::srem(&index, &orphans).query_async(conn).await?;
+                pruned += removed;
+            }
+
+            processed += ids.len();
+            self.report(processed);
+
+            cursor = next;
+
+            if cursor == 0 {
+                return Ok(pruned);
+            }
+        }
+    }
+
+    /// Add the ids of all existing entries of the given kind to its index
+    /// set.
+    ///
+    /// Returns the amount of ids that were missing in the index set. Kinds
+    /// without a global index set, e.g. members, are skipped and return `0`.
+    pub async fn rebuild_index(&mut self, kind: EntityKind) -> CacheResult<usize> {
+        let Some(index) = global_index(kind) else {
+            return Ok(0);
+        };
+
+        let namespace = self.cache.namespace();
+        let index = namespace.key(index);
+        let prefix = entry_prefix(kind);
+        let pattern = scan_pattern(namespace, prefix);
+        let mut cursor = 0;
+        let mut processed = 0;
+        let mut added = 0;
+
+        loop {
+            self.cache
+                .rate_limit(RateLimitedOperation::Maintenance)
+                .await?;
+
+            let conn = self.conn.get().await?;
+            let (next, keys) = scan(conn, cursor, Some(&pattern)).await?;
+
+            let ids: Vec<u64> = keys
+                .iter()
+                .filter_map(|key| parse_entry_id(namespace, prefix, key))
+                .collect();
+
+            if !ids.is_empty() {
+                let count: usize = Cmd::sadd(&index, &ids).query_async(conn).await?;
+                added += count;
+            }
+
+            processed += keys.len();
+            self.report(processed);
+
+            cursor = next;
+
+            if cursor == 0 {
+                return Ok(added);
+            }
+        }
+    }
+
+    /// Compare the index set of each kind with its entries.
+    ///
+    /// Kinds without a global index set, e.g. members, are not included.
+    pub async fn integrity_report(&mut self) -> CacheResult<Vec<(EntityKind, IndexIntegrity)>> {
+        let namespace = self.cache.namespace();
+        let mut report = Vec::new();
+        let mut processed = 0;
+
+        for kind in EntityKind::ALL {
+            let Some(index) = global_index(kind) else {
+                continue;
+            };
+
+            let index = namespace.key(index);
+            let prefix = entry_prefix(kind);
+            let mut integrity = IndexIntegrity::default();
+
+            let conn = self.conn.get().await?;
+            integrity.indexed = Cmd::scard(&index).query_async(conn).await?;
+
+            let mut cursor = 0;
+
+            loop {
+                self.cache
+                    .rate_limit(RateLimitedOperation::Maintenance)
+                    .await?;
+
+                let conn = self.conn.get().await?;
+
+                let (next, ids): (u64, Vec<u64>) = cmd("SSCAN")
+                    .arg(&index)
+                    .cursor_arg(cursor)
+                    .arg("COUNT")
+                    .arg(BATCH_SIZE)
+                    .query_async(conn)
+                    .await?;
+
+                integrity.orphaned += missing_entries(conn, namespace, prefix, &ids).await?.len();
+
+                processed += ids.len();
+                self.report(processed);
+
+                cursor = next;
+
+                if cursor == 0 {
+                    break;
+                }
+            }
+
+            let pattern = scan_pattern(namespace, prefix);
+            cursor = 0;
+
+            loop {
+                self.cache
+                    .rate_limit(RateLimitedOperation::Maintenance)
+                    .await?;
+
+                let conn = self.conn.get().await?;
+                let (next, keys) = scan(conn, cursor, Some(&pattern)).await?;
+
+                let ids: Vec<u64> = keys
+                    .iter()
+                    .filter_map(|key| parse_entry_id(namespace, prefix, key))
+                    .collect();
+
+                if !ids.is_empty() {
+                    let mut pipe = Pipeline::new();
+
+                    for id in ids.iter() {
+                        pipe.sismember(&index, id);
+                    }
+
+                    let indexed: Vec<bool> = pipe.query_async(conn).await?;
+                    integrity.unindexed += indexed.into_iter().filter(|found| !found).count();
+                }
+
+                processed += keys.len();
+                self.report(processed);
+
+                cursor = next;
+
+                if cursor == 0 {
+                    break;
+                }
+            }
+
+            report.push((kind, integrity));
+        }
+
+        Ok(report)
+    }
+
+    /// Delete all entries of the given kind alongside its global index set.
+    ///
+    /// Only the entries themselves are deleted; guild-specific index sets
+    /// and meta keys are left in place and will be cleaned up by future
+    /// events or expirations.
+    ///
+    /// Returns the amount of deleted keys.
+    pub async fn clear(&mut self, kind: EntityKind) -> CacheResult<usize> {
+        let namespace = self.cache.namespace();
+
+        if kind == EntityKind::CurrentUser {
+            let conn = self.conn.get().await?;
+            let deleted: usize = Cmd::del(namespace.key(RedisKey::CurrentUser))
+                .query_async(conn)
+                .await?;
+            self.report(1);
+
+            return Ok(deleted);
+        }
+
+        let pattern = scan_pattern(namespace, entry_prefix(kind));
+        let mut cursor = 0;
+        let mut processed = 0;
+        let mut deleted = 0;
+
+        loop {
+            self.cache
+                .rate_limit(RateLimitedOperation::Maintenance)
+                .await?;
+
+            let conn = self.conn.get().await?;
+            let (next, keys) = scan(conn, cursor, Some(&pattern)).await?;
+
+            if !keys.is_empty() {
+                let count: usize = Cmd::del(&keys).query_async(conn).await?;
+                deleted += count;
+            }
+
+            processed += keys.len();
+            self.report(processed);
+
+            cursor = next;
+
+            if cursor == 0 {
+                break;
+            }
+        }
+
+        if let Some(index) = global_index(kind) {
+            let conn = self.conn.get().await?;
+            let count: usize = Cmd::del(namespace.key(index)).query_async(conn).await?;
+            deleted += count;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Count all keys of the redis instance by their prefix, i.e. the part in
+    /// front of the first `:`.
+    ///
+    /// This includes keys that were not written by the cache. If
+    /// [`CacheConfig::KEY_PREFIX`] is set, only keys within the key prefix
+    /// are counted by the part following it.
+    ///
+    /// [`CacheConfig::KEY_PREFIX`]: crate::config::CacheConfig::KEY_PREFIX
+    pub async fn key_counts(&mut self) -> CacheResult<BTreeMap<String, usize>> {
+        let namespace = self.cache.namespace().as_bytes();
+        let pattern = [namespace, b"*"].concat();
+        let pattern = (!namespace.is_empty()).then_some(pattern.as_slice());
+
+        let mut counts = BTreeMap::new();
+        let mut cursor = 0;
+        let mut processed = 0;
+
+        loop {
+            self.cache
+                .rate_limit(RateLimitedOperation::Maintenance)
+                .await?;
+
+            let conn = self.conn.get().await?;
+            let (next, keys) = scan(conn, cursor, pattern).await?;
+
+            for key in keys.iter() {
+                let key = key.strip_prefix(namespace).unwrap_or(key);
+
+                let end = key
+                    .iter()
+                    .position(|&byte| byte == b':')
+                    .unwrap_or(key.len());
+                let prefix = String::from_utf8_lossy(&key[..end]).into_owned();
+                *counts.entry(prefix).or_default() += 1;
+            }
+
+            processed += keys.len();
+            self.report(processed);
+
+            cursor = next;
+
+            if cursor == 0 {
+                return Ok(counts);
+            }
+        }
+    }
+
+    fn report(&mut self, processed: usize) {
+        if let Some(ref mut progress) = self.progress {
+            progress(processed);
+        }
+    }
+}
+
+/// Prefix of the entry keys of the given kind.
+pub(crate) const fn entry_prefix(kind: EntityKind) -> &'static [u8] {
+    match kind {
+        EntityKind::AutoModerationRule => RedisKey::AUTO_MODERATION_RULE_PREFIX,
+        EntityKind::Channel => RedisKey::CHANNEL_PREFIX,
+        EntityKind::CurrentUser => RedisKey::CURRENT_USER_PREFIX,
+        EntityKind::Emoji => RedisKey::EMOJI_PREFIX,
+        EntityKind::Guild => RedisKey::GUILD_PREFIX,
+        EntityKind::Integration => RedisKey::INTEGRATION_PREFIX,
+        EntityKind::Invite => RedisKey::INVITE_PREFIX,
+        EntityKind::Member => RedisKey::MEMBER_PREFIX,
+        EntityKind::Message => RedisKey::MESSAGE_PREFIX,
+        EntityKind::Presence => RedisKey::PRESENCE_PREFIX,
+        EntityKind::Role => RedisKey::ROLE_PREFIX,
+        EntityKind::ScheduledEvent => RedisKey::SCHEDULED_EVENT_PREFIX,
+        EntityKind::StageInstance => RedisKey::STAGE_INSTANCE_PREFIX,
+        EntityKind::Sticker => RedisKey::STICKER_PREFIX,
+        EntityKind::ThreadMember => RedisKey::THREAD_MEMBER_PREFIX,
+        EntityKind::User => RedisKey::USER_PREFIX,
+        EntityKind::VoiceState => RedisKey::VOICE_STATE_PREFIX,
+    }
+}
+
+/// Index set containing the ids of all entries of the given kind.
+const fn global_index(kind: EntityKind) -> Option<RedisKey> {
+    let index = match kind {
+        EntityKind::AutoModerationRule => RedisKey::AutoModerationRules,
+        EntityKind::Channel => RedisKey::Channels,
+        EntityKind::Emoji => RedisKey::Emojis,
+        EntityKind::Guild => RedisKey::Guilds,
+        EntityKind::Message => RedisKey::Messages,
+        EntityKind::Role => RedisKey::Roles,
+        EntityKind::ScheduledEvent => RedisKey::ScheduledEvents,
+        EntityKind::StageInstance => RedisKey::StageInstances,
+        EntityKind::Sticker => RedisKey::Stickers,
+        EntityKind::User => RedisKey::Users,
+        EntityKind::CurrentUser
+        | EntityKind::Integration
+        | EntityKind::Invite
+        | EntityKind::Member
+        | EntityKind::Presence
+        | EntityKind::ThreadMember
+        | EntityKind::VoiceState => return None,
+    };
+
+    Some(index)
+}
+
+pub(crate) fn scan_pattern(namespace: &Namespace, prefix: &[u8]) -> Vec<u8> {
+    let namespace = namespace.as_bytes();
+
+    let mut pattern = Vec::with_capacity(namespace.len() + prefix.len() + 2);
+    pattern.extend_from_slice(namespace);
+    pattern.extend_from_slice(prefix);
+    pattern.extend_from_slice(b":*");
+
+    pattern
+}
+
+/// Parse the id of an entry key of the form `{prefix}:{id}`.
+pub(crate) fn parse_entry_id(namespace: &Namespace, prefix: &[u8], key: &[u8]) -> Option<u64> {
+    let id = key
+        .strip_prefix(namespace.as_bytes())?
+        .strip_prefix(prefix)?
+        .strip_prefix(b":")?;
+
+    std::str::from_utf8(id).ok()?.parse().ok()
+}
+
+pub(crate) async fn scan(
+    conn: &mut Connection<'_>,
+    cursor: u64,
+    pattern: Option<&[u8]>,
+) -> CacheResult<(u64, Vec<Vec<u8>>)> {
+    let mut command = cmd("SCAN");
+    command.arg(cursor);
+
+    if let Some(pattern) = pattern {
+        command.arg("MATCH").arg(pattern);
+    }
+
+    command
+        .arg("COUNT")
+        .arg(BATCH_SIZE)
+        .query_async(conn)
+        .await
+        .map_err(CacheError::Redis)
+}
+
+/// Returns the ids whose entry does not exist.
+async fn missing_entries(
+    conn: &mut Connection<'_>,
+    namespace: &Namespace,
+    prefix: &[u8],
+    ids: &[u64],
+) -> CacheResult<Vec<u64>> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut pipe = Pipeline::new();
+    let mut buf = Buffer::new();
+    let namespace = namespace.as_bytes();
+
+    for &id in ids {
+        let id = buf.format(id).as_bytes();
+
+        let mut key = Vec::with_capacity(namespace.len() + prefix.len() + 1 + id.len());
+        key.extend_from_slice(namespace);
+        key.extend_from_slice(prefix);
+        key.push(b':');
+        key.extend_from_slice(id);
+
+        pipe.exists(key);
+    }
+
+    let exists: Vec<bool> = pipe.query_async(conn).await?;
+
+    let missing = ids
+        .iter()
+        .zip(exists)
+        .filter_map(|(id, exists)| (!exists).then_some(*id))
+        .collect();
+
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_entry_id, scan_pattern};
+    use crate::key::Namespace;
+
+    #[test]
+    fn entry_ids() {
+        let namespace = Namespace::default();
+
+        assert_eq!(parse_entry_id(&namespace, b"USER", b"USER:123"), Some(123));
+        assert_eq!(
+            parse_entry_id(&namespace, b"USER", b"USER_GUILDS:123"),
+            None
+        );
+        assert_eq!(parse_entry_id(&namespace, b"GUILD", b"GUILD:1:2"), None);
+        assert_eq!(scan_pattern(&namespace, b"ROLE"), b"ROLE:*");
+    }
+
+    #[test]
+    fn prefixed_entry_ids() {
+        let namespace = Namespace::new("bot").unwrap();
+
+        assert_eq!(
+            parse_entry_id(&namespace, b"USER", b"bot:USER:123"),
+            Some(123)
+        );
+        assert_eq!(parse_entry_id(&namespace, b"USER", b"USER:123"), None);
+        assert_eq!(scan_pattern(&namespace, b"ROLE"), b"bot:ROLE:*");
+    }
+}
@@ -1,9 +1,24 @@
 #[cfg(feature = "bb8")]
 pub(crate) use bb8::*;
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    not(feature = "multiplexed"),
+    feature = "cluster"
+))]
+pub(crate) use cluster::*;
 #[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
 pub(crate) use deadpool::*;
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    feature = "multiplexed"
+))]
+pub(crate) use multiplexed::*;
 use tracing::trace;
 
+#[cfg(any(feature = "bb8", feature = "deadpool", feature = "multiplexed"))]
+use crate::error::DedicatedConnectionError;
 use crate::{CacheResult, RedisCache};
 
 #[cfg(feature = "bb8")]
@@ -69,6 +84,479 @@ mod deadpool {
     }
 }
 
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    feature = "multiplexed"
+))]
+pub(crate) mod multiplexed {
+    use std::{
+        marker::PhantomData,
+        ops::{Deref, DerefMut},
+        sync::Arc,
+    };
+
+    use redis::aio::ConnectionManager;
+    pub use redis::*;
+    use tokio::sync::OnceCell;
+
+    pub type Pool = MultiplexedPool;
+
+    /// A single multiplexed connection to be used instead of a connection
+    /// pool.
+    ///
+    /// The connection is established lazily on first use and reconnects
+    /// automatically. Cloning is cheap and shares the underlying connection.
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "multiplexed")))]
+    #[derive(Clone)]
+    pub struct MultiplexedPool {
+        client: Client,
+        manager: Arc<OnceCell<ConnectionManager>>,
+    }
+
+    impl MultiplexedPool {
+        /// Create a new [`MultiplexedPool`] that will connect through the
+        /// given client.
+        pub fn new(client: Client) -> Self {
+            Self {
+                client,
+                manager: Arc::new(OnceCell::new()),
+            }
+        }
+
+        /// Get a reference to the underlying redis client.
+        pub const fn client(&self) -> &Client {
+            &self.client
+        }
+
+        /// Get a handle to the multiplexed connection, connecting first if
+        /// necessary.
+        pub async fn get(&self) -> RedisResult<MultiplexedConnection> {
+            self.manager
+                .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+                .await
+                .cloned()
+                .map(MultiplexedConnection)
+        }
+    }
+
+    /// Handle to the connection of a [`MultiplexedPool`].
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "multiplexed")))]
+    pub struct MultiplexedConnection(ConnectionManager);
+
+    impl Deref for MultiplexedConnection {
+        type Target = ConnectionManager;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for MultiplexedConnection {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+
+    pub struct Connection<'a>(
+        pub(super) MultiplexedConnection,
+        // not necessary but makes handling between pools easier
+        PhantomData<&'a ()>,
+    );
+
+    impl<'a> Connection<'a> {
+        pub async fn get(pool: &'a Pool) -> Result<Connection<'a>, RedisError> {
+            pool.get().await.map(|conn| Self(conn, PhantomData))
+        }
+    }
+
+    pub struct DedicatedConnection(pub(super) aio::Connection);
+
+    impl DedicatedConnection {
+        pub async fn get(pool: &Pool) -> Result<Self, RedisError> {
+            pool.client.get_async_connection().await.map(Self)
+        }
+    }
+}
+
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    not(feature = "multiplexed"),
+    feature = "cluster"
+))]
+pub(crate) mod cluster {
+    use std::{
+        marker::PhantomData,
+        ops::{Deref, DerefMut},
+        sync::Arc,
+    };
+
+    use futures_util::future::try_join_all;
+    pub use redis::*;
+    use redis::{cluster::ClusterClient, cluster_async::ClusterConnection as InnerConnection};
+    use tokio::sync::OnceCell;
+
+    pub type Pool = ClusterPool;
+
+    /// A connection to a redis cluster to be used instead of a connection
+    /// pool.
+    ///
+    /// The connection is established lazily on first use. Cloning is cheap
+    /// and shares the underlying connection.
+    ///
+    /// Each command is routed to the node that owns the slot of its key.
+    /// Pipelines are split into their commands, and `MGET`, `MSET`, `DEL`,
+    /// `UNLINK`, and `EXISTS` are split into one command per key so that keys
+    /// of different slots can be combined freely. The commands of a pipeline
+    /// are sent concurrently but in order.
+    ///
+    /// Keyspace notifications, e.g. for expired entries, are received from
+    /// every primary. The primaries are looked up once when the cache starts
+    /// listening so primaries that join the cluster later are not covered.
+    ///
+    /// Some features are not supported in cluster mode because they rely on
+    /// scripts or commands that access keys of different slots at once:
+    ///   - [`RedisCache::transaction`]
+    ///   - [`RedisCache::message_with_author`] and
+    ///     [`RedisCache::common_guild_ids`]
+    ///   - writes of [`DerivedView`]s are not atomic
+    ///
+    /// Creating a cache whose config enables any of the following options
+    /// fails with [`CacheError::ClusterUnsupported`] for the same reason:
+    ///   - [`CacheConfig::PREVIOUS_VERSION_LIFETIME`]
+    ///   - [`ICachedGuild::TOMBSTONE_DURATION`]
+    ///   - [`ICachedMember::INDEX_NAMES`]
+    ///   - [`ICachedMessage::CHANNEL_BYTE_BUDGET`]
+    ///
+    /// Similarly, updating the cache fails with
+    /// [`CacheError::ClusterUnsupported`] if a cached value provides a
+    /// [`Cacheable::version`] since the entry and its version belong to
+    /// different slots.
+    ///
+    /// [`DerivedView`]: crate::DerivedView
+    /// [`Cacheable::version`]: crate::config::Cacheable::version
+    /// [`CacheConfig::PREVIOUS_VERSION_LIFETIME`]: crate::config::CacheConfig::PREVIOUS_VERSION_LIFETIME
+    /// [`CacheError::ClusterUnsupported`]: crate::error::CacheError::ClusterUnsupported
+    /// [`ICachedGuild::TOMBSTONE_DURATION`]: crate::config::ICachedGuild::TOMBSTONE_DURATION
+    /// [`ICachedMember::INDEX_NAMES`]: crate::config::ICachedMember::INDEX_NAMES
+    /// [`ICachedMessage::CHANNEL_BYTE_BUDGET`]: crate::config::ICachedMessage::CHANNEL_BYTE_BUDGET
+    /// [`RedisCache::transaction`]: crate::RedisCache::transaction
+    /// [`RedisCache::message_with_author`]: crate::RedisCache::message_with_author
+    /// [`RedisCache::common_guild_ids`]: crate::RedisCache::common_guild_ids
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "cluster")))]
+    #[derive(Clone)]
+    pub struct ClusterPool {
+        client: ClusterClient,
+        nodes: Arc<[ConnectionInfo]>,
+        conn: Arc<OnceCell<InnerConnection>>,
+    }
+
+    impl ClusterPool {
+        /// Create a new [`ClusterPool`] that will discover the cluster
+        /// through the given nodes.
+        pub fn new<T: IntoConnectionInfo>(nodes: Vec<T>) -> RedisResult<Self> {
+            let nodes = nodes
+                .into_iter()
+                .map(IntoConnectionInfo::into_connection_info)
+                .collect::<RedisResult<Vec<_>>>()?;
+
+            let client = ClusterClient::new(nodes.clone())?;
+
+            Ok(Self {
+                client,
+                nodes: nodes.into(),
+                conn: Arc::new(OnceCell::new()),
+            })
+        }
+
+        /// Get a handle to the cluster connection, connecting first if
+        /// necessary.
+        pub async fn get(&self) -> RedisResult<ClusterConnection> {
+            self.conn
+                .get_or_try_init(|| self.client.get_async_connection())
+                .await
+                .cloned()
+                .map(ClusterConnection)
+        }
+    }
+
+    /// Handle to the connection of a [`ClusterPool`].
+    ///
+    /// Dereferences to redis' own cluster connection which does not split up
+    /// multi-key commands such as `MGET` across slots.
+    #[cfg_attr(all(docsrs, not(doctest)), doc(cfg(feature = "cluster")))]
+    #[derive(Clone)]
+    pub struct ClusterConnection(InnerConnection);
+
+    impl Deref for ClusterConnection {
+        type Target = InnerConnection;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl DerefMut for ClusterConnection {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.0
+        }
+    }
+
+    impl ClusterConnection {
+        async fn route(&self, cmd: &Cmd) -> RedisResult<Value> {
+            let mut args = cmd.args_iter();
+
+            let Some(Arg::Simple(name)) = args.next() else {
+                return self.route_single(cmd.clone()).await;
+            };
+
+            let args = args.filter_map(|arg| match arg {
+                Arg::Simple(arg) => Some(arg),
+                Arg::Cursor => None,
+            });
+
+            if name.eq_ignore_ascii_case(b"MGET") {
+                let gets = args.map(|key| {
+                    let mut cmd = Cmd::new();
+                    cmd.arg("GET").arg(key);
+
+                    self.route_single(cmd)
+                });
+
+                try_join_all(gets).await.map(Value::Bulk)
+            } else if name.eq_ignore_ascii_case(b"MSET") {
+                let args: Vec<_> = args.collect();
+
+                let sets = args.chunks_exact(2).map(|pair| {
+                    let mut cmd = Cmd::new();
+                    cmd.arg("SET").arg(pair[0]).arg(pair[1]);
+
+                    self.route_single(cmd)
+                });
+
+                try_join_all(sets).await.map(|_| Value::Okay)
+            } else if [b"DEL".as_slice(), b"UNLINK", b"EXISTS"]
+                .iter()
+                .any(|split| name.eq_ignore_ascii_case(split))
+            {
+                let cmds = args.map(|key| {
+                    let mut cmd = Cmd::new();
+                    cmd.arg(name).arg(key);
+
+                    self.route_single(cmd)
+                });
+
+                let count = try_join_all(cmds)
+                    .await?
+                    .into_iter()
+                    .map(|value| match value {
+                        Value::Int(count) => count,
+                        _ => 0,
+                    })
+                    .sum();
+
+                Ok(Value::Int(count))
+            } else {
+                self.route_single(cmd.clone()).await
+            }
+        }
+
+        async fn route_single(&self, cmd: Cmd) -> RedisResult<Value> {
+            let mut conn = self.0.clone();
+
+            aio::ConnectionLike::req_packed_command(&mut conn, &cmd).await
+        }
+    }
+
+    impl aio::ConnectionLike for ClusterConnection {
+        fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+            Box::pin(self.route(cmd))
+        }
+
+        fn req_packed_commands<'a>(
+            &'a mut self,
+            cmd: &'a Pipeline,
+            offset: usize,
+            count: usize,
+        ) -> RedisFuture<'a, Vec<Value>> {
+            let cmds = cmd.cmd_iter().skip(offset).take(count);
+
+            Box::pin(try_join_all(cmds.map(|cmd| self.route(cmd))))
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+    }
+
+    pub struct Connection<'a>(
+        pub(super) ClusterConnection,
+        // not necessary but makes handling between pools easier
+        PhantomData<&'a ()>,
+    );
+
+    impl<'a> Connection<'a> {
+        pub async fn get(pool: &'a Pool) -> Result<Connection<'a>, RedisError> {
+            pool.get().await.map(|conn| Self(conn, PhantomData))
+        }
+    }
+
+    impl aio::ConnectionLike for Connection<'_> {
+        fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+            aio::ConnectionLike::req_packed_command(&mut self.0, cmd)
+        }
+
+        fn req_packed_commands<'a>(
+            &'a mut self,
+            cmd: &'a Pipeline,
+            offset: usize,
+            count: usize,
+        ) -> RedisFuture<'a, Vec<Value>> {
+            aio::ConnectionLike::req_packed_commands(&mut self.0, cmd, offset, count)
+        }
+
+        fn get_db(&self) -> i64 {
+            aio::ConnectionLike::get_db(&self.0)
+        }
+    }
+
+    /// Pub/sub, keyspace notifications, and config commands are not routed
+    /// so they go through a connection to a single node while all other
+    /// commands are routed through the cluster.
+    pub struct DedicatedConnection {
+        node: aio::Connection,
+        cluster: ClusterConnection,
+    }
+
+    impl DedicatedConnection {
+        /// Get a connection whose node is the first configured one.
+        pub async fn get(pool: &Pool) -> Result<Self, RedisError> {
+            let node = first_node(pool)?;
+            let node = Client::open(node)?.get_async_connection().await?;
+            let cluster = pool.get().await?;
+
+            Ok(Self { node, cluster })
+        }
+
+        /// Get a connection for each primary of the cluster.
+        ///
+        /// Keyspace notifications are only published by the node that owns
+        /// the key so they must be received from every primary.
+        pub async fn get_per_primary(pool: &Pool) -> Result<Vec<Self>, RedisError> {
+            let seed = first_node(pool)?;
+            let mut conn = Client::open(seed.clone())?.get_async_connection().await?;
+
+            let slots: Vec<Value> = cmd("CLUSTER").arg("SLOTS").query_async(&mut conn).await?;
+
+            let mut addrs: Vec<ConnectionAddr> = Vec::new();
+
+            for addr in slots
+                .iter()
+                .filter_map(|slot| primary_addr(&seed.addr, slot))
+            {
+                if !addrs.contains(&addr) {
+                    addrs.push(addr);
+                }
+            }
+
+            let cluster = pool.get().await?;
+
+            let conns = addrs.into_iter().map(|addr| {
+                let info = ConnectionInfo {
+                    addr,
+                    redis: seed.redis.clone(),
+                };
+
+                let cluster = cluster.clone();
+
+                async move {
+                    let node = Client::open(info)?.get_async_connection().await?;
+
+                    Ok(Self { node, cluster })
+                }
+            });
+
+            try_join_all(conns).await
+        }
+
+        pub(crate) fn into_pubsub(self) -> aio::PubSub {
+            self.node.into_pubsub()
+        }
+    }
+
+    fn first_node(pool: &Pool) -> RedisResult<ConnectionInfo> {
+        pool.nodes.first().cloned().ok_or_else(|| {
+            RedisError::from((ErrorKind::ClientError, "no cluster nodes configured"))
+        })
+    }
+
+    /// Address of the primary of a `CLUSTER SLOTS` entry which is of the form
+    /// `[start, end, [host, port, ..], ..replicas]`.
+    ///
+    /// An empty host refers to the node that was asked, i.e. the seed.
+    fn primary_addr(seed: &ConnectionAddr, slot: &Value) -> Option<ConnectionAddr> {
+        let Value::Bulk(slot) = slot else {
+            return None;
+        };
+
+        let Some(Value::Bulk(primary)) = slot.get(2) else {
+            return None;
+        };
+
+        let host: String = from_redis_value(primary.first()?).ok()?;
+        let port: u16 = from_redis_value(primary.get(1)?).ok()?;
+
+        let host = match (host.is_empty(), seed) {
+            (false, _) => host,
+            (true, ConnectionAddr::Tcp(host, _) | ConnectionAddr::TcpTls { host, .. }) => {
+                host.clone()
+            }
+            (true, ConnectionAddr::Unix(_)) => return None,
+        };
+
+        let addr = match seed {
+            ConnectionAddr::TcpTls { insecure, .. } => ConnectionAddr::TcpTls {
+                host,
+                port,
+                insecure: *insecure,
+            },
+            ConnectionAddr::Tcp(..) | ConnectionAddr::Unix(_) => ConnectionAddr::Tcp(host, port),
+        };
+
+        Some(addr)
+    }
+
+    impl aio::ConnectionLike for DedicatedConnection {
+        fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+            let is_config = matches!(
+                cmd.args_iter().next(),
+                Some(Arg::Simple(name)) if name.eq_ignore_ascii_case(b"CONFIG")
+            );
+
+            if is_config {
+                aio::ConnectionLike::req_packed_command(&mut self.node, cmd)
+            } else {
+                aio::ConnectionLike::req_packed_command(&mut self.cluster, cmd)
+            }
+        }
+
+        fn req_packed_commands<'a>(
+            &'a mut self,
+            cmd: &'a Pipeline,
+            offset: usize,
+            count: usize,
+        ) -> RedisFuture<'a, Vec<Value>> {
+            aio::ConnectionLike::req_packed_commands(&mut self.cluster, cmd, offset, count)
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+    }
+}
+
+#[cfg(any(feature = "bb8", feature = "deadpool", feature = "multiplexed"))]
 impl aio::ConnectionLike for Connection<'_> {
     fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
         aio::ConnectionLike::req_packed_command(&mut *self.0, cmd)
@@ -88,12 +576,22 @@ impl aio::ConnectionLike for Connection<'_> {
     }
 }
 
+#[cfg(any(feature = "bb8", feature = "deadpool", feature = "multiplexed"))]
 impl DedicatedConnection {
+    /// Get a connection for each primary which is only the one connection
+    /// outside of cluster mode.
+    pub(crate) async fn get_per_primary(
+        pool: &Pool,
+    ) -> Result<Vec<Self>, DedicatedConnectionError> {
+        Self::get(pool).await.map(|conn| vec![conn])
+    }
+
     pub(crate) fn into_pubsub(self) -> aio::PubSub {
         self.0.into_pubsub()
     }
 }
 
+#[cfg(any(feature = "bb8", feature = "deadpool", feature = "multiplexed"))]
 impl aio::ConnectionLike for DedicatedConnection {
     fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
         aio::ConnectionLike::req_packed_command(&mut self.0, cmd)
@@ -144,7 +144,7 @@ const GUILD_FEATURE_VERIFIED: &str = "VERIFIED";
 const GUILD_FEATURE_VIP_REGIONS: &str = "VIP_REGIONS";
 const GUILD_FEATURE_WELCOME_SCREEN_ENABLED: &str = "WELCOME_SCREEN_ENABLED";
 
-fn guild_feature_str(feature: &GuildFeature) -> &str {
+const fn guild_feature_str(feature: &GuildFeature) -> &str {
     #[allow(deprecated)]
     match feature {
         GuildFeature::AnimatedBanner => GUILD_FEATURE_ANIMATED_BANNER,
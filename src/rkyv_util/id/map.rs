@@ -138,7 +138,7 @@ impl<D: Fallible + ?Sized, T> Deserialize<Option<Id<T>>, D> for ArchivedIdOption
 
 /// Auxiliary trait to provide the most efficient (de)serializations of
 /// `&[Id<T>]` across every endian.
-trait INonZeroU64: Archive<Archived = Self> {
+pub(super) trait INonZeroU64: Archive<Archived = Self> {
     /// Serialize `&[Id<T>]` while leveraging when `Self == Archived<Self>`.
     fn serialize<S, T>(
         list: &[Id<T>],
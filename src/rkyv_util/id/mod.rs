@@ -1,6 +1,8 @@
 mod map;
+mod sorted;
 
 use std::{
+    cmp::Ordering,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
     marker::PhantomData,
     num::NonZeroU64,
@@ -15,7 +17,10 @@ use rkyv::{
 };
 use twilight_model::id::Id;
 
-pub use self::map::{ArchivedIdOption, IdRkyvMap};
+pub use self::{
+    map::{ArchivedIdOption, IdRkyvMap},
+    sorted::{SortedArchivedIds, SortedIdRkyvMap},
+};
 
 /// Used to archive [`Id<T>`].
 ///
@@ -137,6 +142,18 @@ impl<T> PartialEq for ArchivedId<T> {
 
 impl<T> Eq for ArchivedId<T> {}
 
+impl<T> PartialOrd for ArchivedId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ArchivedId<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get().cmp(&other.get())
+    }
+}
+
 impl<T> PartialEq<Id<T>> for ArchivedId<T> {
     fn eq(&self, other: &Id<T>) -> bool {
         self.value == other.into_nonzero()
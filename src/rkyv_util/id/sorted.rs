@@ -0,0 +1,209 @@
+use std::num::NonZeroU64;
+
+use rkyv::{
+    rancor::Fallible,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    with::{ArchiveWith, DeserializeWith, SerializeWith},
+    Archived, Place,
+};
+use twilight_model::id::Id;
+
+use super::{map::INonZeroU64, ArchivedId};
+
+/// Used to archive `Vec<Id<T>>`, `&[Id<T>]`, and `Box<[Id<T>]>` sorted in
+/// ascending order.
+///
+/// The archived form is the same as for [`IdRkyvMap`] but since it is sorted,
+/// it can be searched through [`SortedArchivedIds`] in logarithmic time
+/// without deserializing.
+///
+/// # Example
+///
+/// ```
+/// # use rkyv::Archive;
+/// use redlight::rkyv_util::id::{SortedArchivedIds, SortedIdRkyvMap};
+/// use twilight_model::id::{marker::RoleMarker, Id};
+///
+/// #[derive(Archive)]
+/// struct CachedMember {
+///     #[rkyv(with = SortedIdRkyvMap)]
+///     roles: Vec<Id<RoleMarker>>,
+/// }
+///
+/// fn has_role(member: &ArchivedCachedMember, role_id: Id<RoleMarker>) -> bool {
+///     member.roles.contains_id(role_id)
+/// }
+/// ```
+///
+/// [`IdRkyvMap`]: crate::rkyv_util::id::IdRkyvMap
+pub struct SortedIdRkyvMap;
+
+/// Lookups on archived ids that were sorted through [`SortedIdRkyvMap`].
+///
+/// Results are unspecified if the ids are not sorted.
+pub trait SortedArchivedIds<T> {
+    /// Binary search for the given id.
+    ///
+    /// Same semantics as [`slice::binary_search`].
+    fn binary_search_id(&self, id: Id<T>) -> Result<usize, usize>;
+
+    /// Whether the given id is contained.
+    fn contains_id(&self, id: Id<T>) -> bool {
+        self.binary_search_id(id).is_ok()
+    }
+}
+
+impl<T> SortedArchivedIds<T> for [ArchivedId<T>] {
+    fn binary_search_id(&self, id: Id<T>) -> Result<usize, usize> {
+        self.binary_search(&ArchivedId::from(id))
+    }
+}
+
+impl<T> SortedArchivedIds<T> for ArchivedVec<ArchivedId<T>> {
+    fn binary_search_id(&self, id: Id<T>) -> Result<usize, usize> {
+        self.as_slice().binary_search_id(id)
+    }
+}
+
+fn serialize_sorted<S, T>(
+    ids: &[Id<T>],
+    serializer: &mut S,
+) -> Result<VecResolver, <S as Fallible>::Error>
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    if ids.is_sorted() {
+        return <Archived<NonZeroU64> as INonZeroU64>::serialize(ids, serializer);
+    }
+
+    let mut sorted = ids.to_owned();
+    sorted.sort_unstable();
+
+    <Archived<NonZeroU64> as INonZeroU64>::serialize(&sorted, serializer)
+}
+
+// Vec<Id<T>>
+
+impl<T> ArchiveWith<Vec<Id<T>>> for SortedIdRkyvMap {
+    type Archived = ArchivedVec<ArchivedId<T>>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(ids: &Vec<Id<T>>, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(ids.len(), resolver, out);
+    }
+}
+
+impl<S, T> SerializeWith<Vec<Id<T>>, S> for SortedIdRkyvMap
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        ids: &Vec<Id<T>>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, <S as Fallible>::Error> {
+        serialize_sorted(ids, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized, T> DeserializeWith<ArchivedVec<ArchivedId<T>>, Vec<Id<T>>, D>
+    for SortedIdRkyvMap
+{
+    fn deserialize_with(
+        archived: &ArchivedVec<ArchivedId<T>>,
+        deserializer: &mut D,
+    ) -> Result<Vec<Id<T>>, <D as Fallible>::Error> {
+        <Archived<NonZeroU64> as INonZeroU64>::deserialize(archived, deserializer)
+    }
+}
+
+// &[Id<T>]
+
+impl<T> ArchiveWith<&[Id<T>]> for SortedIdRkyvMap {
+    type Archived = ArchivedVec<ArchivedId<T>>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(ids: &&[Id<T>], resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(ids.len(), resolver, out);
+    }
+}
+
+impl<S, T> SerializeWith<&[Id<T>], S> for SortedIdRkyvMap
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        ids: &&[Id<T>],
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, <S as Fallible>::Error> {
+        serialize_sorted(ids, serializer)
+    }
+}
+
+// Box<[Id<T>]>
+
+impl<T> ArchiveWith<Box<[Id<T>]>> for SortedIdRkyvMap {
+    type Archived = ArchivedVec<ArchivedId<T>>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(ids: &Box<[Id<T>]>, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_len(ids.len(), resolver, out);
+    }
+}
+
+impl<S, T> SerializeWith<Box<[Id<T>]>, S> for SortedIdRkyvMap
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        ids: &Box<[Id<T>]>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, <S as Fallible>::Error> {
+        serialize_sorted(ids, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized, T> DeserializeWith<ArchivedVec<ArchivedId<T>>, Box<[Id<T>]>, D>
+    for SortedIdRkyvMap
+{
+    fn deserialize_with(
+        archived: &ArchivedVec<ArchivedId<T>>,
+        deserializer: &mut D,
+    ) -> Result<Box<[Id<T>]>, <D as Fallible>::Error> {
+        <Archived<NonZeroU64> as INonZeroU64>::deserialize(archived, deserializer)
+            .map(Vec::into_boxed_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor::Error, with::With};
+
+    use super::*;
+
+    #[test]
+    fn test_rkyv_sorted_ids() -> Result<(), Error> {
+        let ids = vec![Id::new(345), Id::new(123), Id::new(234)];
+        let bytes = rkyv::to_bytes(With::<_, SortedIdRkyvMap>::cast(&ids))?;
+
+        #[cfg(not(feature = "bytecheck"))]
+        let archived: &ArchivedVec<ArchivedId<()>> = unsafe { rkyv::access_unchecked(&bytes) };
+
+        #[cfg(feature = "bytecheck")]
+        let archived: &ArchivedVec<ArchivedId<()>> = rkyv::access(&bytes)?;
+
+        for id in ids.iter() {
+            assert!(archived.contains_id(*id));
+        }
+
+        assert!(!archived.contains_id(Id::new(200)));
+        assert_eq!(archived.binary_search_id(Id::new(234)), Ok(1));
+
+        let deserialized: Vec<Id<()>> =
+            rkyv::deserialize(With::<_, SortedIdRkyvMap>::cast(archived))?;
+
+        assert_eq!(deserialized, [Id::new(123), Id::new(234), Id::new(345)]);
+
+        Ok(())
+    }
+}
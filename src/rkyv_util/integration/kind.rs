@@ -96,7 +96,7 @@ const INTEGRATION_TYPE_DISCORD: &str = "discord";
 const INTEGRATION_TYPE_TWITCH: &str = "twitch";
 const INTEGRATION_TYPE_YOUTUBE: &str = "youtube";
 
-fn integration_type_str(integration: &GuildIntegrationType) -> &str {
+const fn integration_type_str(integration: &GuildIntegrationType) -> &str {
     match integration {
         GuildIntegrationType::Discord => INTEGRATION_TYPE_DISCORD,
         GuildIntegrationType::Twitch => INTEGRATION_TYPE_TWITCH,
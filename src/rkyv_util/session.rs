@@ -159,7 +159,7 @@ mod tests {
 
     #[test]
     fn test_rkyv_sessions() -> Result<(), Error> {
-        let sessions: HashMap<_, _> = (0..).zip(iter::repeat(session()).take(10)).collect();
+        let sessions: HashMap<_, _> = (0..).zip(iter::repeat_n(session(), 10)).collect();
         let bytes = rkyv::to_bytes(With::<_, SessionsRkyv>::cast(&sessions))?;
 
         #[cfg(not(feature = "bytecheck"))]
@@ -1,7 +1,11 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+
 use rkyv::{
+    primitive::ArchivedU64,
     rancor::Fallible,
+    traits::NoUndef,
     with::{ArchiveWith, DeserializeWith, SerializeWith},
-    Archive, Archived, Place,
+    Archive, Archived, Place, Portable,
 };
 use twilight_model::{
     channel::{message::MessageFlags, ChannelFlags},
@@ -69,6 +73,139 @@ impl_bitflags!(Permissions);
 impl_bitflags!(SystemChannelFlags);
 impl_bitflags!(UserFlags);
 
+/// Used to archive [`Permissions`] as [`ArchivedPermissions`].
+///
+/// Unlike [`BitflagsRkyv`], the archived value provides convenience methods
+/// to check permissions without converting back to [`Permissions`] first.
+///
+/// # Example
+///
+/// ```
+/// # use rkyv::Archive;
+/// use redlight::rkyv_util::util::{ArchivedPermissions, PermissionsRkyv};
+/// use twilight_model::guild::Permissions;
+///
+/// #[derive(Archive)]
+/// struct CachedRole {
+///     #[rkyv(with = PermissionsRkyv)]
+///     permissions: Permissions,
+/// }
+///
+/// fn can_kick(role: &ArchivedCachedRole) -> bool {
+///     let permissions: ArchivedPermissions = role.permissions;
+///
+///     permissions.contains(Permissions::KICK_MEMBERS)
+/// }
+/// ```
+pub struct PermissionsRkyv;
+
+#[derive(Copy, Clone, PartialEq, Eq, Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(rkyv::bytecheck::CheckBytes),
+    bytecheck(crate = rkyv::bytecheck),
+)]
+#[repr(transparent)]
+/// An archived [`Permissions`].
+///
+/// Can also be created from a field archived through [`BitflagsRkyv`] via its
+/// `From` implementation.
+pub struct ArchivedPermissions(ArchivedU64);
+
+impl ArchivedPermissions {
+    /// The raw bits of the permissions.
+    pub fn bits(self) -> u64 {
+        self.0.into()
+    }
+
+    /// Convert into [`Permissions`], ignoring unknown bits.
+    pub fn get(self) -> Permissions {
+        Permissions::from_bits_truncate(self.bits())
+    }
+
+    /// Whether all permissions of `other` are contained.
+    pub fn contains(self, other: Permissions) -> bool {
+        self.get().contains(other)
+    }
+
+    /// Whether any permission of `other` is contained.
+    pub fn intersects(self, other: Permissions) -> bool {
+        self.get().intersects(other)
+    }
+
+    /// Whether no permission is set.
+    pub fn is_empty(self) -> bool {
+        self.get().is_empty()
+    }
+
+    /// The union of these and the given permissions.
+    pub fn union(self, other: impl Into<Permissions>) -> Permissions {
+        self.get() | other.into()
+    }
+
+    /// Iterate over each individual permission that is set.
+    pub fn iter(self) -> impl Iterator<Item = Permissions> {
+        let bits = self.get().bits();
+
+        (0..u64::BITS)
+            .map(|shift| 1 << shift)
+            .filter(move |bit| bits & bit != 0)
+            .filter_map(Permissions::from_bits)
+    }
+}
+
+unsafe impl NoUndef for ArchivedPermissions {}
+
+impl From<ArchivedU64> for ArchivedPermissions {
+    fn from(bits: ArchivedU64) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<ArchivedPermissions> for Permissions {
+    fn from(archived: ArchivedPermissions) -> Self {
+        archived.get()
+    }
+}
+
+impl PartialEq<Permissions> for ArchivedPermissions {
+    fn eq(&self, other: &Permissions) -> bool {
+        self.bits() == other.bits()
+    }
+}
+
+impl Debug for ArchivedPermissions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.get(), f)
+    }
+}
+
+impl ArchiveWith<Permissions> for PermissionsRkyv {
+    type Archived = ArchivedPermissions;
+    type Resolver = ();
+
+    fn resolve_with(flags: &Permissions, (): Self::Resolver, out: Place<Self::Archived>) {
+        out.write(ArchivedPermissions(flags.bits().into()));
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<Permissions, S> for PermissionsRkyv {
+    fn serialize_with(_: &Permissions, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedPermissions, Permissions, D>
+    for PermissionsRkyv
+{
+    fn deserialize_with(
+        archived: &ArchivedPermissions,
+        _: &mut D,
+    ) -> Result<Permissions, <D as Fallible>::Error> {
+        Ok(archived.get())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rkyv::{rancor::Error, with::With};
@@ -92,4 +229,30 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_archived_permissions() -> Result<(), Error> {
+        let permissions = Permissions::KICK_MEMBERS | Permissions::BAN_MEMBERS;
+        let bytes = rkyv::to_bytes(With::<_, PermissionsRkyv>::cast(&permissions))?;
+
+        #[cfg(feature = "bytecheck")]
+        let archived: &ArchivedPermissions = rkyv::access(&bytes)?;
+
+        #[cfg(not(feature = "bytecheck"))]
+        let archived: &ArchivedPermissions = unsafe { rkyv::access_unchecked(&bytes) };
+
+        assert_eq!(*archived, permissions);
+        assert!(archived.contains(Permissions::KICK_MEMBERS));
+        assert!(!archived.contains(Permissions::KICK_MEMBERS | Permissions::ADMINISTRATOR));
+        assert!(archived.intersects(Permissions::BAN_MEMBERS | Permissions::ADMINISTRATOR));
+        assert_eq!(
+            archived.union(Permissions::ADMINISTRATOR),
+            permissions | Permissions::ADMINISTRATOR
+        );
+
+        let flags: Vec<_> = archived.iter().collect();
+        assert_eq!(flags, [Permissions::KICK_MEMBERS, Permissions::BAN_MEMBERS]);
+
+        Ok(())
+    }
 }
@@ -2,4 +2,8 @@ mod flags;
 mod rkyv_as_u8;
 mod timestamp;
 
-pub use self::{flags::BitflagsRkyv, rkyv_as_u8::RkyvAsU8, timestamp::TimestampRkyv};
+pub use self::{
+    flags::{ArchivedPermissions, BitflagsRkyv, PermissionsRkyv},
+    rkyv_as_u8::RkyvAsU8,
+    timestamp::{ArchivedTimestamp, ArchivedTimestampOption, TimestampRkyv},
+};
@@ -1,11 +1,23 @@
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Formatter, Result as FmtResult},
+};
+
 use rkyv::{
+    primitive::ArchivedI64,
     rancor::{Fallible, Source},
+    traits::NoUndef,
     with::{ArchiveWith, DeserializeWith, SerializeWith},
-    Archive, Archived, Place,
+    Place, Portable,
 };
 use twilight_model::util::{datetime::TimestampParseError, Timestamp};
 
-/// Used to archive [`Timestamp`].
+/// Used to archive [`Timestamp`] and `Option<Timestamp>`.
+///
+/// Timestamps are archived as [`ArchivedTimestamp`] which can be compared
+/// and converted without deserializing. Options are archived as
+/// [`ArchivedTimestampOption`] which takes up as much space as a plain
+/// timestamp, unlike [`Map<TimestampRkyv>`](rkyv::with::Map).
 ///
 /// # Example
 ///
@@ -18,6 +30,15 @@ use twilight_model::util::{datetime::TimestampParseError, Timestamp};
 /// struct Cached {
 ///     #[rkyv(with = TimestampRkyv)]
 ///     timestamp: Timestamp,
+///     #[rkyv(with = TimestampRkyv)]
+///     edited_timestamp: Option<Timestamp>,
+/// }
+///
+/// fn was_edited_after(cached: &ArchivedCached, unix_millis: i64) -> bool {
+///     cached
+///         .edited_timestamp
+///         .as_unix_millis()
+///         .is_some_and(|edited| edited > unix_millis)
 /// }
 /// ```
 pub struct TimestampRkyv;
@@ -34,12 +55,179 @@ impl TimestampRkyv {
     }
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(rkyv::bytecheck::CheckBytes),
+    bytecheck(crate = rkyv::bytecheck),
+)]
+#[repr(transparent)]
+/// An archived [`Timestamp`].
+///
+/// Shares its layout with `Archived<i64>` so it can also be created from
+/// previously archived timestamps via its `From` implementation.
+pub struct ArchivedTimestamp(ArchivedI64);
+
+impl ArchivedTimestamp {
+    /// The amount of microseconds since the unix epoch.
+    pub const fn as_micros(self) -> i64 {
+        self.0.to_native()
+    }
+
+    /// The amount of milliseconds since the unix epoch.
+    pub const fn as_unix_millis(self) -> i64 {
+        self.as_micros().div_euclid(1000)
+    }
+
+    /// The amount of seconds since the unix epoch.
+    pub const fn as_secs(self) -> i64 {
+        self.as_micros().div_euclid(1_000_000)
+    }
+
+    /// Convert into a [`Timestamp`].
+    pub fn to_timestamp(self) -> Result<Timestamp, TimestampParseError> {
+        TimestampRkyv::try_deserialize(self.as_micros())
+    }
+
+    /// Convert into an [`OffsetDateTime`](time::OffsetDateTime) in UTC.
+    #[cfg(feature = "time")]
+    pub fn to_offsetdatetime(self) -> Result<time::OffsetDateTime, time::error::ComponentRange> {
+        time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(self.as_micros()) * 1000)
+    }
+}
+
+unsafe impl NoUndef for ArchivedTimestamp {}
+
+impl From<ArchivedI64> for ArchivedTimestamp {
+    fn from(micros: ArchivedI64) -> Self {
+        Self(micros)
+    }
+}
+
+impl From<i64> for ArchivedTimestamp {
+    fn from(micros: i64) -> Self {
+        Self(micros.into())
+    }
+}
+
+impl From<&Timestamp> for ArchivedTimestamp {
+    fn from(timestamp: &Timestamp) -> Self {
+        Self::from(TimestampRkyv::archive(timestamp))
+    }
+}
+
+impl PartialEq<i64> for ArchivedTimestamp {
+    fn eq(&self, other: &i64) -> bool {
+        self.as_micros() == *other
+    }
+}
+
+impl PartialEq<Timestamp> for ArchivedTimestamp {
+    fn eq(&self, other: &Timestamp) -> bool {
+        self.as_micros() == other.as_micros()
+    }
+}
+
+impl PartialOrd for ArchivedTimestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArchivedTimestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_micros().cmp(&other.as_micros())
+    }
+}
+
+impl PartialOrd<Timestamp> for ArchivedTimestamp {
+    fn partial_cmp(&self, other: &Timestamp) -> Option<Ordering> {
+        Some(self.as_micros().cmp(&other.as_micros()))
+    }
+}
+
+impl Debug for ArchivedTimestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.to_timestamp() {
+            Ok(timestamp) => Debug::fmt(&timestamp, f),
+            Err(_) => Debug::fmt(&self.as_micros(), f),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(rkyv::bytecheck::CheckBytes),
+    bytecheck(crate = rkyv::bytecheck),
+)]
+#[repr(transparent)]
+/// An efficiently archived `Option<Timestamp>`.
+///
+/// `None` is represented by `i64::MIN` which is far outside of the range of
+/// valid timestamps.
+pub struct ArchivedTimestampOption(ArchivedTimestamp);
+
+impl ArchivedTimestampOption {
+    const NONE: i64 = i64::MIN;
+
+    /// Convert into an `Option<ArchivedTimestamp>`.
+    pub fn get(self) -> Option<ArchivedTimestamp> {
+        (self.0.as_micros() != Self::NONE).then_some(self.0)
+    }
+
+    /// Whether a timestamp is present.
+    pub fn is_some(self) -> bool {
+        self.get().is_some()
+    }
+
+    /// Whether no timestamp is present.
+    pub fn is_none(self) -> bool {
+        self.get().is_none()
+    }
+
+    /// The amount of milliseconds since the unix epoch if a timestamp is
+    /// present.
+    pub fn as_unix_millis(self) -> Option<i64> {
+        self.get().map(ArchivedTimestamp::as_unix_millis)
+    }
+
+    /// Convert into an `Option<Timestamp>`.
+    pub fn to_timestamp_option(self) -> Result<Option<Timestamp>, TimestampParseError> {
+        self.get().map(ArchivedTimestamp::to_timestamp).transpose()
+    }
+
+    /// Resolves an `ArchivedTimestampOption` from an `Option<Timestamp>`.
+    pub fn resolve_from_timestamp(opt: Option<&Timestamp>, out: Place<Self>) {
+        let micros = opt.map_or(Self::NONE, TimestampRkyv::archive);
+        out.write(Self(micros.into()));
+    }
+}
+
+unsafe impl NoUndef for ArchivedTimestampOption {}
+
+impl PartialEq<Option<Timestamp>> for ArchivedTimestampOption {
+    fn eq(&self, other: &Option<Timestamp>) -> bool {
+        match (self.get(), other) {
+            (Some(archived), Some(timestamp)) => archived == *timestamp,
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Debug for ArchivedTimestampOption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.get(), f)
+    }
+}
+
 impl ArchiveWith<Timestamp> for TimestampRkyv {
-    type Archived = Archived<i64>;
+    type Archived = ArchivedTimestamp;
     type Resolver = ();
 
-    fn resolve_with(field: &Timestamp, resolver: Self::Resolver, out: Place<Self::Archived>) {
-        Self::archive(field).resolve(resolver, out);
+    fn resolve_with(field: &Timestamp, (): Self::Resolver, out: Place<Self::Archived>) {
+        out.write(ArchivedTimestamp::from(field));
     }
 }
 
@@ -49,13 +237,41 @@ impl<S: Fallible + ?Sized> SerializeWith<Timestamp, S> for TimestampRkyv {
     }
 }
 
-impl<D> DeserializeWith<Archived<i64>, Timestamp, D> for TimestampRkyv
+impl<D> DeserializeWith<ArchivedTimestamp, Timestamp, D> for TimestampRkyv
 where
     D: Fallible + ?Sized,
     D::Error: Source,
 {
-    fn deserialize_with(archived: &Archived<i64>, _: &mut D) -> Result<Timestamp, D::Error> {
-        Self::try_deserialize((*archived).into()).map_err(Source::new)
+    fn deserialize_with(archived: &ArchivedTimestamp, _: &mut D) -> Result<Timestamp, D::Error> {
+        archived.to_timestamp().map_err(Source::new)
+    }
+}
+
+impl ArchiveWith<Option<Timestamp>> for TimestampRkyv {
+    type Archived = ArchivedTimestampOption;
+    type Resolver = ();
+
+    fn resolve_with(field: &Option<Timestamp>, (): Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedTimestampOption::resolve_from_timestamp(field.as_ref(), out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<Option<Timestamp>, S> for TimestampRkyv {
+    fn serialize_with(_: &Option<Timestamp>, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D> DeserializeWith<ArchivedTimestampOption, Option<Timestamp>, D> for TimestampRkyv
+where
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        archived: &ArchivedTimestampOption,
+        _: &mut D,
+    ) -> Result<Option<Timestamp>, D::Error> {
+        archived.to_timestamp_option().map_err(Source::new)
     }
 }
 
@@ -71,10 +287,13 @@ mod tests {
         let bytes = rkyv::to_bytes(With::<_, TimestampRkyv>::cast(&timestamp))?;
 
         #[cfg(feature = "bytecheck")]
-        let archived: &Archived<i64> = rkyv::access(&bytes)?;
+        let archived: &ArchivedTimestamp = rkyv::access(&bytes)?;
 
         #[cfg(not(feature = "bytecheck"))]
-        let archived: &Archived<i64> = unsafe { rkyv::access_unchecked(&bytes) };
+        let archived: &ArchivedTimestamp = unsafe { rkyv::access_unchecked(&bytes) };
+
+        assert_eq!(*archived, timestamp);
+        assert_eq!(archived.as_unix_millis(), 1_609_462_861_010);
 
         let deserialized: Timestamp = rkyv::deserialize(With::<_, TimestampRkyv>::cast(archived))?;
 
@@ -82,4 +301,29 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_rkyv_timestamp_option() -> Result<(), Error> {
+        let timestamp = Timestamp::parse("2021-01-01T01:01:01.010000+00:00").unwrap();
+
+        for opt in [Some(timestamp), None] {
+            let bytes = rkyv::to_bytes(With::<_, TimestampRkyv>::cast(&opt))?;
+            assert_eq!(bytes.len(), size_of::<i64>());
+
+            #[cfg(feature = "bytecheck")]
+            let archived: &ArchivedTimestampOption = rkyv::access(&bytes)?;
+
+            #[cfg(not(feature = "bytecheck"))]
+            let archived: &ArchivedTimestampOption = unsafe { rkyv::access_unchecked(&bytes) };
+
+            assert_eq!(*archived, opt);
+
+            let deserialized: Option<Timestamp> =
+                rkyv::deserialize(With::<_, TimestampRkyv>::cast(archived))?;
+
+            assert_eq!(opt, deserialized);
+        }
+
+        Ok(())
+    }
 }
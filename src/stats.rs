@@ -1,12 +1,21 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use itoa::Buffer;
 use twilight_model::id::{
     marker::{ChannelMarker, GuildMarker, UserMarker},
     Id,
 };
 
 use crate::{
+    config::RateLimitedOperation,
     error::CacheError,
     key::RedisKey,
-    redis::{Cmd, ConnectionState},
+    redis::{Cmd, ConnectionState, Pipeline},
     CacheResult, RedisCache,
 };
 
@@ -14,6 +23,7 @@ use crate::{
 ///
 /// Created via [`RedisCache::stats`].
 pub struct RedisCacheStats<'c, C> {
+    cache: &'c RedisCache<C>,
     conn: ConnectionState<'c, C>,
 }
 
@@ -23,7 +33,7 @@ macro_rules! impl_stats_fn {
         pub async fn $fn(&mut self) -> CacheResult<usize> {
             let conn = self.conn.get().await?;
 
-            Cmd::scard(RedisKey::$variant)
+            Cmd::scard(self.cache.namespace().key(RedisKey::$variant))
                 .query_async(conn)
                 .await
                 .map_err(CacheError::Redis)
@@ -34,10 +44,25 @@ macro_rules! impl_stats_fn {
         pub async fn $fn(&mut self, guild_id: Id<GuildMarker>) -> CacheResult<usize> {
             let conn = self.conn.get().await?;
 
-            Cmd::scard(RedisKey::$variant { id: guild_id })
-                .query_async(conn)
+            Cmd::scard(
+                self.cache
+                    .namespace()
+                    .key(RedisKey::$variant { id: guild_id }),
+            )
+            .query_async(conn)
+            .await
+            .map_err(CacheError::Redis)
+        }
+    };
+}
+
+macro_rules! impl_ttl_stats_fn {
+    ($doc:literal, $fn:ident, $variant:ident, $prefix:ident) => {
+        #[doc = $doc]
+        /// Checks the TTL of up to `sample_size` random entries.
+        pub async fn $fn(&mut self, sample_size: usize) -> CacheResult<TtlStats> {
+            self.ttl_stats(RedisKey::$variant, RedisKey::$prefix, sample_size)
                 .await
-                .map_err(CacheError::Redis)
         }
     };
 }
@@ -45,12 +70,19 @@ macro_rules! impl_stats_fn {
 impl<'c, C> RedisCacheStats<'c, C> {
     pub(crate) const fn new(cache: &'c RedisCache<C>) -> RedisCacheStats<'c, C> {
         Self {
+            cache,
             conn: ConnectionState::new(cache),
         }
     }
 }
 
 impl<C> RedisCacheStats<'_, C> {
+    impl_stats_fn!(
+        "Total amount of currently cached auto moderation rules.",
+        auto_moderation_rules,
+        AutoModerationRules
+    );
+
     impl_stats_fn!(
         "Total amount of currently cached channels.",
         channels,
@@ -69,6 +101,12 @@ impl<C> RedisCacheStats<'_, C> {
 
     impl_stats_fn!("Total amount of currently cached roles.", roles, Roles);
 
+    impl_stats_fn!(
+        "Total amount of currently cached scheduled events.",
+        scheduled_events,
+        ScheduledEvents
+    );
+
     impl_stats_fn!(
         "Total amount of currently cached stage instances.",
         stage_instances,
@@ -89,6 +127,20 @@ impl<C> RedisCacheStats<'_, C> {
 
     impl_stats_fn!("Total amount of currently cached users.", users, Users);
 
+    impl_stats_fn!(
+        Guild:
+       "Amount of currently cached bans for a guild.",
+        guild_bans,
+        GuildBans
+    );
+
+    impl_stats_fn!(
+        Guild:
+       "Amount of members that currently boost a guild.",
+        guild_boosters,
+        GuildBoosters
+    );
+
     impl_stats_fn!(
         Guild:
        "Amount of currently cached channels for a guild.",
@@ -110,6 +162,13 @@ impl<C> RedisCacheStats<'_, C> {
         GuildIntegrations
     );
 
+    impl_stats_fn!(
+        Guild:
+       "Amount of currently cached auto moderation rules for a guild.",
+        guild_auto_moderation_rules,
+        GuildAutoModerationRules
+    );
+
     impl_stats_fn!(
         Guild:
        "Amount of currently cached members for a guild.",
@@ -131,6 +190,13 @@ impl<C> RedisCacheStats<'_, C> {
         GuildRoles
     );
 
+    impl_stats_fn!(
+        Guild:
+       "Amount of currently cached scheduled events for a guild.",
+        guild_scheduled_events,
+        GuildScheduledEvents
+    );
+
     impl_stats_fn!(
         Guild:
        "Amount of currently cached stage instances for a guild.",
@@ -160,19 +226,529 @@ impl<C> RedisCacheStats<'_, C> {
             channel: channel_id,
         };
 
-        Cmd::zcard(key)
+        Cmd::zcard(self.cache.namespace().key(key))
             .query_async(conn)
             .await
             .map_err(CacheError::Redis)
     }
 
-    /// Amount of known guilds that a user is in.
-    pub async fn common_guilds(&mut self, user_id: Id<UserMarker>) -> CacheResult<usize> {
+    /// Amount of currently cached members of a thread.
+    pub async fn thread_members(&mut self, channel_id: Id<ChannelMarker>) -> CacheResult<usize> {
         let conn = self.conn.get().await?;
 
-        Cmd::scard(RedisKey::UserGuilds { id: user_id })
+        let key = RedisKey::ChannelThreadMembers {
+            channel: channel_id,
+        };
+
+        Cmd::scard(self.cache.namespace().key(key))
             .query_async(conn)
             .await
             .map_err(CacheError::Redis)
     }
+
+    /// Amount of known guilds that a user is in.
+    pub async fn common_guilds(&mut self, user_id: Id<UserMarker>) -> CacheResult<usize> {
+        let conn = self.conn.get().await?;
+
+        Cmd::scard(
+            self.cache
+                .namespace()
+                .key(RedisKey::UserGuilds { id: user_id }),
+        )
+        .query_async(conn)
+        .await
+        .map_err(CacheError::Redis)
+    }
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached auto moderation rules.",
+        auto_moderation_rule_ttls,
+        AutoModerationRules,
+        AUTO_MODERATION_RULE_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached channels.",
+        channel_ttls,
+        Channels,
+        CHANNEL_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached emojis.",
+        emoji_ttls,
+        Emojis,
+        EMOJI_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached guilds.",
+        guild_ttls,
+        Guilds,
+        GUILD_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached messages.",
+        message_ttls,
+        Messages,
+        MESSAGE_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached roles.",
+        role_ttls,
+        Roles,
+        ROLE_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached scheduled events.",
+        scheduled_event_ttls,
+        ScheduledEvents,
+        SCHEDULED_EVENT_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached stage instances.",
+        stage_instance_ttls,
+        StageInstances,
+        STAGE_INSTANCE_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached stickers.",
+        sticker_ttls,
+        Stickers,
+        STICKER_PREFIX
+    );
+
+    impl_ttl_stats_fn!(
+        "TTL statistics of cached users.",
+        user_ttls,
+        Users,
+        USER_PREFIX
+    );
+
+    /// Sampled getter hits and misses per entity kind, e.g. `"user"`.
+    ///
+    /// Only counted if [`CacheConfig::READ_COUNTER_SAMPLING`] is non-zero.
+    ///
+    /// [`CacheConfig::READ_COUNTER_SAMPLING`]: crate::config::CacheConfig::READ_COUNTER_SAMPLING
+    pub async fn read_counts(&mut self) -> CacheResult<BTreeMap<String, ReadCounts>> {
+        let conn = self.conn.get().await?;
+
+        let fields: Vec<(String, u64)> =
+            Cmd::hgetall(self.cache.namespace().key(RedisKey::ReadCounters))
+                .query_async(conn)
+                .await?;
+
+        let mut counts = BTreeMap::<_, ReadCounts>::new();
+
+        for (field, count) in fields {
+            let Some((name, kind)) = field.split_once(':') else {
+                continue;
+            };
+
+            let entry = counts.entry(name.to_owned()).or_default();
+
+            match kind {
+                "hits" => entry.hits = count,
+                "misses" => entry.misses = count,
+                _ => {}
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Render collection sizes and update latencies in the prometheus text
+    /// exposition format.
+    ///
+    /// Useful to provide a scrape endpoint without relying on the `metrics`
+    /// ecosystem. All metric names are prefixed with `redlight_`.
+    pub async fn prometheus_text(&mut self) -> CacheResult<String> {
+        const GAUGES: [(&str, &str, RedisKey); 11] = [
+            (
+                "auto_moderation_rule_count",
+                "Amount of cached auto moderation rules",
+                RedisKey::AutoModerationRules,
+            ),
+            (
+                "channel_count",
+                "Amount of cached channels",
+                RedisKey::Channels,
+            ),
+            ("emoji_count", "Amount of cached emojis", RedisKey::Emojis),
+            ("guild_count", "Amount of cached guilds", RedisKey::Guilds),
+            (
+                "message_count",
+                "Amount of cached messages",
+                RedisKey::Messages,
+            ),
+            ("role_count", "Amount of cached roles", RedisKey::Roles),
+            (
+                "scheduled_event_count",
+                "Amount of cached scheduled events",
+                RedisKey::ScheduledEvents,
+            ),
+            (
+                "stage_instance_count",
+                "Amount of cached stage instances",
+                RedisKey::StageInstances,
+            ),
+            (
+                "sticker_count",
+                "Amount of cached stickers",
+                RedisKey::Stickers,
+            ),
+            (
+                "unavailable_guild_count",
+                "Amount of unavailable guilds",
+                RedisKey::UnavailableGuilds,
+            ),
+            ("user_count", "Amount of cached users", RedisKey::Users),
+        ];
+
+        let conn = self.conn.get().await?;
+
+        let mut pipe = Pipeline::new();
+
+        for (.., key) in GAUGES.iter() {
+            pipe.scard(self.cache.namespace().key(key));
+        }
+
+        let counts: Vec<usize> = pipe.query_async(conn).await?;
+
+        let mut text = String::new();
+
+        for ((name, help, _), count) in GAUGES.iter().zip(counts) {
+            write_metric(&mut text, name, help, "gauge", count);
+        }
+
+        let (updates, duration) = self.cache.update_counters.get();
+
+        write_metric(
+            &mut text,
+            "updates_total",
+            "Amount of processed gateway events",
+            "counter",
+            updates,
+        );
+
+        write_metric(
+            &mut text,
+            "update_duration_seconds_total",
+            "Total time it took to process gateway events",
+            "counter",
+            duration.as_secs_f64(),
+        );
+
+        Ok(text)
+    }
+
+    /// Fetch the sizes of all global collections in a single round trip.
+    ///
+    /// Member counts are only fetched for the given guilds so that large bots
+    /// don't need to query each of their guilds.
+    pub async fn snapshot(
+        &mut self,
+        member_guilds: &[Id<GuildMarker>],
+    ) -> CacheResult<StatsSnapshot> {
+        const KEYS: [RedisKey; 11] = [
+            RedisKey::AutoModerationRules,
+            RedisKey::Channels,
+            RedisKey::Emojis,
+            RedisKey::Guilds,
+            RedisKey::Messages,
+            RedisKey::Roles,
+            RedisKey::ScheduledEvents,
+            RedisKey::StageInstances,
+            RedisKey::Stickers,
+            RedisKey::UnavailableGuilds,
+            RedisKey::Users,
+        ];
+
+        let conn = self.conn.get().await?;
+
+        let mut pipe = Pipeline::new();
+
+        let namespace = self.cache.namespace();
+
+        for key in KEYS.iter() {
+            pipe.scard(namespace.key(key));
+        }
+
+        for &guild_id in member_guilds {
+            pipe.scard(namespace.key(RedisKey::GuildMembers { id: guild_id }));
+        }
+
+        let counts: Vec<usize> = pipe.query_async(conn).await?;
+        let mut counts = counts.into_iter();
+        let mut next = || counts.next().unwrap_or(0);
+
+        Ok(StatsSnapshot {
+            auto_moderation_rules: next(),
+            channels: next(),
+            emojis: next(),
+            guilds: next(),
+            messages: next(),
+            roles: next(),
+            scheduled_events: next(),
+            stage_instances: next(),
+            stickers: next(),
+            unavailable_guilds: next(),
+            users: next(),
+            guild_members: member_guilds
+                .iter()
+                .map(|&guild_id| (guild_id, next()))
+                .collect(),
+        })
+    }
+
+    async fn ttl_stats(
+        &mut self,
+        key: RedisKey,
+        prefix: &[u8],
+        sample_size: usize,
+    ) -> CacheResult<TtlStats> {
+        self.cache
+            .rate_limit(RateLimitedOperation::StatsScan)
+            .await?;
+
+        let conn = self.conn.get().await?;
+
+        let namespace = self.cache.namespace();
+        let key = namespace.key(key);
+
+        let mut pipe = Pipeline::new();
+        pipe.scard(&key).srandmember_multiple(&key, sample_size);
+
+        let (total, ids): (usize, Vec<u64>) = pipe.query_async(conn).await?;
+
+        if ids.is_empty() {
+            return Ok(TtlStats::new(total, &[]));
+        }
+
+        let mut pipe = Pipeline::new();
+        let mut buf = Buffer::new();
+        let namespace = namespace.as_bytes();
+
+        for id in ids {
+            let id = buf.format(id).as_bytes();
+
+            let mut entry_key = Vec::with_capacity(namespace.len() + prefix.len() + 1 + id.len());
+            entry_key.extend_from_slice(namespace);
+            entry_key.extend_from_slice(prefix);
+            entry_key.push(b':');
+            entry_key.extend_from_slice(id);
+
+            pipe.pttl(entry_key);
+        }
+
+        let pttls: Vec<i64> = pipe.query_async(conn).await?;
+
+        Ok(TtlStats::new(total, &pttls))
+    }
+}
+
+fn write_metric(
+    text: &mut String,
+    name: &str,
+    help: &str,
+    kind: &str,
+    value: impl std::fmt::Display,
+) {
+    let _ = writeln!(text, "# HELP redlight_{name} {help}");
+    let _ = writeln!(text, "# TYPE redlight_{name} {kind}");
+    let _ = writeln!(text, "redlight_{name} {value}");
+}
+
+/// Sizes of cached collections at one point in time.
+///
+/// Created via [`RedisCacheStats::snapshot`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StatsSnapshot {
+    /// Amount of cached auto moderation rules.
+    pub auto_moderation_rules: usize,
+    /// Amount of cached channels.
+    pub channels: usize,
+    /// Amount of cached emojis.
+    pub emojis: usize,
+    /// Amount of cached guilds.
+    pub guilds: usize,
+    /// Amount of cached messages.
+    pub messages: usize,
+    /// Amount of cached roles.
+    pub roles: usize,
+    /// Amount of cached scheduled events.
+    pub scheduled_events: usize,
+    /// Amount of cached stage instances.
+    pub stage_instances: usize,
+    /// Amount of cached stickers.
+    pub stickers: usize,
+    /// Amount of unavailable guilds.
+    pub unavailable_guilds: usize,
+    /// Amount of cached users.
+    pub users: usize,
+    /// Amount of cached members of the guilds passed to
+    /// [`RedisCacheStats::snapshot`].
+    pub guild_members: BTreeMap<Id<GuildMarker>, usize>,
+}
+
+/// Estimated getter hits and misses of an entity kind.
+///
+/// Created via [`RedisCacheStats::read_counts`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReadCounts {
+    /// Amount of reads that found a cached entry.
+    pub hits: u64,
+    /// Amount of reads that found no cached entry.
+    pub misses: u64,
+}
+
+impl ReadCounts {
+    /// Ratio of hits among all reads.
+    ///
+    /// Returns `None` if no reads were counted.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+
+        #[allow(clippy::cast_precision_loss)]
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Process-local counters of cache updates.
+#[derive(Default)]
+pub(crate) struct UpdateCounters {
+    count: AtomicU64,
+    micros: AtomicU64,
+}
+
+impl UpdateCounters {
+    pub(crate) fn record(&self, elapsed: Duration) {
+        let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.micros.fetch_add(micros, Ordering::Relaxed);
+    }
+
+    /// Amount of recorded updates and their total duration.
+    pub(crate) fn get(&self) -> (u64, Duration) {
+        let count = self.count.load(Ordering::Relaxed);
+        let micros = self.micros.load(Ordering::Relaxed);
+
+        (count, Duration::from_micros(micros))
+    }
+}
+
+/// Sampled TTL statistics of an entity type.
+///
+/// Created via methods such as [`RedisCacheStats::message_ttls`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TtlStats {
+    /// Total amount of cached entries.
+    pub total: usize,
+    /// Amount of sampled entries that will not expire.
+    pub persistent: usize,
+    /// Amount of sampled ids whose entry no longer exists.
+    pub missing: usize,
+    /// Remaining TTLs of sampled entries that will expire, sorted ascending.
+    pub remaining: Vec<Duration>,
+}
+
+impl TtlStats {
+    fn new(total: usize, pttls: &[i64]) -> Self {
+        let mut stats = Self {
+            total,
+            ..Self::default()
+        };
+
+        for &pttl in pttls {
+            match u64::try_from(pttl) {
+                Ok(millis) => stats.remaining.push(Duration::from_millis(millis)),
+                Err(_) if pttl == -1 => stats.persistent += 1,
+                Err(_) => stats.missing += 1,
+            }
+        }
+
+        stats.remaining.sort_unstable();
+
+        stats
+    }
+
+    /// Amount of sampled entries that still exist.
+    pub const fn sampled(&self) -> usize {
+        self.persistent + self.remaining.len()
+    }
+
+    /// Estimated total amount of entries that will expire, extrapolated from
+    /// the sample.
+    pub const fn estimated_expiring(&self) -> usize {
+        let sampled = self.sampled();
+
+        if sampled == 0 {
+            return 0;
+        }
+
+        self.total * self.remaining.len() / sampled
+    }
+
+    /// Remaining TTL at the given quantile among sampled entries that will
+    /// expire, e.g. `0.5` for the median.
+    ///
+    /// Returns `None` if no sampled entry will expire.
+    pub fn quantile(&self, quantile: f64) -> Option<Duration> {
+        let last = self.remaining.len().checked_sub(1)?;
+
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_precision_loss,
+            clippy::cast_sign_loss
+        )]
+        let idx = (quantile.clamp(0.0, 1.0) * last as f64).round() as usize;
+
+        self.remaining.get(idx).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ReadCounts, TtlStats, UpdateCounters};
+
+    #[test]
+    fn read_counts_hit_ratio() {
+        let counts = ReadCounts { hits: 3, misses: 1 };
+        assert_eq!(counts.hit_ratio(), Some(0.75));
+
+        assert_eq!(ReadCounts::default().hit_ratio(), None);
+    }
+
+    #[test]
+    fn update_counters() {
+        let counters = UpdateCounters::default();
+        counters.record(Duration::from_millis(3));
+        counters.record(Duration::from_millis(5));
+
+        assert_eq!(counters.get(), (2, Duration::from_millis(8)));
+    }
+
+    #[test]
+    fn ttl_stats_from_pttls() {
+        let stats = TtlStats::new(100, &[3000, -1, 1000, -2, 2000, -1]);
+
+        assert_eq!(stats.persistent, 2);
+        assert_eq!(stats.missing, 1);
+        assert_eq!(stats.sampled(), 5);
+        assert_eq!(stats.estimated_expiring(), 60);
+        assert_eq!(stats.quantile(0.0), Some(Duration::from_secs(1)));
+        assert_eq!(stats.quantile(0.5), Some(Duration::from_secs(2)));
+        assert_eq!(stats.quantile(1.0), Some(Duration::from_secs(3)));
+
+        assert_eq!(TtlStats::new(5, &[-1]).quantile(0.5), None);
+    }
 }
@@ -4,7 +4,18 @@ use crate::redis::{
     ErrorKind, FromRedisValue, RedisError, RedisResult, RedisWrite, ToRedisArgs, Value,
 };
 
-pub(crate) struct BytesWrap<B>(pub(crate) B);
+/// Raw bytes that are written to or read from redis.
+///
+/// Reading a response as `BytesWrap<AlignedVec<16>>` copies it straight into
+/// an aligned buffer; missing entries are read as empty bytes. The buffer can
+/// then be turned into a [`CachedArchive`] without copying it again through
+/// `CachedArchive::from_aligned` with the `bytecheck` feature or
+/// [`CachedArchive::from_aligned_unchecked`].
+///
+/// [`CachedArchive`]: crate::CachedArchive
+/// [`CachedArchive::from_aligned_unchecked`]: crate::CachedArchive::from_aligned_unchecked
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BytesWrap<B>(pub B);
 
 impl<B: AsRef<[u8]>> ToRedisArgs for BytesWrap<B> {
     fn write_redis_args<W: ?Sized + RedisWrite>(&self, out: &mut W) {
@@ -22,6 +33,8 @@ impl FromRedisValue for BytesWrap<AlignedVec<16>> {
 
                 Ok(Self(bytes))
             }
+            // Missing entries are represented by empty bytes
+            Value::Nil => Ok(Self(AlignedVec::new())),
             value => Err(RedisError::from((
                 ErrorKind::TypeError,
                 "Response was of incompatible type",
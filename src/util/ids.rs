@@ -0,0 +1,82 @@
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use tracing::warn;
+use twilight_model::id::Id;
+
+/// Amount of invalid ids that were skipped throughout the process.
+static INVALID_IDS: AtomicU64 = AtomicU64::new(0);
+
+/// Amount of invalid ids that were skipped so far.
+pub(crate) fn invalid_id_count() -> u64 {
+    INVALID_IDS.load(Ordering::Relaxed)
+}
+
+fn record_invalid_ids(count: usize) {
+    warn!(count, "Skipping invalid ids; IDs must not be zero");
+
+    INVALID_IDS.fetch_add(count as u64, Ordering::Relaxed);
+
+    #[cfg(feature = "metrics")]
+    crate::cache::metrics::record_invalid_ids(count);
+}
+
+/// Remove all zero ids, e.g. of a corrupted index set.
+pub(crate) fn retain_valid_ids(ids: &mut Vec<u64>) {
+    let len = ids.len();
+    ids.retain(|&id| id != 0);
+
+    if ids.len() < len {
+        record_invalid_ids(len - ids.len());
+    }
+}
+
+/// Convert ids into [`Id`]s, skipping zero ids.
+pub(crate) fn convert_ids<T>(mut ids: HashSet<u64>) -> HashSet<Id<T>> {
+    if ids.remove(&0) {
+        record_invalid_ids(1);
+    }
+
+    // SAFETY: we ensured that all u64s are non-zero
+    unsafe { std::mem::transmute(ids) }
+}
+
+/// Convert ids into [`Id`]s, skipping zero ids while preserving the order.
+pub(crate) fn convert_ids_vec<T>(mut ids: Vec<u64>) -> Vec<Id<T>> {
+    retain_valid_ids(&mut ids);
+
+    // SAFETY: we ensured that all u64s are non-zero
+    unsafe { std::mem::transmute(ids) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use twilight_model::id::{marker::GenericMarker, Id};
+
+    use super::{convert_ids, convert_ids_vec, invalid_id_count};
+
+    #[test]
+    fn test_convert_ids_zero() {
+        let mut ids = HashSet::new();
+        ids.insert(3);
+        ids.insert(0);
+        ids.insert(5);
+        let converted: HashSet<Id<GenericMarker>> = convert_ids(ids);
+
+        assert_eq!(converted.len(), 2);
+    }
+
+    #[test]
+    fn test_convert_ids_vec_zero() {
+        let before = invalid_id_count();
+
+        let converted: Vec<Id<GenericMarker>> = convert_ids_vec(vec![3, 0, 5, 0]);
+
+        assert_eq!(converted, [Id::new(3), Id::new(5)]);
+        assert!(invalid_id_count() >= before + 2);
+    }
+}
@@ -0,0 +1,22 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::SystemTime,
+};
+
+use crate::clock::Clock;
+
+/// Identifier that is unique across processes sharing the same redis.
+pub(crate) fn instance_id(clock: &dyn Clock) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = clock
+        .now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    let pid = std::process::id();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+
+    format!("{pid}-{nanos}-{count}")
+}
@@ -1,4 +1,13 @@
 mod bytes_wrap;
+mod hash;
+mod ids;
+mod instance_id;
 mod zipped;
 
-pub(crate) use self::{bytes_wrap::BytesWrap, zipped::ZippedVecs};
+pub use self::bytes_wrap::BytesWrap;
+pub(crate) use self::{
+    hash::fnv1a,
+    ids::{convert_ids, convert_ids_vec, invalid_id_count, retain_valid_ids},
+    instance_id::instance_id,
+    zipped::ZippedVecs,
+};
@@ -1,8 +1,16 @@
-use std::{marker::PhantomData, ops::Deref};
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
 
-use rkyv::{rancor::Strategy, seal::Seal, util::AlignedVec, Archive, Archived, Deserialize};
+use rkyv::{
+    api::high::HighDeserializer, rancor::Strategy, seal::Seal, util::AlignedVec, Archive, Archived,
+    Deserialize,
+};
 
-use crate::{config::Cacheable, error::UpdateArchiveError};
+use crate::{archive_utils::SealedMut, config::Cacheable, error::UpdateArchiveError, util::fnv1a};
 
 /// Archived form of a cache entry.
 ///
@@ -71,13 +79,100 @@ impl<T> CachedArchive<T> {
         }
     }
 
+    /// Create a [`CachedArchive`] from serialized bytes without validating
+    /// them.
+    ///
+    /// The bytes are copied into an aligned buffer.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must be a valid archive of `T`, e.g. the bytes of another
+    /// [`CachedArchive<T>`] retrieved through [`CachedArchive::bytes`].
+    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> Self {
+        Self::new_unchecked(aligned(bytes))
+    }
+
+    /// Create a [`CachedArchive`] from an aligned buffer without validating
+    /// it.
+    ///
+    /// Unlike [`CachedArchive::from_bytes_unchecked`], the buffer is taken
+    /// as is, e.g. after reading it through [`BytesWrap`].
+    ///
+    /// # Safety
+    ///
+    /// The bytes must be a valid archive of `T`.
+    ///
+    /// [`BytesWrap`]: crate::BytesWrap
+    pub const unsafe fn from_aligned_unchecked(bytes: AlignedVec<16>) -> Self {
+        Self::new_unchecked(bytes)
+    }
+
+    /// Return a reference to the serialized bytes.
+    ///
+    /// The bytes can be relayed to or persisted for other processes with the
+    /// same types and turned into a [`CachedArchive`] again through
+    /// `CachedArchive::from_bytes` with the `bytecheck` feature or
+    /// [`CachedArchive::from_bytes_unchecked`].
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
     /// Consume `self` and return the contained bytes.
     pub fn into_bytes(self) -> AlignedVec<16> {
         self.bytes
     }
 }
 
+fn aligned(bytes: &[u8]) -> AlignedVec<16> {
+    let mut aligned = AlignedVec::with_capacity(bytes.len());
+    aligned.extend_from_slice(bytes);
+
+    aligned
+}
+
 impl<T: Cacheable> CachedArchive<T> {
+    /// Deserialize the archived value into an owned instance.
+    ///
+    /// The target type is usually `T` itself but may be any type that the
+    /// archived form can be deserialized into.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rkyv::{Archive, Deserialize, Serialize};
+    /// use redlight::{config::Cacheable, CachedArchive};
+    ///
+    /// #[derive(Archive, Serialize, Deserialize)]
+    /// struct CachedData {
+    ///     name: String,
+    /// }
+    ///
+    /// impl Cacheable for CachedData {
+    ///     # /*
+    ///     // ...
+    ///     # */
+    ///     # type Bytes = [u8; 0];
+    ///     # fn expire() -> Option<std::time::Duration> { None }
+    ///     # fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> { Ok([]) }
+    /// }
+    ///
+    /// impl rkyv::rancor::Fallible for CachedData {
+    ///     type Error = rkyv::rancor::Error;
+    /// }
+    ///
+    /// fn owned_name(archive: &CachedArchive<CachedData>) -> String {
+    ///     let data: CachedData = archive.deserialize_into().unwrap();
+    ///
+    ///     data.name
+    /// }
+    /// ```
+    pub fn deserialize_into<U>(&self) -> Result<U, T::Error>
+    where
+        T::Archived: Deserialize<U, HighDeserializer<T::Error>>,
+    {
+        rkyv::deserialize::<U, T::Error>(&**self)
+    }
+
     /// Update the contained value by mutating the archive itself.
     ///
     /// This should be preferred over [`update_by_deserializing`] when possible
@@ -137,6 +232,60 @@ impl<T: Cacheable> CachedArchive<T> {
         Ok(())
     }
 
+    /// Same as [`update_archive`] but wraps the sealed archive in a
+    /// [`SealedMut`] guard.
+    ///
+    /// The guard only allows replacing values of a fixed size which
+    /// reduces the amount of unsafe reasoning required to mutate the archive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rkyv::Archive;
+    /// use redlight::{config::Cacheable, CachedArchive};
+    /// use rkyv::munge::munge;
+    ///
+    /// #[derive(Archive)]
+    /// struct CachedData {
+    ///     num: u32,
+    /// }
+    ///
+    /// impl Cacheable for CachedData {
+    ///     # /*
+    ///     // ...
+    ///     # */
+    ///     # type Bytes = [u8; 0];
+    ///     # fn expire() -> Option<std::time::Duration> { None }
+    ///     # fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> { Ok([]) }
+    /// }
+    ///
+    /// impl rkyv::rancor::Fallible for CachedData {
+    ///     type Error = rkyv::rancor::Error;
+    /// }
+    ///
+    /// fn handle_archive(archive: &mut CachedArchive<CachedData>, new_num: u32) {
+    ///     archive
+    ///         .update_sealed(|sealed| {
+    ///             sealed
+    ///                 .project(|seal| {
+    ///                     munge!(let ArchivedCachedData { num } = seal);
+    ///
+    ///                     num
+    ///                 })
+    ///                 .set(new_num);
+    ///         })
+    ///         .unwrap();
+    /// }
+    /// ```
+    ///
+    /// [`update_archive`]: CachedArchive::update_archive
+    pub fn update_sealed(
+        &mut self,
+        f: impl FnOnce(SealedMut<'_, Archived<T>>),
+    ) -> Result<(), T::Error> {
+        self.update_archive(|sealed| f(SealedMut::new(sealed)))
+    }
+
     /// Update the contained value by deserializing the archive, mutating it,
     /// and then serializing again.
     ///
@@ -205,7 +354,94 @@ impl<T: Cacheable> CachedArchive<T> {
 
         f(&mut deserialized);
 
-        let bytes = deserialized
+        self.reserialize(&deserialized)
+    }
+
+    /// Same as [`update_by_deserializing`] but first looks up the
+    /// deserialized value in the given [`DeserializeCache`].
+    ///
+    /// If the archive's bytes were deserialized through the same cache before,
+    /// and no other write happened in the meanwhile, the value is taken from
+    /// the cache instead of being deserialized again. The updated value is
+    /// then stored in the cache for the next update.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rkyv::{Archive, Deserialize, Serialize};
+    /// use std::sync::OnceLock;
+    ///
+    /// use redlight::{config::Cacheable, CachedArchive, DeserializeCache};
+    /// use rkyv::rancor::Fallible;
+    ///
+    /// #[derive(Archive, Serialize, Deserialize)]
+    /// struct CachedData {
+    ///     nums: Vec<u32>,
+    /// }
+    ///
+    /// impl Cacheable for CachedData {
+    ///     # /*
+    ///     // ...
+    ///     # */
+    ///     # type Bytes = [u8; 0];
+    ///     # fn expire() -> Option<std::time::Duration> { None }
+    ///     # fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> { Ok([]) }
+    /// }
+    ///
+    /// impl Fallible for CachedData {
+    ///     type Error = rkyv::rancor::Error;
+    /// }
+    ///
+    /// fn deserialize_cache() -> &'static DeserializeCache<CachedData> {
+    ///     static CACHE: OnceLock<DeserializeCache<CachedData>> = OnceLock::new();
+    ///
+    ///     CACHE.get_or_init(|| DeserializeCache::new(32))
+    /// }
+    ///
+    /// fn handle_archive(
+    ///     archive: &mut CachedArchive<CachedData>,
+    ///     new_num: u32,
+    /// ) -> Result<(), <CachedData as Fallible>::Error> {
+    ///     archive
+    ///         .update_by_deserializing_cached(
+    ///             |deserialized| deserialized.nums.push(new_num),
+    ///             &mut (),
+    ///             deserialize_cache(),
+    ///         )
+    ///         .map_err(rkyv::rancor::Source::new)
+    /// }
+    /// ```
+    ///
+    /// [`update_by_deserializing`]: CachedArchive::update_by_deserializing
+    #[allow(clippy::similar_names)]
+    pub fn update_by_deserializing_cached<D>(
+        &mut self,
+        f: impl FnOnce(&mut T),
+        deserializer: &mut D,
+        cache: &DeserializeCache<T>,
+    ) -> Result<(), UpdateArchiveError<T::Error>>
+    where
+        T::Archived: Deserialize<T, Strategy<D, T::Error>>,
+    {
+        let mut deserialized = if let Some(value) = cache.take(self.bytes.as_slice()) {
+            value
+        } else {
+            let archived: &T::Archived = &*self;
+
+            rkyv::api::deserialize_using(archived, deserializer)
+                .map_err(UpdateArchiveError::Deserialization)?
+        };
+
+        f(&mut deserialized);
+
+        self.reserialize(&deserialized)?;
+        cache.insert(self.bytes.as_slice(), deserialized);
+
+        Ok(())
+    }
+
+    fn reserialize(&mut self, value: &T) -> Result<(), UpdateArchiveError<T::Error>> {
+        let bytes = value
             .serialize_one()
             .map_err(UpdateArchiveError::Serialization)?;
 
@@ -216,6 +452,95 @@ impl<T: Cacheable> CachedArchive<T> {
     }
 }
 
+/// Small in-process LRU of deserialized values.
+///
+/// Entries are keyed by the archived bytes they were deserialized from so a
+/// cached value is only used if the bytes in redis did not change since.
+/// Taking a value out of the cache removes it, and storing the updated value
+/// replaces the previous version.
+///
+/// Used through [`CachedArchive::update_by_deserializing_cached`].
+pub struct DeserializeCache<T> {
+    capacity: usize,
+    entries: Mutex<Vec<DeserializeCacheEntry<T>>>,
+}
+
+struct DeserializeCacheEntry<T> {
+    hash: u64,
+    bytes: Box<[u8]>,
+    value: T,
+}
+
+impl<T> DeserializeCache<T> {
+    /// Create a new [`DeserializeCache`] that holds up to `capacity` values.
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The amount of currently cached values.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no values are currently cached.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all cached values.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn take(&self, bytes: &[u8]) -> Option<T> {
+        let hash = fnv1a(bytes);
+        let mut entries = self.lock();
+
+        let idx = entries
+            .iter()
+            .position(|entry| entry.hash == hash && *entry.bytes == *bytes)?;
+
+        Some(entries.remove(idx).value)
+    }
+
+    fn insert(&self, bytes: &[u8], value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let entry = DeserializeCacheEntry {
+            hash: fnv1a(bytes),
+            bytes: bytes.into(),
+            value,
+        };
+
+        let mut entries = self.lock();
+
+        // The least recently used entry is at the front
+        if entries.len() >= self.capacity {
+            entries.remove(0);
+        }
+
+        entries.push(entry);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<DeserializeCacheEntry<T>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T> Debug for DeserializeCache<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("DeserializeCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(feature = "bytecheck")]
 const _: () = {
     use rkyv::rancor::{BoxedError, Source};
@@ -230,6 +555,24 @@ const _: () = {
 
             Ok(Self::new_unchecked(bytes))
         }
+
+        /// Create a [`CachedArchive`] from serialized bytes, e.g. those of
+        /// [`CachedArchive::bytes`], after validating them.
+        ///
+        /// The bytes are copied into an aligned buffer.
+        pub fn from_bytes(bytes: &[u8]) -> CacheResult<Self> {
+            Self::new(aligned(bytes))
+        }
+
+        /// Create a [`CachedArchive`] from an aligned buffer, e.g. one read
+        /// through [`BytesWrap`], after validating it.
+        ///
+        /// Unlike [`CachedArchive::from_bytes`], the buffer is taken as is.
+        ///
+        /// [`BytesWrap`]: crate::BytesWrap
+        pub fn from_aligned(bytes: AlignedVec<16>) -> CacheResult<Self> {
+            Self::new(bytes)
+        }
     }
 };
 
@@ -249,3 +592,121 @@ impl<T> Clone for CachedArchive<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rkyv::{rancor::Error, util::AlignedVec, Archive, Deserialize, Serialize};
+
+    use super::{CachedArchive, DeserializeCache};
+    use crate::config::Cacheable;
+
+    #[derive(Archive, Serialize, Deserialize)]
+    struct Data {
+        nums: Vec<u32>,
+    }
+
+    impl Cacheable for Data {
+        type Bytes = AlignedVec<16>;
+
+        fn expire() -> Option<std::time::Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+    }
+
+    impl rkyv::rancor::Fallible for Data {
+        type Error = Error;
+    }
+
+    fn archive(nums: Vec<u32>) -> CachedArchive<Data> {
+        let bytes = Data { nums }.serialize_one().unwrap();
+
+        CachedArchive::new_unchecked(bytes)
+    }
+
+    #[test]
+    fn deserialize_into_owned() {
+        let archive = archive(vec![1, 2, 3]);
+
+        let data: Data = archive.deserialize_into().unwrap();
+        assert_eq!(data.nums, [1, 2, 3]);
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let archive = archive(vec![1, 2, 3]);
+
+        let relayed: CachedArchive<Data> =
+            unsafe { CachedArchive::from_bytes_unchecked(archive.bytes()) };
+
+        assert_eq!(relayed.nums.as_slice(), [1, 2, 3]);
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn from_bytes_validates() {
+        let archive = archive(vec![1, 2, 3]);
+
+        let relayed = CachedArchive::<Data>::from_bytes(archive.bytes()).unwrap();
+        assert_eq!(relayed.nums.as_slice(), [1, 2, 3]);
+
+        assert!(CachedArchive::<Data>::from_bytes(&[0xFF; 3]).is_err());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn from_aligned_keeps_buffer() {
+        let bytes = archive(vec![1, 2, 3]).into_bytes();
+        let ptr = bytes.as_ptr();
+
+        let archive = CachedArchive::<Data>::from_aligned(bytes).unwrap();
+        assert_eq!(archive.nums.as_slice(), [1, 2, 3]);
+        assert_eq!(archive.bytes().as_ptr(), ptr);
+    }
+
+    #[test]
+    fn deserialize_cache_reuses_values() {
+        let cache = DeserializeCache::new(2);
+        let mut archive = archive(vec![1]);
+
+        archive
+            .update_by_deserializing_cached(|data| data.nums.push(2), &mut (), &cache)
+            .unwrap();
+
+        assert_eq!(cache.len(), 1);
+
+        // A different archive with the same bytes hits the cache
+        let mut other = archive.clone();
+
+        other
+            .update_by_deserializing_cached(|data| data.nums.push(3), &mut (), &cache)
+            .unwrap();
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(other.nums.as_slice(), [1, 2, 3]);
+
+        // The previous version was replaced so the stale archive misses
+        archive
+            .update_by_deserializing_cached(|data| data.nums.push(4), &mut (), &cache)
+            .unwrap();
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(archive.nums.as_slice(), [1, 2, 4]);
+    }
+
+    #[test]
+    fn deserialize_cache_evicts_least_recent() {
+        let cache = DeserializeCache::new(1);
+
+        for i in 0..3 {
+            archive(vec![i])
+                .update_by_deserializing_cached(|_| {}, &mut (), &cache)
+                .unwrap();
+        }
+
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "attachments")]
+
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{RoleCreate, RoleDelete},
+    },
+    guild::Role,
+    id::Id,
+};
+
+use crate::{events::version::role, pool};
+
+#[tokio::test]
+async fn test_attachments() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole;
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(_: &'a Role) -> Self {
+            Self
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct Note {
+        warnings: u32,
+    }
+
+    impl Cacheable for Note {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+    }
+
+    impl Fallible for Note {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(321);
+    let role = role("attached", 1);
+    let role_id = role.id;
+    let key = RedisKey::Role { id: role_id };
+
+    let note = Note { warnings: 2 };
+    assert!(!cache.set_attachment(key.clone(), &note).await?);
+
+    let create = Event::RoleCreate(RoleCreate { guild_id, role });
+    cache.update(&create).await?;
+
+    assert!(cache.set_attachment(key.clone(), &note).await?);
+
+    let attached = cache
+        .get_attachment::<Note>(key.clone())
+        .await?
+        .expect("missing attachment");
+    assert_eq!(attached.warnings, 2);
+
+    let delete = Event::RoleDelete(RoleDelete { guild_id, role_id });
+    cache.update(&delete).await?;
+
+    assert!(cache.get_attachment::<Note>(key).await?.is_none());
+
+    Ok(())
+}
@@ -1,6 +1,11 @@
+mod attachment;
 mod cold_resume;
 mod events;
+mod inmemory;
+mod local_cache;
 mod metrics;
+mod mirror;
+mod pubsub;
 mod util;
 
 use std::{env, sync::OnceLock};
@@ -14,6 +19,21 @@ type Pool = bb8_redis::bb8::Pool<bb8_redis::RedisConnectionManager>;
 #[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
 type Pool = deadpool_redis::Pool;
 
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    feature = "multiplexed"
+))]
+type Pool = redlight::MultiplexedPool;
+
+#[cfg(all(
+    not(feature = "bb8"),
+    not(feature = "deadpool"),
+    not(feature = "multiplexed"),
+    feature = "cluster"
+))]
+type Pool = redlight::ClusterPool;
+
 static POOL: OnceLock<Pool> = OnceLock::new();
 
 pub fn pool() -> Pool {
@@ -51,6 +71,25 @@ pub fn pool() -> Pool {
             .unwrap()
     };
 
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        feature = "multiplexed"
+    ))]
+    let init = || {
+        let client = redis::Client::open(redis_url()).unwrap();
+
+        redlight::MultiplexedPool::new(client)
+    };
+
+    #[cfg(all(
+        not(feature = "bb8"),
+        not(feature = "deadpool"),
+        not(feature = "multiplexed"),
+        feature = "cluster"
+    ))]
+    let init = || redlight::ClusterPool::new(vec![redis_url()]).unwrap();
+
     // cannot flush db on startup due to potentially initializing multiple times
     // cannot flush db on cleanup due do lacking async drop
     POOL.get_or_init(init).clone()
@@ -19,17 +19,21 @@ async fn test_cold_resume() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: std::time::Duration = std::time::Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -43,12 +47,25 @@ async fn test_cold_resume() -> Result<(), CacheError> {
     cache.freeze(&sessions, Some(duration)).await?;
 
     let defrosted = cache.defrost(false).await?;
-    assert_eq!(defrosted, Some(sessions));
+    assert_eq!(defrosted.as_ref(), Some(&sessions));
 
     tokio::time::sleep(duration + Duration::from_secs(1)).await;
 
     let defrosted = cache.defrost(true).await?;
     assert_eq!(defrosted, None);
 
+    let sessions: HashMap<_, _> = sessions.into_iter().filter(|(id, _)| *id < 2).collect();
+    cache.freeze(&sessions, Some(duration)).await?;
+
+    let timeout = Duration::from_secs(5);
+    let defrosted = cache.defrost_within(0..4, timeout, true).await?;
+    assert_eq!(defrosted.sessions, sessions);
+    assert_eq!(defrosted.missing, [2, 3]);
+
+    // Sessions were deleted while reading
+    let defrosted = cache.defrost_within(0..4, timeout, false).await?;
+    assert!(defrosted.sessions.is_empty());
+    assert_eq!(defrosted.missing, [0, 1, 2, 3]);
+
     Ok(())
 }
@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedAutoModerationRule, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    ser::writer::Buffer,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{AutoModerationRuleCreate, AutoModerationRuleDelete},
+    },
+    guild::auto_moderation::{
+        AutoModerationEventType, AutoModerationRule, AutoModerationTriggerMetadata,
+        AutoModerationTriggerType,
+    },
+    id::Id,
+};
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_auto_moderation_rule() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = CachedAutoModerationRule;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedAutoModerationRule {
+        enabled: bool,
+    }
+
+    impl<'a> ICachedAutoModerationRule<'a> for CachedAutoModerationRule {
+        fn from_auto_moderation_rule(rule: &'a AutoModerationRule) -> Self {
+            Self {
+                enabled: rule.enabled,
+            }
+        }
+    }
+
+    impl Cacheable for CachedAutoModerationRule {
+        type Bytes = [u8; 1];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            let mut bytes = [0_u8; 1];
+            rkyv::api::high::to_bytes_in(self, Buffer::from(&mut bytes))?;
+
+            Ok(bytes)
+        }
+    }
+
+    impl Fallible for CachedAutoModerationRule {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let expected = auto_moderation_rule();
+
+    let event =
+        Event::AutoModerationRuleCreate(Box::new(AutoModerationRuleCreate(expected.clone())));
+    cache.update(&event).await?;
+
+    let rule = cache
+        .auto_moderation_rule(expected.id)
+        .await?
+        .expect("missing auto moderation rule");
+
+    assert_eq!(rule.enabled, expected.enabled);
+
+    let rule_ids = cache
+        .guild_auto_moderation_rule_ids(expected.guild_id)
+        .await?;
+
+    assert!(rule_ids.contains(&expected.id));
+
+    let event =
+        Event::AutoModerationRuleDelete(Box::new(AutoModerationRuleDelete(expected.clone())));
+    cache.update(&event).await?;
+
+    assert!(cache.auto_moderation_rule(expected.id).await?.is_none());
+
+    let rule_ids = cache
+        .guild_auto_moderation_rule_ids(expected.guild_id)
+        .await?;
+
+    assert!(!rule_ids.contains(&expected.id));
+
+    Ok(())
+}
+
+pub fn auto_moderation_rule() -> AutoModerationRule {
+    AutoModerationRule {
+        actions: Vec::new(),
+        creator_id: Id::new(8_100),
+        enabled: true,
+        event_type: AutoModerationEventType::MessageSend,
+        exempt_channels: Vec::new(),
+        exempt_roles: Vec::new(),
+        guild_id: Id::new(8_101),
+        id: Id::new(8_102),
+        name: "auto moderation rule".to_owned(),
+        trigger_metadata: AutoModerationTriggerMetadata {
+            allow_list: None,
+            keyword_filter: None,
+            presets: None,
+            mention_raid_protection_enabled: None,
+            mention_total_limit: None,
+            regex_patterns: None,
+        },
+        trigger_type: AutoModerationTriggerType::Keyword,
+    }
+}
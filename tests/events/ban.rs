@@ -0,0 +1,130 @@
+use std::time::{Duration, SystemTime};
+
+use redlight::{
+    clock::MockClock,
+    config::{CacheConfig, Cacheable, ICachedGuild, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{BanAdd, BanRemove, GuildUpdate},
+    },
+    guild::{Ban, Guild},
+    id::Id,
+    user::User,
+};
+
+use super::user::user;
+use crate::pool;
+
+#[tokio::test]
+async fn test_guild_bans() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = CachedGuild;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedGuild;
+
+    impl<'a> ICachedGuild<'a> for CachedGuild {
+        const CACHE_BANS: bool = true;
+
+        fn from_guild(_: &'a Guild) -> Self {
+            Self
+        }
+
+        fn on_guild_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    impl Cacheable for CachedGuild {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedGuild {
+        type Error = Panic;
+    }
+
+    let snapshot_time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let clock = MockClock::new(snapshot_time);
+    let cache = RedisCache::<Config>::new_with_clock(pool(), clock).await?;
+
+    let guild_id = Id::new(987);
+    let bans = [ban(user()), ban(banned_user(2))];
+
+    cache.store_guild_bans(guild_id, &bans).await?;
+
+    let ban_ids = cache.guild_ban_ids(guild_id).await?;
+    assert_eq!(ban_ids.len(), 2);
+    assert!(bans.iter().all(|ban| ban_ids.contains(&ban.user.id)));
+
+    let snapshot = cache.guild_bans_snapshot(guild_id).await?;
+    assert_eq!(snapshot, Some(snapshot_time));
+
+    let ban_add = Event::BanAdd(BanAdd {
+        guild_id,
+        user: banned_user(3),
+    });
+    cache.update(&ban_add).await?;
+
+    let ban_remove = Event::BanRemove(BanRemove {
+        guild_id,
+        user: user(),
+    });
+    cache.update(&ban_remove).await?;
+
+    let ban_ids = cache.guild_ban_ids(guild_id).await?;
+    assert_eq!(ban_ids.len(), 2);
+    assert!(ban_ids.contains(&Id::new(2)));
+    assert!(ban_ids.contains(&Id::new(3)));
+
+    Ok(())
+}
+
+fn ban(user: User) -> Ban {
+    Ban { reason: None, user }
+}
+
+fn banned_user(id: u64) -> User {
+    User {
+        id: Id::new(id),
+        ..user()
+    }
+}
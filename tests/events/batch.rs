@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleCreate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_batched_updater() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+    let mut updater = cache.batched(3, Duration::from_secs(60));
+
+    let guild_id = Id::new(8_300);
+
+    let roles: Vec<_> = (8_301..=8_304)
+        .zip(1..)
+        .map(|(id, position)| {
+            let mut role = role("batch", position);
+            role.id = Id::new(id);
+
+            role
+        })
+        .collect();
+
+    for role in &roles[..2] {
+        let event = Event::RoleCreate(RoleCreate {
+            guild_id,
+            role: role.clone(),
+        });
+
+        assert!(!updater.update(&event).await?);
+    }
+
+    assert_eq!(updater.len(), 2);
+    assert!(cache.role(roles[0].id).await?.is_none());
+
+    // Reaching the threshold flushes all buffered events
+    let event = Event::RoleCreate(RoleCreate {
+        guild_id,
+        role: roles[2].clone(),
+    });
+
+    assert!(updater.update(&event).await?);
+    assert!(updater.is_empty());
+
+    for role in &roles[..3] {
+        let cached = cache.role(role.id).await?.expect("missing role");
+        assert_eq!(cached.position, role.position);
+    }
+
+    // Trailing events are sent on manual flush
+    let event = Event::RoleCreate(RoleCreate {
+        guild_id,
+        role: roles[3].clone(),
+    });
+
+    assert!(!updater.update(&event).await?);
+    assert!(cache.role(roles[3].id).await?.is_none());
+
+    updater.flush().await?;
+
+    assert!(cache.role(roles[3].id).await?.is_some());
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use std::{
     fmt::{Debug, Formatter, Result as FmtResult},
     ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
     time::Duration,
 };
 
@@ -21,17 +22,23 @@ use rkyv::{
     Archive, Serialize,
 };
 use twilight_model::{
-    channel::{Channel, ChannelFlags, ChannelType, VideoQualityMode},
+    channel::{
+        permission_overwrite::{PermissionOverwrite, PermissionOverwriteType},
+        Channel, ChannelFlags, ChannelType, VideoQualityMode,
+    },
     gateway::{
         event::Event,
-        payload::incoming::{ChannelCreate, ChannelPinsUpdate},
+        payload::incoming::{ChannelCreate, ChannelDelete, ChannelPinsUpdate, ChannelUpdate},
     },
+    guild::Permissions,
     id::{marker::ChannelMarker, Id},
     util::{ImageHash, Timestamp},
 };
 
 use crate::pool;
 
+static PERMISSION_UPDATES: AtomicUsize = AtomicUsize::new(0);
+
 #[tokio::test]
 async fn test_channel() -> Result<(), CacheError> {
     struct Config;
@@ -40,17 +47,21 @@ async fn test_channel() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = CachedChannel<'a>;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -179,6 +190,178 @@ async fn test_channel() -> Result<(), CacheError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_channel_permission_update() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = CachedChannel;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedChannel {
+        overwrites: u32,
+    }
+
+    impl<'a> ICachedChannel<'a> for CachedChannel {
+        fn from_channel(channel: &'a Channel) -> Self {
+            Self {
+                overwrites: overwrite_count(channel),
+            }
+        }
+
+        fn on_permission_update() -> Option<fn(&CachedArchive<Self>, &Channel)> {
+            Some(|cached, channel| {
+                if cached.overwrites.to_native() != overwrite_count(channel) {
+                    PERMISSION_UPDATES.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        }
+    }
+
+    impl Cacheable for CachedChannel {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::<8>::new())
+        }
+    }
+
+    impl Fallible for CachedChannel {
+        type Error = Panic;
+    }
+
+    fn overwrite_count(channel: &Channel) -> u32 {
+        channel
+            .permission_overwrites
+            .as_ref()
+            .map_or(0, |overwrites| overwrites.len() as u32)
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut channel = text_channel();
+    channel.id = Id::new(7_500);
+
+    // Not cached yet so the hook is not called
+    let event = Event::ChannelUpdate(Box::new(ChannelUpdate(channel.clone())));
+    cache.update(&event).await?;
+    assert_eq!(PERMISSION_UPDATES.load(Ordering::SeqCst), 0);
+
+    channel.topic = Some("new_topic".to_owned());
+    let event = Event::ChannelUpdate(Box::new(ChannelUpdate(channel.clone())));
+    cache.update(&event).await?;
+    assert_eq!(PERMISSION_UPDATES.load(Ordering::SeqCst), 0);
+
+    channel.permission_overwrites = Some(vec![PermissionOverwrite {
+        allow: Permissions::VIEW_CHANNEL,
+        deny: Permissions::SEND_MESSAGES,
+        id: Id::new(7_501),
+        kind: PermissionOverwriteType::Role,
+    }]);
+    let event = Event::ChannelUpdate(Box::new(ChannelUpdate(channel)));
+    cache.update(&event).await?;
+    assert_eq!(PERMISSION_UPDATES.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_channel_guild_id() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = CachedChannel;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedChannel;
+
+    impl<'a> ICachedChannel<'a> for CachedChannel {
+        fn from_channel(_: &'a Channel) -> Self {
+            Self
+        }
+    }
+
+    impl Cacheable for CachedChannel {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedChannel {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut channel = text_channel();
+    channel.id = Id::new(8_700);
+    channel.guild_id = Some(Id::new(8_701));
+
+    assert!(cache.channel_guild_id(channel.id).await?.is_none());
+
+    let event = Event::ChannelCreate(Box::new(ChannelCreate(channel.clone())));
+    cache.update(&event).await?;
+
+    assert_eq!(cache.channel_guild_id(channel.id).await?, channel.guild_id);
+
+    let event = Event::ChannelDelete(Box::new(ChannelDelete(channel.clone())));
+    cache.update(&event).await?;
+
+    assert!(cache.channel_guild_id(channel.id).await?.is_none());
+
+    Ok(())
+}
+
 pub fn text_channel() -> Channel {
     Channel {
         application_id: None,
@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{
+        CacheConfig, Cacheable, ICachedMember, ICachedMessage, ICachedUser, Ignore, ReactionEvent,
+    },
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    channel::Message,
+    gateway::{
+        event::Event,
+        payload::incoming::{
+            invite_create::PartialUser, MemberAdd, MemberUpdate, MessageCreate, MessageUpdate,
+        },
+    },
+    guild::{Member, PartialMember},
+    id::{marker::GuildMarker, Id},
+    user::User,
+};
+
+use super::{member::member, message::message};
+use crate::pool;
+
+#[tokio::test]
+async fn test_combined_getters() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        pending: bool,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                pending: member.pending,
+            }
+        }
+
+        fn on_member_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage {
+        pinned: bool,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        fn from_message(message: &'a Message) -> Self {
+            Self {
+                pinned: message.pinned,
+            }
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        discriminator: u16,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self {
+                discriminator: user.discriminator,
+            }
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    macro_rules! impl_cacheable {
+        ( $( $ty:ident ),* ) => {
+            $(
+                impl Cacheable for $ty {
+                    type Bytes = AlignedVec;
+
+                    fn expire() -> Option<Duration> {
+                        None
+                    }
+
+                    fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+                        rkyv::to_bytes(self)
+                    }
+                }
+
+                impl Fallible for $ty {
+                    type Error = Panic;
+                }
+            )*
+        };
+    }
+
+    impl_cacheable!(CachedMember, CachedMessage, CachedUser);
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(115);
+    let expected_member = member();
+    let user_id = expected_member.user.id;
+
+    let event = Event::MemberAdd(Box::new(MemberAdd {
+        guild_id,
+        member: expected_member.clone(),
+    }));
+    cache.update(&event).await?;
+
+    let (member, user) = cache.member_with_user(guild_id, user_id).await?;
+    assert_eq!(
+        member.expect("missing member").pending,
+        expected_member.pending
+    );
+    assert_eq!(
+        user.expect("missing user").discriminator,
+        expected_member.user.discriminator
+    );
+
+    let mut expected_msg = message();
+    expected_msg.id = Id::new(920);
+    expected_msg.author = expected_member.user.clone();
+
+    let event = Event::MessageCreate(Box::new(MessageCreate(expected_msg.clone())));
+    cache.update(&event).await?;
+
+    let (msg, author) = cache.message_with_author(expected_msg.id).await?;
+    assert_eq!(msg.expect("missing message").pinned, expected_msg.pinned);
+    assert_eq!(
+        author.expect("missing author").discriminator,
+        expected_msg.author.discriminator
+    );
+
+    let (msg, author) = cache.message_with_author(Id::new(921)).await?;
+    assert!(msg.is_none());
+    assert!(author.is_none());
+
+    Ok(())
+}
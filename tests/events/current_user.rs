@@ -4,7 +4,7 @@ use redlight::{
     config::{CacheConfig, Cacheable, ICachedCurrentUser, Ignore},
     error::CacheError,
     rkyv_util::id::IdRkyv,
-    RedisCache,
+    RedisCache, RedisKey,
 };
 use rkyv::{
     rancor::{Fallible, Panic},
@@ -29,17 +29,21 @@ async fn test_current_user() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = CachedCurrentUser<'a>;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -110,6 +114,13 @@ async fn test_current_user() -> Result<(), CacheError> {
     assert_eq!(current_user.name.as_ref(), expected.name);
     assert_eq!(current_user.id, expected.id);
 
+    let raw = cache
+        .raw_bytes(RedisKey::CurrentUser)
+        .await?
+        .expect("missing raw bytes");
+
+    assert_eq!(raw.as_ref(), current_user.into_bytes().as_slice());
+
     Ok(())
 }
 
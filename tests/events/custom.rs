@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_custom() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct Settings {
+        volume: u32,
+    }
+
+    impl Cacheable for Settings {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for Settings {
+        type Error = Panic;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct Cooldown;
+
+    impl Cacheable for Cooldown {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_millis(200))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for Cooldown {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    assert!(cache.custom::<Settings>("settings", "1").await?.is_none());
+
+    cache
+        .set_custom("settings", "1", &Settings { volume: 50 })
+        .await?;
+
+    let settings = cache
+        .custom::<Settings>("settings", "1")
+        .await?
+        .expect("missing settings");
+
+    assert_eq!(settings.volume, 50);
+
+    // Namespaces do not share keys
+    assert!(cache.custom::<Settings>("cooldowns", "1").await?.is_none());
+
+    cache.delete_custom("settings", "1").await?;
+    assert!(cache.custom::<Settings>("settings", "1").await?.is_none());
+
+    // Sub-second expire durations are neither rejected nor rounded down
+    cache.set_custom("cooldowns", "1", &Cooldown).await?;
+    assert!(cache.custom::<Cooldown>("cooldowns", "1").await?.is_some());
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(cache.custom::<Cooldown>("cooldowns", "1").await?.is_none());
+
+    // Separators within namespaces or keys are rejected
+    let res = cache
+        .set_custom("settings", "1:2", &Settings { volume: 0 })
+        .await;
+    assert!(matches!(res, Err(CacheError::InvalidCustomKey { .. })));
+
+    let res = cache.custom::<Settings>("guild:settings", "1").await;
+    assert!(matches!(res, Err(CacheError::InvalidCustomKey { .. })));
+
+    Ok(())
+}
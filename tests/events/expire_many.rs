@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleCreate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_expire_many() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(115);
+    let role_ids = [Id::new(225), Id::new(226)];
+
+    for role_id in role_ids {
+        let mut role = role("expire_many", 1);
+        role.id = role_id;
+
+        let event = Event::RoleCreate(RoleCreate { guild_id, role });
+        cache.update(&event).await?;
+    }
+
+    let uncached = Id::new(227);
+    let keys = role_ids.into_iter().chain([uncached]);
+
+    let expiring = cache.expire_many(keys, Duration::from_millis(100)).await?;
+    assert_eq!(expiring, 2);
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    for role_id in role_ids {
+        assert!(cache.role(role_id).await?.is_none());
+    }
+
+    // Give the expire listener some time to clean up
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let cached_ids = cache.role_ids().await?;
+    assert!(role_ids.iter().all(|role_id| !cached_ids.contains(role_id)));
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedMember, ICachedUser, Ignore},
+    error::CacheError,
+    CacheConfigGroup, CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{invite_create::PartialUser, MemberAdd, MemberUpdate},
+    },
+    guild::{Member, PartialMember},
+    id::{marker::GuildMarker, Id},
+    user::User,
+};
+
+use super::member::member;
+use crate::pool;
+
+#[tokio::test]
+async fn test_config_group() -> Result<(), CacheError> {
+    struct MemberConfig;
+
+    impl CacheConfig for MemberConfig {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    struct UserConfig;
+
+    impl CacheConfig for UserConfig {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        pending: bool,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                pending: member.pending,
+            }
+        }
+
+        fn on_member_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        discriminator: u16,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self {
+                discriminator: user.discriminator,
+            }
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    macro_rules! impl_cacheable {
+        ( $( $ty:ident ),* ) => {
+            $(
+                impl Cacheable for $ty {
+                    type Bytes = AlignedVec;
+
+                    fn expire() -> Option<Duration> {
+                        None
+                    }
+
+                    fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+                        rkyv::to_bytes(self)
+                    }
+                }
+
+                impl Fallible for $ty {
+                    type Error = Panic;
+                }
+            )*
+        };
+    }
+
+    impl_cacheable!(CachedMember, CachedUser);
+
+    let members = RedisCache::<MemberConfig>::new_with_pool(pool()).await?;
+    let users = RedisCache::<UserConfig>::new_with_pool(pool()).await?;
+    let group = CacheConfigGroup::new(members, users);
+
+    let mut member = member();
+    member.user.id = Id::new(228);
+    member.pending = true;
+    let user_id = member.user.id;
+    let guild_id = Id::new(119);
+
+    let event = Event::MemberAdd(Box::new(MemberAdd { guild_id, member }));
+
+    // The group's future is `Send` for concrete configs
+    tokio::spawn(async move {
+        group.update(&event).await?;
+
+        let member = group.first().member(guild_id, user_id).await?.unwrap();
+        assert!(member.pending);
+
+        let user = group.second().user(user_id).await?.unwrap();
+        assert_eq!(user.discriminator, 1234);
+
+        Ok::<_, CacheError>(())
+    })
+    .await
+    .unwrap()?;
+
+    Ok(())
+}
@@ -6,7 +6,9 @@ use std::{
 };
 
 use redlight::{
-    config::{CacheConfig, Cacheable, ICachedGuild, ICachedSticker, Ignore},
+    config::{
+        CacheConfig, Cacheable, ICachedChannel, ICachedGuild, ICachedMember, ICachedSticker, Ignore,
+    },
     error::{CacheError, UpdateArchiveError},
     rkyv_util::{
         guild::{AfkTimeoutRkyv, GuildFeatureRkyv},
@@ -23,20 +25,25 @@ use rkyv::{
     Archive, Deserialize, Serialize,
 };
 use twilight_model::{
-    channel::message::Sticker,
+    channel::{message::Sticker, Channel},
     gateway::{
         event::Event,
-        payload::incoming::{GuildCreate, GuildUpdate},
+        payload::incoming::{
+            ChannelPinsUpdate, GuildCreate, GuildStickersUpdate, GuildUpdate, MemberUpdate,
+        },
     },
     guild::{
         AfkTimeout, DefaultMessageNotificationLevel, ExplicitContentFilter, Guild, GuildFeature,
-        MfaLevel, NSFWLevel, PartialGuild, Permissions, PremiumTier, SystemChannelFlags,
-        VerificationLevel,
+        Member, MfaLevel, NSFWLevel, PartialGuild, PartialMember, Permissions, PremiumTier,
+        SystemChannelFlags, VerificationLevel,
+    },
+    id::{
+        marker::{GuildMarker, StickerMarker},
+        Id,
     },
-    id::{marker::StickerMarker, Id},
 };
 
-use super::{channel::text_channel, sticker::stickers};
+use super::{channel::text_channel, member::member, sticker::stickers};
 use crate::pool;
 
 #[tokio::test]
@@ -47,17 +54,21 @@ async fn test_guild() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = CachedGuild;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = CachedSticker;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -253,6 +264,393 @@ async fn test_guild() -> Result<(), CacheError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_guild_stickers_hook() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = CachedGuild;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize, Deserialize)]
+    struct CachedGuild {
+        sticker_count: u32,
+    }
+
+    impl<'a> ICachedGuild<'a> for CachedGuild {
+        fn from_guild(guild: &'a Guild) -> Self {
+            Self {
+                sticker_count: guild.stickers.len() as u32,
+            }
+        }
+
+        fn on_guild_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+            None
+        }
+
+        fn on_stickers_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildStickersUpdate) -> Result<(), Self::Error>>
+        {
+            Some(|archived, update| {
+                archived
+                    .update_by_deserializing(
+                        |deserialized| deserialized.sticker_count = update.stickers.len() as u32,
+                        &mut (),
+                    )
+                    .map_err(UpdateArchiveError::unwrap_ser)
+            })
+        }
+    }
+
+    impl Cacheable for CachedGuild {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedGuild {
+        type Error = Panic;
+    }
+
+    let mut guild = guild();
+    guild.id = Id::new(778);
+    let guild_id = guild.id;
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_create = Event::GuildCreate(Box::new(GuildCreate(guild)));
+    cache.update(&guild_create).await?;
+
+    let cached = cache.guild(guild_id).await?.expect("missing guild");
+    assert_eq!(cached.sticker_count, 2);
+
+    let mut stickers = stickers();
+    stickers.truncate(1);
+
+    let stickers_update = Event::GuildStickersUpdate(GuildStickersUpdate { guild_id, stickers });
+    cache.update(&stickers_update).await?;
+
+    let cached = cache.guild(guild_id).await?.expect("missing guild");
+    assert_eq!(cached.sticker_count, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_guilds_with_counts() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = CachedChannel;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = CachedGuild;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedGuild {
+        #[rkyv(with = IdRkyv)]
+        id: Id<GuildMarker>,
+    }
+
+    impl<'a> ICachedGuild<'a> for CachedGuild {
+        fn from_guild(guild: &'a Guild) -> Self {
+            Self { id: guild.id }
+        }
+
+        fn on_guild_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    impl Cacheable for CachedGuild {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedGuild {
+        type Error = Panic;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedChannel {
+        position: Option<i32>,
+    }
+
+    impl<'a> ICachedChannel<'a> for CachedChannel {
+        fn from_channel(channel: &'a Channel) -> Self {
+            Self {
+                position: channel.position,
+            }
+        }
+
+        fn on_pins_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &ChannelPinsUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedChannel {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedChannel {
+        type Error = Panic;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        pending: bool,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                pending: member.pending,
+            }
+        }
+
+        fn on_member_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMember {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMember {
+        type Error = Panic;
+    }
+
+    let mut guild = guild();
+    guild.id = Id::new(779);
+    guild.members = vec![member()];
+    let guild_id = guild.id;
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_create = Event::GuildCreate(Box::new(GuildCreate(guild)));
+    cache.update(&guild_create).await?;
+
+    let mut iter = cache.iter().guilds_with_counts().await?;
+    let mut found = false;
+
+    while let Some(res) = iter.next_item().await {
+        let (cached, member_count, channel_count) = res?;
+
+        if cached.id == guild_id {
+            assert_eq!(member_count, 1);
+            assert_eq!(channel_count, 1);
+            found = true;
+        }
+    }
+
+    assert!(found, "missing guild");
+
+    Ok(())
+}
+
+#[cfg(any(feature = "bb8", feature = "deadpool"))]
+#[tokio::test]
+async fn test_guild_snapshot() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = CachedChannel;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = CachedGuild;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedGuild {
+        #[rkyv(with = IdRkyv)]
+        id: Id<GuildMarker>,
+    }
+
+    impl<'a> ICachedGuild<'a> for CachedGuild {
+        fn from_guild(guild: &'a Guild) -> Self {
+            Self { id: guild.id }
+        }
+
+        fn on_guild_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    impl Cacheable for CachedGuild {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedGuild {
+        type Error = Panic;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedChannel {
+        position: Option<i32>,
+    }
+
+    impl<'a> ICachedChannel<'a> for CachedChannel {
+        fn from_channel(channel: &'a Channel) -> Self {
+            Self {
+                position: channel.position,
+            }
+        }
+
+        fn on_pins_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &ChannelPinsUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedChannel {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedChannel {
+        type Error = Panic;
+    }
+
+    let mut guild = guild();
+    guild.id = Id::new(8_940);
+    guild.channels[0].id = Id::new(8_941);
+    let guild_id = guild.id;
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_create = Event::GuildCreate(Box::new(GuildCreate(guild)));
+    cache.update(&guild_create).await?;
+
+    let snapshot = cache.guild_snapshot(guild_id).await?;
+
+    assert_eq!(snapshot.guild.expect("missing guild").id, guild_id);
+    assert_eq!(snapshot.channel_ids, [Id::new(8_941)].into_iter().collect());
+    assert!(snapshot.role_ids.is_empty());
+    assert!(snapshot.channels.is_empty());
+
+    let snapshot = cache.guild_snapshot_with_payloads(guild_id).await?;
+
+    assert_eq!(snapshot.channels.len(), 1);
+    let position = snapshot.channels[0]
+        .position
+        .as_ref()
+        .map(|pos| pos.to_native());
+    assert_eq!(position, text_channel().position);
+
+    Ok(())
+}
+
 pub fn guild() -> Guild {
     Guild {
         afk_channel_id: None,
@@ -2,6 +2,7 @@ use std::{
     borrow::Cow,
     fmt::{Debug, Formatter, Result as FmtResult},
     ops::Deref,
+    sync::atomic::{AtomicBool, Ordering},
     time::Duration,
 };
 
@@ -21,7 +22,10 @@ use rkyv::{
     Archive, Serialize,
 };
 use twilight_model::{
-    gateway::{event::Event, payload::incoming::IntegrationCreate},
+    gateway::{
+        event::Event,
+        payload::incoming::{GuildIntegrationsUpdate, IntegrationCreate},
+    },
     guild::{
         GuildIntegration, GuildIntegrationType, IntegrationAccount, IntegrationApplication,
         IntegrationExpireBehavior,
@@ -31,6 +35,8 @@ use twilight_model::{
 
 use crate::pool;
 
+static HOOK_CALLED: AtomicBool = AtomicBool::new(false);
+
 #[tokio::test]
 async fn test_integration() -> Result<(), CacheError> {
     struct Config;
@@ -39,17 +45,21 @@ async fn test_integration() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = CachedIntegration<'a>;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -75,6 +85,10 @@ async fn test_integration() -> Result<(), CacheError> {
                 scopes: integration.scopes.as_deref(),
             }
         }
+
+        fn on_integrations_update() -> Option<fn(&GuildIntegrationsUpdate)> {
+            Some(|_| HOOK_CALLED.store(true, Ordering::SeqCst))
+        }
     }
 
     impl Cacheable for CachedIntegration<'_> {
@@ -145,6 +159,35 @@ async fn test_integration() -> Result<(), CacheError> {
 
     assert!(iter.next_item().await.is_none());
 
+    let guild_id = expected.guild_id.unwrap();
+
+    let event = Event::GuildIntegrationsUpdate(GuildIntegrationsUpdate { guild_id });
+    cache.update(&event).await?;
+
+    assert!(HOOK_CALLED.load(Ordering::SeqCst));
+
+    // Cached integrations are removed until they are re-synced
+    assert!(cache.integration(guild_id, expected.id).await?.is_none());
+
+    let mut iter = cache.iter().guild_integrations(guild_id).await?;
+    assert!(iter.next_item().await.is_none());
+
+    let mut resynced = self::integration();
+    resynced.id = Id::new(expected.id.get() + 1);
+
+    cache
+        .store_guild_integrations(guild_id, &[resynced.clone()])
+        .await?;
+
+    assert!(cache.integration(guild_id, expected.id).await?.is_none());
+
+    let integration = cache
+        .integration(guild_id, resynced.id)
+        .await?
+        .expect("missing integration");
+
+    assert_eq!(integration.deref(), &resynced);
+
     Ok(())
 }
 
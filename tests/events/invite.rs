@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedInvite, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{ChannelDelete, InviteCreate, InviteDelete},
+    },
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use super::channel::text_channel;
+use crate::pool;
+
+#[tokio::test]
+async fn test_invite() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = CachedInvite;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedInvite {
+        max_uses: u64,
+        uses: u8,
+    }
+
+    impl<'a> ICachedInvite<'a> for CachedInvite {
+        fn from_invite(invite: &'a InviteCreate) -> Self {
+            Self {
+                max_uses: invite.max_uses,
+                uses: invite.uses,
+            }
+        }
+    }
+
+    impl Cacheable for CachedInvite {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+    }
+
+    impl Fallible for CachedInvite {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let channel = text_channel();
+    let guild_id = channel.guild_id.unwrap();
+
+    let create = invite_create("abc", channel.id, guild_id);
+    cache.update(&Event::InviteCreate(Box::new(create))).await?;
+
+    let create = invite_create("xyz", channel.id, guild_id);
+    cache.update(&Event::InviteCreate(Box::new(create))).await?;
+
+    let invite = cache.invite("abc").await?.expect("missing invite");
+    assert_eq!(invite.max_uses, 10);
+    assert_eq!(invite.uses, 2);
+
+    let codes = cache.guild_invite_codes(guild_id).await?;
+    assert_eq!(codes.len(), 2);
+
+    let delete = Event::InviteDelete(InviteDelete {
+        channel_id: channel.id,
+        code: "abc".to_owned(),
+        guild_id,
+    });
+    cache.update(&delete).await?;
+
+    assert!(cache.invite("abc").await?.is_none());
+
+    let codes = cache.channel_invite_codes(channel.id).await?;
+    assert_eq!(codes.len(), 1);
+    assert!(codes.contains("xyz"));
+
+    let delete = Event::ChannelDelete(Box::new(ChannelDelete(channel)));
+    cache.update(&delete).await?;
+
+    assert!(cache.invite("xyz").await?.is_none());
+    assert!(cache.guild_invite_codes(guild_id).await?.is_empty());
+
+    Ok(())
+}
+
+fn invite_create(
+    code: &str,
+    channel_id: Id<ChannelMarker>,
+    guild_id: Id<GuildMarker>,
+) -> InviteCreate {
+    InviteCreate {
+        channel_id,
+        code: code.to_owned(),
+        created_at: Timestamp::from_secs(1_700_000_000).unwrap(),
+        guild_id,
+        inviter: None,
+        max_age: 86_400,
+        max_uses: 10,
+        target_user_type: None,
+        target_user: None,
+        temporary: false,
+        uses: 2,
+    }
+}
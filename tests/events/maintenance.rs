@@ -0,0 +1,143 @@
+#![cfg(any(feature = "bb8", feature = "deadpool"))]
+
+use std::{
+    ops::DerefMut,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{CacheConfig, Cacheable, EntityKind, ICachedUser, Ignore},
+    error::CacheError,
+    RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{id::Id, user::User};
+
+use crate::{events::user::user, pool};
+
+#[tokio::test]
+async fn test_maintenance() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        id: u64,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self { id: user.id.get() }
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = user();
+    expected.id = Id::new(8_900);
+
+    cache
+        .transaction(|tx| {
+            tx.store_user(&expected)?;
+
+            Ok(())
+        })
+        .await?;
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    // Index an id without entry and unindex an existing entry
+    let _: () = Cmd::sadd(RedisKey::Users, 8_901)
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let _: () = Cmd::srem(RedisKey::Users, 8_900)
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let processed = Arc::new(AtomicUsize::new(0));
+    let progress = Arc::clone(&processed);
+
+    let mut maintenance = cache
+        .maintenance()
+        .with_progress(move |count| progress.store(count, Ordering::SeqCst));
+
+    let report = maintenance.integrity_report().await?;
+    let (_, users) = report
+        .iter()
+        .find(|(kind, _)| *kind == EntityKind::User)
+        .expect("missing user integrity");
+
+    assert!(users.orphaned >= 1);
+    assert!(users.unindexed >= 1);
+    assert!(processed.load(Ordering::SeqCst) > 0);
+
+    assert!(maintenance.prune_orphans(EntityKind::User).await? >= 1);
+    assert!(maintenance.rebuild_index(EntityKind::User).await? >= 1);
+
+    let user_ids = cache.user_ids().await?;
+    assert!(user_ids.contains(&Id::new(8_900)));
+    assert!(!user_ids.contains(&Id::new(8_901)));
+
+    let counts = maintenance.key_counts().await?;
+    assert!(counts.get("USER").is_some_and(|&count| count >= 1));
+
+    assert_eq!(maintenance.prune_orphans(EntityKind::Member).await?, 0);
+
+    Ok(())
+}
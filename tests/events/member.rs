@@ -1,25 +1,22 @@
-use std::{
-    fmt::{Debug, Formatter, Result as FmtResult},
-    ops::Deref,
-    time::Duration,
-};
+use std::{collections::HashSet, ops::Deref, time::Duration};
 
 use redlight::{
+    archive_utils::impl_archived_debug_eq,
     config::{CacheConfig, Cacheable, ICachedMember, Ignore},
     error::{CacheError, UpdateArchiveError},
-    rkyv_util::util::BitflagsRkyv,
+    rkyv_util::util::{BitflagsRkyv, TimestampRkyv},
     CachedArchive, RedisCache,
 };
 use rkyv::{
     rancor::{Fallible, Panic},
     ser::writer::Buffer,
-    util::Align,
+    util::{Align, AlignedVec},
     Archive, Deserialize, Serialize,
 };
 use twilight_model::{
     gateway::{
         event::Event,
-        payload::incoming::{MemberAdd, MemberUpdate, MessageCreate},
+        payload::incoming::{MemberAdd, MemberChunk, MemberRemove, MemberUpdate, MessageCreate},
     },
     guild::{Member, MemberFlags, PartialMember},
     id::{marker::GuildMarker, Id},
@@ -37,17 +34,21 @@ async fn test_member() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = CachedMember;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -111,18 +112,10 @@ async fn test_member() -> Result<(), CacheError> {
         type Error = Panic;
     }
 
-    impl PartialEq<Member> for ArchivedCachedMember {
-        fn eq(&self, other: &Member) -> bool {
-            self.flags == other.flags.bits() && self.pending == other.pending
-        }
-    }
-
-    impl Debug for ArchivedCachedMember {
-        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-            f.debug_struct("ArchivedCachedMember")
-                .field("flags", &self.flags)
-                .field("pending", &self.pending)
-                .finish()
+    impl_archived_debug_eq! {
+        ArchivedCachedMember == Member {
+            flags: |flags, member| *flags == member.flags.bits(),
+            pending,
         }
     }
 
@@ -186,6 +179,574 @@ async fn test_member() -> Result<(), CacheError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_member_pagination() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        pending: bool,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                pending: member.pending,
+            }
+        }
+
+        fn on_member_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMember {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMember {
+        type Error = Panic;
+    }
+
+    let guild_id = Id::new(112);
+
+    let user_ids = [10_000_000_000_000_000_000, 123, 10, 9];
+
+    let members = user_ids
+        .iter()
+        .map(|&user_id| {
+            let mut member = member();
+            member.user.id = Id::new(user_id);
+
+            member
+        })
+        .collect();
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let chunk = Event::MemberChunk(MemberChunk {
+        chunk_count: 1,
+        chunk_index: 0,
+        guild_id,
+        members,
+        nonce: None,
+        not_found: Vec::new(),
+        presences: Vec::new(),
+    });
+
+    cache.update(&chunk).await?;
+
+    let page = cache.guild_member_ids_page(guild_id, None, 2).await?;
+    assert_eq!(page, [Id::new(9), Id::new(10)]);
+
+    let page = cache
+        .guild_member_ids_page(guild_id, page.last().copied(), 2)
+        .await?;
+    assert_eq!(page, [Id::new(123), Id::new(10_000_000_000_000_000_000)]);
+
+    let page = cache
+        .guild_member_ids_page(guild_id, page.last().copied(), 2)
+        .await?;
+    assert!(page.is_empty());
+
+    let mut user = user();
+    user.id = Id::new(10);
+
+    let member_remove = Event::MemberRemove(MemberRemove { guild_id, user });
+    cache.update(&member_remove).await?;
+
+    let page = cache.guild_member_ids_page(guild_id, None, 10).await?;
+    assert_eq!(
+        page,
+        [
+            Id::new(9),
+            Id::new(123),
+            Id::new(10_000_000_000_000_000_000)
+        ]
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_member_boosters() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize, Deserialize)]
+    struct CachedMember {
+        #[rkyv(with = TimestampRkyv)]
+        premium_since: Option<Timestamp>,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                premium_since: member.premium_since,
+            }
+        }
+
+        fn on_member_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MemberUpdate) -> Result<(), Self::Error>>
+        {
+            Some(|archived, update| {
+                archived
+                    .update_by_deserializing(
+                        |deserialized| deserialized.premium_since = update.premium_since,
+                        &mut (),
+                    )
+                    .map_err(UpdateArchiveError::unwrap_ser)
+            })
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialMember) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn premium_since() -> Option<fn(&CachedArchive<Self>) -> Option<Timestamp>> {
+            Some(|archived| archived.premium_since.to_timestamp_option().ok().flatten())
+        }
+    }
+
+    impl Cacheable for CachedMember {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMember {
+        type Error = Panic;
+    }
+
+    let guild_id = Id::new(113);
+    let boosting_since = Timestamp::parse("2022-02-02T02:02:02+00:00").unwrap();
+
+    let mut booster = member();
+    booster.premium_since = Some(boosting_since);
+
+    let mut non_booster = member();
+    non_booster.user.id = Id::new(booster.user.id.get() + 1);
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let chunk = Event::MemberChunk(MemberChunk {
+        chunk_count: 1,
+        chunk_index: 0,
+        guild_id,
+        members: vec![booster.clone(), non_booster.clone()],
+        nonce: None,
+        not_found: Vec::new(),
+        presences: Vec::new(),
+    });
+
+    cache.update(&chunk).await?;
+
+    let boosters = cache.guild_boosters(guild_id).await?;
+    assert_eq!(boosters, HashSet::from([booster.user.id]));
+
+    let mut update = member_update();
+    update.guild_id = guild_id;
+    update.user = non_booster.user.clone();
+    update.premium_since = Some(boosting_since);
+
+    cache.update(&Event::MemberUpdate(Box::new(update))).await?;
+
+    let boosters = cache.guild_boosters(guild_id).await?;
+    assert_eq!(
+        boosters,
+        HashSet::from([booster.user.id, non_booster.user.id])
+    );
+
+    let mut update = member_update();
+    update.guild_id = guild_id;
+    update.user = booster.user.clone();
+    update.premium_since = None;
+
+    cache.update(&Event::MemberUpdate(Box::new(update))).await?;
+
+    let boosters = cache.guild_boosters(guild_id).await?;
+    assert_eq!(boosters, HashSet::from([non_booster.user.id]));
+
+    let member_remove = Event::MemberRemove(MemberRemove {
+        guild_id,
+        user: non_booster.user,
+    });
+
+    cache.update(&member_remove).await?;
+
+    let boosters = cache.guild_boosters(guild_id).await?;
+    assert!(boosters.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_member_search() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        user_id: u64,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        const INDEX_NAMES: bool = true;
+
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                user_id: member.user.id.get(),
+            }
+        }
+    }
+
+    impl Cacheable for CachedMember {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMember {
+        type Error = Panic;
+    }
+
+    async fn search(
+        cache: &RedisCache<Config>,
+        guild_id: Id<GuildMarker>,
+        prefix: &str,
+    ) -> Result<Vec<u64>, CacheError> {
+        let mut iter = cache.iter().search_members(guild_id, prefix).await?;
+        let mut user_ids = Vec::new();
+
+        while let Some(res) = iter.next_item().await {
+            user_ids.push(res?.user_id.to_native());
+        }
+
+        Ok(user_ids)
+    }
+
+    let guild_id = Id::new(8_910);
+
+    let mut alice = member();
+    alice.user.id = Id::new(8_911);
+    alice.user.name = "Alice".to_owned();
+    alice.user.global_name = None;
+    alice.nick = Some("Zed".to_owned());
+
+    let mut alfred = member();
+    alfred.user.id = Id::new(8_912);
+    alfred.user.name = "alfred".to_owned();
+    alfred.user.global_name = Some("Bob".to_owned());
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let chunk = Event::MemberChunk(MemberChunk {
+        chunk_count: 1,
+        chunk_index: 0,
+        guild_id,
+        members: vec![alice.clone(), alfred.clone()],
+        nonce: None,
+        not_found: Vec::new(),
+        presences: Vec::new(),
+    });
+
+    cache.update(&chunk).await?;
+
+    assert_eq!(search(&cache, guild_id, "AL").await?, [8_912, 8_911]);
+    assert_eq!(search(&cache, guild_id, "z").await?, [8_911]);
+    assert_eq!(search(&cache, guild_id, "bob").await?, [8_912]);
+    assert!(search(&cache, guild_id, "carl").await?.is_empty());
+
+    let mut update = member_update();
+    update.guild_id = guild_id;
+    update.user = alice.user.clone();
+    update.nick = None;
+
+    cache.update(&Event::MemberUpdate(Box::new(update))).await?;
+
+    assert!(search(&cache, guild_id, "z").await?.is_empty());
+    assert_eq!(search(&cache, guild_id, "alice").await?, [8_911]);
+
+    let member_remove = Event::MemberRemove(MemberRemove {
+        guild_id,
+        user: alfred.user,
+    });
+
+    cache.update(&member_remove).await?;
+
+    assert_eq!(search(&cache, guild_id, "").await?, [8_911]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_partial_member_update_only() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        pending: bool,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        const PARTIALS_UPDATE_ONLY: bool = true;
+
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                pending: member.pending,
+            }
+        }
+    }
+
+    impl Cacheable for CachedMember {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMember {
+        type Error = Panic;
+    }
+
+    let guild_id = Id::new(8_920);
+
+    let mut partial = partial_member();
+    partial.user.as_mut().unwrap().id = Id::new(8_921);
+
+    let mut msg = message();
+    msg.guild_id = Some(guild_id);
+    msg.member = Some(partial.clone());
+    msg.mentions.clear();
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let message_create = Event::MessageCreate(Box::new(MessageCreate(msg)));
+    cache.update(&message_create).await?;
+
+    // The partial member must not establish the membership
+    assert!(cache.guild_member_ids(guild_id).await?.is_empty());
+
+    let mut full = member();
+    full.user = partial.user.clone().unwrap();
+
+    let member_create = Event::MemberAdd(Box::new(MemberAdd {
+        guild_id,
+        member: full,
+    }));
+
+    cache.update(&member_create).await?;
+    cache.update(&message_create).await?;
+
+    let member_ids = cache.guild_member_ids(guild_id).await?;
+    assert_eq!(member_ids, HashSet::from([Id::new(8_921)]));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_stats_snapshot() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = CachedMember;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMember {
+        pending: bool,
+    }
+
+    impl<'a> ICachedMember<'a> for CachedMember {
+        fn from_member(_: Id<GuildMarker>, member: &'a Member) -> Self {
+            Self {
+                pending: member.pending,
+            }
+        }
+    }
+
+    impl Cacheable for CachedMember {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMember {
+        type Error = Panic;
+    }
+
+    let guild_id = Id::new(8_930);
+    let empty_guild_id = Id::new(8_931);
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    for user_id in [8_932, 8_933] {
+        let mut member = member();
+        member.user.id = Id::new(user_id);
+
+        let event = Event::MemberAdd(Box::new(MemberAdd { guild_id, member }));
+        cache.update(&event).await?;
+    }
+
+    let snapshot = cache.stats().snapshot(&[guild_id, empty_guild_id]).await?;
+
+    assert_eq!(snapshot.guild_members.len(), 2);
+    assert_eq!(snapshot.guild_members[&guild_id], 2);
+    assert_eq!(snapshot.guild_members[&empty_guild_id], 0);
+
+    Ok(())
+}
+
 pub fn member() -> Member {
     Member {
         avatar: None,
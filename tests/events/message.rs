@@ -1,11 +1,14 @@
 use std::{
-    fmt::{Debug, Formatter, Result as FmtResult},
+    collections::HashMap,
     ops::Deref,
-    time::Duration,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use futures_util::TryStreamExt;
+use futures_util::{future::BoxFuture, TryStreamExt};
 use redlight::{
+    archive_utils::impl_archived_debug_eq,
+    cache::{ColdStore, ColdStoreError},
     config::{CacheConfig, Cacheable, ICachedMessage, Ignore, ReactionEvent},
     error::CacheError,
     rkyv_util::util::{BitflagsRkyv, RkyvAsU8},
@@ -14,8 +17,8 @@ use redlight::{
 use rkyv::{
     rancor::{Fallible, Panic},
     ser::writer::Buffer,
-    util::Align,
-    with::Map,
+    util::{Align, AlignedVec},
+    with::{InlineAsBox, Map},
     Archive, Serialize,
 };
 use twilight_model::{
@@ -29,9 +32,12 @@ use twilight_model::{
     },
     gateway::{
         event::Event,
-        payload::incoming::{MessageCreate, MessageUpdate},
+        payload::incoming::{
+            MessageCreate, MessageDelete, MessageDeleteBulk, MessageUpdate, ReactionAdd,
+        },
+        GatewayReaction,
     },
-    id::Id,
+    id::{marker::MessageMarker, Id},
     user::UserFlags,
     util::Timestamp,
 };
@@ -47,17 +53,21 @@ async fn test_message() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = CachedMessage;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -126,18 +136,10 @@ async fn test_message() -> Result<(), CacheError> {
         type Error = Panic;
     }
 
-    impl PartialEq<Message> for ArchivedCachedMessage {
-        fn eq(&self, other: &Message) -> bool {
-            self.flags == other.flags.map(|flags| flags.bits()) && self.kind == u8::from(other.kind)
-        }
-    }
-
-    impl Debug for ArchivedCachedMessage {
-        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-            f.debug_struct("ArchivedCachedMessage")
-                .field("flags", &self.flags)
-                .field("kind", &self.kind)
-                .finish()
+    impl_archived_debug_eq! {
+        ArchivedCachedMessage == Message {
+            flags: |flags, msg| *flags == msg.flags.map(|flags| flags.bits()),
+            kind: |kind, msg| *kind == u8::from(msg.kind),
         }
     }
 
@@ -199,6 +201,607 @@ async fn test_message() -> Result<(), CacheError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_message_byte_budget() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage<'a>;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage<'a> {
+        #[rkyv(with = InlineAsBox)]
+        content: &'a str,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage<'a> {
+        // Fits two but not three messages
+        const CHANNEL_BYTE_BUDGET: Option<usize> = Some(250);
+
+        fn from_message(message: &'a Message) -> Self {
+            Self {
+                content: &message.content,
+            }
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMessage<'_> {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMessage<'_> {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let channel_id = Id::new(223);
+
+    let messages: Vec<_> = (1..=3)
+        .map(|i| {
+            let mut msg = message();
+            msg.id = Id::new(910 + i);
+            msg.channel_id = channel_id;
+            msg.content = "a".repeat(100);
+            msg.timestamp = Timestamp::from_secs(1_600_000_000 + i as i64).unwrap();
+
+            msg
+        })
+        .collect();
+
+    for msg in messages.iter() {
+        let event = Event::MessageCreate(Box::new(MessageCreate(msg.clone())));
+        cache.update(&event).await?;
+    }
+
+    assert!(cache.message(messages[0].id).await?.is_none());
+    assert!(cache.message(messages[1].id).await?.is_some());
+    assert!(cache.message(messages[2].id).await?.is_some());
+
+    let message_ids = cache.channel_message_ids(channel_id).await?;
+    assert_eq!(message_ids, [messages[2].id, messages[1].id]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_delete_bulk() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage;
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        fn from_message(_: &'a Message) -> Self {
+            Self
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMessage {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedMessage {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let channel_id = Id::new(7_400);
+
+    let messages: Vec<_> = (1..=3)
+        .map(|i| {
+            let mut msg = message();
+            msg.id = Id::new(7_400 + i);
+            msg.channel_id = channel_id;
+            msg.timestamp = Timestamp::from_secs(1_600_000_000 + i as i64).unwrap();
+
+            msg
+        })
+        .collect();
+
+    for msg in messages.iter() {
+        let event = Event::MessageCreate(Box::new(MessageCreate(msg.clone())));
+        cache.update(&event).await?;
+    }
+
+    let delete_bulk = Event::MessageDeleteBulk(MessageDeleteBulk {
+        channel_id,
+        guild_id: None,
+        ids: vec![messages[0].id, messages[1].id],
+    });
+    cache.update(&delete_bulk).await?;
+
+    assert!(cache.message(messages[0].id).await?.is_none());
+    assert!(cache.message(messages[1].id).await?.is_none());
+
+    let message_ids = cache.channel_message_ids(channel_id).await?;
+    assert_eq!(message_ids, [messages[2].id]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_starboard() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage {
+        stars: u32,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        fn from_message(_: &'a Message) -> Self {
+            Self { stars: 0 }
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            Some(|archived, event| {
+                archived.update_archive(|sealed| {
+                    rkyv::munge::munge!(let ArchivedCachedMessage { mut stars } = sealed);
+
+                    match event {
+                        ReactionEvent::Add(_) => *stars = (stars.to_native() + 1).into(),
+                        ReactionEvent::Remove(_) => {
+                            *stars = stars.to_native().saturating_sub(1).into();
+                        }
+                        ReactionEvent::RemoveAll(_) | ReactionEvent::RemoveEmoji(_) => {
+                            *stars = 0.into();
+                        }
+                    }
+                })
+            })
+        }
+
+        fn starboard_score() -> Option<fn(&CachedArchive<Self>) -> Option<f64>> {
+            Some(|archived| (archived.stars > 0).then(|| f64::from(archived.stars.to_native())))
+        }
+    }
+
+    impl Cacheable for CachedMessage {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMessage {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(116);
+
+    let messages: Vec<_> = (1..=3)
+        .map(|i| {
+            let mut msg = message();
+            msg.id = Id::new(930 + i);
+            msg.guild_id = Some(guild_id);
+
+            msg
+        })
+        .collect();
+
+    for msg in messages.iter() {
+        let event = Event::MessageCreate(Box::new(MessageCreate(msg.clone())));
+        cache.update(&event).await?;
+    }
+
+    let star = |msg: &Message, user_id: u64| {
+        Event::ReactionAdd(Box::new(ReactionAdd(GatewayReaction {
+            channel_id: msg.channel_id,
+            emoji: ReactionType::Unicode {
+                name: "⭐".to_owned(),
+            },
+            guild_id: Some(guild_id),
+            member: None,
+            message_author_id: None,
+            message_id: msg.id,
+            user_id: Id::new(user_id),
+        })))
+    };
+
+    cache.update(&star(&messages[0], 1)).await?;
+    cache.update(&star(&messages[1], 1)).await?;
+    cache.update(&star(&messages[1], 2)).await?;
+
+    let top = cache.top_messages(guild_id, 5).await?;
+    assert_eq!(top, [(messages[1].id, 2.0), (messages[0].id, 1.0)]);
+
+    let top = cache.top_messages(guild_id, 1).await?;
+    assert_eq!(top, [(messages[1].id, 2.0)]);
+
+    let event = Event::MessageDelete(MessageDelete {
+        channel_id: messages[1].channel_id,
+        guild_id: Some(guild_id),
+        id: messages[1].id,
+    });
+    cache.update(&event).await?;
+
+    let top = cache.top_messages(guild_id, 5).await?;
+    assert_eq!(top, [(messages[0].id, 1.0)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_history() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage {
+        #[rkyv(with = RkyvAsU8)]
+        kind: MessageType,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        const EDIT_HISTORY: usize = 2;
+
+        fn from_message(message: &'a Message) -> Self {
+            Self { kind: message.kind }
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            Some(|archived, update| {
+                archived.update_archive(|sealed| {
+                    if let Some(update_kind) = update.kind {
+                        rkyv::munge::munge!(let ArchivedCachedMessage { mut kind } = sealed);
+                        *kind = u8::from(update_kind);
+                    }
+                })
+            })
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMessage {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMessage {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut msg = message();
+    msg.id = Id::new(934);
+    msg.kind = MessageType::Regular;
+
+    let event = Event::MessageCreate(Box::new(MessageCreate(msg.clone())));
+    cache.update(&event).await?;
+
+    assert!(cache.message_history(msg.id).await?.is_empty());
+
+    let kinds = [MessageType::Reply, MessageType::Call, MessageType::UserJoin];
+
+    for kind in kinds {
+        let mut update = message_update();
+        update.id = msg.id;
+        update.kind = Some(kind);
+
+        let event = Event::MessageUpdate(Box::new(update));
+        cache.update(&event).await?;
+    }
+
+    let history: Vec<_> = cache
+        .message_history(msg.id)
+        .await?
+        .iter()
+        .map(|version| version.kind)
+        .collect();
+
+    // Most recent first, capped at two versions
+    assert_eq!(
+        history,
+        [u8::from(MessageType::Call), u8::from(MessageType::Reply)]
+    );
+
+    let event = Event::MessageDelete(MessageDelete {
+        channel_id: msg.channel_id,
+        guild_id: None,
+        id: msg.id,
+    });
+    cache.update(&event).await?;
+
+    assert!(cache.message_history(msg.id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_cold_store() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage {
+        timestamp: i64,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        fn from_message(message: &'a Message) -> Self {
+            Self {
+                timestamp: message.timestamp.as_micros(),
+            }
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMessage {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMessage {
+        type Error = Panic;
+    }
+
+    #[derive(Clone, Default)]
+    struct MemoryStore(Arc<Mutex<HashMap<Id<MessageMarker>, Vec<u8>>>>);
+
+    impl ColdStore for MemoryStore {
+        fn store(
+            &self,
+            messages: Vec<(Id<MessageMarker>, Vec<u8>)>,
+        ) -> BoxFuture<'_, Result<(), ColdStoreError>> {
+            self.0.lock().unwrap().extend(messages);
+
+            Box::pin(async { Ok(()) })
+        }
+
+        fn load(
+            &self,
+            msg_id: Id<MessageMarker>,
+        ) -> BoxFuture<'_, Result<Option<Vec<u8>>, ColdStoreError>> {
+            let bytes = self.0.lock().unwrap().get(&msg_id).cloned();
+
+            Box::pin(async move { Ok(bytes) })
+        }
+    }
+
+    let store = MemoryStore::default();
+
+    let cache = RedisCache::<Config>::new_with_pool(pool())
+        .await?
+        .with_cold_store(store.clone());
+
+    let channel_id = Id::new(8_960);
+    let now = Timestamp::from_secs(
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64,
+    )
+    .unwrap();
+
+    let mut old = message();
+    old.id = Id::new(8_961);
+    old.channel_id = channel_id;
+    old.timestamp = Timestamp::from_secs(1_600_000_000).unwrap();
+
+    let mut recent = message();
+    recent.id = Id::new(8_962);
+    recent.channel_id = channel_id;
+    recent.timestamp = now;
+
+    for msg in [old.clone(), recent.clone()] {
+        let event = Event::MessageCreate(Box::new(MessageCreate(msg)));
+        cache.update(&event).await?;
+    }
+
+    let week = Duration::from_secs(60 * 60 * 24 * 7);
+    let moved = cache.move_cold_channel_messages(channel_id, week).await?;
+
+    assert_eq!(moved, 1);
+    assert!(store.0.lock().unwrap().contains_key(&old.id));
+    assert_eq!(cache.channel_message_ids(channel_id).await?, [recent.id]);
+
+    // Read-through on miss
+    let msg = cache.message(old.id).await?.expect("missing cold message");
+    assert_eq!(msg.timestamp.to_native(), old.timestamp.as_micros());
+
+    Ok(())
+}
+
 pub fn message() -> Message {
     Message {
         activity: Some(MessageActivity {
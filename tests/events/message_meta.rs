@@ -0,0 +1,240 @@
+#![cfg(any(feature = "bb8", feature = "deadpool"))]
+
+use std::{ops::DerefMut, time::Duration};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedMessage, Ignore, ReactionEvent},
+    error::CacheError,
+    CachedArchive, RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    channel::Message,
+    gateway::{
+        event::Event,
+        payload::incoming::{MessageCreate, MessageUpdate},
+    },
+    id::Id,
+};
+
+use crate::{events::message::message, pool};
+
+#[tokio::test]
+async fn test_message_legacy_meta() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage {
+        timestamp: i64,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        const SKIP_UNCHANGED: bool = true;
+
+        fn from_message(message: &'a Message) -> Self {
+            Self {
+                timestamp: message.timestamp.as_micros(),
+            }
+        }
+
+        fn on_message_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &MessageUpdate) -> Result<(), Self::Error>>
+        {
+            None
+        }
+
+        fn on_reaction_event(
+        ) -> Option<fn(&mut CachedArchive<Self>, ReactionEvent<'_>) -> Result<(), Self::Error>>
+        {
+            None
+        }
+    }
+
+    impl Cacheable for CachedMessage {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMessage {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = message();
+    expected.id = Id::new(8_993);
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    // Meta as it was stored before it contained the message hash
+    let key = RedisKey::MessageMeta { id: expected.id };
+    let legacy = expected.channel_id.get().to_le_bytes();
+
+    let _: () = Cmd::set(&key, legacy.as_slice())
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let event = Event::MessageCreate(Box::new(MessageCreate(expected.clone())));
+    cache.update(&event).await?;
+
+    let message = cache.message(expected.id).await?.expect("missing message");
+    assert_eq!(message.timestamp, expected.timestamp.as_micros());
+
+    // The outdated meta has been replaced
+    let len: usize = Cmd::strlen(&key).query_async(conn.deref_mut()).await?;
+    assert_eq!(len, 16);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_unchanged_refresh() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = CachedMessage;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedMessage {
+        timestamp: i64,
+    }
+
+    impl<'a> ICachedMessage<'a> for CachedMessage {
+        const SKIP_UNCHANGED: bool = true;
+
+        fn from_message(message: &'a Message) -> Self {
+            Self {
+                timestamp: message.timestamp.as_micros(),
+            }
+        }
+    }
+
+    impl Cacheable for CachedMessage {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedMessage {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = message();
+    expected.id = Id::new(8_994);
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    let event = Event::MessageCreate(Box::new(MessageCreate(expected.clone())));
+    cache.update(&event).await?;
+
+    // Lose the index entries and most of the lifetime of the message
+    let key = RedisKey::Message { id: expected.id };
+
+    let _: () = Cmd::expire(&key, 5).query_async(conn.deref_mut()).await?;
+    let _: () = Cmd::srem(RedisKey::Messages, expected.id.get())
+        .query_async(conn.deref_mut())
+        .await?;
+    let key = RedisKey::ChannelMessages {
+        channel: expected.channel_id,
+    };
+    let _: () = Cmd::zrem(&key, expected.id.get())
+        .query_async(conn.deref_mut())
+        .await?;
+
+    // Replaying the unchanged message restores both
+    cache.update(&event).await?;
+
+    assert!(cache.message_ids().await?.contains(&expected.id));
+
+    let channel_message_ids = cache.channel_message_ids(expected.channel_id).await?;
+    assert!(channel_message_ids.contains(&expected.id));
+
+    let key = RedisKey::Message { id: expected.id };
+    let ttl: i64 = Cmd::ttl(&key).query_async(conn.deref_mut()).await?;
+    assert!(ttl > 5);
+
+    // The meta may outlive its message in which case the message is written
+    let _: () = Cmd::del(&key).query_async(conn.deref_mut()).await?;
+
+    cache.update(&event).await?;
+
+    let message = cache.message(expected.id).await?.expect("missing message");
+    assert_eq!(message.timestamp, expected.timestamp.as_micros());
+
+    Ok(())
+}
@@ -1,10 +1,43 @@
+pub mod auto_moderation;
+pub mod ban;
+pub mod batch;
 pub mod channel;
+pub mod combined;
 pub mod current_user;
+pub mod custom;
+pub mod expire_many;
+pub mod group;
 pub mod guild;
 pub mod integration;
+pub mod invite;
+pub mod maintenance;
 pub mod member;
 pub mod message;
+pub mod message_meta;
+pub mod overrides;
+pub mod paged;
 pub mod presence;
+pub mod pressure;
+pub mod previous;
+pub mod read_counts;
+pub mod refresh;
+pub mod replica;
+pub mod resolve;
+pub mod role;
+pub mod run;
+pub mod scheduled_event;
+pub mod schema;
+pub mod sequence;
+pub mod shadow;
 pub mod stage_instance;
+pub mod stale_guilds;
 pub mod sticker;
+pub mod thread_member;
+pub mod tombstone;
+pub mod transaction;
 pub mod user;
+pub mod version;
+pub mod view;
+pub mod voice_state;
+pub mod webhooks;
+pub mod writer;
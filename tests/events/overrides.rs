@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ConfigOverrides, EntityKind, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleCreate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_disabled_kind() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let overrides = ConfigOverrides::new().disable(EntityKind::Role);
+
+    let cache = RedisCache::<Config>::new_with_pool(pool())
+        .await?
+        .with_overrides(overrides);
+
+    let guild_id = Id::new(7_100);
+    let mut role = role("disabled", 1);
+    role.id = Id::new(7_101);
+    let role_id = role.id;
+
+    let event = Event::RoleCreate(RoleCreate { guild_id, role });
+    cache.update(&event).await?;
+
+    assert!(cache.role(role_id).await?.is_none());
+    assert!(cache.guild_role_ids(guild_id).await?.contains(&role_id));
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use std::{collections::HashSet, time::Duration};
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedUser, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{id::Id, user::User};
+
+use super::user::user;
+use crate::pool;
+
+#[tokio::test]
+async fn test_users_paged() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        id: u64,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self { id: user.id.get() }
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    const PAGE_SIZE: usize = 3;
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let expected: HashSet<u64> = (8_000..8_010).collect();
+
+    cache
+        .transaction(|tx| {
+            for &id in expected.iter() {
+                let mut user = user();
+                user.id = Id::new(id);
+                tx.store_user(&user)?;
+            }
+
+            Ok(())
+        })
+        .await?;
+
+    let mut pages = cache
+        .iter()
+        .users_paged(PAGE_SIZE)
+        .await?
+        .with_delay(Duration::from_millis(1));
+
+    let mut found = HashSet::new();
+
+    while let Some(page) = pages.next_page().await {
+        let page = page?;
+        assert!(page.len() <= PAGE_SIZE);
+
+        // Other tests may store users too
+        found.extend(
+            page.iter()
+                .map(|user| user.id.to_native())
+                .filter(|id| expected.contains(id)),
+        );
+    }
+
+    assert_eq!(found, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_users_stream() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        id: u64,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self { id: user.id.get() }
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let expected: HashSet<u64> = (8_950..8_960).collect();
+
+    cache
+        .transaction(|tx| {
+            for &id in expected.iter() {
+                let mut user = user();
+                user.id = Id::new(id);
+                tx.store_user(&user)?;
+            }
+
+            Ok(())
+        })
+        .await?;
+
+    let mut users = cache.iter().users_stream(4).await?;
+    let mut found = HashSet::new();
+
+    while let Some(user) = users.next_item().await {
+        let id = user?.id.to_native();
+
+        // Other tests may store users too
+        if expected.contains(&id) {
+            found.insert(id);
+        }
+    }
+
+    assert_eq!(found, expected);
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use redlight::{
+    clock::MockClock,
     config::{CacheConfig, Cacheable, ICachedPresence, Ignore},
     error::CacheError,
     rkyv_util::{id::IdRkyv, presence::StatusRkyv},
@@ -9,14 +10,14 @@ use redlight::{
 use rkyv::{
     rancor::{Fallible, Panic},
     ser::writer::Buffer,
-    util::Align,
+    util::{Align, AlignedVec},
     with::Map,
     Archive, Serialize,
 };
 use twilight_model::{
     gateway::{
         event::Event,
-        payload::incoming::PresenceUpdate,
+        payload::incoming::{MemberRemove, PresenceUpdate},
         presence::{ClientStatus, Presence, Status, UserOrId},
     },
     id::{marker::UserMarker, Id},
@@ -33,17 +34,21 @@ async fn test_presence() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = CachedPresence;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -105,6 +110,272 @@ async fn test_presence() -> Result<(), CacheError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_presence_skip_unchanged() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = CachedPresence;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedPresence {
+        #[rkyv(with = Map<StatusRkyv>)]
+        desktop_status: Option<Status>,
+    }
+
+    impl<'a> ICachedPresence<'a> for CachedPresence {
+        const SKIP_UNCHANGED: bool = true;
+
+        fn from_presence(presence: &'a Presence) -> Self {
+            Self {
+                desktop_status: presence.client_status.desktop,
+            }
+        }
+    }
+
+    impl Cacheable for CachedPresence {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_secs(60))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedPresence {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = presence();
+    expected.guild_id = Id::new(225);
+
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    // Unchanged so the write is skipped
+    cache.update(&event).await?;
+
+    let presence = cache
+        .presence(expected.guild_id, expected.user.id())
+        .await?
+        .expect("missing presence");
+
+    assert_eq!(
+        presence.desktop_status.as_ref().copied().map(From::from),
+        Some(Status::DoNotDisturb)
+    );
+
+    expected.client_status.desktop = Some(Status::Idle);
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    let presence = cache
+        .presence(expected.guild_id, expected.user.id())
+        .await?
+        .expect("missing presence");
+
+    assert_eq!(
+        presence.desktop_status.as_ref().copied().map(From::from),
+        Some(Status::Idle)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_presence_age() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = CachedPresence;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedPresence {
+        #[rkyv(with = Map<StatusRkyv>)]
+        desktop_status: Option<Status>,
+    }
+
+    impl<'a> ICachedPresence<'a> for CachedPresence {
+        const TRACK_FRESHNESS: bool = true;
+
+        fn from_presence(presence: &'a Presence) -> Self {
+            Self {
+                desktop_status: presence.client_status.desktop,
+            }
+        }
+    }
+
+    impl Cacheable for CachedPresence {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedPresence {
+        type Error = Panic;
+    }
+
+    let clock = MockClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    let cache = RedisCache::<Config>::new_with_clock(pool(), clock.clone()).await?;
+
+    let mut expected = presence();
+    expected.guild_id = Id::new(8_400);
+    let user_id = expected.user.id();
+
+    assert!(cache
+        .presence_age(expected.guild_id, user_id)
+        .await?
+        .is_none());
+
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    let age = cache.presence_age(expected.guild_id, user_id).await?;
+    assert_eq!(age, Some(Duration::ZERO));
+
+    clock.advance(Duration::from_secs(90));
+
+    let age = cache.presence_age(expected.guild_id, user_id).await?;
+    assert_eq!(age, Some(Duration::from_secs(90)));
+
+    // Another update refreshes the stamp
+    cache.update(&event).await?;
+
+    let age = cache.presence_age(expected.guild_id, user_id).await?;
+    assert_eq!(age, Some(Duration::ZERO));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_presence_status() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const PRESENCE_STATUS: bool = true;
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = presence();
+    expected.guild_id = Id::new(8_992);
+    let guild_id = expected.guild_id;
+    let user_id = expected.user.id();
+
+    assert!(cache.presence_status(guild_id, user_id).await?.is_none());
+
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    let status = cache.presence_status(guild_id, user_id).await?;
+    assert_eq!(status, Some(Status::Online));
+
+    // Only the status is stored, not the full presence
+    assert!(cache.presence(guild_id, user_id).await?.is_none());
+
+    expected.status = Status::Idle;
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    let status = cache.presence_status(guild_id, user_id).await?;
+    assert_eq!(status, Some(Status::Idle));
+
+    expected.status = Status::Offline;
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    assert!(cache.presence_status(guild_id, user_id).await?.is_none());
+
+    expected.status = Status::DoNotDisturb;
+    let event = Event::PresenceUpdate(Box::new(PresenceUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    let status = cache.presence_status(guild_id, user_id).await?;
+    assert_eq!(status, Some(Status::DoNotDisturb));
+
+    let event = Event::MemberRemove(MemberRemove {
+        guild_id,
+        user: user(),
+    });
+    cache.update(&event).await?;
+
+    assert!(cache.presence_status(guild_id, user_id).await?.is_none());
+
+    Ok(())
+}
+
 pub fn presence() -> Presence {
     Presence {
         activities: Vec::new(),
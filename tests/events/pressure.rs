@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore, PressureThresholds},
+    error::CacheError,
+    Pressure, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleCreate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_pressure() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const PRESSURE_THRESHOLDS: Option<PressureThresholds> = Some(PressureThresholds {
+            pending_commands: 1000,
+            pool_wait: Duration::from_secs(10),
+            error_rate: 0.5,
+        });
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut role = role("pressure", 1);
+    role.id = Id::new(7_301);
+
+    let event = Event::RoleCreate(RoleCreate {
+        guild_id: Id::new(7_300),
+        role,
+    });
+
+    let pressure = cache.update_with_pressure(&event).await?;
+    assert_eq!(pressure, Pressure::Normal);
+
+    let gauge = cache.pressure();
+    assert_eq!(gauge.pending_commands, 0);
+    assert!(gauge.error_rate < f64::EPSILON);
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{RoleCreate, RoleUpdate},
+    },
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_previous_version() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const PREVIOUS_VERSION_LIFETIME: Option<Duration> = Some(Duration::from_secs(60));
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(114);
+
+    let mut role = role("previous", 1);
+    role.id = Id::new(224);
+
+    let key = RedisKey::Role { id: role.id };
+
+    let event = Event::RoleCreate(RoleCreate {
+        guild_id,
+        role: role.clone(),
+    });
+
+    cache.update(&event).await?;
+
+    let previous = cache.previous::<CachedRole>(key.clone()).await?;
+    assert!(previous.is_none());
+
+    role.position = 2;
+
+    let event = Event::RoleUpdate(RoleUpdate {
+        guild_id,
+        role: role.clone(),
+    });
+
+    cache.update(&event).await?;
+
+    let current = cache.role(role.id).await?.expect("missing role");
+    assert_eq!(current.position, 2);
+
+    let previous = cache
+        .previous::<CachedRole>(key)
+        .await?
+        .expect("missing previous version");
+    assert_eq!(previous.position, 1);
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedUser, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{invite_create::PartialUser, MemberAdd},
+    },
+    id::Id,
+    user::User,
+};
+
+use super::member::member;
+use crate::pool;
+
+#[tokio::test]
+async fn test_read_counts() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const READ_COUNTER_SAMPLING: u32 = 1;
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        discriminator: u16,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self {
+                discriminator: user.discriminator,
+            }
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut member = member();
+    member.user.id = Id::new(226);
+    let user_id = member.user.id;
+
+    let event = Event::MemberAdd(Box::new(MemberAdd {
+        guild_id: Id::new(118),
+        member,
+    }));
+    cache.update(&event).await?;
+
+    let before = cache
+        .stats()
+        .read_counts()
+        .await?
+        .remove("user")
+        .unwrap_or_default();
+
+    assert!(cache.user(user_id).await?.is_some());
+    assert!(cache.user(Id::new(227)).await?.is_none());
+
+    let after = cache
+        .stats()
+        .read_counts()
+        .await?
+        .remove("user")
+        .unwrap_or_default();
+
+    // Other tests may read users concurrently
+    assert!(after.hits > before.hits);
+    assert!(after.misses > before.misses);
+
+    Ok(())
+}
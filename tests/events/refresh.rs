@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use redlight::{
+    cache::Refresher,
+    config::{CacheConfig, Cacheable, ICachedUser, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use tokio::sync::mpsc::{self, UnboundedSender};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{invite_create::PartialUser, MemberAdd},
+    },
+    id::Id,
+    user::User,
+};
+
+use super::member::member;
+use crate::pool;
+
+#[tokio::test]
+async fn test_refresh_expiring() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        discriminator: u16,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self {
+                discriminator: user.discriminator,
+            }
+        }
+
+        fn update_via_partial(
+        ) -> Option<fn(&mut CachedArchive<Self>, &PartialUser) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            Some(Duration::from_secs(10))
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    struct SendingRefresher(UnboundedSender<RedisKey>);
+
+    impl Refresher for SendingRefresher {
+        fn refresh(&self, key: RedisKey) -> BoxFuture<'_, ()> {
+            Box::pin(async move {
+                let _ = self.0.send(key);
+            })
+        }
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let cache = RedisCache::<Config>::new_with_pool(pool())
+        .await?
+        .with_refresher(Duration::from_secs(60), 4, SendingRefresher(tx));
+
+    let mut member = member();
+    member.user.id = Id::new(224);
+    let user_id = member.user.id;
+
+    let event = Event::MemberAdd(Box::new(MemberAdd {
+        guild_id: Id::new(117),
+        member,
+    }));
+    cache.update(&event).await?;
+
+    assert!(cache.user(user_id).await?.is_some());
+
+    let key = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+        .await
+        .expect("refresher was not called");
+
+    assert_eq!(key, Some(RedisKey::User { id: user_id }));
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    ReadPreference, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleCreate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_read_preference() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool())
+        .await?
+        .with_replica(pool())
+        .with_read_preference(ReadPreference::Replica);
+
+    let mut role = role("replica", 1);
+    role.id = Id::new(7_501);
+
+    let event = Event::RoleCreate(RoleCreate {
+        guild_id: Id::new(7_500),
+        role,
+    });
+
+    cache.update(&event).await?;
+
+    // Primary and replica share the same instance in tests
+    let role = cache.role(Id::new(7_501)).await?.expect("missing role");
+    assert_eq!(role.position, 1);
+
+    for preference in [
+        ReadPreference::Primary,
+        ReadPreference::Replica,
+        ReadPreference::ReplicaPreferred,
+    ] {
+        let role = cache
+            .read_from(preference)
+            .role(Id::new(7_501))
+            .await?
+            .expect("missing role");
+
+        assert_eq!(role.position, 1);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleUpdate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_run_preserves_guild_order() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    const GUILDS: u64 = 5;
+    const UPDATES: i64 = 50;
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let event = |guild: u64, position: i64| {
+        let mut role = role("run", position);
+        role.id = Id::new(7_600 + guild);
+
+        Event::RoleUpdate(RoleUpdate {
+            guild_id: Id::new(7_650 + guild),
+            role,
+        })
+    };
+
+    // Interleave the updates of all guilds
+    let events =
+        (1..=UPDATES).flat_map(|position| (0..GUILDS).map(move |guild| event(guild, position)));
+
+    cache.run(futures_util::stream::iter(events), 3).await?;
+
+    for guild in 0..GUILDS {
+        let role = cache
+            .role(Id::new(7_600 + guild))
+            .await?
+            .expect("missing role");
+
+        assert_eq!(role.position, UPDATES);
+    }
+
+    Ok(())
+}
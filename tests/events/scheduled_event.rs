@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedScheduledEvent, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{
+            GuildScheduledEventCreate, GuildScheduledEventUpdate, GuildScheduledEventUserAdd,
+            GuildScheduledEventUserRemove,
+        },
+    },
+    guild::scheduled_event::{EntityType, GuildScheduledEvent, PrivacyLevel, Status},
+    id::Id,
+    util::Timestamp,
+};
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_scheduled_event() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = CachedScheduledEvent;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedScheduledEvent {
+        name: String,
+        user_count: u64,
+    }
+
+    impl<'a> ICachedScheduledEvent<'a> for CachedScheduledEvent {
+        fn from_scheduled_event(scheduled_event: &'a GuildScheduledEvent) -> Self {
+            Self {
+                name: scheduled_event.name.clone(),
+                user_count: scheduled_event.user_count.unwrap_or(0),
+            }
+        }
+
+        fn on_user_count_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, i64) -> Result<(), Self::Error>> {
+            Some(|value, delta| {
+                value.update_archive(|sealed| {
+                    rkyv::munge::munge! {
+                        let ArchivedCachedScheduledEvent { mut user_count, .. } = sealed
+                    };
+
+                    *user_count = user_count.to_native().saturating_add_signed(delta).into();
+                })
+            })
+        }
+    }
+
+    impl Cacheable for CachedScheduledEvent {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::<8>::new())
+        }
+    }
+
+    impl Fallible for CachedScheduledEvent {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = scheduled_event();
+
+    let event =
+        Event::GuildScheduledEventCreate(Box::new(GuildScheduledEventCreate(expected.clone())));
+    cache.update(&event).await?;
+
+    let scheduled_event = cache
+        .scheduled_event(expected.id)
+        .await?
+        .expect("missing scheduled event");
+
+    assert_eq!(scheduled_event.name.as_str(), expected.name);
+
+    let scheduled_event_ids = cache.guild_scheduled_event_ids(expected.guild_id).await?;
+    assert!(scheduled_event_ids.contains(&expected.id));
+
+    // User additions and removals update the count in-place
+    for user_id in [Id::new(8_203), Id::new(8_204)] {
+        let event = Event::GuildScheduledEventUserAdd(GuildScheduledEventUserAdd {
+            guild_id: expected.guild_id,
+            guild_scheduled_event_id: expected.id,
+            user_id,
+        });
+        cache.update(&event).await?;
+    }
+
+    let event = Event::GuildScheduledEventUserRemove(GuildScheduledEventUserRemove {
+        guild_id: expected.guild_id,
+        guild_scheduled_event_id: expected.id,
+        user_id: Id::new(8_203),
+    });
+    cache.update(&event).await?;
+
+    let scheduled_event = cache
+        .scheduled_event(expected.id)
+        .await?
+        .expect("missing scheduled event");
+
+    assert_eq!(scheduled_event.user_count, 1);
+
+    // Completed events are removed
+    expected.status = Status::Completed;
+    let event =
+        Event::GuildScheduledEventUpdate(Box::new(GuildScheduledEventUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    assert!(cache.scheduled_event(expected.id).await?.is_none());
+
+    let scheduled_event_ids = cache.guild_scheduled_event_ids(expected.guild_id).await?;
+    assert!(!scheduled_event_ids.contains(&expected.id));
+
+    Ok(())
+}
+
+pub fn scheduled_event() -> GuildScheduledEvent {
+    GuildScheduledEvent {
+        channel_id: Some(Id::new(8_200)),
+        creator: None,
+        creator_id: None,
+        description: Some("scheduled event description".to_owned()),
+        entity_id: None,
+        entity_metadata: None,
+        entity_type: EntityType::Voice,
+        guild_id: Id::new(8_201),
+        id: Id::new(8_202),
+        image: None,
+        name: "scheduled event name".to_owned(),
+        privacy_level: PrivacyLevel::GuildOnly,
+        scheduled_end_time: None,
+        scheduled_start_time: Timestamp::from_secs(1_700_000_000).unwrap(),
+        status: Status::Scheduled,
+        user_count: None,
+    }
+}
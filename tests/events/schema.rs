@@ -0,0 +1,133 @@
+#![cfg(any(feature = "bb8", feature = "deadpool"))]
+
+use std::{ops::DerefMut, time::Duration};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{CacheConfig, Cacheable, EntityKind, ICachedUser, Ignore},
+    error::CacheError,
+    RedisCache, RedisKey, SchemaMismatch,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{id::Id, user::User};
+
+use crate::{events::user::user, pool};
+
+#[tokio::test]
+async fn test_schema_versions() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        id: u64,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self { id: user.id.get() }
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        const VERSION: u16 = 1;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = user();
+    expected.id = Id::new(8_991);
+
+    cache
+        .transaction(|tx| {
+            tx.store_user(&expected)?;
+
+            Ok(())
+        })
+        .await?;
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    // Forget the version recorded by previous runs
+    let _: () = Cmd::hdel(RedisKey::SchemaVersions, "user")
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let mismatches = cache.check_schema().await?;
+
+    assert_eq!(
+        mismatches,
+        [SchemaMismatch {
+            kind: EntityKind::User,
+            stored: 0,
+            current: 1,
+        }]
+    );
+
+    // Other tests may store users concurrently so their bytes are kept as is
+    let migrated = cache
+        .migrate_with(EntityKind::User, |bytes| Some(bytes.to_vec()))
+        .await?;
+
+    assert!(migrated >= 1);
+    assert!(cache.check_schema().await?.is_empty());
+    assert_eq!(
+        cache
+            .migrate_with(EntityKind::User, |bytes| Some(bytes.to_vec()))
+            .await?,
+        0
+    );
+
+    let cached = cache.user(Id::new(8_991)).await?.expect("missing user");
+    assert_eq!(cached.id.to_native(), 8_991);
+
+    Ok(())
+}
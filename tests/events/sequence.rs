@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    EventSequence, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleUpdate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_update_with_meta_skips_replays() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(114);
+    let role_id = Id::new(224);
+
+    let event = |position| {
+        let mut role = role("sequence", position);
+        role.id = role_id;
+
+        Event::RoleUpdate(RoleUpdate { guild_id, role })
+    };
+
+    // Sequences of previous runs are still stored so use a fresh shard
+    let shard_id = unused_shard_id();
+
+    let applied = cache
+        .update_with_meta(&event(1), Some(EventSequence::new(shard_id, 1)))
+        .await?;
+    assert!(applied);
+
+    let applied = cache
+        .update_with_meta(&event(2), Some(EventSequence::new(shard_id, 1)))
+        .await?;
+    assert!(!applied);
+
+    let cached = cache.role(role_id).await?.expect("missing role");
+    assert_eq!(cached.position, 1);
+
+    let applied = cache
+        .update_with_meta(&event(3), Some(EventSequence::new(shard_id, 2)))
+        .await?;
+    assert!(applied);
+
+    let cached = cache.role(role_id).await?.expect("missing role");
+    assert_eq!(cached.position, 3);
+
+    Ok(())
+}
+
+/// Shard id that is unlikely to be tracked already.
+fn unused_shard_id() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos()
+}
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore, WriterLease},
+    error::CacheError,
+    EventSequence, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleCreate},
+    guild::Role,
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_shadow_discards_writes() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const SHADOW: bool = true;
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(113);
+
+    let mut role = role("shadow", 1);
+    role.id = Id::new(223);
+
+    let event = Event::RoleCreate(RoleCreate {
+        guild_id,
+        role: role.clone(),
+    });
+
+    cache.update(&event).await?;
+
+    assert!(cache.role(role.id).await?.is_none());
+    assert!(cache.guild_role_ids(guild_id).await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shadow_ignores_writer_lease() -> Result<(), CacheError> {
+    struct LiveConfig;
+
+    impl CacheConfig for LiveConfig {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const WRITER_LEASE: Option<WriterLease> =
+            Some(WriterLease::new(Duration::from_secs(30)).rejecting());
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    struct ShadowConfig;
+
+    impl CacheConfig for ShadowConfig {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const SHADOW: bool = true;
+        const WRITER_LEASE: Option<WriterLease> =
+            Some(WriterLease::new(Duration::from_secs(30)).rejecting());
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    let live = RedisCache::<LiveConfig>::new_with_pool(pool()).await?;
+    let shadow = RedisCache::<ShadowConfig>::new_with_pool(pool()).await?;
+
+    // Leases and sequences of previous runs may still be stored so use a
+    // fresh shard
+    let shard_id = unused_shard_id();
+
+    // The shadow cache neither claims the lease nor stores the sequence so
+    // the live cache is unaffected
+    let applied = shadow
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 1)),
+        )
+        .await?;
+    assert!(applied);
+
+    let applied = live
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 1)),
+        )
+        .await?;
+    assert!(applied);
+
+    // The shadow cache neither conflicts with the live writer nor skips
+    // sequences that the live cache already applied
+    let applied = shadow
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 1)),
+        )
+        .await?;
+    assert!(applied);
+
+    let applied = live
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 1)),
+        )
+        .await?;
+    assert!(!applied);
+
+    Ok(())
+}
+
+/// Shard id that is unlikely to be tracked already.
+fn unused_shard_id() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos()
+}
@@ -1,24 +1,33 @@
-use std::time::Duration;
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
 use redlight::{
     config::{CacheConfig, Cacheable, ICachedStageInstance, Ignore},
     error::CacheError,
     rkyv_util::stage_instance::PrivacyLevelRkyv,
-    RedisCache,
+    CachedArchive, RedisCache,
 };
 use rkyv::{
     rancor::{Fallible, Panic},
     ser::writer::Buffer,
+    util::AlignedVec,
     Archive, Serialize,
 };
 use twilight_model::{
     channel::{stage_instance::PrivacyLevel, StageInstance},
-    gateway::{event::Event, payload::incoming::StageInstanceCreate},
+    gateway::{
+        event::Event,
+        payload::incoming::{StageInstanceCreate, StageInstanceUpdate},
+    },
     id::Id,
 };
 
 use crate::pool;
 
+static STAGE_UPDATES: AtomicUsize = AtomicUsize::new(0);
+
 #[tokio::test]
 async fn test_stage_instance() -> Result<(), CacheError> {
     struct Config;
@@ -27,17 +36,21 @@ async fn test_stage_instance() -> Result<(), CacheError> {
         #[cfg(feature = "metrics")]
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = Ignore;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = CachedStageInstance;
         type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -92,6 +105,110 @@ async fn test_stage_instance() -> Result<(), CacheError> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_stage_instance_update() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = CachedStageInstance;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedStageInstance {
+        topic_len: u32,
+    }
+
+    impl<'a> ICachedStageInstance<'a> for CachedStageInstance {
+        fn from_stage_instance(stage_instance: &'a StageInstance) -> Self {
+            Self {
+                topic_len: stage_instance.topic.len() as u32,
+            }
+        }
+
+        fn on_stage_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &StageInstanceUpdate) -> Result<(), Self::Error>>
+        {
+            Some(|value, update| {
+                STAGE_UPDATES.fetch_add(1, Ordering::SeqCst);
+
+                value.update_archive(|sealed| {
+                    rkyv::munge::munge! {
+                        let ArchivedCachedStageInstance { mut topic_len, .. } = sealed
+                    };
+
+                    *topic_len = (update.topic.len() as u32).into();
+                })
+            })
+        }
+    }
+
+    impl Cacheable for CachedStageInstance {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::<8>::new())
+        }
+    }
+
+    impl Fallible for CachedStageInstance {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = stage_instance();
+    expected.id = Id::new(7_900);
+
+    let event = Event::StageInstanceCreate(StageInstanceCreate(expected.clone()));
+    cache.update(&event).await?;
+
+    expected.topic = "new stage instance topic".to_owned();
+    let event = Event::StageInstanceUpdate(StageInstanceUpdate(expected.clone()));
+    cache.update(&event).await?;
+
+    assert_eq!(STAGE_UPDATES.load(Ordering::SeqCst), 1);
+
+    let stage = cache
+        .stage_instance(expected.id)
+        .await?
+        .expect("missing stage instance");
+
+    assert_eq!(stage.topic_len.to_native(), expected.topic.len() as u32);
+
+    // Uncached stage instances are stored without calling the hook
+    expected.id = Id::new(7_901);
+    let event = Event::StageInstanceUpdate(StageInstanceUpdate(expected.clone()));
+    cache.update(&event).await?;
+
+    assert_eq!(STAGE_UPDATES.load(Ordering::SeqCst), 1);
+    assert!(cache.stage_instance(expected.id).await?.is_some());
+
+    Ok(())
+}
+
 pub fn stage_instance() -> StageInstance {
     StageInstance {
         channel_id: Id::new(555),
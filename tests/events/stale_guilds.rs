@@ -0,0 +1,136 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use redlight::{
+    clock::MockClock,
+    config::{CacheConfig, Cacheable, ICachedGuild, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{GuildDelete, GuildUpdate, RoleDelete},
+    },
+    guild::Guild,
+    id::{marker::GuildMarker, Id},
+};
+
+use crate::pool;
+
+static STALE_GUILDS: Mutex<Vec<Id<GuildMarker>>> = Mutex::new(Vec::new());
+
+#[tokio::test]
+async fn test_stale_guilds() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = CachedGuild;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedGuild;
+
+    impl<'a> ICachedGuild<'a> for CachedGuild {
+        const STALENESS_THRESHOLD: Option<Duration> = Some(Duration::from_secs(60));
+
+        fn from_guild(_: &'a Guild) -> Self {
+            Self
+        }
+
+        fn on_guild_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+            None
+        }
+
+        fn on_stale_guilds() -> Option<fn(&[Id<GuildMarker>])> {
+            Some(|guild_ids| STALE_GUILDS.lock().unwrap().extend_from_slice(guild_ids))
+        }
+    }
+
+    impl Cacheable for CachedGuild {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedGuild {
+        type Error = Panic;
+    }
+
+    let clock = MockClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    let cache = RedisCache::<Config>::new_with_clock(pool(), clock.clone()).await?;
+
+    let active_guild = Id::new(7_001);
+    let deleted_guild = Id::new(7_002);
+
+    for guild_id in [active_guild, deleted_guild] {
+        let event = Event::RoleDelete(RoleDelete {
+            guild_id,
+            role_id: Id::new(1),
+        });
+        cache.update(&event).await?;
+    }
+
+    let stale = cache.stale_guild_ids(Duration::from_secs(60)).await?;
+    assert!(!stale.contains(&active_guild));
+    assert!(!stale.contains(&deleted_guild));
+
+    let stale = cache.stale_guild_ids(Duration::ZERO).await?;
+    assert!(stale.contains(&active_guild));
+    assert!(stale.contains(&deleted_guild));
+
+    let delete = Event::GuildDelete(GuildDelete {
+        id: deleted_guild,
+        unavailable: false,
+    });
+    cache.update(&delete).await?;
+
+    let stale = cache.stale_guild_ids(Duration::ZERO).await?;
+    assert!(stale.contains(&active_guild));
+    assert!(!stale.contains(&deleted_guild));
+
+    cache.update(&Event::GatewayReconnect).await?;
+    assert!(!STALE_GUILDS.lock().unwrap().contains(&active_guild));
+
+    clock.advance(Duration::from_secs(120));
+
+    cache.update(&Event::GatewayReconnect).await?;
+
+    let hinted = STALE_GUILDS.lock().unwrap();
+    assert!(hinted.contains(&active_guild));
+    assert!(!hinted.contains(&deleted_guild));
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use std::{collections::HashSet, time::Duration};
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedThreadMember, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    channel::{thread::ThreadMember, ChannelType},
+    gateway::{
+        event::Event,
+        payload::incoming::{ThreadDelete, ThreadMembersUpdate},
+    },
+    id::{
+        marker::{ChannelMarker, UserMarker},
+        Id,
+    },
+    util::Timestamp,
+};
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_thread_members() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = CachedThreadMember;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedThreadMember {
+        flags: u64,
+        join_timestamp: i64,
+    }
+
+    impl ICachedThreadMember<'_> for CachedThreadMember {
+        fn from_thread_member(thread_member: &ThreadMember) -> Self {
+            Self {
+                flags: thread_member.flags,
+                join_timestamp: thread_member.join_timestamp.as_secs(),
+            }
+        }
+    }
+
+    impl Cacheable for CachedThreadMember {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+    }
+
+    impl Fallible for CachedThreadMember {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(8_980);
+    let thread_id = Id::new(8_981);
+    let parent_id = Id::new(8_982);
+    let user_a = Id::new(8_983);
+    let user_b = Id::new(8_984);
+
+    let event = Event::ThreadMembersUpdate(ThreadMembersUpdate {
+        added_members: vec![
+            thread_member(thread_id, user_a, 1),
+            thread_member(thread_id, user_b, 2),
+        ],
+        guild_id,
+        id: thread_id,
+        member_count: 2,
+        removed_member_ids: Vec::new(),
+    });
+    cache.update(&event).await?;
+
+    let thread_member = cache
+        .thread_member(thread_id, user_b)
+        .await?
+        .expect("missing thread member");
+
+    assert_eq!(thread_member.flags.to_native(), 2);
+    assert_eq!(thread_member.join_timestamp.to_native(), 1_700_000_000);
+
+    let user_ids = cache.channel_thread_member_ids(thread_id).await?;
+    assert_eq!(user_ids, HashSet::from([user_a, user_b]));
+
+    let mut iter = cache.iter().channel_thread_members(thread_id).await?;
+    let mut count = 0;
+
+    while let Some(res) = iter.next_item().await {
+        res?;
+        count += 1;
+    }
+
+    assert_eq!(count, 2);
+
+    let event = Event::ThreadMembersUpdate(ThreadMembersUpdate {
+        added_members: Vec::new(),
+        guild_id,
+        id: thread_id,
+        member_count: 1,
+        removed_member_ids: vec![user_a],
+    });
+    cache.update(&event).await?;
+
+    assert!(cache.thread_member(thread_id, user_a).await?.is_none());
+
+    let user_ids = cache.channel_thread_member_ids(thread_id).await?;
+    assert_eq!(user_ids, HashSet::from([user_b]));
+
+    // Deleting the thread removes all of its members
+    let event = Event::ThreadDelete(ThreadDelete {
+        guild_id,
+        id: thread_id,
+        kind: ChannelType::PublicThread,
+        parent_id,
+    });
+    cache.update(&event).await?;
+
+    assert!(cache.thread_member(thread_id, user_b).await?.is_none());
+    assert!(cache.channel_thread_member_ids(thread_id).await?.is_empty());
+
+    Ok(())
+}
+
+fn thread_member(
+    thread_id: Id<ChannelMarker>,
+    user_id: Id<UserMarker>,
+    flags: u64,
+) -> ThreadMember {
+    ThreadMember {
+        flags,
+        id: Some(thread_id),
+        join_timestamp: Timestamp::from_secs(1_700_000_000).unwrap(),
+        member: None,
+        presence: None,
+        user_id: Some(user_id),
+    }
+}
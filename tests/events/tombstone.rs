@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedGuild, ICachedRole, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{GuildDelete, GuildUpdate, RoleCreate},
+    },
+    guild::{Guild, Role},
+    id::Id,
+};
+
+use super::version::role;
+use crate::pool;
+
+#[tokio::test]
+async fn test_guild_tombstone() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = CachedGuild;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedGuild;
+
+    impl<'a> ICachedGuild<'a> for CachedGuild {
+        const TOMBSTONE_DURATION: Option<Duration> = Some(Duration::from_secs(60));
+
+        fn from_guild(_: &'a Guild) -> Self {
+            Self
+        }
+
+        fn on_guild_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &GuildUpdate) -> Result<(), Self::Error>> {
+            None
+        }
+    }
+
+    impl Cacheable for CachedGuild {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedGuild {
+        type Error = Panic;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(654);
+    let role = role("tombstone", 4);
+    let role_id = role.id;
+
+    let create = Event::RoleCreate(RoleCreate { guild_id, role });
+    cache.update(&create).await?;
+
+    let delete = Event::GuildDelete(GuildDelete {
+        id: guild_id,
+        unavailable: false,
+    });
+    cache.update(&delete).await?;
+
+    assert!(cache.role(role_id).await?.is_none());
+    assert!(!cache.role_ids().await?.contains(&role_id));
+    assert!(cache.guild_role_ids(guild_id).await?.is_empty());
+
+    assert!(cache.restore_guild_tombstone(guild_id).await?);
+
+    let cached = cache.role(role_id).await?.expect("missing role");
+    assert_eq!(cached.position, 4);
+    assert!(cache.role_ids().await?.contains(&role_id));
+    assert!(cache.guild_role_ids(guild_id).await?.contains(&role_id));
+
+    assert!(!cache.restore_guild_tombstone(guild_id).await?);
+
+    // Entries that are cached again in the meantime are not overwritten
+    cache.update(&delete).await?;
+
+    let create = Event::RoleCreate(RoleCreate {
+        guild_id,
+        role: super::version::role("fresh", 5),
+    });
+    cache.update(&create).await?;
+
+    assert!(!cache.restore_guild_tombstone(guild_id).await?);
+
+    let cached = cache.role(role_id).await?.expect("missing role");
+    assert_eq!(cached.position, 5);
+
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, ICachedUser, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{guild::Role, id::Id, user::User};
+
+use super::{user::user, version::role};
+use crate::pool;
+
+#[tokio::test]
+async fn test_transaction() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole {
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let old_guild = Id::new(7_700);
+    let new_guild = Id::new(7_701);
+
+    let mut old_role = role("transaction", 1);
+    old_role.id = Id::new(7_702);
+
+    let mut new_role = role("transaction", 2);
+    new_role.id = Id::new(7_703);
+
+    cache
+        .transaction(|tx| {
+            tx.store_role(old_guild, &old_role)?;
+
+            Ok(())
+        })
+        .await?;
+
+    assert!(cache.role(old_role.id).await?.is_some());
+
+    // Move the role from one guild to the other
+    cache
+        .transaction(|tx| {
+            tx.store_role(new_guild, &new_role)?
+                .delete_role(old_guild, old_role.id);
+
+            Ok(())
+        })
+        .await?;
+
+    assert!(cache.role(old_role.id).await?.is_none());
+
+    let role = cache.role(new_role.id).await?.expect("missing role");
+    assert_eq!(role.position, 2);
+
+    // Failing transactions apply none of their operations
+    let res = cache
+        .transaction(|tx| {
+            tx.delete_role(new_guild, new_role.id);
+
+            Err(CacheError::InvalidResponse)
+        })
+        .await;
+
+    assert!(matches!(res, Err(CacheError::InvalidResponse)));
+    assert!(cache.role(new_role.id).await?.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transaction_coalesced_users() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        discriminator: u16,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self {
+                discriminator: user.discriminator,
+            }
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut old_user = user();
+    old_user.id = Id::new(8_600);
+    old_user.discriminator = 1;
+
+    let mut new_user = old_user.clone();
+    new_user.discriminator = 2;
+
+    // Repeated writes of the same user are coalesced into the last one
+    cache
+        .transaction(|tx| {
+            tx.store_user(&old_user)?.store_user(&new_user)?;
+
+            Ok(())
+        })
+        .await?;
+
+    let user = cache.user(new_user.id).await?.expect("missing user");
+    assert_eq!(user.discriminator, 2);
+
+    Ok(())
+}
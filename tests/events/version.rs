@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    with::InlineAsBox,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::RoleUpdate},
+    guild::{Permissions, Role, RoleFlags},
+    id::Id,
+};
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_versioned_writes() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole<'a>;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole<'a> {
+        #[rkyv(with = InlineAsBox)]
+        name: &'a str,
+        position: i64,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole<'a> {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                name: &role.name,
+                position: role.position,
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole<'_> {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+
+        // Pretend the position increases with every update
+        fn version(&self) -> Option<u64> {
+            u64::try_from(self.position).ok()
+        }
+    }
+
+    impl Fallible for CachedRole<'_> {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(111);
+
+    let newer = Event::RoleUpdate(RoleUpdate {
+        guild_id,
+        role: role("newer", 5),
+    });
+    cache.update(&newer).await?;
+
+    let older = Event::RoleUpdate(RoleUpdate {
+        guild_id,
+        role: role("older", 3),
+    });
+    cache.update(&older).await?;
+
+    let cached = cache.role(Id::new(222)).await?.expect("missing role");
+    assert_eq!(cached.name.as_ref(), "newer");
+    assert_eq!(cached.position, 5);
+
+    let newest = Event::RoleUpdate(RoleUpdate {
+        guild_id,
+        role: role("newest", 7),
+    });
+    cache.update(&newest).await?;
+
+    let cached = cache.role(Id::new(222)).await?.expect("missing role");
+    assert_eq!(cached.name.as_ref(), "newest");
+
+    Ok(())
+}
+
+pub fn role(name: &str, position: i64) -> Role {
+    Role {
+        color: 0,
+        hoist: false,
+        icon: None,
+        id: Id::new(222),
+        managed: false,
+        mentionable: false,
+        name: name.to_owned(),
+        permissions: Permissions::empty(),
+        position,
+        flags: RoleFlags::empty(),
+        tags: None,
+        unicode_emoji: None,
+    }
+}
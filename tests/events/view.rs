@@ -0,0 +1,92 @@
+#![cfg(any(feature = "bb8", feature = "deadpool"))]
+
+use std::ops::DerefMut;
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    cache::{CacheBatch, DerivedView},
+    config::{CacheConfig, Ignore},
+    error::CacheError,
+    RedisCache, RedisKey,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::MessageCreate},
+    id::Id,
+};
+
+use crate::{events::message::message, pool};
+
+#[tokio::test]
+async fn test_derived_view() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: std::time::Duration = std::time::Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    struct MessageCounter;
+
+    impl DerivedView for MessageCounter {
+        fn apply(&self, event: &Event, batch: &mut CacheBatch<'_>) {
+            if let Event::MessageCreate(msg) = event {
+                batch.incr_by("message_counts", &msg.channel_id.to_string(), 1);
+            }
+        }
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool())
+        .await?
+        .with_view(MessageCounter);
+
+    let channel_id = Id::new(8_970);
+
+    let key = RedisKey::Custom {
+        namespace: "message_counts".into(),
+        key: channel_id.to_string().into(),
+    };
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    let _: () = Cmd::del(&key).query_async(conn.deref_mut()).await?;
+
+    for id in 8_971..8_973 {
+        let mut msg = message();
+        msg.id = Id::new(id);
+        msg.channel_id = channel_id;
+
+        let event = Event::MessageCreate(Box::new(MessageCreate(msg)));
+        cache.update(&event).await?;
+    }
+
+    let count: i64 = Cmd::get(&key).query_async(conn.deref_mut()).await?;
+    assert_eq!(count, 2);
+
+    Ok(())
+}
@@ -0,0 +1,161 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedVoiceState, Ignore},
+    error::CacheError,
+    CachedArchive, RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{event::Event, payload::incoming::VoiceStateUpdate},
+    id::{
+        marker::{ChannelMarker, GuildMarker},
+        Id,
+    },
+    voice::VoiceState,
+};
+
+use crate::pool;
+
+static VOICE_STATE_UPDATES: AtomicUsize = AtomicUsize::new(0);
+
+#[tokio::test]
+async fn test_voice_state_update() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = CachedVoiceState;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedVoiceState {
+        channel_id: u64,
+        self_mute: bool,
+    }
+
+    impl<'a> ICachedVoiceState<'a> for CachedVoiceState {
+        fn from_voice_state(
+            channel_id: Id<ChannelMarker>,
+            _: Id<GuildMarker>,
+            voice_state: &'a VoiceState,
+        ) -> Self {
+            Self {
+                channel_id: channel_id.get(),
+                self_mute: voice_state.self_mute,
+            }
+        }
+
+        fn on_voice_state_update(
+        ) -> Option<fn(&mut CachedArchive<Self>, &VoiceState) -> Result<(), Self::Error>> {
+            Some(|value, update| {
+                VOICE_STATE_UPDATES.fetch_add(1, Ordering::SeqCst);
+
+                value.update_archive(|sealed| {
+                    rkyv::munge::munge! {
+                        let ArchivedCachedVoiceState { mut self_mute, .. } = sealed
+                    };
+
+                    *self_mute = update.self_mute;
+                })
+            })
+        }
+    }
+
+    impl Cacheable for CachedVoiceState {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::<8>::new())
+        }
+    }
+
+    impl Fallible for CachedVoiceState {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut expected = voice_state();
+    let guild_id = expected.guild_id.unwrap();
+
+    // Uncached voice states are stored without calling the hook
+    let event = Event::VoiceStateUpdate(Box::new(VoiceStateUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    assert_eq!(VOICE_STATE_UPDATES.load(Ordering::SeqCst), 0);
+
+    let voice_state = cache
+        .voice_state(guild_id, expected.user_id)
+        .await?
+        .expect("missing voice state");
+
+    assert!(!voice_state.self_mute);
+
+    // Cached voice states are updated in-place
+    expected.self_mute = true;
+    expected.channel_id = Some(Id::new(8_102));
+    let event = Event::VoiceStateUpdate(Box::new(VoiceStateUpdate(expected.clone())));
+    cache.update(&event).await?;
+
+    assert_eq!(VOICE_STATE_UPDATES.load(Ordering::SeqCst), 1);
+
+    let voice_state = cache
+        .voice_state(guild_id, expected.user_id)
+        .await?
+        .expect("missing voice state");
+
+    assert!(voice_state.self_mute);
+
+    // Fields that the hook does not touch remain as they were
+    assert_eq!(voice_state.channel_id.to_native(), 8_101);
+
+    Ok(())
+}
+
+pub fn voice_state() -> VoiceState {
+    VoiceState {
+        channel_id: Some(Id::new(8_101)),
+        deaf: false,
+        guild_id: Some(Id::new(8_100)),
+        member: None,
+        mute: false,
+        self_deaf: false,
+        self_mute: false,
+        self_stream: false,
+        self_video: false,
+        session_id: "voice state session".to_owned(),
+        suppress: false,
+        user_id: Id::new(8_103),
+        request_to_speak_timestamp: None,
+    }
+}
@@ -0,0 +1,119 @@
+#![cfg(any(feature = "bb8", feature = "deadpool"))]
+
+use std::{
+    ops::DerefMut,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedChannel, Ignore},
+    error::CacheError,
+    RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    Archive, Serialize,
+};
+use twilight_model::{
+    channel::Channel,
+    gateway::{event::Event, payload::incoming::WebhooksUpdate},
+    id::Id,
+};
+
+use crate::pool;
+
+static HOOK_CALLED: AtomicBool = AtomicBool::new(false);
+
+#[tokio::test]
+async fn test_webhooks_update() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = CachedChannel;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedChannel;
+
+    impl<'a> ICachedChannel<'a> for CachedChannel {
+        fn from_channel(_: &'a Channel) -> Self {
+            Self
+        }
+
+        fn on_webhooks_update() -> Option<fn(&WebhooksUpdate)> {
+            Some(|_| HOOK_CALLED.store(true, Ordering::SeqCst))
+        }
+    }
+
+    impl Cacheable for CachedChannel {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedChannel {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let channel_id = Id::new(7_700);
+    let key = RedisKey::ChannelWebhooks {
+        channel: channel_id,
+    };
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    let _: () = Cmd::set(&key, "webhooks")
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let event = Event::WebhooksUpdate(WebhooksUpdate {
+        channel_id,
+        guild_id: Id::new(7_701),
+    });
+
+    cache.update(&event).await?;
+
+    assert!(HOOK_CALLED.load(Ordering::SeqCst));
+
+    let exists: bool = Cmd::exists(&key).query_async(conn.deref_mut()).await?;
+    assert!(!exists);
+
+    Ok(())
+}
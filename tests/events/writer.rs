@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Ignore, WriterLease},
+    error::CacheError,
+    EventSequence, RedisCache,
+};
+use twilight_model::gateway::event::Event;
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_duplicate_writer() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const WRITER_LEASE: Option<WriterLease> =
+            Some(WriterLease::new(Duration::from_secs(30)).rejecting());
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    let leader = RedisCache::<Config>::new_with_pool(pool()).await?;
+    let intruder = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    // Leases of previous runs may still be held so use a fresh shard
+    let shard_id = unused_shard_id();
+
+    let applied = leader
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 1)),
+        )
+        .await?;
+    assert!(applied);
+
+    let res = intruder
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 2)),
+        )
+        .await;
+
+    assert!(
+        matches!(res, Err(CacheError::DuplicateWriter { shard }) if shard == shard_id),
+        "{res:?}"
+    );
+
+    // Other shards are unaffected
+    let applied = intruder
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id.wrapping_add(1), 1)),
+        )
+        .await?;
+    assert!(applied);
+
+    let applied = leader
+        .update_with_meta(
+            &Event::GatewayHeartbeatAck,
+            Some(EventSequence::new(shard_id, 3)),
+        )
+        .await?;
+    assert!(applied);
+
+    Ok(())
+}
+
+/// Shard id that is unlikely to be tracked already.
+fn unused_shard_id() -> u32 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .subsec_nanos()
+}
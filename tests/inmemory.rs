@@ -0,0 +1,113 @@
+#![cfg(feature = "inmemory")]
+
+use std::time::Duration;
+
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    with::InlineAsBox,
+    Archive, Serialize,
+};
+use twilight_cache_inmemory::InMemoryCache;
+use twilight_model::{
+    gateway::payload::incoming::RoleCreate,
+    guild::{Permissions, Role, RoleFlags},
+    id::Id,
+};
+
+use crate::pool;
+
+#[tokio::test]
+async fn test_import_inmemory() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole<'a>;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedRole<'a> {
+        #[rkyv(with = InlineAsBox)]
+        name: &'a str,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole<'a> {
+        fn from_role(role: &'a Role) -> Self {
+            Self { name: &role.name }
+        }
+    }
+
+    impl Cacheable for CachedRole<'_> {
+        type Bytes = AlignedVec<8>;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::api::high::to_bytes_in(self, AlignedVec::default())
+        }
+    }
+
+    impl Fallible for CachedRole<'_> {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(444);
+    let role = Role {
+        color: 0,
+        hoist: false,
+        icon: None,
+        id: Id::new(555),
+        managed: false,
+        mentionable: false,
+        name: "imported".to_owned(),
+        permissions: Permissions::empty(),
+        position: 1,
+        flags: RoleFlags::empty(),
+        tags: None,
+        unicode_emoji: None,
+    };
+
+    let inmemory = InMemoryCache::new();
+    inmemory.update(&RoleCreate {
+        guild_id,
+        role: role.clone(),
+    });
+
+    cache.import_inmemory(&inmemory).await?;
+
+    let cached = cache.role(role.id).await?.expect("missing role");
+    assert_eq!(cached.name.as_ref(), "imported");
+
+    let role_ids = cache.guild_role_ids(guild_id).await?;
+    assert!(role_ids.contains(&role.id));
+
+    Ok(())
+}
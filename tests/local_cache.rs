@@ -0,0 +1,143 @@
+#![cfg(all(feature = "local_cache", any(feature = "bb8", feature = "deadpool")))]
+
+use std::{ops::DerefMut, time::Duration};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{CacheConfig, Cacheable, EntityKind, ICachedUser, Ignore, LocalCache},
+    error::CacheError,
+    RedisCache, RedisKey,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{id::Id, user::User};
+
+use crate::{events::user::user, pool};
+
+#[tokio::test]
+async fn test_local_cache() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const LOCAL_CACHE: Option<LocalCache> = Some(LocalCache {
+            capacity: 8,
+            lifetime: Duration::from_secs(60),
+            kinds: &[EntityKind::User],
+        });
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser {
+        discriminator: u16,
+    }
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(user: &'a User) -> Self {
+            Self {
+                discriminator: user.discriminator,
+            }
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = AlignedVec;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let user_id = Id::new(8_990);
+
+    let mut expected = user();
+    expected.id = user_id;
+    expected.discriminator = 1;
+
+    cache
+        .transaction(|tx| {
+            tx.store_user(&expected)?;
+
+            Ok(())
+        })
+        .await?;
+
+    let cached = cache.user(user_id).await?.expect("missing user");
+    assert_eq!(cached.discriminator.to_native(), 1);
+
+    // Delete the entry behind the cache's back; reads are still served
+    // from memory
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    let _: () = Cmd::del(RedisKey::User { id: user_id })
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let cached = cache.user(user_id).await?.expect("missing local user");
+    assert_eq!(cached.discriminator.to_native(), 1);
+
+    // Writes through the cache invalidate the in-memory entry
+    expected.discriminator = 2;
+
+    cache
+        .transaction(|tx| {
+            tx.store_user(&expected)?;
+
+            Ok(())
+        })
+        .await?;
+
+    let cached = cache.user(user_id).await?.expect("missing updated user");
+    assert_eq!(cached.discriminator.to_native(), 2);
+
+    let _: () = Cmd::del(RedisKey::User { id: user_id })
+        .query_async(conn.deref_mut())
+        .await?;
+
+    cache.clear_local_cache();
+
+    assert!(cache.user(user_id).await?.is_none());
+
+    Ok(())
+}
@@ -44,17 +44,21 @@ async fn test_metrics() -> Result<(), CacheError> {
     impl CacheConfig for Config {
         const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(2);
 
+        type AutoModerationRule<'a> = Ignore;
         type Channel<'a> = CachedChannel;
         type CurrentUser<'a> = Ignore;
         type Emoji<'a> = Ignore;
         type Guild<'a> = Ignore;
         type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
         type Member<'a> = Ignore;
         type Message<'a> = Ignore;
         type Presence<'a> = Ignore;
         type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
         type StageInstance<'a> = Ignore;
         type Sticker<'a> = CachedSticker;
+        type ThreadMember<'a> = Ignore;
         type User<'a> = Ignore;
         type VoiceState<'a> = Ignore;
     }
@@ -186,7 +190,7 @@ async fn test_metrics() -> Result<(), CacheError> {
         fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
         fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
         fn register_counter(&self, _: &Key, _: &Metadata) -> Counter { unimplemented!() }
-        fn register_histogram(&self, _: &Key, _: &Metadata) -> Histogram { unimplemented!() }
+        fn register_histogram(&self, _: &Key, _: &Metadata) -> Histogram { Histogram::noop() }
     }
 
     let recorder = MetricRecorder::default();
@@ -212,11 +216,10 @@ async fn test_metrics() -> Result<(), CacheError> {
     assert_eq!(recorder.render(), "channel_count: 1\nsticker_count: 0");
 
     let stickers = stickers();
+    let guild_id = stickers[0].guild_id.unwrap();
 
-    let guild_stickers_update = Event::GuildStickersUpdate(GuildStickersUpdate {
-        guild_id: stickers[0].guild_id.unwrap(),
-        stickers,
-    });
+    let guild_stickers_update =
+        Event::GuildStickersUpdate(GuildStickersUpdate { guild_id, stickers });
 
     cache.update(&guild_stickers_update).await?;
 
@@ -224,5 +227,28 @@ async fn test_metrics() -> Result<(), CacheError> {
 
     assert_eq!(recorder.render(), "channel_count: 1\nsticker_count: 2");
 
+    // Another instance took over so this one no longer reports sizes
+    {
+        let mut conn = cache
+            .pool()
+            .get()
+            .await
+            .map_err(CacheError::GetConnection)?;
+        let _: () = Cmd::set("METRICS_LEADER", "other instance")
+            .query_async(conn.deref_mut())
+            .await?;
+    }
+
+    let delete_stickers = Event::GuildStickersUpdate(GuildStickersUpdate {
+        guild_id,
+        stickers: Vec::new(),
+    });
+
+    cache.update(&delete_stickers).await?;
+
+    tokio::time::sleep(Config::METRICS_INTERVAL_DURATION + Duration::from_secs(1)).await;
+
+    assert_eq!(recorder.render(), "channel_count: 1\nsticker_count: 2");
+
     Ok(())
 }
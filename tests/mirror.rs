@@ -0,0 +1,124 @@
+#![cfg(all(feature = "serde-mirror", any(feature = "bb8", feature = "deadpool")))]
+
+use std::{ops::DerefMut, time::Duration};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{to_json, CacheConfig, Cacheable, ICachedRole, Ignore},
+    error::CacheError,
+    RedisCache,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    util::AlignedVec,
+    Archive, Serialize,
+};
+use twilight_model::{
+    gateway::{
+        event::Event,
+        payload::incoming::{RoleCreate, RoleDelete},
+    },
+    guild::Role,
+    id::Id,
+};
+
+use crate::{events::version::role, pool};
+
+#[tokio::test]
+async fn test_mirror() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = CachedRole;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize, serde::Serialize)]
+    struct CachedRole {
+        name: String,
+    }
+
+    impl<'a> ICachedRole<'a> for CachedRole {
+        fn from_role(role: &'a Role) -> Self {
+            Self {
+                name: role.name.clone(),
+            }
+        }
+    }
+
+    impl Cacheable for CachedRole {
+        type Bytes = AlignedVec;
+
+        const MIRROR: bool = true;
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            rkyv::to_bytes(self)
+        }
+
+        fn mirror(&self) -> Option<Result<Vec<u8>, Self::Error>> {
+            Some(to_json(self))
+        }
+    }
+
+    impl Fallible for CachedRole {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let guild_id = Id::new(8_200);
+    let mut role = role("mirrored", 1);
+    role.id = Id::new(8_201);
+    let role_id = role.id;
+    let mirror_key = format!("JSON:ROLE:{role_id}");
+
+    let mut conn = cache
+        .pool()
+        .get()
+        .await
+        .map_err(CacheError::GetConnection)?;
+
+    let create = Event::RoleCreate(RoleCreate { guild_id, role });
+    cache.update(&create).await?;
+
+    let json: Option<String> = Cmd::get(&mirror_key).query_async(conn.deref_mut()).await?;
+
+    assert_eq!(json.as_deref(), Some(r#"{"name":"mirrored"}"#));
+
+    let delete = Event::RoleDelete(RoleDelete { guild_id, role_id });
+    cache.update(&delete).await?;
+
+    let exists: bool = Cmd::exists(&mirror_key)
+        .query_async(conn.deref_mut())
+        .await?;
+
+    assert!(!exists);
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use std::{ops::DerefMut, time::Duration};
+
+#[cfg(feature = "bb8")]
+use bb8_redis::redis;
+#[cfg(all(not(feature = "bb8"), feature = "deadpool"))]
+use deadpool_redis::redis;
+use redis::Cmd;
+use redlight::{
+    config::{CacheConfig, Cacheable, ICachedUser, Ignore},
+    error::CacheError,
+    ChangeKind, RedisCache, RedisKey, Topics,
+};
+use rkyv::{
+    rancor::{Fallible, Panic},
+    Archive, Serialize,
+};
+use twilight_model::{id::Id, user::User};
+
+use crate::{events::user::user, pool};
+
+#[tokio::test]
+async fn test_subscribe() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = Ignore;
+        type VoiceState<'a> = Ignore;
+    }
+
+    let pool = pool();
+    let cache = RedisCache::<Config>::new_with_pool(pool.clone()).await?;
+
+    let topics = Topics::new()
+        .channel("redlight-test-channel")
+        .pattern("redlight-test-pattern:*");
+
+    let mut subscription = cache.subscribe(topics).await?;
+
+    let mut conn = pool.get().await.map_err(CacheError::GetConnection)?;
+
+    let _: i64 = Cmd::publish("redlight-test-channel", "foo")
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let _: i64 = Cmd::publish("redlight-test-pattern:bar", "baz")
+        .query_async(conn.deref_mut())
+        .await?;
+
+    let msg = tokio::time::timeout(Duration::from_secs(5), subscription.recv())
+        .await
+        .expect("timed out")
+        .expect("subscription closed");
+
+    assert_eq!(msg.channel(), "redlight-test-channel");
+    assert_eq!(msg.pattern(), None);
+    assert_eq!(msg.payload(), b"foo");
+
+    let msg = tokio::time::timeout(Duration::from_secs(5), subscription.recv())
+        .await
+        .expect("timed out")
+        .expect("subscription closed");
+
+    assert_eq!(msg.channel(), "redlight-test-pattern:bar");
+    assert_eq!(msg.pattern(), Some("redlight-test-pattern:*"));
+    assert_eq!(msg.into_payload(), b"baz");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_invalidations() -> Result<(), CacheError> {
+    struct Config;
+
+    impl CacheConfig for Config {
+        #[cfg(feature = "metrics")]
+        const METRICS_INTERVAL_DURATION: Duration = Duration::from_secs(60);
+
+        const PUBLISH_INVALIDATIONS: bool = true;
+
+        type AutoModerationRule<'a> = Ignore;
+        type Channel<'a> = Ignore;
+        type CurrentUser<'a> = Ignore;
+        type Emoji<'a> = Ignore;
+        type Guild<'a> = Ignore;
+        type Integration<'a> = Ignore;
+        type Invite<'a> = Ignore;
+        type Member<'a> = Ignore;
+        type Message<'a> = Ignore;
+        type Presence<'a> = Ignore;
+        type Role<'a> = Ignore;
+        type ScheduledEvent<'a> = Ignore;
+        type StageInstance<'a> = Ignore;
+        type Sticker<'a> = Ignore;
+        type ThreadMember<'a> = Ignore;
+        type User<'a> = CachedUser;
+        type VoiceState<'a> = Ignore;
+    }
+
+    #[derive(Archive, Serialize)]
+    struct CachedUser;
+
+    impl<'a> ICachedUser<'a> for CachedUser {
+        fn from_user(_: &'a User) -> Self {
+            Self
+        }
+    }
+
+    impl Cacheable for CachedUser {
+        type Bytes = [u8; 0];
+
+        fn expire() -> Option<Duration> {
+            None
+        }
+
+        fn serialize_one(&self) -> Result<Self::Bytes, Self::Error> {
+            Ok([])
+        }
+    }
+
+    impl Fallible for CachedUser {
+        type Error = Panic;
+    }
+
+    let cache = RedisCache::<Config>::new_with_pool(pool()).await?;
+
+    let mut invalidations = cache.subscribe_invalidations().await?;
+
+    let mut user = user();
+    user.id = Id::new(8_800);
+
+    cache
+        .transaction(|tx| {
+            tx.store_user(&user)?;
+
+            Ok(())
+        })
+        .await?;
+
+    // Other tests may publish invalidations concurrently
+    let received = tokio::time::timeout(Duration::from_secs(5), async {
+        while let Some((key, kind)) = invalidations.recv().await {
+            if key == (RedisKey::User { id: user.id }) {
+                return Some(kind);
+            }
+        }
+
+        None
+    })
+    .await
+    .expect("timed out");
+
+    assert_eq!(received, Some(ChangeKind::Stored));
+
+    Ok(())
+}